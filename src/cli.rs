@@ -1,17 +1,106 @@
-use crate::commands::QueryExecutor;
-use crate::completion::{metadata::DatabaseMetadata, MySQLHelper};
-use crate::database::Connection;
+use crate::commands::alias::AliasTable;
+use crate::commands::query::{self, Outcome, VerticalResultHandler};
+use crate::commands::read_only::{self, ReadOnlyCheck};
+use crate::commands::{
+    admin, advisor, charset, check, clone_row, edit_row, find, generallog, hist, import_csv,
+    import_json, index_stats, literal, pipe, protect, session, slowlog, sql_mode, tail,
+    transaction, wizard, xlsx, DisplayFormat, QueryExecutor,
+};
+use crate::completion::metadata::{ColumnInfo, DatabaseMetadata, TemporaryTableChange};
+use crate::completion::schema_change::SchemaChange;
+use crate::completion::special_commands;
+use crate::completion::MySQLHelper;
+use crate::config;
+use crate::database::session::{SessionState, SessionTarget};
+use crate::database::{Connection, ExecOutcome, QueryResult};
+use crate::help::{self, KeywordHelpHandler};
+use crate::pager;
+use crate::parsers::explain::{self, PlanIssue};
+use crate::parsers::innodb_status;
+use crate::platform;
+use crate::settings::Settings;
+use crate::statement::{self, Terminator, TimeLimit};
 use anyhow::Result;
+use rustyline::config::Configurer;
 use rustyline::error::ReadlineError;
-use rustyline::{history::DefaultHistory, CompletionType, Config, Editor};
+use rustyline::{
+    history::{DefaultHistory, History, SearchDirection},
+    Cmd, Config, Editor, EventHandler, KeyCode, KeyEvent, Modifiers,
+};
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Number of recent statements kept for analysis commands
+const MAX_LAST_QUERIES: usize = 100;
+
+/// Prompt shown while a statement spans several lines
+const CONTINUATION_PROMPT: &str = "    -> ";
+
+/// How a statement entered at the prompt ended
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum StatementRun {
+    Succeeded,
+    Failed,
+    /// Not sent to the server, e.g. declined at the paste guard
+    Cancelled,
+}
 
 pub struct Cli {
     connection: Connection,
     query_executor: QueryExecutor,
     editor: Editor<MySQLHelper, DefaultHistory>,
-    current_database: Option<String>,
+    session: SessionState,
     metadata: Arc<Mutex<DatabaseMetadata>>,
+    settings: Settings,
+    last_queries: VecDeque<String>,
+    aliases: AliasTable,
+    variables: HashMap<String, String>,
+    /// Values collected by `\useids`, expanded once into the next `${ids}`
+    ids: Option<String>,
+    /// Statement delimiter, changed by `DELIMITER` and `--delimiter`
+    delimiter: String,
+    show_banner: bool,
+    /// `-s` hides the banner, row counts and timing; `-ss` also column names
+    silent_level: u8,
+    /// Key currently bound with Alt to re-display the last result vertically
+    vertical_key: Option<char>,
+    /// Databases where destructive statements need a typed `yes`
+    protected_databases: Vec<String>,
+    /// Text placed at the next prompt for editing, e.g. by `\clone-row`
+    pending_input: Option<String>,
+    /// Statements come from `--batch`, `-e` or stdin, so there is no one to prompt
+    batch: bool,
+    /// Whether the session has an open transaction
+    transaction: transaction::TransactionState,
+    /// Roll back the open transaction when a statement in it fails (`--rollback-on-error`)
+    rollback_on_error: bool,
+    /// Character set from `--charset` the session is expected to use
+    charset: Option<String>,
+    /// File the line history is loaded from and appended to on exit
+    history_file: Option<PathBuf>,
+    /// Entries kept in the line history and its file (`--history-size`)
+    history_size: usize,
+    /// Server-side time limit in milliseconds for interactive SELECTs (`\maxtime`)
+    max_execution_time: Option<u64>,
+    /// Transcript file statements are appended to while `\record` is on
+    recorder: Option<session::Recorder>,
+    /// Workbook the next result set is written to (`\export-xlsx`)
+    xlsx_export: Option<String>,
+    /// Whether the server is a read-only replica, re-checked every few minutes
+    read_only: ReadOnlyCheck,
+    /// Task templates offered by `\admin`
+    admin_templates: Vec<admin::Template>,
+    /// File `\save-settings` and `\load-settings` use without a path
+    settings_file: Option<PathBuf>,
+    /// The configuration file as loaded at startup
+    user_config: config::Config,
+    /// Where each setting was last set, for `\config show`
+    provenance: config::Provenance,
 }
 
 impl Cli {
@@ -21,24 +110,57 @@ impl Cli {
         user: &str,
         password: &str,
         database: Option<&str>,
+        show_banner: bool,
+        init_command: Option<&str>,
     ) -> Result<Self> {
         let mut connection = Connection::new(host, port, user, password, database)?;
-        let query_executor = QueryExecutor::new();
-        let current_database = database.map(|d| d.to_string());
+        let mut query_executor = QueryExecutor::new();
 
-        println!("Welcome to the MySQL monitor. Commands end with ; or \\g.");
-        println!("Your MySQL connection id is {}", connection.connection_id());
-        println!("Server version: {}", connection.server_version());
-        println!();
-        println!(
-            "Type 'help;' or '\\h' for help. Type '\\c' to clear the current input statement."
-        );
-        println!();
+        let init_commands: Vec<String> = init_command
+            .map(statement::split_statements)
+            .unwrap_or_default();
+        for command in &init_commands {
+            connection.execute(command)?;
+        }
+
+        // Trust the server, not the flag, for which database is actually selected
+        let current_database = connection
+            .current_database()?
+            .map(|db| connection.normalize_database_name(&db));
+        if let Some(requested) = database {
+            match &current_database {
+                Some(selected) if selected.eq_ignore_ascii_case(requested) => {}
+                Some(selected) => println!(
+                    "Warning: requested database '{}' but server selected '{}'",
+                    requested, selected
+                ),
+                None => println!(
+                    "Warning: requested database '{}' but no database is selected",
+                    requested
+                ),
+            }
+        }
+
+        let (user_config, diagnostics) = config::Config::load().unwrap_or_else(|e| {
+            println!("Warning: ignoring configuration file: {}", e);
+            Default::default()
+        });
+        for diagnostic in diagnostics {
+            println!("Warning: {}", diagnostic);
+        }
+        let aliases = AliasTable::new(user_config.aliases.clone());
+        let admin_templates = admin::templates(&user_config.admin);
+        let protected_databases = load_protected_databases();
 
         // 配置 rustyline 编辑器
+        let mut settings = Settings::new();
+        settings.compress = connection.compressed();
         let config = Config::builder()
-            .completion_type(CompletionType::List)
-            .auto_add_history(true)
+            .completion_type(settings.completion_type)
+            // History is written only by `add_history`, which applies the history_* settings
+            .auto_add_history(false)
+            .max_history_size(hist::DEFAULT_HISTORY_SIZE)?
+            .history_ignore_dups(false)?
             .edit_mode(rustyline::EditMode::Emacs)
             .build();
 
@@ -47,64 +169,334 @@ impl Cli {
 
         // 设置 MySQL 补全助手
         let helper = MySQLHelper::with_metadata(metadata.clone());
+        query_executor.set_metadata(metadata.clone());
 
         // 更新数据库元数据
         if let Ok(mut meta) = metadata.lock() {
+            meta.set_requested_database(database.map(str::to_string));
+            meta.bind_to(&connection.identity());
             let _ = meta.update_from_connection(connection.get_conn_mut());
         }
 
         editor.set_helper(Some(helper));
 
+        // F1 shows help for the keyword under the cursor
+        if let Ok(printer) = editor.create_external_printer() {
+            editor.bind_sequence(
+                KeyEvent(KeyCode::F(1), Modifiers::NONE),
+                EventHandler::Conditional(Box::new(KeywordHelpHandler::new(Box::new(printer)))),
+            );
+        }
+
         // Set initial current database in completion engine if available
         if let Some(helper) = editor.helper() {
             helper.set_current_database(current_database.clone());
         }
 
-        Ok(Self {
+        let mut cli = Self {
             connection,
             query_executor,
             editor,
-            current_database,
+            session: SessionState::new(current_database, init_commands),
             metadata,
-        })
+            settings,
+            last_queries: VecDeque::new(),
+            aliases,
+            variables: HashMap::new(),
+            ids: None,
+            delimiter: ";".to_string(),
+            show_banner,
+            silent_level: 0,
+            vertical_key: None,
+            protected_databases,
+            pending_input: None,
+            batch: false,
+            transaction: transaction::TransactionState::default(),
+            rollback_on_error: false,
+            charset: None,
+            history_file: None,
+            history_size: hist::DEFAULT_HISTORY_SIZE,
+            max_execution_time: None,
+            recorder: None,
+            xlsx_export: None,
+            read_only: ReadOnlyCheck::new(),
+            admin_templates,
+            settings_file: config::settings_path(),
+            user_config,
+            provenance: config::Provenance::default(),
+        };
+        cli.read_only.refresh(&mut cli.connection);
+        cli.bind_vertical_key();
+        Ok(cli)
+    }
+
+    /// Bind Alt-<vertical_key> to re-display the last result vertically,
+    /// replacing a previous binding
+    fn bind_vertical_key(&mut self) {
+        if let Some(old) = self.vertical_key.take() {
+            self.editor.unbind_sequence(KeyEvent::alt(old));
+        }
+        if let Ok(printer) = self.editor.create_external_printer() {
+            let key = self.settings.vertical_key;
+            self.editor.bind_sequence(
+                KeyEvent::alt(key),
+                EventHandler::Conditional(Box::new(VerticalResultHandler::new(
+                    self.query_executor.shared_last_result(),
+                    Box::new(printer),
+                ))),
+            );
+            self.vertical_key = Some(key);
+        }
+    }
+
+    /// Offer tables of these databases, in priority order, after the current database
+    pub fn set_search_path(&mut self, databases: Vec<String>) {
+        if let Some(helper) = self.editor.helper() {
+            helper.set_search_path(databases);
+        }
+    }
+
+    /// Roll back an open transaction when a statement inside it fails
+    pub fn set_rollback_on_error(&mut self, rollback: bool) {
+        self.rollback_on_error = rollback;
+    }
+
+    /// Statement delimiter in effect from the start, as if `DELIMITER` had been run
+    pub fn set_delimiter(&mut self, delimiter: &str) {
+        self.delimiter = delimiter.to_string();
+    }
+
+    /// Tag every statement with a `/* ... */` comment, from `--query-comment`
+    pub fn set_query_comment(&mut self, template: &str) {
+        self.connection
+            .set_query_comment(Some(template.to_string()));
+    }
+
+    /// Limit interactive SELECTs to `ms` milliseconds of server execution time; 0 disables
+    pub fn set_max_execution_time(&mut self, ms: u64) {
+        self.max_execution_time = (ms > 0).then_some(ms);
+    }
+
+    /// Use `path` (or `~/.mysql_cli_settings.toml`) as the settings file and
+    /// apply it when it exists
+    pub fn set_settings_file(&mut self, path: Option<PathBuf>) -> Result<()> {
+        if path.is_some() {
+            self.settings_file = path;
+        }
+        match &self.settings_file {
+            Some(path) if path.exists() => self.load_settings(&path.clone()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Print or leave out result headers, from `--column-names` / `--no-column-names`
+    pub fn set_column_names(&mut self, column_names: bool) {
+        self.settings.column_names = column_names;
+        let flag = if column_names {
+            "--column-names"
+        } else {
+            "--no-column-names"
+        };
+        self.provenance
+            .record("column_names", config::Source::Flag(flag));
+        self.apply_settings();
+    }
+
+    /// Expect the session to use `charset`, from `--charset`
+    pub fn set_charset(&mut self, charset: &str) {
+        self.charset = Some(charset.to_string());
+    }
+
+    /// The warning to show when the session character sets differ from each
+    /// other or from `--charset`
+    pub fn charset_warning(&mut self) -> Result<Option<String>> {
+        let charsets = charset::SessionCharsets {
+            client: self.connection.session_variable("character_set_client")?,
+            connection: self
+                .connection
+                .session_variable("character_set_connection")?,
+            results: self.connection.session_variable("character_set_results")?,
+        };
+        Ok(charset::mismatch(&charsets, self.charset.as_deref()))
+    }
+
+    /// Keep at most `size` history entries, in memory and in the history file
+    pub fn set_history_size(&mut self, size: usize) -> Result<()> {
+        self.history_size = size;
+        self.editor.history_mut().set_max_len(size)?;
+        Ok(())
+    }
+
+    /// Load the line history from `path`, pruned to the history size first, and
+    /// append the session's entries to it on exit; the file is readable only by
+    /// its owner, as statements may hold passwords
+    pub fn set_history_file(&mut self, path: PathBuf) -> Result<()> {
+        if path.exists() {
+            hist::prune_file(
+                &path,
+                self.history_size,
+                self.settings.history_max_entry_bytes,
+            )?;
+            self.editor.load_history(&path)?;
+        } else {
+            std::fs::File::create(&path)?;
+        }
+        platform::restrict_to_owner(&path)?;
+        self.history_file = Some(path);
+        Ok(())
+    }
+
+    /// Append new history entries to the history file and prune it
+    fn save_history(&mut self) {
+        let Some(path) = self.history_file.clone() else {
+            return;
+        };
+        let saved = self
+            .editor
+            .append_history(&path)
+            .map_err(anyhow::Error::from);
+        if let Err(e) = saved.and_then(|_| {
+            hist::prune_file(
+                &path,
+                self.history_size,
+                self.settings.history_max_entry_bytes,
+            )
+        }) {
+            println!(
+                "Warning: could not save history to {}: {}",
+                path.display(),
+                e
+            );
+        }
+    }
+
+    /// Record that `--compress` asked for a compressed connection, over the
+    /// settings file
+    pub fn set_compress_flag(&mut self) {
+        self.settings.compress = true;
+        self.connection.set_compress(true);
+        self.provenance
+            .record("compress", config::Source::Flag("--compress"));
+    }
+
+    /// Print tab-separated values unescaped, from `--raw`
+    pub fn set_raw(&mut self, raw: bool) {
+        self.settings.raw = raw;
+        self.provenance.record("raw", config::Source::Flag("--raw"));
+        self.apply_settings();
+    }
+
+    /// Reduce output for scripts (`-s`, `-ss`)
+    pub fn set_silent_level(&mut self, level: u8) {
+        self.silent_level = level;
+        self.query_executor.set_silent(level > 0);
+        if level > 1 {
+            self.set_column_names(false);
+            self.provenance
+                .record("column_names", config::Source::Flag("-ss"));
+        }
+    }
+
+    /// How result sets are shown, e.g. only their row count with `--count-only`
+    pub fn set_display_format(&mut self, format: DisplayFormat) {
+        self.query_executor.set_display_format(format);
+    }
+
+    /// Print the welcome banner unless it was suppressed
+    fn print_banner(&self) {
+        if !self.show_banner || self.silent_level > 0 {
+            return;
+        }
+        println!("Welcome to the MySQL monitor. Commands end with ; or \\g.");
+        println!(
+            "Your MySQL connection id is {}",
+            self.connection.connection_id()
+        );
+        println!("Server version: {}", self.connection.server_version());
+        println!();
+        println!(
+            "Type 'help;' or '\\h' for help. Type '\\c' to clear the current input statement."
+        );
+        println!();
     }
 
     pub fn run(&mut self) -> Result<()> {
+        self.print_banner();
+        let mut buffer = String::new();
         loop {
-            let prompt = self.get_prompt();
+            self.sync_statement_terminator();
+            let prompt = if buffer.is_empty() {
+                self.read_only.refresh_if_stale(&mut self.connection);
+                self.get_prompt()
+            } else {
+                CONTINUATION_PROMPT.to_string()
+            };
 
-            let readline = self.editor.readline(&prompt);
+            let readline = match self.pending_input.take() {
+                Some(text) => self.editor.readline_with_initial(&prompt, (&text, "")),
+                None => self.editor.readline(&prompt),
+            };
+            if let Some(helper) = self.editor.helper() {
+                helper.clear_status_bar();
+            }
             match readline {
                 Ok(line) => {
-                    let line = line.trim();
+                    let typed = sanitize_input(&line, &buffer);
+                    let line = typed.trim();
                     if line.is_empty() {
+                        // With auto_semicolon, an empty line ends a complete multi-line statement
+                        if self.settings.auto_semicolon && statement::is_complete_sql(&buffer) {
+                            let query = std::mem::take(&mut buffer);
+                            self.run_statement(&query, Terminator::Delimiter);
+                        }
                         continue;
                     }
 
                     // 添加到历史记录
-                    self.editor.add_history_entry(line)?;
+                    self.add_history(&typed, &buffer)?;
 
                     // Handle special commands
-                    if line.starts_with('\\') {
+                    if line.starts_with('\\') && buffer.is_empty() {
+                        self.record_command(line);
                         if let Err(e) = self.handle_special_command(line) {
-                            println!("Error: {}", e);
+                            self.query_executor
+                                .summary(Outcome::Error, &format!("Error: {}", e));
                         }
                         continue;
                     }
-
-                    // Handle SQL queries
-                    if line.ends_with(';') || line.ends_with("\\g") {
-                        let query = line.trim_end_matches(';').trim_end_matches("\\g").trim();
-                        if let Err(e) = self.execute_query(query) {
-                            println!("ERROR: {}", e);
+                    if line == "\\c" || line == "\\clear" {
+                        buffer.clear();
+                        continue;
+                    }
+                    if buffer.is_empty() {
+                        if let Some(delimiter) = statement::delimiter_command(line) {
+                            self.delimiter = delimiter;
+                            continue;
                         }
-                    } else {
-                        // For simplicity, require explicit semicolons
-                        println!("Please end your SQL statement with ';' or '\\g'");
+                    }
+
+                    // Accumulate lines until the buffer ends with a terminator
+                    let first_line = buffer.is_empty();
+                    if !first_line {
+                        buffer.push('\n');
+                    }
+                    buffer.push_str(line);
+                    if let Some((query, terminator)) =
+                        statement::complete_statement(&buffer, &self.delimiter)
+                    {
+                        buffer.clear();
+                        self.run_statement(&query, terminator);
+                    } else if first_line
+                        && self.settings.auto_semicolon
+                        && statement::is_complete_sql(&buffer)
+                    {
+                        let query = std::mem::take(&mut buffer);
+                        self.run_statement(&query, Terminator::Delimiter);
                     }
                 }
                 Err(ReadlineError::Interrupted) => {
                     println!("^C");
+                    buffer.clear();
                     continue;
                 }
                 Err(ReadlineError::Eof) => {
@@ -117,28 +509,283 @@ impl Cli {
                 }
             }
         }
+        self.save_history();
+        Ok(())
+    }
+
+    /// Run one statement entered at the prompt, reporting errors
+    fn run_statement(&mut self, query: &str, terminator: Terminator) -> StatementRun {
+        if statement::looks_like_pasted_data(
+            query,
+            self.settings.paste_guard_bytes,
+            self.settings.paste_guard_lines,
+        ) {
+            let prompt = format!(
+                "This doesn't look like SQL ({} pasted) — execute anyway? [y/N] ",
+                check::format_bytes(query.len() as u64)
+            );
+            if !matches!(prompt_line(&prompt).as_deref(), Ok("y" | "yes")) {
+                println!("Query cancelled.");
+                return StatementRun::Cancelled;
+            }
+        }
+
+        let query = match terminator {
+            Terminator::Explain => match statement::explain(query) {
+                Some(explained) => explained,
+                None => {
+                    self.query_executor.summary(
+                        Outcome::Error,
+                        "ERROR: \\E only explains SELECT, INSERT, UPDATE, DELETE and REPLACE statements",
+                    );
+                    return StatementRun::Failed;
+                }
+            },
+            _ => query.to_string(),
+        };
+        if self.read_only.is_read_only() && read_only::is_write_statement(&query) {
+            println!("Warning: server is read_only — statement will likely fail");
+        }
+        if let Some(recorder) = &mut self.recorder {
+            if let Err(e) = recorder.record_statement(&query, &self.delimiter) {
+                println!("Warning: recording stopped: {}", e);
+                self.recorder = None;
+            }
+        }
+
+        // With \maxtime, SELECTs get an optimizer hint or, failing that, a session limit
+        let mut query = query;
+        let mut limited = false;
+        let mut previous_limit = None;
+        if let Some(ms) = self.max_execution_time {
+            match statement::limit_execution_time(&query, ms) {
+                TimeLimit::Hint(hinted) => {
+                    query = hinted;
+                    limited = true;
+                }
+                TimeLimit::Session => match self.set_session_time_limit(Some(ms)) {
+                    Ok(previous) => {
+                        previous_limit = Some(previous);
+                        limited = true;
+                    }
+                    Err(e) => {
+                        self.query_executor
+                            .summary(Outcome::Error, &format!("ERROR: {}", e));
+                        return StatementRun::Failed;
+                    }
+                },
+                TimeLimit::Unchanged => {}
+            }
+        }
+        if limited {
+            self.query_executor.set_time_limit(self.max_execution_time);
+        }
+
+        self.query_executor
+            .set_vertical(terminator == Terminator::Vertical);
+        let mut succeeded = match self.execute_user_statement(&query) {
+            Ok(()) => self.query_executor.last_outcome() != Outcome::Error,
+            Err(e) => {
+                self.query_executor
+                    .summary(Outcome::Error, &format!("ERROR: {}", e));
+                false
+            }
+        };
+        self.query_executor.set_vertical(false);
+        self.query_executor.set_time_limit(None);
+
+        if let Some(previous) = previous_limit {
+            if let Err(e) = self.set_session_time_limit(previous) {
+                self.query_executor
+                    .summary(Outcome::Error, &format!("ERROR: {}", e));
+                succeeded = false;
+            }
+        }
+        if succeeded {
+            StatementRun::Succeeded
+        } else {
+            StatementRun::Failed
+        }
+    }
+
+    /// Append a backslash command to the transcript while `\record --all` is on
+    fn record_command(&mut self, command: &str) {
+        if command.starts_with("\\record") {
+            return;
+        }
+        if let Some(recorder) = &mut self.recorder {
+            if let Err(e) = recorder.record_command(command) {
+                println!("Warning: recording stopped: {}", e);
+                self.recorder = None;
+            }
+        }
+    }
+
+    /// Start or stop recording executed statements to a transcript file
+    fn record(&mut self, args: &str) -> Result<()> {
+        let args: Vec<&str> = args.split_whitespace().collect();
+        match args.as_slice() {
+            [] => match &self.recorder {
+                Some(recorder) => println!("Recording to '{}'", recorder.path()),
+                None => println!("Not recording."),
+            },
+            ["off"] => match self.recorder.take() {
+                Some(recorder) => {
+                    println!("Stopped recording to '{}'", recorder.path());
+                    recorder.finish()?;
+                }
+                None => println!("Not recording."),
+            },
+            [path] | [path, "--all"] => {
+                if let Some(previous) = self.recorder.take() {
+                    previous.finish()?;
+                }
+                let recorder = session::Recorder::create(path, args.len() == 2)?;
+                println!("Recording statements to '{}'", path);
+                self.recorder = Some(recorder);
+            }
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "Usage: \\record <file> [--all] | \\record off"
+                ))
+            }
+        }
+        Ok(())
+    }
+
+    /// Feed a transcript back through the prompt's execution path, echoing each statement
+    fn replay(&mut self, options: &session::ReplayOptions) -> Result<()> {
+        let text = std::fs::read_to_string(&options.path)
+            .map_err(|e| anyhow::anyhow!("Failed to open file '{}': {}", options.path, e))?;
+        let (statements, delimiter) = session::read_transcript(&text, &self.delimiter);
+        let total = statements.len();
+
+        for (i, (query, query_delimiter)) in statements.iter().enumerate() {
+            self.delimiter = query_delimiter.clone();
+            let is_command = query.starts_with('\\');
+            if is_command {
+                println!("{}{}", self.get_prompt(), query);
+            } else {
+                println!("{}{}{}", self.get_prompt(), query, self.delimiter);
+            }
+            if options.step {
+                let answer = prompt_line("[Enter] run, [q] stop: ")?;
+                if answer == "q" {
+                    println!("Replay stopped after {} of {} statements.", i, total);
+                    return Ok(());
+                }
+            } else if let Some(delay) = options.delay.filter(|_| i > 0) {
+                std::thread::sleep(delay);
+            }
+
+            let run = if is_command {
+                self.record_command(query);
+                match self.handle_special_command(query) {
+                    Ok(()) => StatementRun::Succeeded,
+                    Err(e) => {
+                        self.query_executor
+                            .summary(Outcome::Error, &format!("Error: {}", e));
+                        StatementRun::Failed
+                    }
+                }
+            } else {
+                self.run_statement(query, Terminator::Delimiter)
+            };
+            if run == StatementRun::Cancelled {
+                println!("Replay stopped at statement {} of {}.", i + 1, total);
+                return Ok(());
+            }
+            if run == StatementRun::Failed && !options.force {
+                println!(
+                    "Replay stopped at statement {} of {} (--force continues past errors).",
+                    i + 1,
+                    total
+                );
+                return Ok(());
+            }
+        }
+        self.delimiter = delimiter;
+        Ok(())
+    }
+
+    /// Set the session `max_execution_time`, returning the value it replaced
+    fn set_session_time_limit(&mut self, ms: Option<u64>) -> Result<Option<u64>> {
+        let previous = self.connection.session_variable("max_execution_time")?;
+        self.connection.execute_query(&format!(
+            "SET SESSION max_execution_time = {}",
+            ms.unwrap_or(0)
+        ))?;
+        Ok(previous.parse().ok())
+    }
+
+    /// Execute statements non-interactively, stopping at the first error
+    pub fn run_batch(&mut self, input: &str) -> Result<()> {
+        self.batch = true;
+        self.print_banner();
+        let (statements, delimiter) = statement::split_script(input, &self.delimiter);
+        self.delimiter = delimiter;
+        for query in statements {
+            if query.starts_with('\\') {
+                self.handle_special_command(&query)?;
+                // There is no prompt to edit at, so show generated statements instead
+                if let Some(text) = self.pending_input.take() {
+                    println!("{}", text);
+                }
+            } else {
+                self.execute_script_statement(&query)?;
+            }
+        }
         Ok(())
     }
 
     fn get_prompt(&self) -> String {
-        match &self.current_database {
-            Some(db) => format!("mysql [{}]> ", db),
-            None => "mysql> ".to_string(),
+        if !self.settings.prompt.is_empty() {
+            return read_only::expand_prompt(
+                &self.settings.prompt,
+                self.session.database.as_deref(),
+                self.read_only.mode(),
+                self.connection.server_version(),
+            );
+        }
+        let marker = match self.read_only.is_read_only() {
+            true if io::stdout().is_terminal() => " \x1b[1;31mro\x1b[0m",
+            true => " ro",
+            false => "",
+        };
+        match &self.session.database {
+            Some(db) => format!("mysql [{}]{}> ", db, marker),
+            None => format!("mysql{}> ", marker),
         }
     }
 
     fn handle_special_command(&mut self, command: &str) -> Result<()> {
+        let command = special_commands::normalize(command);
+        if let Some(usage) = special_commands::missing_arguments(&command) {
+            return Err(anyhow::anyhow!(usage));
+        }
+        let command = command.as_str();
         match command {
             "\\q" | "\\quit" | "\\exit" => {
+                self.save_history();
                 println!("Bye");
                 std::process::exit(0);
             }
             "\\h" | "\\help" => {
                 self.show_help();
             }
+            _ if command.starts_with("\\help ") || command.starts_with("\\h ") => {
+                let keyword = command.split_once(' ').unwrap().1.trim();
+                match help::lookup(keyword) {
+                    Some(text) => println!("{}", text),
+                    None => println!("No help available for '{}'.", keyword),
+                }
+            }
             "\\c" | "\\clear" => {
                 println!("Query cleared.");
             }
+            "\\r" | "\\reconnect" => {
+                self.reconnect()?;
+            }
             "\\s" | "\\status" => {
                 self.show_status()?;
             }
@@ -152,84 +799,1756 @@ impl Cli {
                 let db_name = command.strip_prefix("\\u ").unwrap().trim();
                 self.use_database(db_name)?;
             }
-            _ => {
-                println!("Unknown command: {}", command);
-                println!("Type '\\h' for help.");
+            "\\v" | "\\vertical" => match self.query_executor.last_result() {
+                Some(result) => query::display_vertical(&result),
+                None => println!("No result to display."),
+            },
+            "\\set" => {
+                self.show_settings();
+            }
+            _ if command.starts_with("\\set ") => {
+                let assignment = command.strip_prefix("\\set ").unwrap().trim();
+                self.settings.apply(assignment)?;
+                if let Some((name, _)) = assignment.split_once('=') {
+                    self.provenance.record(name.trim(), config::Source::Session);
+                }
+                self.apply_settings();
+                self.offer_reconnect()?;
+            }
+            "\\pset" => {
+                self.show_settings();
+            }
+            _ if command.starts_with("\\config ") => match &command["\\config ".len()..] {
+                "show" => pager::page(&self.effective_config()),
+                _ => return Err(anyhow::anyhow!("Usage: \\config show")),
+            },
+            _ if command == "\\save-settings" || command.starts_with("\\save-settings ") => {
+                let path = self.settings_file_argument(&command["\\save-settings".len()..])?;
+                self.settings.save(&path)?;
+                println!("Settings saved to {}", path.display());
+            }
+            _ if command == "\\load-settings" || command.starts_with("\\load-settings ") => {
+                let path = self.settings_file_argument(&command["\\load-settings".len()..])?;
+                self.load_settings(&path)?;
+                println!("Settings loaded from {}", path.display());
+                if self.connection.compress_pending() {
+                    println!(
+                        "Note: compress={} takes effect after \\reconnect",
+                        u8::from(self.settings.compress)
+                    );
+                }
+            }
+            _ if command.starts_with("\\pset ") => {
+                // psql-style `\pset name value`, with `-` accepted for `_` in names
+                let args = command.strip_prefix("\\pset ").unwrap().trim();
+                let (name, value) = args
+                    .split_once(char::is_whitespace)
+                    .ok_or_else(|| anyhow::anyhow!("Usage: \\pset <name> <value>"))?;
+                // `auto-semicolon` is the completion terminator, not `\set auto_semicolon`
+                let name = match name {
+                    "auto-semicolon" => "completion_semicolon".to_string(),
+                    name => name.replace('-', "_"),
+                };
+                self.settings.set(&name, value.trim())?;
+                self.provenance.record(&name, config::Source::Session);
+                self.apply_settings();
+                self.offer_reconnect()?;
+            }
+            _ if command.starts_with("\\d+ ") || command.starts_with("\\describe+ ") => {
+                let table = command.split_once(' ').unwrap().1.trim();
+                self.describe_table(table)?;
+            }
+            _ if command.starts_with("\\show ") => {
+                match command["\\show ".len()..].split_once(' ') {
+                    Some(("columns", table)) => self.show_full_columns(table.trim())?,
+                    _ => return Err(anyhow::anyhow!("Usage: \\show columns <table>")),
+                }
+            }
+            _ if command.starts_with("\\marker ") => {
+                let value = command.strip_prefix("\\marker ").unwrap().trim();
+                self.settings.set("marker", value)?;
+                self.provenance.record("marker", config::Source::Session);
+                self.apply_settings();
+            }
+            "\\sqlmode" => {
+                println!("{}", self.connection.session_variable("sql_mode")?);
+            }
+            _ if command.starts_with("\\sqlmode ") => {
+                let changes: Vec<&str> = command
+                    .strip_prefix("\\sqlmode ")
+                    .unwrap()
+                    .split_whitespace()
+                    .collect();
+                let current = self.connection.session_variable("sql_mode")?;
+                let mode = sql_mode::edit_sql_mode(&current, &changes)?;
+                self.execute_query(&format!("SET SESSION sql_mode = '{}'", mode))?;
+                println!("sql_mode: {}", mode);
+            }
+            "\\maxtime" => match self.max_execution_time {
+                Some(ms) => println!("SELECTs are limited to {} ms.", ms),
+                None => println!("No execution time limit (\\maxtime <ms> sets one)."),
+            },
+            _ if command.starts_with("\\maxtime ") => {
+                let ms = command.strip_prefix("\\maxtime ").unwrap().trim();
+                let ms: u64 = ms
+                    .parse()
+                    .map_err(|_| anyhow::anyhow!("Usage: \\maxtime <milliseconds> (0 disables)"))?;
+                self.set_max_execution_time(ms);
+            }
+            _ if command.starts_with("\\hist ") => {
+                let args = command.strip_prefix("\\hist ").unwrap();
+                self.history_command(hist::HistCommand::parse(args)?)?;
+            }
+            _ if command.starts_with("\\tail-slowlog ") => {
+                let path = command.strip_prefix("\\tail-slowlog ").unwrap().trim();
+                let color = std::io::stdout().is_terminal();
+                let mut parser = slowlog::SlowLogParser::new();
+                tail::follow(path, |line| {
+                    parser
+                        .feed(line)
+                        .map(|entry| slowlog::format_entry(&entry, color))
+                })?;
+            }
+            _ if command.starts_with("\\tail-generallog ") => {
+                let args = command.strip_prefix("\\tail-generallog ").unwrap();
+                let (path, types) = generallog::parse_args(args)?;
+                let color = std::io::stdout().is_terminal();
+                let mut parser = generallog::GeneralLogParser::new();
+                tail::follow(&path, |line| {
+                    parser
+                        .feed(line)
+                        .and_then(|line| generallog::format_line(&line, &types, color))
+                })?;
+            }
+            _ if command.starts_with("\\import-csv ") => {
+                let options = import_csv::ImportOptions::parse(
+                    command.strip_prefix("\\import-csv ").unwrap(),
+                )?;
+                self.import_csv(&options)?;
+            }
+            _ if command.starts_with("\\import-json ") => {
+                self.import_json(command.strip_prefix("\\import-json ").unwrap())?;
+            }
+            "\\record" => self.record("")?,
+            _ if command.starts_with("\\record ") => {
+                self.record(command.strip_prefix("\\record ").unwrap())?;
+            }
+            _ if command.starts_with("\\replay ") => {
+                let options =
+                    session::ReplayOptions::parse(command.strip_prefix("\\replay ").unwrap())?;
+                self.replay(&options)?;
+            }
+            _ if command.starts_with("\\export-xlsx ") => {
+                let path = command.strip_prefix("\\export-xlsx ").unwrap().trim();
+                self.xlsx_export = Some(path.to_string());
+                println!("The next result will be written to {}", path);
+            }
+            "\\innodb-status" | "\\innodb-status --json" => {
+                self.innodb_status(command.ends_with("--json"))?;
+            }
+            "\\admin" => {
+                for template in &self.admin_templates {
+                    println!("{:<24}{}", template.name, template.description);
+                }
+            }
+            _ if command.starts_with("\\admin ") => {
+                let task = command.strip_prefix("\\admin ").unwrap().trim();
+                self.admin_task(task)?;
+            }
+            "\\charset-check" => {
+                if let Some(warning) = self.charset_warning()? {
+                    println!("{}", warning);
+                } else {
+                    println!(
+                        "Character sets agree: {}",
+                        self.connection
+                            .session_variable("character_set_connection")?
+                    );
+                }
+            }
+            "\\clear-cache" => {
+                self.query_executor.clear_cache();
+                println!("Query cache cleared.");
+            }
+            "\\protect" => {
+                if self.protected_databases.is_empty() {
+                    println!("No protected databases.");
+                }
+                for db in &self.protected_databases {
+                    println!("{}", db);
+                }
+            }
+            _ if command.starts_with("\\protect ") => {
+                let db = command.strip_prefix("\\protect ").unwrap().trim();
+                let db = db.trim_matches('`').to_string();
+                if !self
+                    .protected_databases
+                    .iter()
+                    .any(|p| p.eq_ignore_ascii_case(&db))
+                {
+                    self.protected_databases.push(db);
+                }
+            }
+            "\\edit-row" => {
+                self.edit_row()?;
+            }
+            "\\create-table" => {
+                self.create_table_wizard()?;
+            }
+            _ if command.starts_with("\\alter-table ") => {
+                let table = command.strip_prefix("\\alter-table ").unwrap().trim();
+                self.alter_table_wizard(table)?;
+            }
+            _ if command.starts_with("\\clone-row ") => {
+                let args = command.strip_prefix("\\clone-row ").unwrap();
+                let (table, condition, first) = clone_row::parse_args(args)?;
+                let (schema, table) =
+                    check::resolve_table_name(&table, self.session.database.as_deref())?;
+                self.sync_sql_mode();
+                let insert =
+                    clone_row::clone_row(&mut self.connection, &schema, &table, &condition, first)?;
+                self.pending_input = Some(insert);
+            }
+            _ if command.starts_with("\\check ") => {
+                let table = command.strip_prefix("\\check ").unwrap().trim();
+                self.check_table(table)?;
+            }
+            _ if command.starts_with("\\index-stats ") => {
+                let table = command.strip_prefix("\\index-stats ").unwrap().trim();
+                self.show_index_stats(table)?;
+            }
+            "\\var" => {
+                self.show_variables();
+            }
+            _ if command.starts_with("\\var ") => {
+                let assignment = command.strip_prefix("\\var ").unwrap();
+                let (name, value) = assignment
+                    .split_once('=')
+                    .ok_or_else(|| anyhow::anyhow!("Usage: \\var <name>=<value>"))?;
+                self.variables
+                    .insert(name.trim().to_string(), value.trim().to_string());
+            }
+            _ if command.starts_with("\\source ") || command.starts_with("\\. ") => {
+                let path = command.split_once(' ').unwrap().1.trim();
+                self.source_file(path)?;
+            }
+            _ if command.starts_with("\\export-metadata ") => {
+                let path = command.strip_prefix("\\export-metadata ").unwrap().trim();
+                self.export_metadata(path)?;
+            }
+            _ if command.starts_with("\\useids ") => {
+                let column = command.strip_prefix("\\useids ").unwrap().trim();
+                let result = self
+                    .query_executor
+                    .last_result()
+                    .ok_or_else(|| anyhow::anyhow!("No result set to take ids from"))?;
+                self.sync_sql_mode();
+                let (list, count) = pipe::values_list(&result, column)?;
+                self.ids = Some(list);
+                println!(
+                    "Collected {} value(s) from '{}' for ${{ids}}",
+                    count, column
+                );
+            }
+            _ if command.starts_with("\\find ") => {
+                let args = command.strip_prefix("\\find ").unwrap();
+                self.find(args)?;
             }
+            "\\alias" => {
+                self.show_aliases();
+            }
+            "\\alias --save" => {
+                let path = config::save_string_section("aliases", self.aliases.entries())?;
+                self.user_config.aliases = self.aliases.entries().clone();
+                println!("Aliases saved to {}", path.display());
+            }
+            _ if command.starts_with("\\alias ") => {
+                let definition = command.strip_prefix("\\alias ").unwrap();
+                let (name, expansion) = definition
+                    .split_once('=')
+                    .ok_or_else(|| anyhow::anyhow!("Usage: \\alias <name> = <expansion>"))?;
+                self.aliases.define(name, expansion)?;
+            }
+            _ if command.starts_with("\\unalias ") => {
+                let name = command.strip_prefix("\\unalias ").unwrap().trim();
+                if !self.aliases.remove(name) {
+                    println!("No such alias: {}", name);
+                }
+            }
+            _ => match self.aliases.expand(command)? {
+                Some(expanded) if expanded.starts_with('\\') => {
+                    self.handle_special_command(&expanded)?;
+                }
+                Some(expanded) => {
+                    self.execute_query(expanded.trim_end_matches(';'))?;
+                }
+                None => {
+                    println!("Unknown command: {}", command);
+                    println!("Type '\\h' for help.");
+                }
+            },
         }
         Ok(())
     }
 
-    fn show_help(&self) {
-        println!("General SQL help:");
-        println!("Note that all text commands must be first on line and end with ';'");
-        println!();
-        println!("\\c (\\clear)     Clear the current input statement.");
-        println!("\\d (\\databases) List databases.");
-        println!("\\h (\\help)      Display this help.");
-        println!("\\q (\\quit)      Quit mysql.");
-        println!("\\s (\\status)    Get status information from the server.");
-        println!("\\t (\\tables)    List tables in current database.");
-        println!("\\u <db> (\\use)  Use database <db>.");
-        println!();
-    }
+    /// Search databases, tables and columns by name
+    fn find(&mut self, args: &str) -> Result<()> {
+        let (pattern, scope) = find::parse_args(args)?;
 
-    fn show_status(&self) -> Result<()> {
-        println!("--------------");
-        println!("Connection id:\t\t{}", self.connection.connection_id());
-        println!(
-            "Current database:\t{}",
-            self.current_database.as_deref().unwrap_or("(none)")
-        );
-        println!("Server version:\t\t{}", self.connection.server_version());
-        println!("--------------");
+        let cached = {
+            let meta = self
+                .metadata
+                .lock()
+                .map_err(|_| anyhow::anyhow!("Metadata is unavailable"))?;
+            meta.is_complete()
+                .then(|| find::search_metadata(&meta, &pattern, scope))
+        };
+        let matches = match cached {
+            Some(matches) => matches,
+            None => find::search_server(&mut self.connection, &pattern, scope)?,
+        };
+
+        if matches.is_empty() {
+            println!("No matches found.");
+        } else {
+            self.query_executor
+                .display_results(&find::to_result(&matches));
+            println!("{} match(es)", matches.len());
+        }
         Ok(())
     }
 
-    fn use_database(&mut self, db_name: &str) -> Result<()> {
-        self.execute_query(&format!("USE {}", db_name))?;
-        self.current_database = Some(db_name.to_string());
+    /// Write the cached schema metadata as JSON to a file, or stdout for `-`
+    fn export_metadata(&self, path: &str) -> Result<()> {
+        let meta = self
+            .metadata
+            .lock()
+            .map_err(|_| anyhow::anyhow!("Metadata is unavailable"))?;
 
-        // Update completion engine with current database
-        if let Some(helper) = self.editor.helper() {
-            helper.set_current_database(self.current_database.clone());
+        if path == "-" {
+            let stdout = io::stdout();
+            let mut out = stdout.lock();
+            serde_json::to_writer_pretty(&mut out, &*meta)?;
+            writeln!(out)?;
+        } else {
+            let file = std::fs::File::create(path)
+                .map_err(|e| anyhow::anyhow!("Failed to create file '{}': {}", path, e))?;
+            serde_json::to_writer_pretty(io::BufWriter::new(file), &*meta)?;
+            println!("Metadata exported to {}", path);
         }
+        Ok(())
+    }
+
+    fn show_variables(&self) {
+        let mut names: Vec<&String> = self.variables.keys().collect();
+        names.sort();
+        for name in names {
+            if is_secret_variable(name) {
+                println!(":{} = ********", name);
+            } else {
+                println!(":{} = {}", name, self.variables[name]);
+            }
+        }
+    }
 
-        println!("Database changed");
+    /// Execute every statement of a SQL file, stopping at the first error
+    fn source_file(&mut self, path: &str) -> Result<()> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("Failed to open file '{}': {}", path, e))?;
+        let (statements, delimiter) = statement::split_script(&content, &self.delimiter);
+        self.delimiter = delimiter;
+        for query in statements {
+            self.execute_script_statement(&query)?;
+        }
         Ok(())
     }
 
-    fn execute_query(&mut self, query: &str) -> Result<()> {
-        let trimmed_query = query.trim().to_uppercase();
+    /// Execute a statement read from a script, which `\g` and `\G` may split
+    /// further; `\G` prints its rows vertically
+    fn execute_script_statement(&mut self, query: &str) -> Result<()> {
+        for (query, terminator) in statement::split_terminators(query) {
+            self.query_executor
+                .set_vertical(terminator == Terminator::Vertical);
+            let result = self.execute_user_statement(&query);
+            self.query_executor.set_vertical(false);
+            result?;
+        }
+        Ok(())
+    }
 
-        // Check if this query might change database structure
-        let should_refresh_metadata = trimmed_query.starts_with("CREATE")
-            || trimmed_query.starts_with("DROP")
-            || trimmed_query.starts_with("ALTER")
-            || trimmed_query.starts_with("USE");
+    /// Substitute `:name` placeholders, prompting for variables that are not set yet
+    fn substitute_variables(&mut self, query: &str) -> Result<String> {
+        for name in statement::find_placeholders(query) {
+            if let Entry::Vacant(entry) = self.variables.entry(name) {
+                if self.batch || !io::stdin().is_terminal() {
+                    return Err(anyhow::anyhow!("unset variable :{}", entry.key()));
+                }
+                let value = prompt_variable(entry.key())?;
+                entry.insert(value);
+            }
+        }
+        Ok(statement::substitute_placeholders(query, |name| {
+            self.variables.get(name).cloned()
+        }))
+    }
 
-        let result = self.query_executor.execute(&mut self.connection, query);
+    /// Execute a statement typed or sourced by the user
+    fn execute_user_statement(&mut self, query: &str) -> Result<()> {
+        let mut query = self.substitute_variables(query)?;
+        if query.contains(pipe::IDS_PLACEHOLDER) {
+            let ids = self
+                .ids
+                .take()
+                .ok_or_else(|| anyhow::anyhow!("No ids collected; run \\useids <column> first"))?;
+            query = query.replace(pipe::IDS_PLACEHOLDER, &ids);
+        }
+        if let Some(db) = protect::protected_target(
+            &query,
+            self.session.database.as_deref(),
+            &self.protected_databases,
+        ) {
+            let prompt = format!("⚠ Protected database '{}'. Confirm: [yes/no]: ", db);
+            if prompt_line(&prompt)? != "yes" {
+                println!("Query cancelled.");
+                return Ok(());
+            }
+        }
+        self.remember_query(&query);
+        let Some(path) = self.xlsx_export.take() else {
+            return self.execute_query(&query);
+        };
 
-        // Refresh metadata if needed and query was successful
-        if result.is_ok() && should_refresh_metadata {
-            // Update database metadata
-            if let Ok(mut meta) = self.metadata.lock() {
-                let _ = meta.update_from_connection(self.connection.get_conn_mut());
+        // Only a result set produced by this statement is exported
+        self.query_executor.clear_last_result();
+        self.execute_query(&query)?;
+        match self.query_executor.last_result() {
+            Some(result) => {
+                let rows = xlsx::write(&result, &path)
+                    .map_err(|e| anyhow::anyhow!("Failed to write '{}': {}", path, e))?;
+                println!("Wrote {} rows to {}", rows, path);
             }
+            None => println!("No result set to export; {} was not written", path),
+        }
+        Ok(())
+    }
 
-            // Update current database if USE command was executed
-            if trimmed_query.starts_with("USE") {
-                if let Some(db_name) = query.split_whitespace().nth(1) {
-                    self.current_database = Some(db_name.trim_matches('`').to_string());
+    /// Prompt for a task template's parameters, show the statement and run it once confirmed
+    fn admin_task(&mut self, task: &str) -> Result<()> {
+        let template = self
+            .admin_templates
+            .iter()
+            .find(|template| template.name.eq_ignore_ascii_case(task))
+            .cloned()
+            .ok_or_else(|| {
+                anyhow::anyhow!("Unknown admin task '{}'. Type \\admin to list tasks.", task)
+            })?;
 
-                    // Update completion engine with current database
-                    if let Some(helper) = self.editor.helper() {
-                        helper.set_current_database(self.current_database.clone());
-                    }
-                }
+        let mut values = HashMap::new();
+        for parameter in template.parameters()? {
+            match &parameter.default {
+                Some(default) => print!("{} [{}]: ", parameter.name, default),
+                None => print!("{}: ", parameter.name),
+            }
+            io::stdout().flush()?;
+            let value = if parameter.kind == admin::ParameterKind::Secret {
+                rpassword::read_password()?
+            } else {
+                let mut value = String::new();
+                io::stdin().read_line(&mut value)?;
+                value.trim().to_string()
+            };
+            if !value.is_empty() {
+                values.insert(parameter.name, value);
             }
         }
 
-        result
+        self.sync_sql_mode();
+        let sql = template.fill(&values)?;
+        println!("{}", hist::scrub_passwords(&sql));
+        if !matches!(prompt_line("Execute? [y/N] ")?.as_str(), "y" | "yes") {
+            println!("Cancelled.");
+            return Ok(());
+        }
+        self.execute_query(&sql)
     }
-}
+
+    fn show_aliases(&self) {
+        if self.aliases.entries().is_empty() {
+            println!("No aliases defined.");
+            return;
+        }
+        for (name, expansion) in self.aliases.entries() {
+            println!("\\{} = {}", name, expansion);
+        }
+    }
+
+    fn show_help(&self) {
+        println!("General SQL help:");
+        println!("Note that all text commands must be first on line and end with ';'");
+        println!();
+        println!(
+            "\\alias [n = e]  List aliases or define alias \\n expanding to e (--save persists)."
+        );
+        println!("\\c (\\clear)     Clear the current input statement.");
+        println!(
+            "\\g / \\G / \\E   Send the statement; \\G prints rows vertically, \\E runs EXPLAIN."
+        );
+        println!("\\clear-cache    Flush cached SELECT results (\\set query_cache=1).");
+        println!(
+            "\\config show    Show the effective configuration and where each value comes from."
+        );
+        println!(
+            "\\clone-row <table> <where> [--first]  Put an INSERT copying a row at the prompt."
+        );
+        println!("\\admin [task]   List admin task templates, or fill one in and run it after confirmation.");
+        println!("\\alter-table <table>  Change columns and indexes from a menu, then review the ALTER TABLE.");
+        println!("\\create-table   Build a CREATE TABLE statement step by step.");
+        println!("\\charset-check  Warn when the session character sets don't agree.");
+        println!("\\check <table>  Check a table and report fragmentation.");
+        println!("\\d (\\databases) List databases.");
+        println!(
+            "\\d+ <table>     Describe a table with its indexes and foreign keys (\\describe+)."
+        );
+        println!("\\edit-row       Edit the single row of the last SELECT as a form; saves by primary key.");
+        println!(
+            "\\export-metadata <path>  Write cached schema metadata as JSON ('-' for stdout)."
+        );
+        println!("\\find <pattern> Search databases, tables and columns (--tables-only, --columns-only).");
+        println!(
+            "\\hist grep <p>  List history entries matching a substring or regex, newest first."
+        );
+        println!("\\hist run <n>   Put history entry n at the prompt for editing.");
+        println!("\\hist stats     Show the number of history entries and the history file size.");
+        println!(
+            "\\h (\\help)      Display this help; \\help <keyword> (or F1) explains a keyword."
+        );
+        println!("\\index-stats <table>  Show index cardinality and recent queries using them.");
+        println!("\\maxtime [ms]   Show or set a server-side time limit for SELECTs (0 disables).");
+        println!(
+            "\\marker on|off  Mark statement summaries with ✓/✗ ([OK]/[ERR] when not a terminal)."
+        );
+        println!("\\protect [db]   List or add databases where DELETE/UPDATE/DROP/TRUNCATE need a typed yes.");
+        println!("\\q (\\quit)      Quit mysql.");
+        println!("\\r (\\reconnect) Reconnect to the server.");
+        println!("\\s (\\status)    Get status information from the server.");
+        println!(
+            "\\pset <n> <v>   Change a setting, e.g. \\pset completion-limit 0 (0 = unlimited)."
+        );
+        println!("\\set [n=v]      Show settings or change a setting.");
+        println!("\\save-settings [file]  Save settings (default ~/.mysql_cli_settings.toml).");
+        println!("\\load-settings [file]  Load and apply saved settings.");
+        println!(
+            "\\set prompt='\\r \\d> '  Custom prompt: \\d database, \\r ro/rw, \\v server version."
+        );
+        println!("\\show columns <table>  Show columns with collation, privileges and comments.");
+        println!("\\sqlmode [+F|-F] Show sql_mode, or add/remove flags, e.g. \\sqlmode -ONLY_FULL_GROUP_BY.");
+        println!("\\source <file>  Execute an SQL script file (also \\. <file>).");
+        println!("\\export-xlsx <path>  Write the next query's result to an Excel workbook.");
+        println!("\\import-csv <path> <table> [--skip-rows=N] [--delimiter=C]  Load a CSV file with a header row into a table.");
+        println!("\\import-json <path> <table>  Load a JSON array of objects into a table.");
+        println!("\\record <file> [--all] | off  Append executed statements (--all: and commands) to a file.");
+        println!("\\replay <file> [--delay ms] [--step] [--force]  Run a recorded file, echoing each statement.");
+        println!(
+            "\\innodb-status [--json]  Summarize SHOW ENGINE INNODB STATUS, or print it as JSON."
+        );
+        println!("\\tail-generallog [--type=T] <path>  Follow a general query log, e.g. --type=Query (q stops).");
+        println!("\\tail-slowlog <path>  Follow a slow query log, slow queries in red (q stops).");
+        println!("\\t (\\tables)    List tables in current database.");
+        println!("\\u <db> (\\use)  Use database <db>.");
+        println!("\\unalias <n>    Remove alias \\n.");
+        println!("\\useids <col>   Collect a column of the last result for ${{ids}} in the next statement.");
+        println!("\\v (\\vertical)  Show the last result vertically again (also Alt-V at an empty prompt).");
+        println!("\\var [n=v]      List variables or set :n, used as a placeholder in statements.");
+        println!();
+        println!("Tab completion: \\set completion_type=list shows all matches at once (default);");
+        println!("\\set completion_type=circular cycles through them with repeated Tab presses.");
+        println!();
+    }
+
+    fn show_status(&mut self) -> Result<()> {
+        println!("--------------");
+        println!("Connection id:\t\t{}", self.connection.connection_id());
+        println!(
+            "Current database:\t{}",
+            self.session.database.as_deref().unwrap_or("(none)")
+        );
+        println!("Server version:\t\t{}", self.connection.server_version());
+        println!("Delimiter:\t\t{}", self.delimiter);
+        self.read_only.refresh(&mut self.connection);
+        if let Some(mode) = self.read_only.mode() {
+            println!("Server mode:\t\t{}", mode.describe());
+        }
+        if let Some(id) = self.query_executor.last_insert_id() {
+            println!("Last insert id:\t\t{}", id);
+        }
+        if let Some(limit) = self.query_executor.sql_select_limit(&mut self.connection) {
+            println!("sql_select_limit:\t{}", limit);
+        }
+        if let Ok(mode) = self.connection.session_variable("sql_mode") {
+            println!("sql_mode:\t\t{}", mode);
+        }
+        if let Ok(collation) = self.connection.session_variable("collation_connection") {
+            println!("Conn. collation:\t{}", collation);
+        }
+        println!(
+            "Compression:\t\t{}",
+            if self.connection.compressed() {
+                "requested"
+            } else {
+                "off"
+            }
+        );
+        if let Ok((received, sent)) = self.connection.traffic() {
+            // Counted by the server on the wire, so compressed when compression is on
+            println!(
+                "Traffic:\t\t{} sent to the server, {} received",
+                check::format_bytes(received),
+                check::format_bytes(sent)
+            );
+        }
+        if self.session.was_restored() {
+            println!(
+                "Session state:\t\trestored after reconnect ({} setting(s))",
+                self.session.setting_count()
+            );
+        }
+        println!("--------------");
+        Ok(())
+    }
+
+    /// Summarize `SHOW ENGINE INNODB STATUS`, or print its parsed sections as JSON
+    fn innodb_status(&mut self, json: bool) -> Result<()> {
+        let result = self
+            .connection
+            .execute_query("SHOW ENGINE INNODB STATUS")?
+            .into_result_set()?;
+        let text = result
+            .rows
+            .first()
+            .and_then(|row| row.get(2))
+            .ok_or_else(|| anyhow::anyhow!("SHOW ENGINE INNODB STATUS returned no status"))?;
+        let status = innodb_status::parse_innodb_status(text);
+        if json {
+            println!("{}", serde_json::to_string_pretty(&status)?);
+        } else {
+            println!("{}", innodb_status::format_summary(&status));
+        }
+        Ok(())
+    }
+
+    /// Let completion append the delimiter in effect, unless turned off
+    fn sync_statement_terminator(&self) {
+        if let Some(helper) = self.editor.helper() {
+            helper.set_statement_terminator(
+                self.settings
+                    .completion_semicolon
+                    .then(|| self.delimiter.clone()),
+            );
+        }
+    }
+
+    /// Push settings into the line editor, completer and query executor
+    fn apply_settings(&mut self) {
+        self.editor
+            .set_completion_type(self.settings.completion_type);
+        if let Some(helper) = self.editor.helper() {
+            helper.set_status_bar(self.settings.completion_status_bar);
+            helper.set_completion_limit(self.settings.completion_limit);
+        }
+        if self.vertical_key != Some(self.settings.vertical_key) {
+            self.bind_vertical_key();
+        }
+        self.query_executor.set_marker(self.settings.marker);
+        self.query_executor
+            .set_raw_single_column(self.settings.raw_single_column);
+        self.query_executor
+            .set_explain_slow_ms(self.settings.explain_slow_ms);
+        self.query_executor
+            .set_auto_vertical(self.settings.auto_vertical_output);
+        self.query_executor
+            .set_wide_result_columns(self.settings.wide_result_columns);
+        self.query_executor
+            .set_column_names(self.settings.column_names);
+        self.query_executor.set_raw(self.settings.raw);
+        self.connection.set_compress(self.settings.compress);
+        self.query_executor.set_result_cache(
+            self.settings
+                .query_cache
+                .then(|| Duration::from_secs(self.settings.query_cache_ttl)),
+        );
+    }
+
+    /// Apply a settings file, warning about entries that don't apply
+    fn load_settings(&mut self, path: &Path) -> Result<()> {
+        let applied = self.settings.load(path)?;
+        for warning in applied.warnings {
+            println!("Warning: {}: {}", path.display(), warning);
+        }
+        for name in applied.names {
+            self.provenance
+                .record(&name, config::Source::File(path.to_path_buf()));
+        }
+        self.apply_settings();
+        Ok(())
+    }
+
+    /// Offer to reconnect when a setting changed compression for the next connection
+    fn offer_reconnect(&mut self) -> Result<()> {
+        if !self.connection.compress_pending() {
+            return Ok(());
+        }
+        let answer =
+            prompt_line("Compression changes on the next connection. Reconnect now? [y/N] ")?;
+        if matches!(answer.as_str(), "y" | "yes") {
+            self.reconnect()?;
+        } else {
+            println!(
+                "Use \\reconnect to apply compress={}.",
+                u8::from(self.settings.compress)
+            );
+        }
+        Ok(())
+    }
+
+    /// The path given to `\save-settings` / `\load-settings`, or the settings file
+    fn settings_file_argument(&self, argument: &str) -> Result<PathBuf> {
+        match argument.trim() {
+            "" => self
+                .settings_file
+                .clone()
+                .ok_or_else(|| anyhow::anyhow!("Cannot determine home directory")),
+            path => Ok(PathBuf::from(path)),
+        }
+    }
+
+    /// Settings, aliases, admin templates and the pager with where each value comes from
+    fn effective_config(&self) -> String {
+        let config_file =
+            || config::config_path().map_or(config::Source::Default, config::Source::File);
+        let aliases = self
+            .aliases
+            .entries()
+            .iter()
+            .map(|(name, expansion)| {
+                let source = if self.user_config.aliases.get(name) == Some(expansion) {
+                    config_file()
+                } else {
+                    config::Source::Session
+                };
+                (name.clone(), expansion.clone(), source)
+            })
+            .collect();
+        let admin = self
+            .admin_templates
+            .iter()
+            .map(|template| {
+                let source = if self.user_config.admin.contains_key(&template.name) {
+                    config_file()
+                } else {
+                    config::Source::Default
+                };
+                (template.name.clone(), template.statement.clone(), source)
+            })
+            .collect();
+        let (pager, pager_source) = pager::pager_command();
+        config::format_effective(&[
+            (
+                "settings",
+                self.provenance.annotate(self.settings.entries()),
+            ),
+            ("aliases", aliases),
+            ("admin", admin),
+            (
+                "environment",
+                vec![("pager".to_string(), pager, pager_source)],
+            ),
+        ])
+    }
+
+    fn show_settings(&self) {
+        for (name, value) in self.settings.entries() {
+            println!("{} = {}", name, value);
+        }
+    }
+
+    /// Edit the single row of the last result in a form and UPDATE it by primary key
+    fn edit_row(&mut self) -> Result<()> {
+        self.sync_sql_mode();
+        let result = self
+            .query_executor
+            .last_result()
+            .ok_or_else(|| anyhow::anyhow!("No result to edit; run SELECT ... LIMIT 1 first"))?;
+        let [row] = result.rows.as_slice() else {
+            return Err(anyhow::anyhow!(
+                "\\edit-row needs a result with exactly one row, the last one has {}",
+                result.rows.len()
+            ));
+        };
+        let (database, table) = self
+            .last_queries
+            .back()
+            .and_then(|query| edit_row::source_table(query))
+            .ok_or_else(|| {
+                anyhow::anyhow!("\\edit-row needs a result from a single-table SELECT")
+            })?;
+        let database = database
+            .or_else(|| self.session.database.clone())
+            .ok_or_else(|| anyhow::anyhow!("No database selected"))?;
+
+        // Reload the table's metadata so the primary key is current
+        let table_key = format!("{}.{}", database, table).to_lowercase();
+        let (primary_key, table_columns) = match self.metadata.lock() {
+            Ok(mut meta) => {
+                meta.refresh_tables(
+                    self.connection.get_conn_mut(),
+                    &database,
+                    std::slice::from_ref(&table),
+                )?;
+                let primary_key = meta
+                    .get_indexes(&table_key)
+                    .iter()
+                    .find(|index| index.name == "PRIMARY")
+                    .map(|index| index.columns.clone());
+                (primary_key, meta.get_columns(&table_key).cloned())
+            }
+            Err(_) => (None, None),
+        };
+        let primary_key = primary_key.ok_or_else(|| {
+            anyhow::anyhow!("Table `{}`.`{}` has no primary key", database, table)
+        })?;
+
+        let mut key = Vec::new();
+        for column in &primary_key {
+            let index = result
+                .columns
+                .iter()
+                .position(|c| c.eq_ignore_ascii_case(column))
+                .ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "The result does not include primary key column '{}'",
+                        column
+                    )
+                })?;
+            key.push((column.clone(), row[index].clone()));
+        }
+
+        // Only real table columns can be edited; expressions and aliases are left out
+        let (columns, values): (Vec<String>, Vec<String>) = result
+            .columns
+            .iter()
+            .zip(row)
+            .filter(|(column, _)| {
+                table_columns
+                    .as_ref()
+                    .is_none_or(|known| known.iter().any(|c| c.eq_ignore_ascii_case(column)))
+            })
+            .map(|(column, value)| (column.clone(), value.clone()))
+            .unzip();
+        if columns.is_empty() {
+            return Err(anyhow::anyhow!("The result has no columns of `{}`", table));
+        }
+        let mut form = edit_row::RowForm::new(&columns, &values);
+
+        let mut editor: Editor<(), DefaultHistory> = Editor::new()?;
+        let previous = Arc::new(AtomicBool::new(false));
+        editor.bind_sequence(
+            KeyEvent(KeyCode::Tab, Modifiers::NONE),
+            EventHandler::Simple(Cmd::AcceptLine),
+        );
+        editor.bind_sequence(
+            KeyEvent(KeyCode::BackTab, Modifiers::NONE),
+            EventHandler::Conditional(Box::new(edit_row::PreviousFieldHandler::new(
+                previous.clone(),
+            ))),
+        );
+        println!("Editing `{}`.`{}`; Tab or Enter moves to the next field, Shift-Tab back, Ctrl-C cancels. Type NULL for NULL.", database, table);
+
+        loop {
+            print!("{}", form.render());
+            let field = form.current().clone();
+            let prompt = format!("{}: ", field.column);
+            let value = match editor.readline_with_initial(&prompt, (&field.value, "")) {
+                Ok(value) => value,
+                Err(ReadlineError::Interrupted) | Err(ReadlineError::Eof) => {
+                    println!("Cancelled.");
+                    return Ok(());
+                }
+                Err(err) => return Err(err.into()),
+            };
+            form.set_value(&value);
+
+            if previous.swap(false, Ordering::SeqCst) {
+                form.move_previous();
+                continue;
+            }
+            if form.move_next() {
+                continue;
+            }
+
+            let changes = form.changes();
+            if changes.is_empty() {
+                println!("No changes.");
+                return Ok(());
+            }
+            let update = edit_row::update_statement(&database, &table, &changes, &key);
+            println!("{}", update);
+            match prompt_line("Save? [y]es, [e]dit again, [n]o: ")?.as_str() {
+                "y" | "yes" => return self.execute_query(&update),
+                "e" | "edit" => form.restart(),
+                _ => {
+                    println!("Cancelled.");
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    /// Add a typed line to the line history, subject to the history_* settings;
+    /// `statement` holds the earlier lines of the statement it continues
+    fn add_history(&mut self, line: &str, statement: &str) -> Result<()> {
+        let filter = hist::HistoryFilter {
+            ignore_dups: self.settings.history_ignore_dups,
+            ignore_space: self.settings.history_ignore_space,
+            scrub_passwords: self.settings.history_scrub_passwords,
+        };
+        let history = self.editor.history();
+        let previous = match history.len() {
+            0 => None,
+            len => history
+                .get(len - 1, SearchDirection::Forward)?
+                .map(|result| result.entry.into_owned()),
+        };
+        if let Some(entry) = filter.entry(line, statement, previous.as_deref()) {
+            self.editor.add_history_entry(entry)?;
+        }
+        Ok(())
+    }
+
+    /// Search the line history or bring an entry back to the prompt
+    fn history_command(&mut self, command: hist::HistCommand) -> Result<()> {
+        let history = self.editor.history();
+        let mut entries = Vec::with_capacity(history.len());
+        for index in 0..history.len() {
+            if let Some(result) = history.get(index, SearchDirection::Forward)? {
+                entries.push(result.entry.into_owned());
+            }
+        }
+
+        match command {
+            hist::HistCommand::Grep(pattern) => {
+                let matches = hist::grep(&entries, &pattern);
+                if matches.is_empty() {
+                    println!("No history entries match '{}'.", pattern);
+                    return Ok(());
+                }
+                // rustyline's history keeps no timestamps, so only numbers are shown
+                let listing: String = matches
+                    .iter()
+                    .map(|(number, entry)| format!("{:>5}  {}\n", number, entry))
+                    .collect();
+                pager::page(&listing);
+            }
+            hist::HistCommand::Run(number) => {
+                self.pending_input = Some(hist::resolve(&entries, number)?.to_string());
+            }
+            hist::HistCommand::Stats => {
+                println!("Entries:\t{} (max {})", entries.len(), self.history_size);
+                match &self.history_file {
+                    Some(path) if path.exists() => {
+                        let (count, bytes) = hist::file_stats(path)?;
+                        println!(
+                            "File:\t\t{} ({} entries, {})",
+                            path.display(),
+                            count,
+                            check::format_bytes(bytes)
+                        );
+                    }
+                    Some(path) => println!("File:\t\t{} (not written yet)", path.display()),
+                    None => println!("File:\t\tnone, history isn't saved"),
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Load a CSV file into a table with batched INSERTs, showing progress
+    fn import_csv(&mut self, options: &import_csv::ImportOptions) -> Result<()> {
+        self.sync_sql_mode();
+        let (database, table) =
+            check::resolve_table_name(&options.table, self.session.database.as_deref())?;
+        let open = || {
+            csv::ReaderBuilder::new()
+                .delimiter(options.delimiter)
+                .from_path(&options.path)
+                .map_err(|e| anyhow::anyhow!("Failed to open file '{}': {}", options.path, e))
+        };
+
+        let table_columns = self.import_columns(&database, &table)?;
+        let names: Vec<String> = table_columns.iter().map(|(name, _)| name.clone()).collect();
+
+        let mut reader = open()?;
+        let header: Vec<String> = reader.headers()?.iter().map(str::to_string).collect();
+        let columns = import_csv::match_columns(&header, &names)?;
+        let nullable: Vec<bool> = columns
+            .iter()
+            .map(|column| {
+                table_columns
+                    .iter()
+                    .any(|(name, info)| name == column && info.nullable)
+            })
+            .collect();
+
+        // A first pass counts the rows for the progress line
+        let total = open()?.records().count().saturating_sub(options.skip_rows);
+
+        let mut imported = 0;
+        let mut batch = Vec::with_capacity(import_csv::BATCH_SIZE);
+        let mut records = reader.records().skip(options.skip_rows).peekable();
+        while let Some(record) = records.next() {
+            let record = record?;
+            if record.len() != columns.len() {
+                return Err(anyhow::anyhow!(
+                    "Line {} has {} fields, the header has {} ({} rows imported)",
+                    record.position().map_or(0, |p| p.line()),
+                    record.len(),
+                    columns.len(),
+                    imported
+                ));
+            }
+            batch.push(record.iter().map(str::to_string).collect());
+            if batch.len() == import_csv::BATCH_SIZE || records.peek().is_none() {
+                let insert =
+                    import_csv::insert_statement(&database, &table, &columns, &nullable, &batch);
+                self.import_batch(&insert, batch.len(), &mut imported, total)?;
+                batch.clear();
+            }
+        }
+        self.finish_import(imported, &database, &table);
+        Ok(())
+    }
+
+    /// Load a JSON array of objects into a table with batched INSERTs, showing progress
+    fn import_json(&mut self, args: &str) -> Result<()> {
+        self.sync_sql_mode();
+        let (path, table) = args
+            .split_once(char::is_whitespace)
+            .map(|(path, table)| (path.trim(), table.trim()))
+            .filter(|(_, table)| !table.is_empty())
+            .ok_or_else(|| anyhow::anyhow!("Usage: \\import-json <path> <table>"))?;
+        let (database, table) = check::resolve_table_name(table, self.session.database.as_deref())?;
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("Failed to open file '{}': {}", path, e))?;
+        let objects = import_json::read_objects(&text)?;
+
+        let table_columns = self.import_columns(&database, &table)?;
+        let is_json = |key: &str| {
+            table_columns
+                .iter()
+                .any(|(name, info)| name.eq_ignore_ascii_case(key) && info.is_json())
+        };
+        let rows: Vec<Vec<(String, serde_json::Value)>> = objects
+            .iter()
+            .map(|object| import_json::flatten_object(object, &is_json))
+            .collect();
+
+        // Keys without a column are skipped only when the user agrees
+        let names: Vec<String> = table_columns.iter().map(|(name, _)| name.clone()).collect();
+        let (columns, unknown) = import_json::match_keys(&rows, &names);
+        for key in &unknown {
+            let answer = prompt_line(&format!(
+                "Column '{}' not found in table. Skip? [Y/n]: ",
+                key
+            ))?;
+            if answer == "n" || answer == "no" {
+                println!("Import cancelled.");
+                return Ok(());
+            }
+        }
+        if columns.is_empty() {
+            return Err(anyhow::anyhow!(
+                "No keys match columns of `{}`.`{}`",
+                database,
+                table
+            ));
+        }
+
+        let mut imported = 0;
+        for batch in rows.chunks(import_csv::BATCH_SIZE) {
+            let literals: Vec<Vec<String>> = batch
+                .iter()
+                .map(|row| import_json::row_literals(row, &columns))
+                .collect();
+            let insert = import_csv::insert_literals(&database, &table, &columns, &literals);
+            self.import_batch(&insert, batch.len(), &mut imported, rows.len())?;
+        }
+        self.finish_import(imported, &database, &table);
+        Ok(())
+    }
+
+    /// Columns of an import's target table, reloaded so they match its current definition
+    fn import_columns(&mut self, database: &str, table: &str) -> Result<Vec<(String, ColumnInfo)>> {
+        let table_key = format!("{}.{}", database, table).to_lowercase();
+        let columns = match self.metadata.lock() {
+            Ok(mut meta) => {
+                meta.refresh_tables(
+                    self.connection.get_conn_mut(),
+                    database,
+                    &[table.to_string()],
+                )?;
+                let columns = meta.get_columns(&table_key).cloned().unwrap_or_default();
+                columns
+                    .into_iter()
+                    .map(|column| {
+                        let info = meta.get_column_info(&table_key, &column).cloned();
+                        let info = info.unwrap_or_default();
+                        (column, info)
+                    })
+                    .collect()
+            }
+            Err(_) => Vec::new(),
+        };
+        if columns.is_empty() {
+            return Err(anyhow::anyhow!(
+                "Table `{}`.`{}` doesn't exist",
+                database,
+                table
+            ));
+        }
+        Ok(columns)
+    }
+
+    /// Run one INSERT of an import and update the progress line
+    fn import_batch(
+        &mut self,
+        insert: &str,
+        rows: usize,
+        imported: &mut usize,
+        total: usize,
+    ) -> Result<()> {
+        if let Err(e) = self.connection.execute_query(insert) {
+            println!();
+            return Err(anyhow::anyhow!("{} ({} rows imported)", e, imported));
+        }
+        *imported += rows;
+        print!("\r{}", import_csv::progress_line(*imported, total));
+        io::stdout().flush()?;
+        Ok(())
+    }
+
+    fn finish_import(&mut self, imported: usize, database: &str, table: &str) {
+        if imported > 0 {
+            println!();
+        }
+        self.query_executor.clear_cache();
+        println!("Imported {} rows into `{}`.`{}`", imported, database, table);
+    }
+
+    /// Show columns, indexes and foreign keys of a table as separate sections
+    fn describe_table(&mut self, table: &str) -> Result<()> {
+        let (schema, table) = check::resolve_table_name(table, self.session.database.as_deref())?;
+
+        let columns = self
+            .connection
+            .execute_query(&format!("DESCRIBE `{}`.`{}`", schema, table))?
+            .into_result_set()?;
+        let indexes = self
+            .connection
+            .execute_query(&format!("SHOW INDEX FROM `{}`.`{}`", schema, table))?
+            .into_result_set()?;
+
+        // Reload this table's cached metadata so the foreign keys are current
+        let foreign_keys = match self.metadata.lock() {
+            Ok(mut meta) => {
+                let _ = meta.refresh_tables(
+                    self.connection.get_conn_mut(),
+                    &schema,
+                    std::slice::from_ref(&table),
+                );
+                meta.get_foreign_keys(&format!("{}.{}", schema, table).to_lowercase())
+                    .to_vec()
+            }
+            Err(_) => Vec::new(),
+        };
+
+        println!("Columns");
+        self.query_executor.display_results(&columns);
+
+        println!();
+        println!("Indexes");
+        if indexes.rows.is_empty() {
+            println!("(none)");
+        } else {
+            let wanted = [
+                "Key_name",
+                "Seq_in_index",
+                "Column_name",
+                "Non_unique",
+                "Cardinality",
+                "Index_type",
+            ];
+            let positions: Vec<usize> = wanted
+                .iter()
+                .filter_map(|name| indexes.columns.iter().position(|c| c == name))
+                .collect();
+            self.query_executor.display_results(&QueryResult {
+                tables: Vec::new(),
+                nulls: Vec::new(),
+                columns: positions
+                    .iter()
+                    .map(|&i| indexes.columns[i].clone())
+                    .collect(),
+                rows: indexes
+                    .rows
+                    .iter()
+                    .map(|row| positions.iter().map(|&i| row[i].clone()).collect())
+                    .collect(),
+            });
+        }
+
+        println!();
+        println!("Foreign keys");
+        if foreign_keys.is_empty() {
+            println!("(none)");
+        } else {
+            self.query_executor.display_results(&QueryResult {
+                tables: Vec::new(),
+                nulls: Vec::new(),
+                columns: vec![
+                    "Name".to_string(),
+                    "Columns".to_string(),
+                    "References".to_string(),
+                ],
+                rows: foreign_keys
+                    .iter()
+                    .map(|fk| {
+                        vec![
+                            fk.name.clone(),
+                            fk.columns.join(", "),
+                            format!(
+                                "{} ({})",
+                                fk.referenced_table,
+                                fk.referenced_columns.join(", ")
+                            ),
+                        ]
+                    })
+                    .collect(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Show every column detail, including collation, privileges and comment
+    fn show_full_columns(&mut self, table: &str) -> Result<()> {
+        let (schema, table) = check::resolve_table_name(table, self.session.database.as_deref())?;
+
+        let columns = self
+            .connection
+            .execute_query(&format!(
+                "SHOW FULL COLUMNS FROM {}.{}",
+                literal::quote_identifier(&schema),
+                literal::quote_identifier(&table)
+            ))?
+            .into_result_set()?;
+        self.query_executor.display_results(&columns);
+        Ok(())
+    }
+
+    fn check_table(&mut self, table: &str) -> Result<()> {
+        let (schema, table) = check::resolve_table_name(table, self.session.database.as_deref())?;
+
+        self.execute_query(&format!("CHECK TABLE `{}`.`{}`", schema, table))?;
+
+        let before = check::fetch_table_size(&mut self.connection, &schema, &table)?;
+        let fragmentation = before.fragmentation_percent();
+        println!(
+            "Fragmentation: {:.1}% ({} free of {})",
+            fragmentation,
+            check::format_bytes(before.data_free),
+            check::format_bytes(before.total())
+        );
+
+        if fragmentation <= self.settings.fragmentation_threshold {
+            return Ok(());
+        }
+
+        let optimize = format!("OPTIMIZE TABLE `{}`.`{}`", schema, table);
+        println!(
+            "Fragmentation exceeds {}%. Suggested: {}",
+            self.settings.fragmentation_threshold, optimize
+        );
+        if !matches!(prompt_line("Run it now? [y/N]: ")?.as_str(), "y" | "yes") {
+            return Ok(());
+        }
+
+        self.execute_query(&optimize)?;
+
+        let after = check::fetch_table_size(&mut self.connection, &schema, &table)?;
+        println!(
+            "Size before: {} ({:.1}% free)",
+            check::format_bytes(before.total()),
+            fragmentation
+        );
+        println!(
+            "Size after:  {} ({:.1}% free)",
+            check::format_bytes(after.total()),
+            after.fragmentation_percent()
+        );
+        Ok(())
+    }
+
+    /// Run the `\create-table` wizard and execute the statement once confirmed
+    fn create_table_wizard(&mut self) -> Result<()> {
+        self.sync_sql_mode();
+        let mut editor: Editor<wizard::WizardHelper, DefaultHistory> = Editor::new()?;
+        editor.set_helper(Some(wizard::WizardHelper::default()));
+        println!("Creating a table; \\c cancels at any prompt.");
+
+        let mut table_wizard = wizard::TableWizard::new();
+        while table_wizard.is_active() {
+            if table_wizard.step() == wizard::Step::Confirm {
+                println!("{}", table_wizard.create_statement());
+            }
+            if let Some(helper) = editor.helper_mut() {
+                helper.complete_types = table_wizard.step() == wizard::Step::ColumnType;
+            }
+
+            let answer = match editor.readline(&table_wizard.prompt()) {
+                Ok(answer) => answer,
+                Err(ReadlineError::Interrupted) | Err(ReadlineError::Eof) => {
+                    println!("Cancelled.");
+                    return Ok(());
+                }
+                Err(err) => return Err(err.into()),
+            };
+            if let Err(err) = table_wizard.input(&answer) {
+                println!("{}", err);
+            }
+        }
+
+        if table_wizard.step() == wizard::Step::Done {
+            self.execute_query(&table_wizard.create_statement())
+        } else {
+            println!("Cancelled.");
+            Ok(())
+        }
+    }
+
+    /// Run the `\alter-table` wizard on an existing table and execute the statement once confirmed
+    fn alter_table_wizard(&mut self, table: &str) -> Result<()> {
+        self.sync_sql_mode();
+        let (schema, table) = check::resolve_table_name(table, self.session.database.as_deref())?;
+        let columns = self
+            .connection
+            .execute_query(&format!("SHOW FULL COLUMNS FROM `{}`.`{}`", schema, table))?
+            .into_result_set()?;
+        let indexes = self
+            .connection
+            .execute_query(&format!("SHOW INDEX FROM `{}`.`{}`", schema, table))?
+            .into_result_set()?;
+        let generated = self
+            .connection
+            .execute_query(&format!(
+                "SELECT COLUMN_NAME, GENERATION_EXPRESSION, EXTRA FROM information_schema.COLUMNS \
+                 WHERE TABLE_SCHEMA = {} AND TABLE_NAME = {} AND EXTRA LIKE '%GENERATED'",
+                literal::quote_string(&schema),
+                literal::quote_string(&table)
+            ))?
+            .into_result_set()?;
+        let mut structure = wizard::TableStructure::from_results(&columns, &indexes)?;
+        structure.set_generation_expressions(&generated)?;
+
+        println!("Current structure of `{}`.`{}`:", schema, table);
+        for line in structure.lines() {
+            println!("  {}", line);
+        }
+        println!("\\c cancels at any prompt.");
+
+        let mut editor: Editor<wizard::WizardHelper, DefaultHistory> = Editor::new()?;
+        editor.set_helper(Some(wizard::WizardHelper::default()));
+
+        let mut alter_wizard = wizard::AlterWizard::new(&schema, &table, structure);
+        while alter_wizard.is_active() {
+            if alter_wizard.step() == wizard::AlterStep::Confirm {
+                println!("Changes:");
+                for line in alter_wizard.diff() {
+                    println!("  {}", line);
+                }
+                println!("{}", alter_wizard.alter_statement());
+            }
+            if let Some(helper) = editor.helper_mut() {
+                helper.complete_types = alter_wizard.step() == wizard::AlterStep::ColumnType;
+            }
+
+            let prompt = alter_wizard.prompt();
+            let answer = match alter_wizard.initial_input() {
+                Some(initial) => editor.readline_with_initial(&prompt, (&initial, "")),
+                None => editor.readline(&prompt),
+            };
+            let answer = match answer {
+                Ok(answer) => answer,
+                Err(ReadlineError::Interrupted) | Err(ReadlineError::Eof) => {
+                    println!("Cancelled.");
+                    return Ok(());
+                }
+                Err(err) => return Err(err.into()),
+            };
+            if let Err(err) = alter_wizard.input(&answer) {
+                println!("{}", err);
+            }
+        }
+
+        if alter_wizard.step() == wizard::AlterStep::Done {
+            self.execute_query(&alter_wizard.alter_statement())
+        } else {
+            println!("Cancelled.");
+            Ok(())
+        }
+    }
+
+    fn show_index_stats(&mut self, table: &str) -> Result<()> {
+        let (schema, table) = check::resolve_table_name(table, self.session.database.as_deref())?;
+        let (stats, total_rows) =
+            index_stats::fetch_index_stats(&mut self.connection, &schema, &table)?;
+
+        if stats.is_empty() {
+            println!("Table `{}`.`{}` has no indexes", schema, table);
+            return Ok(());
+        }
+
+        println!(
+            "Index cardinality for `{}`.`{}` (~{} rows):",
+            schema, table, total_rows
+        );
+        for stat in &stats {
+            let percent = stat.cardinality_percent(total_rows);
+            let level = index_stats::CardinalityLevel::from_percent(percent);
+            let line = format!(
+                "  {} ({}){}: {:.1}% ({})",
+                stat.name,
+                stat.columns.join(", "),
+                if stat.unique { " UNIQUE" } else { "" },
+                percent,
+                level.label()
+            );
+            if level == index_stats::CardinalityLevel::Low {
+                println!("\x1b[33m{}\x1b[0m", line);
+            } else {
+                println!("{}", line);
+            }
+        }
+
+        let indexed_columns: Vec<String> = stats
+            .iter()
+            .flat_map(|stat| stat.columns.iter().cloned())
+            .collect();
+        let table_lower = table.to_lowercase();
+        let matches: Vec<(&String, Vec<String>)> = self
+            .last_queries
+            .iter()
+            .filter(|query| query.to_lowercase().contains(&table_lower))
+            .map(|query| {
+                let columns = index_stats::where_columns_matching(query, &indexed_columns);
+                (query, columns)
+            })
+            .filter(|(_, columns)| !columns.is_empty())
+            .collect();
+
+        if !matches.is_empty() {
+            println!();
+            println!("Recent queries filtering on indexed columns:");
+            for (query, columns) in matches {
+                println!("  [{}] {}", columns.join(", "), query);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn remember_query(&mut self, query: &str) {
+        if self.last_queries.len() == MAX_LAST_QUERIES {
+            self.last_queries.pop_front();
+        }
+        self.last_queries.push_back(query.to_string());
+    }
+
+    /// Quote generated string literals for the session's current sql_mode
+    fn sync_sql_mode(&mut self) {
+        if let Ok(mode) = self.connection.session_variable("sql_mode") {
+            literal::set_sql_mode(&mode);
+        }
+    }
+
+    fn reconnect(&mut self) -> Result<()> {
+        self.connection.reconnect()?;
+        self.transaction.reset();
+        self.query_executor.reset_session_cache();
+        self.read_only.refresh(&mut self.connection);
+
+        // Temporary tables died with the old session; a different server's
+        // metadata is dropped and loaded again
+        if let Ok(mut meta) = self.metadata.lock() {
+            meta.clear_session_overlay();
+            if meta.bind_to(&self.connection.identity()) {
+                let _ = meta.update_from_connection(self.connection.get_conn_mut());
+            }
+        }
+
+        // Init commands, SET statements and the current database
+        if let Err(e) = self.session.replay(&mut self.connection) {
+            println!("Warning: could not fully restore the session: {}", e);
+            // Don't let the prompt and completion claim a database the server didn't select
+            let database = self.connection.current_database()?;
+            self.set_current_database(database);
+        }
+
+        println!("Connection id:    {}", self.connection.connection_id());
+        println!(
+            "Current database: {}",
+            self.session.database.as_deref().unwrap_or("*** NONE ***")
+        );
+        Ok(())
+    }
+
+    fn use_database(&mut self, db_name: &str) -> Result<()> {
+        self.execute_query(&format!("USE {}", db_name))?;
+        self.set_current_database(Some(db_name.to_string()));
+        if self.silent_level == 0 {
+            println!("Database changed");
+        }
+        Ok(())
+    }
+
+    /// Remember the selected database for the prompt and completion, spelled as the
+    /// server's `lower_case_table_names` compares it so it matches metadata keys
+    fn set_current_database(&mut self, database: Option<String>) {
+        self.session.database = database.map(|db| self.connection.normalize_database_name(&db));
+        if let Some(helper) = self.editor.helper() {
+            helper.set_current_database(self.session.database.clone());
+        }
+    }
+
+    /// Print a covering index hint when the query plan is a full table scan
+    fn advise_index(&mut self, query: &str) {
+        let Some(columns) = advisor::QueryColumns::from_sql(query) else {
+            return;
+        };
+        let Ok(plan) = self
+            .connection
+            .execute_query(&format!("EXPLAIN {}", query))
+            .and_then(ExecOutcome::into_result_set)
+        else {
+            return;
+        };
+
+        let full_scan = explain::parse_explain_output(&plan)
+            .iter()
+            .any(|row| row.issues().contains(&PlanIssue::FullScan));
+        if !full_scan {
+            return;
+        }
+
+        let Ok((schema, table)) =
+            check::resolve_table_name(&columns.table, self.session.database.as_deref())
+        else {
+            return;
+        };
+        let table_key = format!("{}.{}", schema, table).to_lowercase();
+        let recommendation = match self.metadata.lock() {
+            Ok(meta) => advisor::recommend_index(&columns, meta.get_indexes(&table_key)),
+            Err(_) => None,
+        };
+
+        if let Some(index_columns) = recommendation {
+            println!(
+                "Hint: Consider adding a covering index on ({}) for this query",
+                index_columns.join(", ")
+            );
+        }
+    }
+
+    /// Keep the completion overlay of session temporary tables in sync
+    fn apply_temporary_table_change(&mut self, change: TemporaryTableChange) {
+        match change {
+            TemporaryTableChange::Created {
+                database,
+                table,
+                columns,
+            } => {
+                let Some(database) = database.or_else(|| self.session.database.clone()) else {
+                    return;
+                };
+                let columns = match columns {
+                    Some(columns) => columns,
+                    None => self
+                        .connection
+                        .table_columns(&database, &table)
+                        .unwrap_or_default(),
+                };
+                if let Ok(mut meta) = self.metadata.lock() {
+                    meta.add_temporary_table(&database, &table, columns);
+                }
+            }
+            TemporaryTableChange::Dropped(tables) => {
+                if let Ok(mut meta) = self.metadata.lock() {
+                    for (database, table) in tables {
+                        if let Some(database) = database.or_else(|| self.session.database.clone()) {
+                            meta.remove_temporary_table(&database, &table);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Reload the metadata affected by a successful schema change, at most once per call
+    fn apply_schema_change(&mut self, change: SchemaChange) {
+        // Update current database if USE command was executed
+        if let Some(db_name) = change.use_database.clone() {
+            self.set_current_database(Some(db_name));
+        }
+
+        let mut by_database: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for (database, table) in change.tables {
+            if let Some(db) = database.or_else(|| self.session.database.clone()) {
+                by_database.entry(db).or_default().push(table);
+            }
+        }
+
+        if let Ok(mut meta) = self.metadata.lock() {
+            let conn = self.connection.get_conn_mut();
+            if change.reload_all {
+                meta.invalidate();
+                let _ = meta.update_from_connection(conn);
+                return;
+            }
+            if change.databases {
+                let _ = meta.refresh_databases(conn);
+            }
+            for (db, tables) in &by_database {
+                let _ = meta.refresh_tables(conn, db, tables);
+            }
+        }
+    }
+
+    fn execute_query(&mut self, query: &str) -> Result<()> {
+        let trimmed_query = query.trim().to_uppercase();
+
+        // Check if this query might change database structure
+        let schema_change = SchemaChange::classify(query);
+
+        let result = self.query_executor.execute(&mut self.connection, query);
+
+        if result.is_ok() {
+            if let Some(change) = TemporaryTableChange::parse(query) {
+                self.apply_temporary_table_change(change);
+            }
+            if self.settings.index_advice && trimmed_query.starts_with("SELECT") {
+                self.advise_index(query);
+            }
+            if let Some(change) = schema_change {
+                self.apply_schema_change(change);
+            }
+            self.session.record(query);
+        }
+
+        let succeeded = result.is_ok() && !self.query_executor.last_failed();
+        // Recorded first: a failing DDL statement has already committed
+        self.transaction.record(query, succeeded);
+        if !succeeded && self.rollback_on_error && self.transaction.is_open() {
+            self.transaction.reset();
+            match self.connection.execute_query("ROLLBACK") {
+                Ok(_) => println!("Transaction rolled back after the error (--rollback-on-error)."),
+                Err(e) => println!("Warning: ROLLBACK failed: {}", e),
+            }
+        }
+
+        result
+    }
+}
+
+/// Whether to print the welcome banner: `--banner` wins, otherwise it is
+/// skipped with `--skip-banner` and in batch mode (`--batch`, `-e`, non-TTY stdin)
+pub fn banner_enabled(force_banner: bool, skip_banner: bool, batch: bool) -> bool {
+    force_banner || !(skip_banner || batch)
+}
+
+/// Replace typographic quotes and non-breaking spaces picked up when pasting
+/// SQL from web pages with their ASCII equivalents, and drop BOM markers.
+/// Text inside string literals and quoted identifiers is kept as typed;
+/// `statement` holds the earlier lines of the statement the line continues
+pub fn sanitize_input(line: &str, statement: &str) -> String {
+    let joined = format!("{}\n{}", statement, line);
+    let code = statement::code_mask(&joined);
+    let start = statement.len() + 1;
+    line.char_indices()
+        .filter_map(|(i, c)| {
+            if !code[start + i] {
+                return Some(c);
+            }
+            match c {
+                '\u{feff}' => None,
+                '\u{201c}' | '\u{201d}' | '\u{201e}' | '\u{201f}' => Some('"'),
+                '\u{2018}' | '\u{2019}' | '\u{201a}' | '\u{201b}' => Some('\''),
+                '\u{00a0}' | '\u{2007}' | '\u{202f}' => Some(' '),
+                c => Some(c),
+            }
+        })
+        .collect()
+}
+
+/// Protected databases from the `protected_databases` option of the option files
+/// (comma-separated; the option may be repeated)
+fn load_protected_databases() -> Vec<String> {
+    let options = config::my_cnf_section("mysql-cli-rust").unwrap_or_else(|e| {
+        println!("Warning: ignoring option file: {}", e);
+        Vec::new()
+    });
+    options
+        .iter()
+        .filter(|(name, _)| name == "protected_databases")
+        .flat_map(|(_, value)| value.split(','))
+        .map(|db| db.trim().to_string())
+        .filter(|db| !db.is_empty())
+        .collect()
+}
+
+/// Ask a question on stdin, returning the trimmed, lower-cased answer. Without a
+/// terminal there is nobody to answer, so the caller's action fails instead of
+/// being skipped
+fn prompt_line(prompt: &str) -> Result<String> {
+    if !io::stdin().is_terminal() {
+        return Err(anyhow::anyhow!(
+            "confirmation required; stdin is not a terminal"
+        ));
+    }
+    print!("{}", prompt);
+    io::stdout().flush()?;
+
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+    Ok(answer.trim().to_lowercase())
+}
+
+/// Variables whose values should not be echoed
+fn is_secret_variable(name: &str) -> bool {
+    let name = name.to_lowercase();
+    name.contains("pass") || name.contains("secret")
+}
+
+/// Ask for the value of an unset `:name` placeholder
+fn prompt_variable(name: &str) -> Result<String> {
+    print!("Enter value for :{}: ", name);
+    io::stdout().flush()?;
+
+    if is_secret_variable(name) {
+        return Ok(rpassword::read_password()?);
+    }
+
+    let mut value = String::new();
+    io::stdin().read_line(&mut value)?;
+    Ok(value.trim_end_matches(['\r', '\n']).to_string())
+}
+
+#[cfg(test)]
+#[path = "./cli_tests.rs"]
+mod cli_tests;
@@ -0,0 +1,42 @@
+use super::*;
+
+#[test]
+fn test_banner_shown_interactively() {
+    assert!(banner_enabled(false, false, false));
+}
+
+#[test]
+fn test_banner_skipped_by_flag_or_batch_mode() {
+    assert!(!banner_enabled(false, true, false));
+    assert!(!banner_enabled(false, false, true));
+}
+
+#[test]
+fn test_banner_forced_in_batch_mode() {
+    assert!(banner_enabled(true, false, true));
+}
+
+#[test]
+fn test_sanitize_input_replaces_pasted_punctuation() {
+    assert_eq!(
+        sanitize_input(
+            "\u{feff}SELECT\u{00a0}\u{201c}a\u{201d}, \u{2018}b\u{2019}",
+            ""
+        ),
+        "SELECT \"a\", 'b'"
+    );
+    assert_eq!(sanitize_input("SELECT 'plain'", ""), "SELECT 'plain'");
+}
+
+#[test]
+fn test_sanitize_input_keeps_quoted_text() {
+    assert_eq!(
+        sanitize_input("SELECT 'It\u{2019}s', `a\u{00a0}b` FROM t", ""),
+        "SELECT 'It\u{2019}s', `a\u{00a0}b` FROM t"
+    );
+    // A literal opened on an earlier line of the statement
+    assert_eq!(
+        sanitize_input("It\u{2019}s' WHERE\u{00a0}1", "SELECT 'line one"),
+        "It\u{2019}s' WHERE 1"
+    );
+}
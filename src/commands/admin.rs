@@ -0,0 +1,188 @@
+/*!
+ * Admin task templates
+ *
+ * `\admin` lists parameterized statements for common administration tasks;
+ * `\admin <task>` fills one in from prompted values, quoting each value as an
+ * identifier, string literal or number, and runs it after confirmation.
+ * More templates can be added in the `[admin]` section of the config file.
+ */
+
+use super::literal::{quote_identifier, quote_string};
+use anyhow::{anyhow, Result};
+use regex::Regex;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::OnceLock;
+
+/// Built-in templates as (name, description, statement). Placeholders are
+/// `{name}` for a string literal, `{name:ident}` for an identifier,
+/// `{name:secret}` for a string read without echo and `{name:number}`;
+/// `{name=default}` gives a default value
+const CATALOG: &[(&str, &str, &str)] = &[
+    (
+        "add-user",
+        "Create a user with a password",
+        "CREATE USER {user}@{host=%} IDENTIFIED BY {password:secret}",
+    ),
+    (
+        "grant-read-only",
+        "Grant read-only access to a schema",
+        "GRANT SELECT, SHOW VIEW ON {schema:ident}.* TO {user}@{host=%}",
+    ),
+    (
+        "grant-all",
+        "Grant all privileges on a schema",
+        "GRANT ALL PRIVILEGES ON {schema:ident}.* TO {user}@{host=%}",
+    ),
+    (
+        "change-password",
+        "Change a user's password",
+        "ALTER USER {user}@{host=%} IDENTIFIED BY {password:secret}",
+    ),
+    (
+        "show-grants",
+        "Show the privileges of a user",
+        "SHOW GRANTS FOR {user}@{host=%}",
+    ),
+    ("drop-user", "Remove a user", "DROP USER {user}@{host=%}"),
+    (
+        "blocking-transactions",
+        "Find transactions blocking others",
+        "SELECT waiting_pid, waiting_query, blocking_pid, blocking_query, wait_age \
+         FROM sys.innodb_lock_waits ORDER BY wait_age DESC",
+    ),
+    (
+        "kill-query",
+        "Stop the statement a connection is running",
+        "KILL QUERY {connection_id:number}",
+    ),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ParameterKind {
+    String,
+    Identifier,
+    Secret,
+    Number,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Parameter {
+    pub name: String,
+    pub kind: ParameterKind,
+    pub default: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Template {
+    pub name: String,
+    pub description: String,
+    pub statement: String,
+}
+
+fn placeholder() -> &'static Regex {
+    static PLACEHOLDER: OnceLock<Regex> = OnceLock::new();
+    PLACEHOLDER.get_or_init(|| Regex::new(r"\{(\w+)(?::(\w+))?(?:=([^}]*))?\}").unwrap())
+}
+
+impl Template {
+    /// The template's parameters in order of first use
+    pub fn parameters(&self) -> Result<Vec<Parameter>> {
+        let mut parameters: Vec<Parameter> = Vec::new();
+        for captures in placeholder().captures_iter(&self.statement) {
+            let name = captures[1].to_string();
+            if parameters.iter().any(|p| p.name == name) {
+                continue;
+            }
+            let kind = match captures.get(2).map(|kind| kind.as_str()) {
+                None | Some("string") => ParameterKind::String,
+                Some("ident") => ParameterKind::Identifier,
+                Some("secret") => ParameterKind::Secret,
+                Some("number") => ParameterKind::Number,
+                Some(other) => {
+                    return Err(anyhow!(
+                        "Unknown parameter type '{}' in template '{}'",
+                        other,
+                        self.name
+                    ))
+                }
+            };
+            parameters.push(Parameter {
+                name,
+                kind,
+                default: captures.get(3).map(|d| d.as_str().to_string()),
+            });
+        }
+        Ok(parameters)
+    }
+
+    /// The statement with every placeholder replaced by its quoted value
+    pub fn fill(&self, values: &HashMap<String, String>) -> Result<String> {
+        let parameters = self.parameters()?;
+        let mut error = None;
+        let filled = placeholder().replace_all(&self.statement, |captures: &regex::Captures| {
+            let parameter = parameters.iter().find(|p| p.name == captures[1]).unwrap();
+            let value = values
+                .get(&parameter.name)
+                .filter(|value| !value.is_empty())
+                .or(parameter.default.as_ref())
+                .map(String::as_str);
+            match quote_value(parameter, value) {
+                Ok(quoted) => quoted,
+                Err(e) => {
+                    error.get_or_insert(e);
+                    String::new()
+                }
+            }
+        });
+        match error {
+            Some(e) => Err(e),
+            None => Ok(filled.into_owned()),
+        }
+    }
+}
+
+/// Quote a parameter's value; only a template default may supply an empty one
+fn quote_value(parameter: &Parameter, value: Option<&str>) -> Result<String> {
+    let Some(value) = value else {
+        return Err(anyhow!("{} is required", parameter.name));
+    };
+    match parameter.kind {
+        ParameterKind::String | ParameterKind::Secret => Ok(quote_string(value)),
+        ParameterKind::Identifier if value.is_empty() => {
+            Err(anyhow!("{} must not be empty", parameter.name))
+        }
+        ParameterKind::Identifier => Ok(quote_identifier(value)),
+        ParameterKind::Number => match value.parse::<u64>() {
+            Ok(number) => Ok(number.to_string()),
+            Err(_) => Err(anyhow!(
+                "{} must be a number, got '{}'",
+                parameter.name,
+                value
+            )),
+        },
+    }
+}
+
+/// Built-in templates followed by those from the config file; a config entry
+/// with the name of a built-in one replaces it
+pub fn templates(configured: &BTreeMap<String, String>) -> Vec<Template> {
+    let mut templates: Vec<Template> = CATALOG
+        .iter()
+        .filter(|(name, _, _)| !configured.contains_key(*name))
+        .map(|(name, description, statement)| Template {
+            name: name.to_string(),
+            description: description.to_string(),
+            statement: statement.to_string(),
+        })
+        .collect();
+    templates.extend(configured.iter().map(|(name, statement)| Template {
+        name: name.clone(),
+        description: "From config".to_string(),
+        statement: statement.clone(),
+    }));
+    templates
+}
+
+#[cfg(test)]
+#[path = "./admin_tests.rs"]
+mod admin_tests;
@@ -0,0 +1,120 @@
+use super::*;
+
+fn template(name: &str) -> Template {
+    templates(&BTreeMap::new())
+        .into_iter()
+        .find(|t| t.name == name)
+        .unwrap()
+}
+
+fn values(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+    pairs
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+#[test]
+fn test_parameters_in_order_with_defaults() {
+    let parameters = template("grant-read-only").parameters().unwrap();
+    let names: Vec<&str> = parameters.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, vec!["schema", "user", "host"]);
+    assert_eq!(parameters[0].kind, ParameterKind::Identifier);
+    assert_eq!(parameters[2].default.as_deref(), Some("%"));
+    assert_eq!(
+        template("add-user").parameters().unwrap()[2].kind,
+        ParameterKind::Secret
+    );
+}
+
+#[test]
+fn test_fill_quotes_identifiers_and_strings() {
+    assert_eq!(
+        template("grant-read-only")
+            .fill(&values(&[("schema", "sales`2024"), ("user", "o'brien")]))
+            .unwrap(),
+        "GRANT SELECT, SHOW VIEW ON `sales``2024`.* TO 'o''brien'@'%'"
+    );
+    assert_eq!(
+        template("change-password")
+            .fill(&values(&[
+                ("user", "app"),
+                ("host", "10.0.%"),
+                ("password", "p\\w'd")
+            ]))
+            .unwrap(),
+        "ALTER USER 'app'@'10.0.%' IDENTIFIED BY 'p\\\\w''d'"
+    );
+}
+
+#[test]
+fn test_fill_requires_strings_without_default() {
+    let error = template("drop-user")
+        .fill(&values(&[("user", "")]))
+        .unwrap_err();
+    assert_eq!(error.to_string(), "user is required");
+    assert!(template("add-user")
+        .fill(&values(&[("user", "app")]))
+        .is_err());
+    // An empty answer falls back to the default
+    assert_eq!(
+        template("drop-user")
+            .fill(&values(&[("user", "app"), ("host", "")]))
+            .unwrap(),
+        "DROP USER 'app'@'%'"
+    );
+}
+
+#[test]
+fn test_fill_password_without_backslash_escapes() {
+    crate::commands::literal::set_sql_mode("NO_BACKSLASH_ESCAPES");
+    assert_eq!(
+        template("change-password")
+            .fill(&values(&[("user", "app"), ("password", "p\\w")]))
+            .unwrap(),
+        "ALTER USER 'app'@'%' IDENTIFIED BY 'p\\w'"
+    );
+}
+
+#[test]
+fn test_fill_rejects_bad_values() {
+    assert!(template("kill-query")
+        .fill(&values(&[("connection_id", "1; DROP TABLE t")]))
+        .is_err());
+    assert_eq!(
+        template("kill-query")
+            .fill(&values(&[("connection_id", "42")]))
+            .unwrap(),
+        "KILL QUERY 42"
+    );
+    assert!(template("grant-all")
+        .fill(&values(&[("user", "app")]))
+        .is_err());
+}
+
+#[test]
+fn test_config_templates_extend_and_replace() {
+    let configured = BTreeMap::from([
+        (
+            "drop-user".to_string(),
+            "DROP USER IF EXISTS {user}@{host=%}".to_string(),
+        ),
+        (
+            "lock-user".to_string(),
+            "ALTER USER {user}@{host=%} ACCOUNT LOCK".to_string(),
+        ),
+    ]);
+    let all = templates(&configured);
+    assert_eq!(all.iter().filter(|t| t.name == "drop-user").count(), 1);
+    assert!(all
+        .iter()
+        .any(|t| t.name == "drop-user" && t.statement.contains("IF EXISTS")));
+    assert!(all.iter().any(|t| t.name == "lock-user"));
+
+    let bad = Template {
+        name: "bad".to_string(),
+        description: String::new(),
+        statement: "SELECT {x:date}".to_string(),
+    };
+    assert!(bad.parameters().is_err());
+}
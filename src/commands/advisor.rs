@@ -0,0 +1,187 @@
+use crate::completion::metadata::TableIndex;
+use sqlparser::ast::{BinaryOperator, Expr, Query, SelectItem, SetExpr, Statement, TableFactor};
+use sqlparser::dialect::MySqlDialect;
+use sqlparser::parser::Parser;
+
+/// Maximum number of columns a recommended covering index may contain
+const MAX_COVERING_COLUMNS: usize = 5;
+
+/// Columns a single-table SELECT filters, sorts and returns
+#[derive(Debug, Default, PartialEq)]
+pub struct QueryColumns {
+    /// Table name as written in the query (optionally `db.table`)
+    pub table: String,
+    /// Columns compared with `=` or `IN`
+    pub equality: Vec<String>,
+    /// Columns used in ranges, LIKE or BETWEEN
+    pub range: Vec<String>,
+    /// Columns in ORDER BY
+    pub order_by: Vec<String>,
+    /// Explicitly selected columns (empty for `SELECT *`)
+    pub selected: Vec<String>,
+}
+
+impl QueryColumns {
+    /// Extract columns from a single-table SELECT statement
+    pub fn from_sql(sql: &str) -> Option<Self> {
+        let statements = Parser::parse_sql(&MySqlDialect {}, sql).ok()?;
+        match statements.first()? {
+            Statement::Query(query) => Self::from_query(query),
+            _ => None,
+        }
+    }
+
+    fn from_query(query: &Query) -> Option<Self> {
+        let SetExpr::Select(select) = &*query.body else {
+            return None;
+        };
+        let [from] = select.from.as_slice() else {
+            return None;
+        };
+        if !from.joins.is_empty() {
+            return None;
+        }
+        let TableFactor::Table { name, .. } = &from.relation else {
+            return None;
+        };
+
+        let mut columns = QueryColumns {
+            table: name
+                .0
+                .iter()
+                .map(|ident| ident.value.clone())
+                .collect::<Vec<_>>()
+                .join("."),
+            ..Default::default()
+        };
+
+        if let Some(selection) = &select.selection {
+            columns.collect_conditions(selection);
+        }
+        for order in &query.order_by {
+            if let Some(column) = column_name(&order.expr) {
+                push_unique(&mut columns.order_by, column);
+            }
+        }
+        for item in &select.projection {
+            match item {
+                SelectItem::UnnamedExpr(expr) | SelectItem::ExprWithAlias { expr, .. } => {
+                    if let Some(column) = column_name(expr) {
+                        push_unique(&mut columns.selected, column);
+                    }
+                }
+                _ => {
+                    columns.selected.clear();
+                    break;
+                }
+            }
+        }
+
+        Some(columns)
+    }
+
+    fn collect_conditions(&mut self, expr: &Expr) {
+        match expr {
+            Expr::BinaryOp { left, op, right } => match op {
+                BinaryOperator::And => {
+                    self.collect_conditions(left);
+                    self.collect_conditions(right);
+                }
+                BinaryOperator::Eq => {
+                    if let Some(column) = column_name(left).or_else(|| column_name(right)) {
+                        push_unique(&mut self.equality, column);
+                    }
+                }
+                BinaryOperator::Gt
+                | BinaryOperator::Lt
+                | BinaryOperator::GtEq
+                | BinaryOperator::LtEq => {
+                    if let Some(column) = column_name(left).or_else(|| column_name(right)) {
+                        push_unique(&mut self.range, column);
+                    }
+                }
+                _ => {}
+            },
+            Expr::InList {
+                expr,
+                negated: false,
+                ..
+            } => {
+                if let Some(column) = column_name(expr) {
+                    push_unique(&mut self.equality, column);
+                }
+            }
+            Expr::Between {
+                expr,
+                negated: false,
+                ..
+            }
+            | Expr::Like {
+                expr,
+                negated: false,
+                ..
+            } => {
+                if let Some(column) = column_name(expr) {
+                    push_unique(&mut self.range, column);
+                }
+            }
+            Expr::Nested(inner) => self.collect_conditions(inner),
+            _ => {}
+        }
+    }
+}
+
+/// Suggest a covering index for the query unless an existing index already leads with it
+///
+/// Equality columns come first, then ORDER BY and range columns, then the
+/// selected columns when the index stays small enough to cover the query.
+pub fn recommend_index(columns: &QueryColumns, indexes: &[TableIndex]) -> Option<Vec<String>> {
+    let mut key = columns.equality.clone();
+    for column in columns.order_by.iter().chain(&columns.range) {
+        push_unique(&mut key, column.clone());
+    }
+    if key.is_empty() {
+        return None;
+    }
+
+    let already_indexed = indexes.iter().any(|index| {
+        index.columns.len() >= key.len()
+            && index
+                .columns
+                .iter()
+                .zip(&key)
+                .all(|(a, b)| a.eq_ignore_ascii_case(b))
+    });
+    if already_indexed {
+        return None;
+    }
+
+    let mut covering = key.clone();
+    for column in &columns.selected {
+        push_unique(&mut covering, column.clone());
+    }
+    if !columns.selected.is_empty() && covering.len() <= MAX_COVERING_COLUMNS {
+        Some(covering)
+    } else {
+        Some(key)
+    }
+}
+
+/// Column name of a plain or qualified identifier
+fn column_name(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Identifier(ident) => Some(ident.value.clone()),
+        Expr::CompoundIdentifier(parts) => parts.last().map(|ident| ident.value.clone()),
+        _ => None,
+    }
+}
+
+fn push_unique(columns: &mut Vec<String>, column: String) {
+    if !columns.iter().any(|c| c.eq_ignore_ascii_case(&column)) {
+        columns.push(column);
+    }
+}
+
+#[cfg(test)]
+#[path = "./advisor_tests.rs"]
+mod advisor_tests;
@@ -0,0 +1,63 @@
+use super::*;
+
+fn strings(items: &[&str]) -> Vec<String> {
+    items.iter().map(|s| s.to_string()).collect()
+}
+
+#[test]
+fn test_query_columns_from_sql() {
+    let columns = QueryColumns::from_sql(
+        "SELECT name, email FROM shop.users WHERE status = 'active' AND id IN (1, 2) \
+         AND created > '2024-01-01' AND name LIKE 'a%' ORDER BY u.created",
+    )
+    .unwrap();
+    assert_eq!(
+        columns,
+        QueryColumns {
+            table: "shop.users".to_string(),
+            equality: strings(&["status", "id"]),
+            range: strings(&["created", "name"]),
+            order_by: strings(&["created"]),
+            selected: strings(&["name", "email"]),
+        }
+    );
+}
+
+#[test]
+fn test_query_columns_unsupported_queries() {
+    assert_eq!(
+        QueryColumns::from_sql("SELECT * FROM users WHERE id = 1")
+            .unwrap()
+            .selected,
+        Vec::<String>::new()
+    );
+    assert_eq!(
+        QueryColumns::from_sql("SELECT * FROM a JOIN b ON a.id = b.a_id"),
+        None
+    );
+    assert_eq!(QueryColumns::from_sql("SELECT * FROM a, b"), None);
+    assert_eq!(QueryColumns::from_sql("UPDATE users SET a = 1"), None);
+    assert_eq!(QueryColumns::from_sql("not sql"), None);
+}
+
+#[test]
+fn test_recommend_index() {
+    let columns = QueryColumns::from_sql(
+        "SELECT email FROM users WHERE status = 'active' AND created > '2024-01-01'",
+    )
+    .unwrap();
+    assert_eq!(
+        recommend_index(&columns, &[]),
+        Some(strings(&["status", "created", "email"]))
+    );
+
+    let existing = TableIndex {
+        name: "idx_status_created".to_string(),
+        columns: strings(&["STATUS", "created", "id"]),
+        unique: false,
+    };
+    assert_eq!(recommend_index(&columns, &[existing]), None);
+
+    let no_filter = QueryColumns::from_sql("SELECT * FROM users").unwrap();
+    assert_eq!(recommend_index(&no_filter, &[]), None);
+}
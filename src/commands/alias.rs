@@ -0,0 +1,118 @@
+use anyhow::{anyhow, Result};
+use std::collections::BTreeMap;
+
+/// User-defined backslash command aliases (`\alias name = expansion`)
+#[derive(Debug, Default, Clone)]
+pub struct AliasTable {
+    aliases: BTreeMap<String, String>,
+}
+
+impl AliasTable {
+    /// Create an alias table from `name -> expansion` pairs
+    pub fn new(aliases: BTreeMap<String, String>) -> Self {
+        Self {
+            aliases: aliases
+                .into_iter()
+                .map(|(name, expansion)| (normalize_name(&name), expansion.trim().to_string()))
+                .collect(),
+        }
+    }
+
+    /// All aliases, sorted by name
+    pub fn entries(&self) -> &BTreeMap<String, String> {
+        &self.aliases
+    }
+
+    /// Define or replace an alias, rejecting definitions that would recurse
+    pub fn define(&mut self, name: &str, expansion: &str) -> Result<()> {
+        let name = normalize_name(name);
+        let expansion = expansion.trim();
+        if name.is_empty() || name.contains(char::is_whitespace) {
+            return Err(anyhow!("Invalid alias name: '{}'", name));
+        }
+        if expansion.is_empty() {
+            return Err(anyhow!("Alias expansion cannot be empty"));
+        }
+
+        if let Some(target) = command_name(expansion) {
+            if target == name {
+                return Err(anyhow!("Alias \\{} cannot expand to itself", name));
+            }
+            if self.aliases.contains_key(&target) {
+                return Err(anyhow!(
+                    "Alias \\{} cannot expand to another alias (\\{})",
+                    name,
+                    target
+                ));
+            }
+        }
+        if let Some((other, _)) = self
+            .aliases
+            .iter()
+            .find(|(_, other_expansion)| command_name(other_expansion).as_deref() == Some(&name))
+        {
+            return Err(anyhow!(
+                "Alias \\{} is used by alias \\{} and cannot be an alias itself",
+                name,
+                other
+            ));
+        }
+
+        self.aliases.insert(name, expansion.to_string());
+        Ok(())
+    }
+
+    /// Remove an alias, returning whether it existed
+    pub fn remove(&mut self, name: &str) -> bool {
+        self.aliases.remove(&normalize_name(name)).is_some()
+    }
+
+    /// Expand a backslash command one level, appending its arguments
+    ///
+    /// Returns `Ok(None)` when the command is not an alias.
+    pub fn expand(&self, command: &str) -> Result<Option<String>> {
+        let command = command.trim();
+        let Some(rest) = command.strip_prefix('\\') else {
+            return Ok(None);
+        };
+        let (name, args) = rest
+            .split_once(char::is_whitespace)
+            .map(|(name, args)| (name, args.trim()))
+            .unwrap_or((rest, ""));
+
+        let Some(expansion) = self.aliases.get(name) else {
+            return Ok(None);
+        };
+
+        if let Some(target) = command_name(expansion) {
+            if self.aliases.contains_key(&target) {
+                return Err(anyhow!(
+                    "Recursive alias: \\{} expands to alias \\{}",
+                    name,
+                    target
+                ));
+            }
+        }
+
+        if args.is_empty() {
+            Ok(Some(expansion.clone()))
+        } else {
+            Ok(Some(format!("{} {}", expansion, args)))
+        }
+    }
+}
+
+/// Strip the leading backslash from an alias name
+fn normalize_name(name: &str) -> String {
+    name.trim().trim_start_matches('\\').to_string()
+}
+
+/// Name of the backslash command an expansion invokes, if any
+fn command_name(expansion: &str) -> Option<String> {
+    let rest = expansion.trim().strip_prefix('\\')?;
+    rest.split_whitespace().next().map(|name| name.to_string())
+}
+
+#[cfg(test)]
+#[path = "./alias_tests.rs"]
+mod alias_tests;
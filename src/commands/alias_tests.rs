@@ -0,0 +1,66 @@
+use super::*;
+
+fn create_test_aliases() -> AliasTable {
+    let mut aliases = BTreeMap::new();
+    aliases.insert("\\pt".to_string(), "\\pl --watch 2".to_string());
+    aliases.insert("qlog".to_string(), "\\log ~/queries.log".to_string());
+    aliases.insert("tables".to_string(), "SHOW TABLES".to_string());
+    AliasTable::new(aliases)
+}
+
+#[test]
+fn test_expand_alias() {
+    let aliases = create_test_aliases();
+    assert_eq!(
+        aliases.expand("\\pt").unwrap(),
+        Some("\\pl --watch 2".to_string())
+    );
+    assert_eq!(
+        aliases.expand("\\tables").unwrap(),
+        Some("SHOW TABLES".to_string())
+    );
+}
+
+#[test]
+fn test_expand_passes_arguments_through() {
+    let aliases = create_test_aliases();
+    assert_eq!(
+        aliases.expand("\\qlog   --append ").unwrap(),
+        Some("\\log ~/queries.log --append".to_string())
+    );
+}
+
+#[test]
+fn test_expand_unknown_command() {
+    let aliases = create_test_aliases();
+    assert_eq!(aliases.expand("\\nope").unwrap(), None);
+    assert_eq!(aliases.expand("SELECT 1").unwrap(), None);
+}
+
+#[test]
+fn test_define_rejects_recursion() {
+    let mut aliases = create_test_aliases();
+    assert!(aliases.define("loop", "\\loop now").is_err());
+    assert!(aliases.define("chain", "\\pt").is_err());
+    // \pl is used by \pt, so it cannot become an alias
+    assert!(aliases.define("pl", "\\status").is_err());
+    assert!(aliases.define("st", "\\status").is_ok());
+}
+
+#[test]
+fn test_expand_detects_recursive_config() {
+    // Config files bypass `define`, so expansion must guard too
+    let mut config = BTreeMap::new();
+    config.insert("a".to_string(), "\\b".to_string());
+    config.insert("b".to_string(), "\\a".to_string());
+    let aliases = AliasTable::new(config);
+    assert!(aliases.expand("\\a").is_err());
+}
+
+#[test]
+fn test_remove_alias() {
+    let mut aliases = create_test_aliases();
+    assert!(aliases.remove("\\pt"));
+    assert!(!aliases.remove("pt"));
+    assert_eq!(aliases.expand("\\pt").unwrap(), None);
+}
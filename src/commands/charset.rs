@@ -0,0 +1,66 @@
+/*!
+ * Character set check
+ *
+ * Compares the session's `character_set_client`, `character_set_connection` and
+ * `character_set_results` with each other and with `--charset`, the usual cause of
+ * garbled non-ASCII text. Runs after connecting and with `\charset-check`.
+ */
+
+/// The session character sets that must agree
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionCharsets {
+    pub client: String,
+    pub connection: String,
+    /// Empty when `character_set_results` is NULL (results are sent unconverted)
+    pub results: String,
+}
+
+impl SessionCharsets {
+    /// `client=..., connection=..., results=...`
+    pub fn describe(&self) -> String {
+        let mut parts = vec![
+            format!("client={}", self.client),
+            format!("connection={}", self.connection),
+        ];
+        if !self.results.is_empty() {
+            parts.push(format!("results={}", self.results));
+        }
+        parts.join(", ")
+    }
+}
+
+/// The warning for session character sets that differ from each other or from
+/// `expected` (the `--charset`), or `None` when they agree
+pub fn mismatch(charsets: &SessionCharsets, expected: Option<&str>) -> Option<String> {
+    let mut names = vec![normalize(&charsets.client), normalize(&charsets.connection)];
+    if !charsets.results.is_empty() {
+        names.push(normalize(&charsets.results));
+    }
+    let disagree = names.iter().any(|name| *name != names[0]);
+    match expected {
+        Some(expected) if normalize(expected) != names[0] => Some(format!(
+            "Warning: character set mismatch: --charset={}, {}",
+            expected,
+            charsets.describe()
+        )),
+        _ if disagree => Some(format!(
+            "Warning: character set mismatch: {}",
+            charsets.describe()
+        )),
+        _ => None,
+    }
+}
+
+/// Character set names as the server compares them; `utf8` is `utf8mb3`
+fn normalize(name: &str) -> String {
+    let name = name.trim().to_lowercase();
+    if name == "utf8" {
+        "utf8mb3".to_string()
+    } else {
+        name
+    }
+}
+
+#[cfg(test)]
+#[path = "./charset_tests.rs"]
+mod charset_tests;
@@ -0,0 +1,35 @@
+use super::*;
+
+fn charsets(client: &str, connection: &str, results: &str) -> SessionCharsets {
+    SessionCharsets {
+        client: client.to_string(),
+        connection: connection.to_string(),
+        results: results.to_string(),
+    }
+}
+
+#[test]
+fn test_matching_charsets() {
+    assert_eq!(
+        mismatch(&charsets("utf8mb4", "utf8mb4", "utf8mb4"), None),
+        None
+    );
+    assert_eq!(
+        mismatch(&charsets("utf8", "utf8mb3", ""), Some("UTF8MB3")),
+        None
+    );
+}
+
+#[test]
+fn test_mismatched_charsets() {
+    assert_eq!(
+        mismatch(&charsets("utf8mb4", "latin1", "utf8mb4"), None).as_deref(),
+        Some("Warning: character set mismatch: client=utf8mb4, connection=latin1, results=utf8mb4")
+    );
+    assert_eq!(
+        mismatch(&charsets("latin1", "latin1", "latin1"), Some("utf8mb4")).as_deref(),
+        Some(
+            "Warning: character set mismatch: --charset=utf8mb4, client=latin1, connection=latin1, results=latin1"
+        )
+    );
+}
@@ -0,0 +1,84 @@
+use crate::database::Connection;
+use anyhow::{anyhow, Result};
+use mysql::prelude::*;
+
+/// Storage figures for a single table from information_schema.TABLES
+#[derive(Debug, Clone, Copy)]
+pub struct TableSize {
+    pub data_length: u64,
+    pub index_length: u64,
+    pub data_free: u64,
+}
+
+impl TableSize {
+    /// Total allocated size including free space
+    pub fn total(&self) -> u64 {
+        self.data_length + self.index_length + self.data_free
+    }
+
+    /// Share of allocated space that is free, as a percentage
+    pub fn fragmentation_percent(&self) -> f64 {
+        let total = self.total();
+        if total == 0 {
+            return 0.0;
+        }
+        self.data_free as f64 / total as f64 * 100.0
+    }
+}
+
+/// Fetch storage figures for `schema`.`table`
+pub fn fetch_table_size(
+    connection: &mut Connection,
+    schema: &str,
+    table: &str,
+) -> Result<TableSize> {
+    let row: Option<(Option<u64>, Option<u64>, Option<u64>)> =
+        connection.get_conn_mut().exec_first(
+            "SELECT DATA_LENGTH, INDEX_LENGTH, DATA_FREE FROM information_schema.TABLES \
+             WHERE TABLE_SCHEMA = ? AND TABLE_NAME = ?",
+            (schema, table),
+        )?;
+
+    let (data_length, index_length, data_free) =
+        row.ok_or_else(|| anyhow!("Table '{}.{}' doesn't exist", schema, table))?;
+
+    Ok(TableSize {
+        data_length: data_length.unwrap_or(0),
+        index_length: index_length.unwrap_or(0),
+        data_free: data_free.unwrap_or(0),
+    })
+}
+
+/// Split a `db.table` or `table` reference, falling back to the current database
+pub fn resolve_table_name(name: &str, current_database: Option<&str>) -> Result<(String, String)> {
+    let name = name.trim();
+    if let Some((db, table)) = name.split_once('.') {
+        return Ok((
+            db.trim_matches('`').to_string(),
+            table.trim_matches('`').to_string(),
+        ));
+    }
+
+    let db = current_database.ok_or_else(|| anyhow!("No database selected"))?;
+    Ok((db.to_string(), name.trim_matches('`').to_string()))
+}
+
+/// Format a byte count using binary units
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.2} {}", size, UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+#[path = "./check_tests.rs"]
+mod check_tests;
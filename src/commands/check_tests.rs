@@ -0,0 +1,42 @@
+use super::*;
+
+#[test]
+fn test_format_bytes() {
+    assert_eq!(format_bytes(0), "0 B");
+    assert_eq!(format_bytes(1023), "1023 B");
+    assert_eq!(format_bytes(1024), "1.00 KiB");
+    assert_eq!(format_bytes(1536), "1.50 KiB");
+    assert_eq!(format_bytes(5 * 1024 * 1024), "5.00 MiB");
+    assert_eq!(format_bytes(2048 * 1024u64.pow(4)), "2048.00 TiB");
+}
+
+#[test]
+fn test_fragmentation_percent() {
+    let size = TableSize {
+        data_length: 600,
+        index_length: 200,
+        data_free: 200,
+    };
+    assert_eq!(size.total(), 1000);
+    assert_eq!(size.fragmentation_percent(), 20.0);
+
+    let empty = TableSize {
+        data_length: 0,
+        index_length: 0,
+        data_free: 0,
+    };
+    assert_eq!(empty.fragmentation_percent(), 0.0);
+}
+
+#[test]
+fn test_resolve_table_name() {
+    assert_eq!(
+        resolve_table_name("`shop`.`orders`", None).unwrap(),
+        ("shop".to_string(), "orders".to_string())
+    );
+    assert_eq!(
+        resolve_table_name(" orders ", Some("shop")).unwrap(),
+        ("shop".to_string(), "orders".to_string())
+    );
+    assert!(resolve_table_name("orders", None).is_err());
+}
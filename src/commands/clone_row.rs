@@ -0,0 +1,132 @@
+/*!
+ * Clone a row
+ *
+ * `\clone-row <table> <where> [--first]` turns an existing row into an
+ * INSERT statement that is placed at the prompt for editing
+ */
+
+use super::literal;
+use crate::database::Connection;
+use anyhow::{anyhow, Result};
+use mysql::prelude::*;
+use mysql::{Row, Value};
+
+/// Parse `<table> <where-clause> [--first]`, accepting an optional leading WHERE
+pub fn parse_args(args: &str) -> Result<(String, String, bool)> {
+    let usage = || anyhow!("Usage: \\clone-row <table> <where-clause> [--first]");
+
+    let mut words: Vec<&str> = args.split_whitespace().collect();
+    let first = words.contains(&"--first");
+    words.retain(|w| *w != "--first");
+
+    let (table, condition) = words.split_first().ok_or_else(usage)?;
+    let condition = match condition.split_first() {
+        Some((keyword, rest)) if keyword.eq_ignore_ascii_case("WHERE") => rest,
+        _ => condition,
+    };
+    if condition.is_empty() {
+        return Err(usage());
+    }
+    Ok((table.to_string(), condition.join(" "), first))
+}
+
+/// Fetch the single row matching the condition (the first one with `first`)
+/// and build an INSERT for it without primary key and auto-increment columns
+pub fn clone_row(
+    connection: &mut Connection,
+    schema: &str,
+    table: &str,
+    condition: &str,
+    first: bool,
+) -> Result<String> {
+    let conn = connection.get_conn_mut();
+
+    // Key metadata: columns the server fills in or that must stay unique
+    let skipped: Vec<String> = conn
+        .query_map(
+            format!(
+                "SHOW COLUMNS FROM {}.{}",
+                literal::quote_identifier(schema),
+                literal::quote_identifier(table)
+            ),
+            |row: Row| {
+                let name: String = row.get(0).unwrap_or_default();
+                let key: String = row.get(3).unwrap_or_default();
+                let extra: String = row.get(5).unwrap_or_default();
+                (
+                    name,
+                    key == "PRI" || extra.to_lowercase().contains("auto_increment"),
+                )
+            },
+        )?
+        .into_iter()
+        .filter_map(|(name, skip)| skip.then_some(name))
+        .collect();
+
+    let rows: Vec<Row> = conn.query(format!(
+        "SELECT * FROM {}.{} WHERE {} LIMIT 2",
+        literal::quote_identifier(schema),
+        literal::quote_identifier(table),
+        condition
+    ))?;
+    let row = match rows.as_slice() {
+        [] => {
+            return Err(anyhow!(
+                "No row in {}.{} matches: {}",
+                schema,
+                table,
+                condition
+            ))
+        }
+        [row] => row,
+        [row, ..] if first => row,
+        _ => {
+            return Err(anyhow!(
+                "More than one row matches; narrow the condition or add --first"
+            ))
+        }
+    };
+
+    let columns: Vec<String> = row
+        .columns_ref()
+        .iter()
+        .map(|c| c.name_str().to_string())
+        .collect();
+    let values: Vec<Value> = (0..row.len())
+        .map(|i| row.as_ref(i).cloned().unwrap_or(Value::NULL))
+        .collect();
+    Ok(insert_statement(schema, table, &columns, &values, &skipped))
+}
+
+/// Build `INSERT INTO schema.table (cols) VALUES (...)`, leaving out the skipped columns
+pub fn insert_statement(
+    schema: &str,
+    table: &str,
+    columns: &[String],
+    values: &[Value],
+    skipped: &[String],
+) -> String {
+    let (names, literals): (Vec<String>, Vec<String>) = columns
+        .iter()
+        .zip(values)
+        .filter(|(column, _)| !skipped.iter().any(|s| s.eq_ignore_ascii_case(column)))
+        .map(|(column, value)| {
+            (
+                literal::quote_identifier(column),
+                literal::value_literal(value),
+            )
+        })
+        .unzip();
+
+    format!(
+        "INSERT INTO {}.{} ({}) VALUES ({});",
+        literal::quote_identifier(schema),
+        literal::quote_identifier(table),
+        names.join(", "),
+        literals.join(", ")
+    )
+}
+
+#[cfg(test)]
+#[path = "./clone_row_tests.rs"]
+mod clone_row_tests;
@@ -0,0 +1,39 @@
+use super::*;
+
+#[test]
+fn test_insert_statement_skips_keys_and_quotes_values() {
+    let columns: Vec<String> = ["id", "name", "note", "avatar", "created"]
+        .iter()
+        .map(|c| c.to_string())
+        .collect();
+    let values = vec![
+        Value::Int(7),
+        Value::Bytes(b"O'Brien\\x".to_vec()),
+        Value::NULL,
+        Value::Bytes(vec![0xff, 0x00, 0x1a]),
+        Value::Date(2024, 3, 9, 8, 5, 0, 0),
+    ];
+
+    assert_eq!(
+        insert_statement("shop", "users", &columns, &values, &["ID".to_string()]),
+        "INSERT INTO `shop`.`users` (`name`, `note`, `avatar`, `created`) \
+         VALUES ('O''Brien\\\\x', NULL, X'FF001A', '2024-03-09 08:05:00');"
+    );
+}
+
+#[test]
+fn test_parse_args() {
+    assert_eq!(
+        parse_args("users WHERE email = 'a@b.c'").unwrap(),
+        ("users".to_string(), "email = 'a@b.c'".to_string(), false)
+    );
+    assert_eq!(
+        parse_args("shop.orders status = 'new' --first").unwrap(),
+        (
+            "shop.orders".to_string(),
+            "status = 'new'".to_string(),
+            true
+        )
+    );
+    assert!(parse_args("users").is_err());
+}
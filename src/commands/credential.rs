@@ -0,0 +1,95 @@
+/*!
+ * `credential` subcommand
+ *
+ * `mysql-cli-rust credential set|remove|list` manages the encrypted
+ * credential store, and connecting reads a profile's password from it
+ * before asking for one
+ */
+
+use crate::credentials::{CredentialStore, KeySource, StoreKey};
+use anyhow::{anyhow, Result};
+use clap::ArgMatches;
+
+/// Run `credential set <profile> [--keyring]`, `credential remove <profile>`
+/// or `credential list`
+pub fn run(matches: &ArgMatches) -> Result<()> {
+    let mut store = open_store()?;
+    match matches.subcommand() {
+        Some(("set", args)) => {
+            let profile = args.get_one::<String>("profile").unwrap();
+            if args.get_flag("keyring") {
+                // The Linux kernel keyring is cleared on reboot, taking the store key with it
+                if cfg!(target_os = "linux") {
+                    return Err(anyhow!(
+                        "--keyring is not supported on Linux; use a master passphrase"
+                    ));
+                }
+                store.set_key_source(KeySource::Keyring)?;
+            } else if store.key_source().is_none() {
+                store.set_key_source(KeySource::Passphrase)?;
+            }
+            let password = rpassword::prompt_password(format!("Password for {}: ", profile))?;
+            let key = unlock(&mut store, true)?;
+            store.set(profile, &password, &key)?;
+            store.save()?;
+            println!("Saved credentials for '{}'", profile);
+        }
+        Some(("remove", args)) => {
+            let profile = args.get_one::<String>("profile").unwrap();
+            if !store.remove(profile) {
+                return Err(anyhow!("No stored credentials for '{}'", profile));
+            }
+            store.save()?;
+            println!("Removed credentials for '{}'", profile);
+        }
+        Some(("list", _)) => {
+            let source = match store.key_source() {
+                Some(KeySource::Keyring) => "OS keyring",
+                _ => "master passphrase",
+            };
+            if store.profiles().is_empty() {
+                println!("No stored credentials.");
+            }
+            for profile in store.profiles() {
+                println!("{} (key: {})", profile, source);
+            }
+        }
+        _ => return Err(anyhow!("Usage: credential set|remove|list")),
+    }
+    Ok(())
+}
+
+/// The stored password of `profile`; the store is only unlocked when it
+/// holds the profile
+pub fn stored_password(profile: &str) -> Result<Option<String>> {
+    let mut store = open_store()?;
+    if !store.contains(profile) {
+        return Ok(None);
+    }
+    let key = unlock(&mut store, false)?;
+    store.get(profile, &key)
+}
+
+fn open_store() -> Result<CredentialStore> {
+    let path = CredentialStore::default_path()
+        .ok_or_else(|| anyhow!("Cannot determine home directory"))?;
+    CredentialStore::open(&path)
+}
+
+/// Get the store's key; `creating` confirms a new passphrase and lets a
+/// keyring key be created
+fn unlock(store: &mut CredentialStore, creating: bool) -> Result<StoreKey> {
+    match store.key_source() {
+        Some(KeySource::Keyring) => StoreKey::from_keyring(creating),
+        _ => {
+            let passphrase = rpassword::prompt_password("Master passphrase: ")?;
+            if creating && store.profiles().is_empty() {
+                let again = rpassword::prompt_password("Repeat master passphrase: ")?;
+                if again != passphrase {
+                    return Err(anyhow!("Passphrases don't match"));
+                }
+            }
+            store.passphrase_key(&passphrase)
+        }
+    }
+}
@@ -0,0 +1,198 @@
+/*!
+ * Row editor
+ *
+ * `\edit-row` shows the single row of the last result as a form of labeled
+ * fields and turns the edited values into an UPDATE by primary key
+ */
+
+use super::literal;
+use rustyline::{Cmd, ConditionalEventHandler, Event, EventContext, RepeatCount};
+use sqlparser::ast::{SetExpr, Statement, TableFactor};
+use sqlparser::dialect::MySqlDialect;
+use sqlparser::parser::Parser;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// One column of the edited row
+#[derive(Debug, Clone)]
+pub struct Field {
+    pub column: String,
+    pub original: String,
+    pub value: String,
+}
+
+impl Field {
+    pub fn is_changed(&self) -> bool {
+        self.value != self.original
+    }
+}
+
+/// Form state: the fields and the one being edited
+pub struct RowForm {
+    fields: Vec<Field>,
+    current: usize,
+}
+
+impl RowForm {
+    pub fn new(columns: &[String], row: &[String]) -> Self {
+        let fields = columns
+            .iter()
+            .zip(row)
+            .map(|(column, value)| Field {
+                column: column.clone(),
+                original: value.clone(),
+                value: value.clone(),
+            })
+            .collect();
+        Self { fields, current: 0 }
+    }
+
+    pub fn current(&self) -> &Field {
+        &self.fields[self.current]
+    }
+
+    pub fn set_value(&mut self, value: &str) {
+        self.fields[self.current].value = value.to_string();
+    }
+
+    /// Move to the next field; false when the last field was left
+    pub fn move_next(&mut self) -> bool {
+        if self.current + 1 < self.fields.len() {
+            self.current += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn move_previous(&mut self) {
+        self.current = self.current.saturating_sub(1);
+    }
+
+    /// Start over at the first field
+    pub fn restart(&mut self) {
+        self.current = 0;
+    }
+
+    pub fn changes(&self) -> Vec<&Field> {
+        self.fields.iter().filter(|f| f.is_changed()).collect()
+    }
+
+    /// Labeled fields, the current one marked with `>` and changed ones with `*`
+    pub fn render(&self) -> String {
+        let width = self
+            .fields
+            .iter()
+            .map(|f| f.column.chars().count())
+            .max()
+            .unwrap_or(0);
+        self.fields
+            .iter()
+            .enumerate()
+            .map(|(index, field)| {
+                format!(
+                    "{}{} {:>width$}: {}\n",
+                    if index == self.current { ">" } else { " " },
+                    if field.is_changed() { "*" } else { " " },
+                    field.column,
+                    field.value,
+                    width = width
+                )
+            })
+            .collect()
+    }
+}
+
+/// Table a single-table SELECT reads from, as `(database, table)`
+pub fn source_table(sql: &str) -> Option<(Option<String>, String)> {
+    let statements = Parser::parse_sql(&MySqlDialect {}, sql).ok()?;
+    let Some(Statement::Query(query)) = statements.first() else {
+        return None;
+    };
+    let SetExpr::Select(select) = &*query.body else {
+        return None;
+    };
+    let [from] = select.from.as_slice() else {
+        return None;
+    };
+    if !from.joins.is_empty() {
+        return None;
+    }
+    let TableFactor::Table { name, .. } = &from.relation else {
+        return None;
+    };
+    match name.0.as_slice() {
+        [table] => Some((None, table.value.clone())),
+        [database, table] => Some((Some(database.value.clone()), table.value.clone())),
+        _ => None,
+    }
+}
+
+/// UPDATE setting the changed fields of the row identified by `key` (column, value) pairs;
+/// the text `NULL` sets NULL
+pub fn update_statement(
+    database: &str,
+    table: &str,
+    changes: &[&Field],
+    key: &[(String, String)],
+) -> String {
+    let assignments = changes
+        .iter()
+        .map(|field| {
+            format!(
+                "{} = {}",
+                literal::quote_identifier(&field.column),
+                field_literal(&field.value)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    let condition = key
+        .iter()
+        .map(|(column, value)| {
+            format!(
+                "{} = {}",
+                literal::quote_identifier(column),
+                literal::quote_string(value)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(" AND ");
+    format!(
+        "UPDATE {}.{} SET {} WHERE {};",
+        literal::quote_identifier(database),
+        literal::quote_identifier(table),
+        assignments,
+        condition
+    )
+}
+
+fn field_literal(value: &str) -> String {
+    if value == "NULL" {
+        value.to_string()
+    } else {
+        literal::quote_string(value)
+    }
+}
+
+/// Shift-Tab in the form: remember that the previous field was asked for and accept the line
+pub struct PreviousFieldHandler {
+    pressed: Arc<AtomicBool>,
+}
+
+impl PreviousFieldHandler {
+    pub fn new(pressed: Arc<AtomicBool>) -> Self {
+        Self { pressed }
+    }
+}
+
+impl ConditionalEventHandler for PreviousFieldHandler {
+    fn handle(&self, _: &Event, _: RepeatCount, _: bool, _: &EventContext) -> Option<Cmd> {
+        self.pressed.store(true, Ordering::SeqCst);
+        Some(Cmd::AcceptLine)
+    }
+}
+
+#[cfg(test)]
+#[path = "./edit_row_tests.rs"]
+mod edit_row_tests;
@@ -0,0 +1,45 @@
+use super::*;
+
+#[test]
+fn test_source_table_of_single_table_select() {
+    assert_eq!(
+        source_table("SELECT * FROM users WHERE id = 3 LIMIT 1"),
+        Some((None, "users".to_string()))
+    );
+    assert_eq!(
+        source_table("select name from `shop`.`orders` limit 1"),
+        Some((Some("shop".to_string()), "orders".to_string()))
+    );
+    assert_eq!(
+        source_table("SELECT * FROM users u JOIN orders o ON o.user_id = u.id"),
+        None
+    );
+    assert_eq!(source_table("UPDATE users SET a = 1"), None);
+}
+
+#[test]
+fn test_form_navigation_and_update() {
+    let columns = ["id", "name", "email"].map(String::from);
+    let row = ["7", "Ann", "ann@example.com"].map(String::from);
+    let mut form = RowForm::new(&columns, &row);
+
+    assert!(form.move_next());
+    form.set_value("Anne O'Neil");
+    assert!(form.move_next());
+    form.set_value("NULL");
+    assert!(!form.move_next());
+    form.move_previous();
+    assert_eq!(form.current().column, "name");
+
+    let changes = form.changes();
+    assert_eq!(
+        update_statement(
+            "shop",
+            "users",
+            &changes,
+            &[("id".to_string(), "7".to_string())]
+        ),
+        "UPDATE `shop`.`users` SET `name` = 'Anne O''Neil', `email` = NULL WHERE `id` = '7';"
+    );
+    assert!(form.render().contains(">*  name: Anne O'Neil"));
+}
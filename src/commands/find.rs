@@ -0,0 +1,242 @@
+use crate::completion::metadata::DatabaseMetadata;
+use crate::database::{Connection, QueryResult};
+use anyhow::{anyhow, Result};
+use mysql::prelude::*;
+use regex::Regex;
+
+/// What `\find` searches
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FindScope {
+    /// Databases, tables and columns
+    All,
+    /// Table names only
+    TablesOnly,
+    /// Column names only
+    ColumnsOnly,
+}
+
+/// Case-insensitive name pattern: glob when it contains `*` or `?`, substring otherwise
+#[derive(Debug)]
+pub struct NamePattern {
+    raw: String,
+    regex: Regex,
+}
+
+impl NamePattern {
+    pub fn new(pattern: &str) -> Result<Self> {
+        let is_glob = pattern.contains(['*', '?']);
+        let mut regex = String::from("(?i)");
+        if is_glob {
+            regex.push('^');
+        }
+        for c in pattern.chars() {
+            match c {
+                '*' if is_glob => regex.push_str(".*"),
+                '?' if is_glob => regex.push('.'),
+                _ => regex.push_str(&regex::escape(&c.to_string())),
+            }
+        }
+        if is_glob {
+            regex.push('$');
+        }
+
+        Ok(Self {
+            raw: pattern.to_string(),
+            regex: Regex::new(&regex)?,
+        })
+    }
+
+    pub fn matches(&self, name: &str) -> bool {
+        self.regex.is_match(name)
+    }
+
+    /// Equivalent SQL LIKE pattern
+    pub fn like_pattern(&self) -> String {
+        let escaped = self
+            .raw
+            .replace('\\', "\\\\")
+            .replace('%', "\\%")
+            .replace('_', "\\_");
+        if self.raw.contains(['*', '?']) {
+            escaped.replace('*', "%").replace('?', "_")
+        } else {
+            format!("%{}%", escaped)
+        }
+    }
+}
+
+/// One line of the grouped `\find` report
+#[derive(Debug, Clone, PartialEq)]
+pub struct FindMatch {
+    pub database: String,
+    pub table: Option<String>,
+    pub columns: Vec<String>,
+}
+
+/// Parse `\find` arguments into a pattern and scope
+pub fn parse_args(args: &str) -> Result<(NamePattern, FindScope)> {
+    let mut scope = FindScope::All;
+    let mut pattern = None;
+
+    for arg in args.split_whitespace() {
+        match arg {
+            "--tables-only" => scope = FindScope::TablesOnly,
+            "--columns-only" => scope = FindScope::ColumnsOnly,
+            _ if pattern.is_none() => pattern = Some(arg),
+            _ => return Err(anyhow!("Unexpected argument: {}", arg)),
+        }
+    }
+
+    let pattern =
+        pattern.ok_or_else(|| anyhow!("Usage: \\find <pattern> [--tables-only|--columns-only]"))?;
+    Ok((NamePattern::new(pattern)?, scope))
+}
+
+/// Search cached metadata, grouped by database and table and sorted by name
+pub fn search_metadata(
+    metadata: &DatabaseMetadata,
+    pattern: &NamePattern,
+    scope: FindScope,
+) -> Vec<FindMatch> {
+    let mut matches = Vec::new();
+
+    let mut databases: Vec<&String> = metadata.get_databases().iter().collect();
+    databases.sort();
+
+    for db in databases {
+        let mut db_matches = Vec::new();
+
+        let mut tables: Vec<&String> = metadata
+            .tables
+            .get(&db.to_lowercase())
+            .map(|tables| tables.iter().collect())
+            .unwrap_or_default();
+        tables.sort();
+
+        for table in tables {
+            let columns: Vec<String> = if scope == FindScope::TablesOnly {
+                Vec::new()
+            } else {
+                metadata
+                    .get_columns(&format!("{}.{}", db, table).to_lowercase())
+                    .map(|columns| {
+                        columns
+                            .iter()
+                            .filter(|column| pattern.matches(column))
+                            .cloned()
+                            .collect()
+                    })
+                    .unwrap_or_default()
+            };
+            let table_matches = scope != FindScope::ColumnsOnly && pattern.matches(table);
+
+            if table_matches || !columns.is_empty() {
+                db_matches.push(FindMatch {
+                    database: db.clone(),
+                    table: Some(table.clone()),
+                    columns,
+                });
+            }
+        }
+
+        if scope == FindScope::All && db_matches.is_empty() && pattern.matches(db) {
+            db_matches.push(FindMatch {
+                database: db.clone(),
+                table: None,
+                columns: Vec::new(),
+            });
+        }
+        matches.extend(db_matches);
+    }
+
+    matches
+}
+
+/// Search information_schema directly when cached metadata is unavailable
+pub fn search_server(
+    connection: &mut Connection,
+    pattern: &NamePattern,
+    scope: FindScope,
+) -> Result<Vec<FindMatch>> {
+    let like = pattern.like_pattern();
+    let matched_columns = "CASE WHEN COLUMN_NAME LIKE ? THEN COLUMN_NAME END";
+    // Each `?` below is bound to the LIKE pattern
+    let (column_filter, condition, placeholders) = match scope {
+        FindScope::All => (
+            matched_columns,
+            "TABLE_NAME LIKE ? OR COLUMN_NAME LIKE ?",
+            3,
+        ),
+        FindScope::TablesOnly => ("NULL", "TABLE_NAME LIKE ?", 1),
+        FindScope::ColumnsOnly => (matched_columns, "COLUMN_NAME LIKE ?", 2),
+    };
+    let query = format!(
+        "SELECT TABLE_SCHEMA, TABLE_NAME, \
+         GROUP_CONCAT({} ORDER BY ORDINAL_POSITION SEPARATOR ', ') \
+         FROM information_schema.COLUMNS WHERE {} \
+         GROUP BY TABLE_SCHEMA, TABLE_NAME ORDER BY TABLE_SCHEMA, TABLE_NAME",
+        column_filter, condition
+    );
+
+    let rows: Vec<(String, String, Option<String>)> = connection
+        .get_conn_mut()
+        .exec(query, vec![like.as_str(); placeholders])?;
+
+    let mut matches: Vec<FindMatch> = rows
+        .into_iter()
+        .map(|(database, table, columns)| FindMatch {
+            database,
+            table: Some(table),
+            columns: columns
+                .map(|c| c.split(", ").map(|s| s.to_string()).collect())
+                .unwrap_or_default(),
+        })
+        .collect();
+
+    // Like the cached search, a matching database is listed when none of its
+    // tables or columns are
+    if scope == FindScope::All {
+        let databases: Vec<String> = connection.get_conn_mut().exec(
+            "SELECT SCHEMA_NAME FROM information_schema.SCHEMATA WHERE SCHEMA_NAME LIKE ?",
+            (like.as_str(),),
+        )?;
+        for database in databases {
+            if !matches.iter().any(|m| m.database == database) {
+                matches.push(FindMatch {
+                    database,
+                    table: None,
+                    columns: Vec::new(),
+                });
+            }
+        }
+        matches.sort_by(|a, b| a.database.cmp(&b.database));
+    }
+    Ok(matches)
+}
+
+/// Convert matches into a result set for the table formatter
+pub fn to_result(matches: &[FindMatch]) -> QueryResult {
+    QueryResult {
+        tables: Vec::new(),
+        nulls: Vec::new(),
+        columns: vec![
+            "Database".to_string(),
+            "Table".to_string(),
+            "Matching columns".to_string(),
+        ],
+        rows: matches
+            .iter()
+            .map(|m| {
+                vec![
+                    m.database.clone(),
+                    m.table.clone().unwrap_or_default(),
+                    m.columns.join(", "),
+                ]
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+#[path = "./find_tests.rs"]
+mod find_tests;
@@ -0,0 +1,100 @@
+use super::*;
+
+fn create_test_metadata() -> DatabaseMetadata {
+    let mut md = DatabaseMetadata::new();
+    md.databases = vec!["sales".to_string(), "test_db".to_string()];
+    md.tables.insert(
+        "test_db".to_string(),
+        vec!["users".to_string(), "invoices".to_string()],
+    );
+    md.tables
+        .insert("sales".to_string(), vec!["orders".to_string()]);
+    md.columns.insert(
+        "test_db.users".to_string(),
+        vec!["id".to_string(), "name".to_string()],
+    );
+    md.columns.insert(
+        "test_db.invoices".to_string(),
+        vec!["id".to_string(), "invoice_no".to_string()],
+    );
+    md.columns.insert(
+        "sales.orders".to_string(),
+        vec!["order_id".to_string(), "Invoice_Ref".to_string()],
+    );
+    md
+}
+
+#[test]
+fn test_find_substring_groups_by_table() {
+    let md = create_test_metadata();
+    let pattern = NamePattern::new("INVOICE").unwrap();
+    let matches = search_metadata(&md, &pattern, FindScope::All);
+
+    assert_eq!(
+        matches,
+        vec![
+            FindMatch {
+                database: "sales".to_string(),
+                table: Some("orders".to_string()),
+                columns: vec!["Invoice_Ref".to_string()],
+            },
+            FindMatch {
+                database: "test_db".to_string(),
+                table: Some("invoices".to_string()),
+                columns: vec!["invoice_no".to_string()],
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_find_scopes() {
+    let md = create_test_metadata();
+    let pattern = NamePattern::new("invoice").unwrap();
+
+    let tables = search_metadata(&md, &pattern, FindScope::TablesOnly);
+    assert_eq!(tables.len(), 1);
+    assert_eq!(tables[0].table.as_deref(), Some("invoices"));
+    assert!(tables[0].columns.is_empty());
+
+    let columns = search_metadata(&md, &pattern, FindScope::ColumnsOnly);
+    assert_eq!(columns.len(), 2);
+}
+
+#[test]
+fn test_find_glob_and_database() {
+    let md = create_test_metadata();
+
+    let pattern = NamePattern::new("*_id").unwrap();
+    let matches = search_metadata(&md, &pattern, FindScope::All);
+    assert_eq!(matches.len(), 1);
+    assert_eq!(matches[0].columns, vec!["order_id".to_string()]);
+
+    let pattern = NamePattern::new("test*").unwrap();
+    let matches = search_metadata(&md, &pattern, FindScope::All);
+    assert_eq!(
+        matches,
+        vec![FindMatch {
+            database: "test_db".to_string(),
+            table: None,
+            columns: Vec::new(),
+        }]
+    );
+}
+
+#[test]
+fn test_like_pattern() {
+    assert_eq!(NamePattern::new("inv").unwrap().like_pattern(), "%inv%");
+    assert_eq!(
+        NamePattern::new("user_*").unwrap().like_pattern(),
+        "user\\_%"
+    );
+}
+
+#[test]
+fn test_parse_args() {
+    let (pattern, scope) = parse_args("invoice --columns-only").unwrap();
+    assert!(pattern.matches("INVOICE_NO"));
+    assert_eq!(scope, FindScope::ColumnsOnly);
+    assert!(parse_args("--tables-only").is_err());
+}
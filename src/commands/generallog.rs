@@ -0,0 +1,136 @@
+/*!
+ * General query log
+ *
+ * Parses general log lines (`Time Id Command Argument`, tab separated) for
+ * `\tail-generallog`
+ */
+
+use anyhow::{anyhow, Result};
+use regex::Regex;
+use std::sync::OnceLock;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeneralLogEntry {
+    pub time: String,
+    pub id: u64,
+    pub command: String,
+    pub argument: String,
+}
+
+/// What one log line holds
+#[derive(Debug, Clone, PartialEq)]
+pub enum GeneralLogLine {
+    Entry(GeneralLogEntry),
+    /// Further line of a multi-line argument, with the command it belongs to
+    Continuation {
+        command: String,
+        text: String,
+    },
+}
+
+/// Classifies log lines fed one at a time
+#[derive(Default)]
+pub struct GeneralLogParser {
+    /// Command of the last entry, for its continuation lines
+    command: Option<String>,
+    /// Older servers only write the time when it changes
+    last_time: String,
+}
+
+impl GeneralLogParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn feed(&mut self, line: &str) -> Option<GeneralLogLine> {
+        // Header written when the server (re)opens the log
+        if line.starts_with("Tcp port:")
+            || line.starts_with("Time ")
+            || line.contains(", Version: ")
+        {
+            self.command = None;
+            return None;
+        }
+
+        static ENTRY: OnceLock<Regex> = OnceLock::new();
+        let entry = ENTRY.get_or_init(|| {
+            Regex::new(r"^([^\t]*)\t\s*(\d+) (\w[\w ]*?)\t?(?:\t|$)(.*)$").unwrap()
+        });
+        if let Some(caps) = entry.captures(line) {
+            let time = caps[1].trim();
+            if !time.is_empty() {
+                self.last_time = time.to_string();
+            }
+            let command = caps[3].to_string();
+            self.command = Some(command.clone());
+            return Some(GeneralLogLine::Entry(GeneralLogEntry {
+                time: self.last_time.clone(),
+                id: caps[2].parse().unwrap_or(0),
+                command,
+                argument: caps[4].to_string(),
+            }));
+        }
+
+        self.command
+            .as_ref()
+            .map(|command| GeneralLogLine::Continuation {
+                command: command.clone(),
+                text: line.to_string(),
+            })
+    }
+}
+
+/// Arguments of `\tail-generallog`: the log path and the command types to show
+pub fn parse_args(args: &str) -> Result<(String, Vec<String>)> {
+    let mut path = None;
+    let mut types = Vec::new();
+    for arg in args.split_whitespace() {
+        if let Some(list) = arg.strip_prefix("--type=") {
+            types.extend(
+                list.split(',')
+                    .filter(|t| !t.is_empty())
+                    .map(|t| t.to_lowercase()),
+            );
+        } else if path.is_none() {
+            path = Some(arg.to_string());
+        } else {
+            return Err(anyhow!("Unexpected argument '{}'", arg));
+        }
+    }
+    let path =
+        path.ok_or_else(|| anyhow!("Usage: \\tail-generallog [--type=Query,Connect] <path>"))?;
+    Ok((path, types))
+}
+
+/// Render a line when its command is one of `types` (all when empty);
+/// Query is green, Connect cyan and Quit gray when `color` is set
+pub fn format_line(line: &GeneralLogLine, types: &[String], color: bool) -> Option<String> {
+    let (command, text) = match line {
+        GeneralLogLine::Entry(entry) => (
+            &entry.command,
+            format!(
+                "{} {:>6} {:<8} {}",
+                entry.time, entry.id, entry.command, entry.argument
+            ),
+        ),
+        GeneralLogLine::Continuation { command, text } => (command, text.clone()),
+    };
+    if !types.is_empty() && !types.contains(&command.to_lowercase()) {
+        return None;
+    }
+
+    let code = match command.as_str() {
+        "Query" => Some("32"),
+        "Connect" => Some("36"),
+        "Quit" => Some("90"),
+        _ => None,
+    };
+    Some(match code {
+        Some(code) if color => format!("\x1b[{}m{}\x1b[0m\n", code, text),
+        _ => format!("{}\n", text),
+    })
+}
+
+#[cfg(test)]
+#[path = "./generallog_tests.rs"]
+mod generallog_tests;
@@ -0,0 +1,67 @@
+use super::*;
+
+const LOG: &str = "/usr/sbin/mysqld, Version: 8.0.36 (MySQL Community Server - GPL). started with:
+Tcp port: 3306  Unix socket: /var/run/mysqld/mysqld.sock
+Time                 Id Command    Argument
+2024-01-02T03:04:05.100000Z\t    8 Connect\tapp@localhost on shop using Socket
+2024-01-02T03:04:05.200000Z\t    8 Query\tSELECT *
+FROM orders
+2024-01-02T03:04:06.000000Z\t    8 Quit\t";
+
+fn parse(log: &str) -> Vec<GeneralLogLine> {
+    let mut parser = GeneralLogParser::new();
+    log.lines().filter_map(|line| parser.feed(line)).collect()
+}
+
+#[test]
+fn test_parse_general_log() {
+    let lines = parse(LOG);
+    assert_eq!(lines.len(), 4);
+    assert_eq!(
+        lines[0],
+        GeneralLogLine::Entry(GeneralLogEntry {
+            time: "2024-01-02T03:04:05.100000Z".to_string(),
+            id: 8,
+            command: "Connect".to_string(),
+            argument: "app@localhost on shop using Socket".to_string(),
+        })
+    );
+    assert_eq!(
+        lines[2],
+        GeneralLogLine::Continuation {
+            command: "Query".to_string(),
+            text: "FROM orders".to_string(),
+        }
+    );
+    assert!(
+        matches!(&lines[3], GeneralLogLine::Entry(e) if e.command == "Quit" && e.argument.is_empty())
+    );
+}
+
+#[test]
+fn test_old_format_reuses_last_time() {
+    let lines = parse("240102  3:04:05\t    8 Query\tSELECT 1\n\t\t    8 Query\tSELECT 2");
+    assert!(
+        matches!(&lines[1], GeneralLogLine::Entry(e) if e.time == "240102  3:04:05" && e.argument == "SELECT 2")
+    );
+}
+
+#[test]
+fn test_filter_and_color_by_type() {
+    let (path, types) = parse_args("--type=Query /var/log/mysql/general.log").unwrap();
+    assert_eq!(path, "/var/log/mysql/general.log");
+
+    let lines = parse(LOG);
+    let shown: Vec<String> = lines
+        .iter()
+        .filter_map(|line| format_line(line, &types, true))
+        .collect();
+    assert_eq!(
+        shown,
+        vec![
+            "\x1b[32m2024-01-02T03:04:05.200000Z      8 Query    SELECT *\x1b[0m\n",
+            "\x1b[32mFROM orders\x1b[0m\n",
+        ]
+    );
+    assert!(parse_args("--type=Query").is_err());
+}
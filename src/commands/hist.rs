@@ -0,0 +1,209 @@
+/*!
+ * History search
+ *
+ * `\hist grep <pattern>` lists matching history entries with their numbers,
+ * `\hist run <n>` brings entry n back to the prompt and `\hist stats` reports
+ * the history's size. The history file is pruned to the newest entries after
+ * each session appends to it.
+ */
+
+use crate::platform;
+use anyhow::{anyhow, Context, Result};
+use regex::{Regex, RegexBuilder};
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// Default number of history entries kept in memory and in the history file
+pub const DEFAULT_HISTORY_SIZE: usize = 10_000;
+
+/// Header of rustyline's history file format
+const FILE_HEADER: &str = "#V2";
+
+/// Rules for what typed lines enter the line history. rustyline's auto-add and
+/// duplicate check are off: every history write goes through `entry`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HistoryFilter {
+    /// Skip a line equal to the previous entry
+    pub ignore_dups: bool,
+    /// Skip lines typed with a leading space
+    pub ignore_space: bool,
+    /// Replace password literals with `'***'`
+    pub scrub_passwords: bool,
+}
+
+impl HistoryFilter {
+    /// The history entry for a typed line, or `None` when it is not kept.
+    /// `statement` holds the earlier lines of a statement the line continues,
+    /// so a password on a line of its own after `IDENTIFIED BY` is still masked
+    pub fn entry(&self, line: &str, statement: &str, previous: Option<&str>) -> Option<String> {
+        if line.trim().is_empty() || (self.ignore_space && line.starts_with(' ')) {
+            return None;
+        }
+        let mut entry = line.trim().to_string();
+        if self.scrub_passwords {
+            entry = scrub_continued_passwords(statement, &entry);
+        }
+        if self.ignore_dups && previous == Some(entry.as_str()) {
+            return None;
+        }
+        Some(entry)
+    }
+}
+
+/// Mask the quoted password in `IDENTIFIED BY '...'`, `PASSWORD('...')` and
+/// `SET PASSWORD ... = '...'`
+pub fn scrub_passwords(line: &str) -> String {
+    password_regex().replace_all(line, "$1'***'").into_owned()
+}
+
+/// Mask the passwords in `line` that belong to the statement begun by
+/// `statement`, e.g. a literal on its own line after `IDENTIFIED BY`
+pub fn scrub_continued_passwords(statement: &str, line: &str) -> String {
+    let joined = format!("{}\n{}", statement, line);
+    let start = statement.len() + 1;
+    let mut scrubbed = String::new();
+    let mut pos = start;
+    for caps in password_regex().captures_iter(&joined) {
+        let literal = caps.get(2).unwrap();
+        if literal.end() <= start {
+            continue;
+        }
+        // A literal opened on an earlier line is masked up to its closing quote
+        let from = literal.start().max(start);
+        scrubbed.push_str(&joined[pos..from]);
+        scrubbed.push_str(if literal.start() >= start {
+            "'***'"
+        } else {
+            "***'"
+        });
+        pos = literal.end();
+    }
+    scrubbed.push_str(&joined[pos..]);
+    scrubbed
+}
+
+/// A password keyword followed by its quoted literal; quotes inside the literal
+/// are backslash-escaped or doubled
+fn password_regex() -> &'static Regex {
+    static PASSWORD: OnceLock<Regex> = OnceLock::new();
+    PASSWORD.get_or_init(|| {
+        Regex::new(
+            r#"(?i)(IDENTIFIED\s+(?:WITH\s+\S+\s+)?BY\s+|PASSWORD\s*\(\s*|SET\s+PASSWORD\s+(?:FOR\s+\S+\s*)?=\s*)('(?:[^'\\]|\\.|'')*'|"(?:[^"\\]|\\.|"")*")"#,
+        )
+        .unwrap()
+    })
+}
+
+#[derive(Debug, PartialEq)]
+pub enum HistCommand {
+    Grep(String),
+    Run(usize),
+    Stats,
+}
+
+impl HistCommand {
+    /// Parse the arguments of `\hist`
+    pub fn parse(args: &str) -> Result<Self> {
+        let usage = || anyhow!("Usage: \\hist grep <pattern> | \\hist run <n> | \\hist stats");
+        let (subcommand, rest) = args
+            .trim()
+            .split_once(char::is_whitespace)
+            .unwrap_or((args.trim(), ""));
+        let rest = rest.trim();
+        match subcommand {
+            "stats" if rest.is_empty() => Ok(HistCommand::Stats),
+            "grep" if !rest.is_empty() => Ok(HistCommand::Grep(rest.to_string())),
+            "run" => rest
+                .parse()
+                .map(HistCommand::Run)
+                .map_err(|_| anyhow!("Expected a history number, got '{}'", rest)),
+            _ => Err(usage()),
+        }
+    }
+}
+
+/// Entries matching `pattern` as `(number, entry)`, newest first. Numbers start at 1
+/// for the oldest entry. The pattern is a case-insensitive regex; text that is not a
+/// valid regex is matched literally. Earlier `\hist` commands are left out.
+pub fn grep<'a>(entries: &'a [String], pattern: &str) -> Vec<(usize, &'a str)> {
+    let regex = RegexBuilder::new(pattern)
+        .case_insensitive(true)
+        .build()
+        .or_else(|_| {
+            RegexBuilder::new(&regex::escape(pattern))
+                .case_insensitive(true)
+                .build()
+        })
+        .expect("escaped pattern is a valid regex");
+
+    entries
+        .iter()
+        .enumerate()
+        .rev()
+        .filter(|(_, entry)| !entry.starts_with("\\hist") && regex.is_match(entry))
+        .map(|(index, entry)| (index + 1, entry.as_str()))
+        .collect()
+}
+
+/// The entry with history number `number`
+pub fn resolve(entries: &[String], number: usize) -> Result<&str> {
+    number
+        .checked_sub(1)
+        .and_then(|index| entries.get(index))
+        .map(String::as_str)
+        .ok_or_else(|| {
+            anyhow!(
+                "No history entry {} (history has {} entries)",
+                number,
+                entries.len()
+            )
+        })
+}
+
+/// Rewrite the history file with its newest `max_entries` entries, leaving out
+/// entries longer than `max_entry_bytes` (0 keeps any length). Returns the number
+/// of entries removed; the file is only written when something was removed.
+pub fn prune_file(path: &Path, max_entries: usize, max_entry_bytes: usize) -> Result<usize> {
+    let content =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let mut lines = content.lines().peekable();
+    let header = lines.next_if(|line| *line == FILE_HEADER);
+    let entries: Vec<&str> = lines.collect();
+
+    let kept: Vec<&str> = entries
+        .iter()
+        .copied()
+        .filter(|entry| max_entry_bytes == 0 || entry.len() <= max_entry_bytes)
+        .collect();
+    let kept = &kept[kept.len().saturating_sub(max_entries)..];
+    let removed = entries.len() - kept.len();
+    if removed == 0 {
+        return Ok(0);
+    }
+
+    let mut pruned = String::new();
+    for line in header.iter().chain(kept) {
+        pruned.push_str(line);
+        pruned.push('\n');
+    }
+    fs::write(path, pruned).with_context(|| format!("Failed to write {}", path.display()))?;
+    platform::restrict_to_owner(path)?;
+    Ok(removed)
+}
+
+/// Number of entries and size in bytes of the history file
+pub fn file_stats(path: &Path) -> Result<(usize, u64)> {
+    let content =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let entries = content
+        .lines()
+        .enumerate()
+        .filter(|(index, line)| !(*index == 0 && *line == FILE_HEADER))
+        .count();
+    Ok((entries, content.len() as u64))
+}
+
+#[cfg(test)]
+#[path = "./hist_tests.rs"]
+mod hist_tests;
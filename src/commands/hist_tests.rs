@@ -0,0 +1,165 @@
+use super::*;
+
+fn entries() -> Vec<String> {
+    [
+        "SELECT * FROM users",
+        "UPDATE users SET name = 'x'",
+        "\\hist grep users",
+        "select count(*) from orders",
+        "SELECT * FROM users WHERE id = 1",
+    ]
+    .map(String::from)
+    .to_vec()
+}
+
+#[test]
+fn test_parse_hist_command() {
+    assert_eq!(
+        HistCommand::parse("grep from users").unwrap(),
+        HistCommand::Grep("from users".to_string())
+    );
+    assert_eq!(HistCommand::parse("run 4").unwrap(), HistCommand::Run(4));
+    assert!(HistCommand::parse("run last").is_err());
+    assert!(HistCommand::parse("grep").is_err());
+}
+
+#[test]
+fn test_grep_newest_first_with_numbers() {
+    let entries = entries();
+    assert_eq!(
+        grep(&entries, "^select .* users"),
+        vec![
+            (5, "SELECT * FROM users WHERE id = 1"),
+            (1, "SELECT * FROM users")
+        ]
+    );
+    // Not a valid regex, so matched as text
+    assert_eq!(
+        grep(&entries, "count(*"),
+        vec![(4, "select count(*) from orders")]
+    );
+}
+
+#[test]
+fn test_resolve_history_number() {
+    let entries = entries();
+    assert_eq!(resolve(&entries, 2).unwrap(), "UPDATE users SET name = 'x'");
+    assert!(resolve(&entries, 0).is_err());
+    assert!(resolve(&entries, 6).is_err());
+}
+
+#[test]
+fn test_history_filter() {
+    let filter = HistoryFilter {
+        ignore_dups: true,
+        ignore_space: true,
+        scrub_passwords: true,
+    };
+    assert_eq!(
+        filter.entry("  SELECT 1 ", "", None),
+        None,
+        "leading space hides the line"
+    );
+    assert_eq!(
+        filter.entry("SELECT 1 ", "", Some("SHOW TABLES")),
+        Some("SELECT 1".to_string())
+    );
+    assert_eq!(filter.entry("SELECT 1", "", Some("SELECT 1")), None);
+    assert_eq!(filter.entry("   ", "", None), None);
+    assert_eq!(
+        filter.entry("'pw';", "CREATE USER bob IDENTIFIED BY\n", None),
+        Some("'***';".to_string())
+    );
+
+    let keep_all = HistoryFilter {
+        ignore_dups: false,
+        ignore_space: false,
+        scrub_passwords: false,
+    };
+    assert_eq!(
+        keep_all.entry(" SELECT 1", "", Some("SELECT 1")),
+        Some("SELECT 1".to_string())
+    );
+}
+
+#[test]
+fn test_scrub_passwords() {
+    assert_eq!(
+        scrub_passwords("CREATE USER 'bob'@'%' IDENTIFIED BY 's3cr\\'et';"),
+        "CREATE USER 'bob'@'%' IDENTIFIED BY '***';"
+    );
+    assert_eq!(
+        scrub_passwords("ALTER USER bob IDENTIFIED WITH mysql_native_password BY \"pw\""),
+        "ALTER USER bob IDENTIFIED WITH mysql_native_password BY '***'"
+    );
+    assert_eq!(
+        scrub_passwords("SET PASSWORD FOR 'bob'@'%' = 'pw'"),
+        "SET PASSWORD FOR 'bob'@'%' = '***'"
+    );
+    assert_eq!(
+        scrub_passwords("SELECT PASSWORD('pw')"),
+        "SELECT PASSWORD('***')"
+    );
+    assert_eq!(
+        scrub_passwords("SELECT * FROM users"),
+        "SELECT * FROM users"
+    );
+    assert_eq!(
+        scrub_passwords("CREATE USER bob IDENTIFIED BY 'it''s' PASSWORD EXPIRE;"),
+        "CREATE USER bob IDENTIFIED BY '***' PASSWORD EXPIRE;"
+    );
+    assert_eq!(
+        scrub_passwords("SET PASSWORD = \"say \"\"hi\"\"\""),
+        "SET PASSWORD = '***'"
+    );
+}
+
+#[test]
+fn test_scrub_continued_passwords() {
+    assert_eq!(
+        scrub_continued_passwords("CREATE USER bob IDENTIFIED BY\n", "'pw';"),
+        "'***';"
+    );
+    assert_eq!(
+        scrub_continued_passwords("ALTER USER bob IDENTIFIED BY 'first\n", "line';"),
+        "***';"
+    );
+    // Passwords on earlier lines were masked when those lines were added
+    assert_eq!(
+        scrub_continued_passwords("CREATE USER bob IDENTIFIED BY 'pw'\n", "PASSWORD EXPIRE;"),
+        "PASSWORD EXPIRE;"
+    );
+    assert_eq!(
+        scrub_continued_passwords("", "SELECT PASSWORD('pw')"),
+        "SELECT PASSWORD('***')"
+    );
+}
+
+#[test]
+fn test_parse_hist_stats() {
+    assert_eq!(HistCommand::parse("stats").unwrap(), HistCommand::Stats);
+    assert_eq!(HistCommand::parse(" stats ").unwrap(), HistCommand::Stats);
+    assert!(HistCommand::parse("stats all").is_err());
+}
+
+#[test]
+fn test_prune_file_keeps_newest_entries() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("history");
+    let blob = format!("INSERT INTO t VALUES ('{}')", "x".repeat(100));
+    fs::write(
+        &path,
+        format!("#V2\nSELECT 1\nSELECT 2\n{}\nSELECT 3\nSELECT 4\n", blob),
+    )
+    .unwrap();
+
+    assert_eq!(prune_file(&path, 3, 64).unwrap(), 2);
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        "#V2\nSELECT 2\nSELECT 3\nSELECT 4\n"
+    );
+    assert_eq!(file_stats(&path).unwrap(), (3, 31));
+
+    // Nothing to remove leaves the file alone
+    assert_eq!(prune_file(&path, 10, 0).unwrap(), 0);
+}
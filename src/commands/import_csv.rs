@@ -0,0 +1,139 @@
+/*!
+ * CSV import
+ *
+ * `\import-csv` reads a CSV file whose header names table columns and loads
+ * it with batched multi-row INSERT statements
+ */
+
+use crate::commands::literal::{quote_identifier, quote_string};
+use anyhow::{anyhow, Result};
+
+/// Rows sent per INSERT statement
+pub const BATCH_SIZE: usize = 1000;
+
+/// Options of `\import-csv`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportOptions {
+    pub path: String,
+    pub table: String,
+    /// Data rows after the header that are not imported, e.g. to resume an import
+    pub skip_rows: usize,
+    pub delimiter: u8,
+}
+
+impl ImportOptions {
+    /// Parse `<path> <table> [--skip-rows=N] [--delimiter=<char>]`
+    pub fn parse(args: &str) -> Result<Self> {
+        let usage =
+            || anyhow!("Usage: \\import-csv <path> <table> [--skip-rows=N] [--delimiter=<char>]");
+        let mut positional = Vec::new();
+        let mut skip_rows = 0;
+        let mut delimiter = b',';
+        for arg in args.split_whitespace() {
+            if let Some(value) = arg.strip_prefix("--skip-rows=") {
+                skip_rows = value
+                    .parse()
+                    .map_err(|_| anyhow!("Invalid --skip-rows value: {}", value))?;
+            } else if let Some(value) = arg.strip_prefix("--delimiter=") {
+                delimiter = parse_delimiter(value)?;
+            } else if arg.starts_with("--") {
+                return Err(anyhow!("Unknown option: {}", arg));
+            } else {
+                positional.push(arg.to_string());
+            }
+        }
+        let [path, table] = <[String; 2]>::try_from(positional).map_err(|_| usage())?;
+        Ok(Self {
+            path,
+            table,
+            skip_rows,
+            delimiter,
+        })
+    }
+}
+
+/// A single-byte delimiter; `\t` and `tab` stand for a tab
+fn parse_delimiter(value: &str) -> Result<u8> {
+    let value = value.trim_matches(|c| c == '\'' || c == '"');
+    match value {
+        "\\t" | "tab" => Ok(b'\t'),
+        _ if value.len() == 1 => Ok(value.as_bytes()[0]),
+        _ => Err(anyhow!(
+            "--delimiter must be a single character, got '{}'",
+            value
+        )),
+    }
+}
+
+/// Match header names against the table's columns, case-insensitively, returning
+/// the columns' own spelling
+pub fn match_columns(header: &[String], table_columns: &[String]) -> Result<Vec<String>> {
+    header
+        .iter()
+        .map(|name| {
+            let name = name.trim();
+            table_columns
+                .iter()
+                .find(|column| column.eq_ignore_ascii_case(name))
+                .cloned()
+                .ok_or_else(|| anyhow!("CSV column '{}' is not a column of the table", name))
+        })
+        .collect()
+}
+
+/// One multi-row INSERT; empty fields of nullable columns become NULL
+pub fn insert_statement(
+    database: &str,
+    table: &str,
+    columns: &[String],
+    nullable: &[bool],
+    rows: &[Vec<String>],
+) -> String {
+    let literals: Vec<Vec<String>> = rows
+        .iter()
+        .map(|row| {
+            row.iter()
+                .zip(nullable)
+                .map(|(value, &nullable)| {
+                    if value.is_empty() && nullable {
+                        "NULL".to_string()
+                    } else {
+                        quote_string(value)
+                    }
+                })
+                .collect()
+        })
+        .collect();
+    insert_literals(database, table, columns, &literals)
+}
+
+/// One multi-row INSERT of rows that are already SQL literals
+pub fn insert_literals(
+    database: &str,
+    table: &str,
+    columns: &[String],
+    rows: &[Vec<String>],
+) -> String {
+    let column_list: Vec<String> = columns.iter().map(|c| quote_identifier(c)).collect();
+    let values: Vec<String> = rows
+        .iter()
+        .map(|row| format!("({})", row.join(", ")))
+        .collect();
+    format!(
+        "INSERT INTO {}.{} ({}) VALUES {}",
+        quote_identifier(database),
+        quote_identifier(table),
+        column_list.join(", "),
+        values.join(", ")
+    )
+}
+
+/// `Importing... 5000/12000 rows (41%)`
+pub fn progress_line(done: usize, total: usize) -> String {
+    let percent = (done * 100).checked_div(total).unwrap_or(100);
+    format!("Importing... {}/{} rows ({}%)", done, total, percent)
+}
+
+#[cfg(test)]
+#[path = "./import_csv_tests.rs"]
+mod import_csv_tests;
@@ -0,0 +1,49 @@
+use super::*;
+
+#[test]
+fn test_parse_options() {
+    let options =
+        ImportOptions::parse("data/users.csv users --skip-rows=5000 --delimiter=;").unwrap();
+    assert_eq!(
+        options,
+        ImportOptions {
+            path: "data/users.csv".to_string(),
+            table: "users".to_string(),
+            skip_rows: 5000,
+            delimiter: b';',
+        }
+    );
+    assert_eq!(
+        ImportOptions::parse("a.tsv t --delimiter=\\t")
+            .unwrap()
+            .delimiter,
+        b'\t'
+    );
+    assert!(ImportOptions::parse("a.csv").is_err());
+    assert!(ImportOptions::parse("a.csv t --delimiter=;;").is_err());
+}
+
+#[test]
+fn test_match_columns_against_table() {
+    let table = vec!["id".to_string(), "Email".to_string(), "note".to_string()];
+    let header = vec!["email".to_string(), " ID".to_string()];
+    assert_eq!(match_columns(&header, &table).unwrap(), vec!["Email", "id"]);
+    assert!(match_columns(&["name".to_string()], &table).is_err());
+}
+
+#[test]
+fn test_insert_statement_nulls_empty_nullable_fields() {
+    let columns = vec!["id".to_string(), "name".to_string(), "note".to_string()];
+    let rows = vec![
+        vec!["1".to_string(), "".to_string(), "".to_string()],
+        vec!["2".to_string(), "O'Brien".to_string(), "x".to_string()],
+    ];
+    assert_eq!(
+        insert_statement("shop", "users", &columns, &[false, false, true], &rows),
+        "INSERT INTO `shop`.`users` (`id`, `name`, `note`) VALUES ('1', '', NULL), ('2', 'O''Brien', 'x')"
+    );
+    assert_eq!(
+        progress_line(5000, 12000),
+        "Importing... 5000/12000 rows (41%)"
+    );
+}
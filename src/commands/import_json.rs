@@ -0,0 +1,103 @@
+/*!
+ * JSON import
+ *
+ * `\import-json` loads a JSON array of objects, mapping keys to table columns;
+ * nested objects go into JSON columns whole or are flattened with `_`
+ */
+
+use crate::commands::literal::quote_string;
+use anyhow::{anyhow, Result};
+use serde_json::{Map, Value};
+
+/// Parse a JSON document that must be an array of objects
+pub fn read_objects(text: &str) -> Result<Vec<Map<String, Value>>> {
+    let document: Value = serde_json::from_str(text).map_err(|e| anyhow!("Invalid JSON: {}", e))?;
+    let Value::Array(items) = document else {
+        return Err(anyhow!("Expected a JSON array of objects"));
+    };
+    items
+        .into_iter()
+        .enumerate()
+        .map(|(i, item)| match item {
+            Value::Object(object) => Ok(object),
+            _ => Err(anyhow!("Element {} of the array is not an object", i + 1)),
+        })
+        .collect()
+}
+
+/// Key/value pairs of an object; nested objects are kept whole for keys that
+/// name a JSON column and flattened to `parent_child` keys otherwise
+pub fn flatten_object(
+    object: &Map<String, Value>,
+    is_json_column: &dyn Fn(&str) -> bool,
+) -> Vec<(String, Value)> {
+    let mut pairs = Vec::new();
+    flatten_into(&mut pairs, "", object, is_json_column);
+    pairs
+}
+
+fn flatten_into(
+    pairs: &mut Vec<(String, Value)>,
+    prefix: &str,
+    object: &Map<String, Value>,
+    is_json_column: &dyn Fn(&str) -> bool,
+) {
+    for (key, value) in object {
+        let key = format!("{}{}", prefix, key);
+        match value {
+            Value::Object(nested) if !is_json_column(&key) => {
+                flatten_into(pairs, &format!("{}_", key), nested, is_json_column)
+            }
+            _ => pairs.push((key, value.clone())),
+        }
+    }
+}
+
+/// Table columns used by the rows, in order of first appearance, and the keys
+/// that match no column
+pub fn match_keys(
+    rows: &[Vec<(String, Value)>],
+    table_columns: &[String],
+) -> (Vec<String>, Vec<String>) {
+    let mut columns: Vec<String> = Vec::new();
+    let mut unknown: Vec<String> = Vec::new();
+    for (key, _) in rows.iter().flatten() {
+        match table_columns
+            .iter()
+            .find(|column| column.eq_ignore_ascii_case(key))
+        {
+            Some(column) if !columns.contains(column) => columns.push(column.clone()),
+            Some(_) => {}
+            None if !unknown.contains(key) => unknown.push(key.clone()),
+            None => {}
+        }
+    }
+    (columns, unknown)
+}
+
+/// SQL literals of a row for `columns`; keys an object lacks get the column DEFAULT
+pub fn row_literals(row: &[(String, Value)], columns: &[String]) -> Vec<String> {
+    columns
+        .iter()
+        .map(|column| {
+            row.iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case(column))
+                .map_or_else(|| "DEFAULT".to_string(), |(_, value)| literal(value))
+        })
+        .collect()
+}
+
+/// Render a JSON value as a SQL literal; arrays and objects are JSON-encoded
+fn literal(value: &Value) -> String {
+    match value {
+        Value::Null => "NULL".to_string(),
+        Value::Bool(b) => if *b { "1" } else { "0" }.to_string(),
+        Value::Number(n) => n.to_string(),
+        Value::String(s) => quote_string(s),
+        Value::Array(_) | Value::Object(_) => quote_string(&value.to_string()),
+    }
+}
+
+#[cfg(test)]
+#[path = "./import_json_tests.rs"]
+mod import_json_tests;
@@ -0,0 +1,84 @@
+use super::*;
+
+const USERS: &str = r#"[
+    {"id": 1, "name": "Ann", "address": {"city": "Oslo", "zip": "0150"}, "prefs": {"theme": "dark"}},
+    {"id": 2, "name": null, "active": true, "tags": ["a", "b"], "nickname": "bo"}
+]"#;
+
+fn table() -> Vec<String> {
+    [
+        "id",
+        "Name",
+        "address_city",
+        "address_zip",
+        "prefs",
+        "active",
+        "tags",
+    ]
+    .iter()
+    .map(|c| c.to_string())
+    .collect()
+}
+
+#[test]
+fn test_flatten_nested_objects_unless_json_column() {
+    let objects = read_objects(USERS).unwrap();
+    let keys: Vec<String> = flatten_object(&objects[0], &|key| key == "prefs")
+        .into_iter()
+        .map(|(key, _)| key)
+        .collect();
+    assert_eq!(
+        keys,
+        vec!["address_city", "address_zip", "id", "name", "prefs"]
+    );
+    assert!(read_objects("{\"id\": 1}").is_err());
+    assert!(read_objects("[1, 2]").is_err());
+}
+
+#[test]
+fn test_match_keys_and_literals() {
+    let rows: Vec<Vec<(String, Value)>> = read_objects(USERS)
+        .unwrap()
+        .iter()
+        .map(|object| flatten_object(object, &|key| key == "prefs"))
+        .collect();
+    let (columns, unknown) = match_keys(&rows, &table());
+    assert_eq!(
+        columns,
+        vec![
+            "address_city",
+            "address_zip",
+            "id",
+            "Name",
+            "prefs",
+            "active",
+            "tags"
+        ]
+    );
+    assert_eq!(unknown, vec!["nickname"]);
+
+    assert_eq!(
+        row_literals(&rows[0], &columns),
+        vec![
+            "'Oslo'",
+            "'0150'",
+            "1",
+            "'Ann'",
+            "'{\"theme\":\"dark\"}'",
+            "DEFAULT",
+            "DEFAULT"
+        ]
+    );
+    assert_eq!(
+        row_literals(&rows[1], &columns),
+        vec![
+            "DEFAULT",
+            "DEFAULT",
+            "2",
+            "NULL",
+            "DEFAULT",
+            "1",
+            "'[\"a\",\"b\"]'"
+        ]
+    );
+}
@@ -0,0 +1,126 @@
+use crate::database::Connection;
+use anyhow::{anyhow, Result};
+use mysql::prelude::*;
+
+/// Cardinality ratio (percent) at or above which an index is considered selective
+const HIGH_CARDINALITY_PERCENT: f64 = 30.0;
+/// Cardinality ratio (percent) below which the optimizer may ignore the index
+const LOW_CARDINALITY_PERCENT: f64 = 1.0;
+
+/// One index with its columns and estimated cardinality
+#[derive(Debug, Clone)]
+pub struct IndexStat {
+    pub name: String,
+    pub columns: Vec<String>,
+    pub unique: bool,
+    /// Estimated distinct values for the full index prefix
+    pub cardinality: u64,
+}
+
+impl IndexStat {
+    /// Effective cardinality as a percentage of the table rows
+    pub fn cardinality_percent(&self, total_rows: u64) -> f64 {
+        if total_rows == 0 {
+            return 0.0;
+        }
+        (self.cardinality as f64 / total_rows as f64 * 100.0).min(100.0)
+    }
+}
+
+/// Cardinality classification for display
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CardinalityLevel {
+    High,
+    Medium,
+    Low,
+}
+
+impl CardinalityLevel {
+    pub fn from_percent(percent: f64) -> Self {
+        if percent >= HIGH_CARDINALITY_PERCENT {
+            CardinalityLevel::High
+        } else if percent < LOW_CARDINALITY_PERCENT {
+            CardinalityLevel::Low
+        } else {
+            CardinalityLevel::Medium
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            CardinalityLevel::High => "high cardinality",
+            CardinalityLevel::Medium => "medium cardinality",
+            CardinalityLevel::Low => "low cardinality, index may not be used",
+        }
+    }
+}
+
+/// Load index statistics and the estimated row count for `schema`.`table`
+pub fn fetch_index_stats(
+    connection: &mut Connection,
+    schema: &str,
+    table: &str,
+) -> Result<(Vec<IndexStat>, u64)> {
+    let conn = connection.get_conn_mut();
+
+    let total_rows: Option<Option<u64>> = conn.exec_first(
+        "SELECT TABLE_ROWS FROM information_schema.TABLES \
+         WHERE TABLE_SCHEMA = ? AND TABLE_NAME = ?",
+        (schema, table),
+    )?;
+    let total_rows = total_rows
+        .ok_or_else(|| anyhow!("Table '{}.{}' doesn't exist", schema, table))?
+        .unwrap_or(0);
+
+    let rows: Vec<(String, String, u8, Option<u64>)> = conn.exec(
+        "SELECT INDEX_NAME, COLUMN_NAME, NON_UNIQUE, CARDINALITY \
+         FROM information_schema.STATISTICS \
+         WHERE TABLE_SCHEMA = ? AND TABLE_NAME = ? \
+         ORDER BY INDEX_NAME, SEQ_IN_INDEX",
+        (schema, table),
+    )?;
+
+    let mut stats: Vec<IndexStat> = Vec::new();
+    for (index_name, column_name, non_unique, cardinality) in rows {
+        match stats.last_mut() {
+            Some(stat) if stat.name == index_name => {
+                stat.columns.push(column_name);
+                stat.cardinality = cardinality.unwrap_or(stat.cardinality);
+            }
+            _ => stats.push(IndexStat {
+                name: index_name,
+                columns: vec![column_name],
+                unique: non_unique == 0,
+                cardinality: cardinality.unwrap_or(0),
+            }),
+        }
+    }
+
+    Ok((stats, total_rows))
+}
+
+/// Return the indexed columns referenced in the WHERE clause of `query`
+pub fn where_columns_matching(query: &str, columns: &[String]) -> Vec<String> {
+    let upper = query.to_uppercase();
+    let Some(where_pos) = upper.find("WHERE") else {
+        return Vec::new();
+    };
+
+    let clause_end = ["GROUP BY", "ORDER BY", "LIMIT", "HAVING"]
+        .iter()
+        .filter_map(|kw| upper[where_pos..].find(kw).map(|i| where_pos + i))
+        .min()
+        .unwrap_or(upper.len());
+    let clause = &upper[where_pos + "WHERE".len()..clause_end];
+
+    let words: Vec<&str> = clause
+        .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .filter(|w| !w.is_empty())
+        .collect();
+
+    columns
+        .iter()
+        .filter(|column| words.contains(&column.to_uppercase().as_str()))
+        .cloned()
+        .collect()
+}
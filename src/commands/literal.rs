@@ -0,0 +1,107 @@
+/*!
+ * SQL literals
+ *
+ * Escaping shared by commands that generate SQL from values
+ */
+
+use mysql::Value;
+use std::cell::Cell;
+
+thread_local! {
+    /// Whether the session reads `\` in string literals as an escape, i.e.
+    /// its sql_mode lacks NO_BACKSLASH_ESCAPES
+    static BACKSLASH_ESCAPES: Cell<bool> = const { Cell::new(true) };
+}
+
+/// Quote strings for a session with this sql_mode from now on
+pub fn set_sql_mode(sql_mode: &str) {
+    let no_escapes = sql_mode
+        .split(',')
+        .any(|flag| flag.trim().eq_ignore_ascii_case("NO_BACKSLASH_ESCAPES"));
+    BACKSLASH_ESCAPES.with(|escapes| escapes.set(!no_escapes));
+}
+
+/// Quote a string as a single-quoted SQL literal; backslashes are doubled
+/// unless the session has NO_BACKSLASH_ESCAPES
+pub fn quote_string(value: &str) -> String {
+    let value = if BACKSLASH_ESCAPES.with(Cell::get) {
+        value.replace('\\', "\\\\")
+    } else {
+        value.to_string()
+    };
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+/// Whether text is one complete single-quoted literal: quotes inside it doubled
+/// and, unless the session has NO_BACKSLASH_ESCAPES, backslashes escaping a character
+pub fn is_quoted_string(text: &str) -> bool {
+    let Some(inner) = text
+        .strip_prefix('\'')
+        .and_then(|text| text.strip_suffix('\''))
+    else {
+        return false;
+    };
+    let escapes = BACKSLASH_ESCAPES.with(Cell::get);
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        let escaped = match c {
+            '\'' => chars.next() == Some('\''),
+            '\\' if escapes => chars.next().is_some(),
+            _ => true,
+        };
+        if !escaped {
+            return false;
+        }
+    }
+    true
+}
+
+/// Quote an identifier with backticks
+pub fn quote_identifier(name: &str) -> String {
+    format!("`{}`", name.replace('`', "``"))
+}
+
+/// Render a server value as a SQL literal; bytes that are not valid UTF-8
+/// become a hex literal so binary data round-trips
+pub fn value_literal(value: &Value) -> String {
+    match value {
+        Value::NULL => "NULL".to_string(),
+        Value::Bytes(bytes) => match std::str::from_utf8(bytes) {
+            Ok(text) => quote_string(text),
+            Err(_) => format!(
+                "X'{}'",
+                bytes
+                    .iter()
+                    .map(|b| format!("{:02X}", b))
+                    .collect::<String>()
+            ),
+        },
+        Value::Int(i) => i.to_string(),
+        Value::UInt(u) => u.to_string(),
+        Value::Float(f) => f.to_string(),
+        Value::Double(d) => d.to_string(),
+        Value::Date(year, month, day, hour, minute, second, micro) => {
+            let mut date = format!("{:04}-{:02}-{:02}", year, month, day);
+            if (*hour, *minute, *second, *micro) != (0, 0, 0, 0) {
+                date.push_str(&format!(" {:02}:{:02}:{:02}", hour, minute, second));
+            }
+            if *micro != 0 {
+                date.push_str(&format!(".{:06}", micro));
+            }
+            quote_string(&date)
+        }
+        Value::Time(negative, days, hours, minutes, seconds, micro) => {
+            let sign = if *negative { "-" } else { "" };
+            let hours = *days * 24 + u32::from(*hours);
+            let mut time = format!("{}{:02}:{:02}:{:02}", sign, hours, minutes, seconds);
+            if *micro != 0 {
+                time.push_str(&format!(".{:06}", micro));
+            }
+            quote_string(&time)
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "./literal_tests.rs"]
+mod literal_tests;
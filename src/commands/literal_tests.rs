@@ -0,0 +1,34 @@
+use super::*;
+
+#[test]
+fn test_quote_string_follows_sql_mode() {
+    assert_eq!(quote_string("O'Brien\\x"), "'O''Brien\\\\x'");
+
+    set_sql_mode("STRICT_TRANS_TABLES,NO_BACKSLASH_ESCAPES");
+    assert_eq!(quote_string("O'Brien\\x"), "'O''Brien\\x'");
+    assert_eq!(
+        value_literal(&Value::Bytes(b"C:\\tmp".to_vec())),
+        "'C:\\tmp'"
+    );
+
+    set_sql_mode("STRICT_TRANS_TABLES");
+    assert_eq!(quote_string("a\\b"), "'a\\\\b'");
+}
+
+#[test]
+fn test_quote_identifier() {
+    assert_eq!(quote_identifier("odd`name"), "`odd``name`");
+}
+
+#[test]
+fn test_is_quoted_string() {
+    assert!(is_quoted_string("'it''s'"));
+    assert!(is_quoted_string("'it\\'s'"));
+    assert!(!is_quoted_string("'a'b'"));
+    assert!(!is_quoted_string("'a\\'"));
+    assert!(!is_quoted_string("'"));
+
+    set_sql_mode("NO_BACKSLASH_ESCAPES");
+    assert!(is_quoted_string("'a\\'"));
+    assert!(!is_quoted_string("'it\\'s'"));
+}
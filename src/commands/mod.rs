@@ -1,2 +1,30 @@
+pub mod admin;
+pub mod advisor;
+pub mod alias;
+pub mod charset;
+pub mod check;
+pub mod clone_row;
+pub mod credential;
+pub mod edit_row;
+pub mod find;
+pub mod generallog;
+pub mod hist;
+pub mod import_csv;
+pub mod import_json;
+pub mod index_stats;
+pub mod literal;
+pub mod pipe;
+pub mod protect;
 pub mod query;
-pub use query::QueryExecutor;
+pub mod read_only;
+pub mod schema_dump;
+pub mod session;
+pub mod slowlog;
+pub mod sql_mode;
+pub mod suggest;
+pub mod tail;
+pub mod transaction;
+pub mod wizard;
+pub mod xlsx;
+pub mod xml;
+pub use query::{DisplayFormat, QueryExecutor};
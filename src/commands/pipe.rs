@@ -0,0 +1,60 @@
+use super::literal;
+use crate::database::QueryResult;
+use anyhow::{anyhow, Result};
+
+/// Placeholder replaced by the values collected with `\useids`
+pub const IDS_PLACEHOLDER: &str = "${ids}";
+
+/// Collect the distinct non-NULL values of a result column as a SQL list body,
+/// e.g. `1, 2, 'x'`, returning the list and the number of values
+pub fn values_list(result: &QueryResult, column: &str) -> Result<(String, usize)> {
+    // Duplicate names are told apart by their unique names, e.g. `b.id`
+    let index = result
+        .unique_columns()
+        .iter()
+        .position(|c| c.eq_ignore_ascii_case(column))
+        .or_else(|| {
+            result
+                .columns
+                .iter()
+                .position(|c| c.eq_ignore_ascii_case(column))
+        })
+        .ok_or_else(|| {
+            anyhow!(
+                "No column '{}' in the last result (columns: {})",
+                column,
+                result.columns.join(", ")
+            )
+        })?;
+
+    let mut values: Vec<&str> = Vec::new();
+    for row in &result.rows {
+        let value = row[index].as_str();
+        if value != "NULL" && !values.contains(&value) {
+            values.push(value);
+        }
+    }
+    if values.is_empty() {
+        return Err(anyhow!("Column '{}' has no non-NULL values", column));
+    }
+
+    let list = values
+        .iter()
+        .map(|value| sql_literal(value))
+        .collect::<Vec<_>>()
+        .join(", ");
+    Ok((list, values.len()))
+}
+
+/// Numbers stay bare, everything else becomes a quoted string
+fn sql_literal(value: &str) -> String {
+    if value.parse::<i64>().is_ok() || value.parse::<f64>().is_ok_and(|v| v.is_finite()) {
+        value.to_string()
+    } else {
+        literal::quote_string(value)
+    }
+}
+
+#[cfg(test)]
+#[path = "./pipe_tests.rs"]
+mod pipe_tests;
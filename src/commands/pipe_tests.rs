@@ -0,0 +1,29 @@
+use super::*;
+
+fn create_test_result() -> QueryResult {
+    QueryResult {
+        tables: Vec::new(),
+        nulls: Vec::new(),
+        columns: vec!["id".to_string(), "code".to_string()],
+        rows: vec![
+            vec!["3".to_string(), "a'b".to_string()],
+            vec!["7".to_string(), "NULL".to_string()],
+            vec!["3".to_string(), "c".to_string()],
+        ],
+    }
+}
+
+#[test]
+fn test_values_list() {
+    let result = create_test_result();
+    assert_eq!(values_list(&result, "ID").unwrap(), ("3, 7".to_string(), 2));
+    assert_eq!(
+        values_list(&result, "code").unwrap(),
+        ("'a''b', 'c'".to_string(), 2)
+    );
+}
+
+#[test]
+fn test_values_list_unknown_column() {
+    assert!(values_list(&create_test_result(), "missing").is_err());
+}
@@ -0,0 +1,127 @@
+/*!
+ * Protected databases
+ *
+ * Destructive statements (DELETE, UPDATE, DROP, TRUNCATE) against databases
+ * marked as protected need an explicit confirmation before they run
+ */
+
+use regex::Regex;
+use sqlparser::ast::{ObjectName, ObjectType, Statement, TableFactor, TableWithJoins};
+use sqlparser::dialect::MySqlDialect;
+use sqlparser::parser::Parser;
+use std::sync::OnceLock;
+
+/// Databases a destructive statement modifies; `None` stands for the current database
+pub fn destructive_targets(sql: &str) -> Vec<Option<String>> {
+    let statements = match Parser::parse_sql(&MySqlDialect {}, sql) {
+        Ok(statements) => statements,
+        // DROP DATABASE and forms such as UPDATE ... LIMIT are not understood by the parser
+        Err(_) => return unparsed_targets(sql),
+    };
+
+    let mut targets = Vec::new();
+    for statement in &statements {
+        match statement {
+            Statement::Delete { tables, from, .. } => {
+                if tables.is_empty() {
+                    targets.extend(from.iter().flat_map(relations).map(database_of));
+                } else {
+                    targets.extend(tables.iter().map(database_of));
+                }
+            }
+            Statement::Update { table, .. } => {
+                targets.extend(relations(table).map(database_of));
+            }
+            Statement::Truncate { table_name, .. } => targets.push(database_of(table_name)),
+            Statement::Drop {
+                object_type: ObjectType::Schema,
+                names,
+                ..
+            } => targets.extend(
+                names
+                    .iter()
+                    .filter_map(|name| name.0.last())
+                    .map(|ident| Some(ident.value.clone())),
+            ),
+            Statement::Drop { names, .. } => targets.extend(names.iter().map(database_of)),
+            _ => {}
+        }
+    }
+    targets
+}
+
+/// The first protected database a statement would modify
+pub fn protected_target(
+    sql: &str,
+    current_database: Option<&str>,
+    protected: &[String],
+) -> Option<String> {
+    destructive_targets(sql)
+        .into_iter()
+        .filter_map(|db| db.or_else(|| current_database.map(str::to_string)))
+        .find(|db| protected.iter().any(|p| p.eq_ignore_ascii_case(db)))
+}
+
+/// Tables named in a FROM item and its joins
+fn relations(table: &TableWithJoins) -> impl Iterator<Item = &ObjectName> {
+    std::iter::once(&table.relation)
+        .chain(table.joins.iter().map(|join| &join.relation))
+        .filter_map(|factor| match factor {
+            TableFactor::Table { name, .. } => Some(name),
+            _ => None,
+        })
+}
+
+/// Database part of a `db.table` name, if qualified
+fn database_of(name: &ObjectName) -> Option<String> {
+    match name.0.as_slice() {
+        [database, _] => Some(database.value.clone()),
+        _ => None,
+    }
+}
+
+/// Targets of a statement the parser rejected, judged by its first keyword:
+/// every `db.` qualifier in the text plus the current database, so that an
+/// unparseable destructive statement still asks for confirmation
+fn unparsed_targets(sql: &str) -> Vec<Option<String>> {
+    if let Some(database) = drop_database_target(sql) {
+        return vec![Some(database)];
+    }
+    let first_word = sql
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .to_uppercase();
+    if !matches!(
+        first_word.as_str(),
+        "DELETE" | "UPDATE" | "DROP" | "TRUNCATE"
+    ) {
+        return Vec::new();
+    }
+
+    static QUALIFIER: OnceLock<Regex> = OnceLock::new();
+    let qualifier = QUALIFIER.get_or_init(|| {
+        Regex::new(r"(?:`([^`]+)`|\b([A-Za-z_$][\w$]*))\s*\.\s*[`A-Za-z_$]").unwrap()
+    });
+    let mut targets: Vec<Option<String>> = qualifier
+        .captures_iter(sql)
+        .filter_map(|caps| caps.get(1).or(caps.get(2)))
+        .map(|name| Some(name.as_str().to_string()))
+        .collect();
+    targets.push(None);
+    targets
+}
+
+/// Database dropped by a `DROP DATABASE` statement
+fn drop_database_target(sql: &str) -> Option<String> {
+    static DROP_DATABASE: OnceLock<Regex> = OnceLock::new();
+    let drop_database = DROP_DATABASE.get_or_init(|| {
+        Regex::new(r"(?i)^\s*DROP\s+(?:DATABASE|SCHEMA)\s+(?:IF\s+EXISTS\s+)?`?([^`\s;]+)`?")
+            .unwrap()
+    });
+    drop_database.captures(sql).map(|caps| caps[1].to_string())
+}
+
+#[cfg(test)]
+#[path = "./protect_tests.rs"]
+mod protect_tests;
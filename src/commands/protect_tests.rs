@@ -0,0 +1,72 @@
+use super::*;
+
+fn protected() -> Vec<String> {
+    vec!["prod".to_string()]
+}
+
+#[test]
+fn test_destructive_statements_on_protected_database() {
+    let protected = protected();
+    for sql in [
+        "DELETE FROM prod.users WHERE id = 1",
+        "UPDATE prod.users SET name = 'x'",
+        "TRUNCATE TABLE prod.logs",
+        "DROP TABLE IF EXISTS prod.logs",
+        "DROP DATABASE prod",
+    ] {
+        assert_eq!(
+            protected_target(sql, Some("dev"), &protected).as_deref(),
+            Some("prod"),
+            "{}",
+            sql
+        );
+    }
+}
+
+#[test]
+fn test_unqualified_tables_use_current_database() {
+    let protected = protected();
+    assert_eq!(
+        protected_target("DELETE FROM users", Some("PROD"), &protected).as_deref(),
+        Some("PROD")
+    );
+    assert_eq!(
+        protected_target("DELETE FROM users", Some("dev"), &protected),
+        None
+    );
+    assert_eq!(
+        protected_target("SELECT * FROM prod.users", Some("prod"), &protected),
+        None
+    );
+}
+
+#[test]
+fn test_unparseable_destructive_statements() {
+    let protected = protected();
+    for sql in [
+        "UPDATE prod.users SET a = 1 LIMIT 1",
+        "UPDATE `prod`.users SET a = 1 ORDER BY id LIMIT 10",
+        "DELETE FROM prod.users ORDER BY id LIMIT 10",
+        "DELETE LOW_PRIORITY QUICK FROM prod.logs WHERE id < 5 LIMIT 100",
+    ] {
+        assert_eq!(
+            protected_target(sql, Some("dev"), &protected).as_deref(),
+            Some("prod"),
+            "{}",
+            sql
+        );
+    }
+    // Without a qualifier the current database decides
+    assert_eq!(
+        protected_target("UPDATE users SET a = 1 LIMIT 1", Some("prod"), &protected).as_deref(),
+        Some("prod")
+    );
+    assert_eq!(
+        protected_target("UPDATE users SET a = 1.5 LIMIT 1", Some("dev"), &protected),
+        None
+    );
+    assert_eq!(
+        protected_target("SELECT * FROM prod.users LIMIT", Some("prod"), &protected),
+        None
+    );
+}
@@ -1,16 +1,310 @@
-use crate::database::{Connection, QueryResult};
+use crate::commands::{suggest, xml};
+use crate::completion::metadata::DatabaseMetadata;
+use crate::database::{Connection, ExecOutcome, QueryResult};
+use crate::parsers::explain;
+use crate::util;
 use anyhow::Result;
 use comfy_table::{Attribute, Cell, ContentArrangement, Table};
-use std::time::Instant;
+use lru::LruCache;
+use regex::Regex;
+use rustyline::{Cmd, ConditionalEventHandler, Event, EventContext, ExternalPrinter, RepeatCount};
+use std::io::{IsTerminal, Write};
+use std::num::NonZeroUsize;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use unicode_width::UnicodeWidthStr;
 
-pub struct QueryExecutor;
+/// Number of SELECT results kept by the query cache
+const RESULT_CACHE_SIZE: usize = 50;
+
+/// Server error raised when MAX_EXECUTION_TIME interrupts a statement
+const ER_QUERY_TIMEOUT: u16 = 3024;
+
+/// How a statement ended, shown by the summary line marker
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Outcome {
+    Success,
+    /// Succeeded but the server raised warnings
+    Warning,
+    Error,
+}
+
+/// How a wide result set is laid out at the terminal
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Layout {
+    Table,
+    /// A table preceded by a hint to show the rows vertically
+    TableWithHint,
+    /// Rows as vertical blocks; `notice` when chosen for a very wide result
+    /// rather than by `auto_vertical_output`
+    Vertical {
+        notice: bool,
+    },
+}
+
+/// Pick the layout of a result with `columns` columns whose table is
+/// `table_width` characters wide; `terminal_width` is `None` when output
+/// isn't a terminal, and `threshold` 0 turns the column check off
+pub fn choose_layout(
+    columns: usize,
+    table_width: usize,
+    terminal_width: Option<usize>,
+    threshold: usize,
+    auto_vertical: bool,
+) -> Layout {
+    let Some(terminal_width) = terminal_width else {
+        return Layout::Table;
+    };
+    if auto_vertical && table_width > terminal_width {
+        Layout::Vertical { notice: false }
+    } else if threshold == 0 || columns <= threshold {
+        Layout::Table
+    } else if table_width > terminal_width * 3 {
+        Layout::Vertical { notice: true }
+    } else {
+        Layout::TableWithHint
+    }
+}
+
+/// How result sets are shown
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DisplayFormat {
+    Table,
+    /// Only the row count and timing (`--count-only`); rows are counted, not kept
+    CountOnly,
+    /// An XML document per result set, without the row count (`--xml`)
+    Xml,
+}
+
+pub struct QueryExecutor {
+    /// Cached session `sql_select_limit`, fetched on first use
+    sql_select_limit: Option<u64>,
+    /// Most recent result set, kept for follow-up commands such as `\useids`;
+    /// shared with the key binding that re-displays it vertically
+    last_result: Arc<Mutex<Option<QueryResult>>>,
+    /// Print rows as `column: value` blocks instead of a table
+    vertical: bool,
+    display_format: DisplayFormat,
+    /// Recent SELECT results by query text, with the time they were fetched
+    result_cache: LruCache<String, (QueryResult, Instant)>,
+    /// How long cached results stay valid; `None` disables the cache
+    cache_ttl: Option<Duration>,
+    /// Whether the most recent statement failed
+    last_failed: bool,
+    /// Prefix summary lines with a success/error marker
+    marker: bool,
+    /// AUTO_INCREMENT value generated by the most recent INSERT, shown by `\s`
+    last_insert_id: Option<u64>,
+    /// Execution time limit applied to the running statement by `\maxtime`
+    time_limit: Option<u64>,
+    /// How the most recent statement ended
+    last_outcome: Outcome,
+    /// Print every single-column result as raw text, not just EXPLAIN trees
+    raw_single_column: bool,
+    /// EXPLAIN ANALYZE steps taking at least this many milliseconds are shown in red
+    explain_slow_ms: f64,
+    /// Show results wider than the terminal vertically
+    auto_vertical: bool,
+    /// Results with more columns get a hint to show them vertically; 0 disables
+    wide_result_columns: usize,
+    /// Print the header row; without it rows are tab-separated lines with no border
+    column_names: bool,
+    /// Print only result data and errors, no row counts, timing or hints (`--silent`)
+    silent: bool,
+    /// Leave special characters in tab-separated rows unescaped (`--raw`)
+    raw: bool,
+    /// Schema metadata for "Did you mean" suggestions after unknown table and column errors
+    metadata: Option<Arc<Mutex<DatabaseMetadata>>>,
+}
 
 impl QueryExecutor {
     pub fn new() -> Self {
-        QueryExecutor
+        QueryExecutor {
+            sql_select_limit: None,
+            last_result: Arc::new(Mutex::new(None)),
+            vertical: false,
+            display_format: DisplayFormat::Table,
+            result_cache: LruCache::new(NonZeroUsize::new(RESULT_CACHE_SIZE).unwrap()),
+            cache_ttl: None,
+            last_failed: false,
+            marker: false,
+            time_limit: None,
+            last_insert_id: None,
+            last_outcome: Outcome::Success,
+            raw_single_column: false,
+            explain_slow_ms: 100.0,
+            auto_vertical: false,
+            wide_result_columns: 25,
+            column_names: true,
+            silent: false,
+            raw: false,
+            metadata: None,
+        }
+    }
+
+    /// Print or leave out the header row (`--no-column-names`)
+    pub fn set_column_names(&mut self, column_names: bool) {
+        self.column_names = column_names;
+    }
+
+    /// Print tab-separated values without escaping (`--raw`)
+    pub fn set_raw(&mut self, raw: bool) {
+        self.raw = raw;
+    }
+
+    /// Suggest close names from this metadata after unknown table and column errors
+    pub fn set_metadata(&mut self, metadata: Arc<Mutex<DatabaseMetadata>>) {
+        self.metadata = Some(metadata);
+    }
+
+    /// "Did you mean" line for an unknown table or column error, once the
+    /// metadata has been loaded
+    fn did_you_mean(
+        &self,
+        connection: &mut Connection,
+        code: u16,
+        message: &str,
+    ) -> Option<String> {
+        let unknown = suggest::unknown_name(code, message)?;
+        // No names to compare with, so spare the server the database lookup
+        if !self.metadata.as_ref()?.lock().ok()?.is_loaded() {
+            return None;
+        }
+        let current_database = match unknown {
+            suggest::UnknownName::Column(_) => connection.current_database().ok().flatten(),
+            suggest::UnknownName::Table { .. } => None,
+        };
+        let meta = self.metadata.as_ref()?.lock().ok()?;
+        suggest::did_you_mean(&meta, current_database.as_deref(), &unknown)
+    }
+
+    /// Leave out row counts, timing and hints (`--silent`)
+    pub fn set_silent(&mut self, silent: bool) {
+        self.silent = silent;
+    }
+
+    /// Show results that don't fit the terminal width vertically
+    pub fn set_auto_vertical(&mut self, auto_vertical: bool) {
+        self.auto_vertical = auto_vertical;
+    }
+
+    /// Column count above which a result gets a hint to show it vertically
+    pub fn set_wide_result_columns(&mut self, columns: usize) {
+        self.wide_result_columns = columns;
+    }
+
+    /// Print single-column results as raw text instead of a table
+    pub fn set_raw_single_column(&mut self, raw: bool) {
+        self.raw_single_column = raw;
+    }
+
+    /// Time in milliseconds from which EXPLAIN ANALYZE timings are highlighted as slow
+    pub fn set_explain_slow_ms(&mut self, ms: u64) {
+        self.explain_slow_ms = ms as f64;
+    }
+
+    /// Turn the success/error marker on summary lines on or off
+    pub fn set_marker(&mut self, marker: bool) {
+        self.marker = marker;
+    }
+
+    /// Print a statement's summary line, with its marker when enabled
+    pub fn summary(&mut self, outcome: Outcome, line: &str) {
+        self.last_outcome = outcome;
+        if self.silent && outcome != Outcome::Error {
+            return;
+        }
+        let marker = self.marker.then(|| !std::io::stdout().is_terminal());
+        println!("{}", format_summary(outcome, line, marker));
+    }
+
+    /// Enable the SELECT result cache with a time-to-live, or disable it with `None`
+    pub fn set_result_cache(&mut self, ttl: Option<Duration>) {
+        if ttl.is_none() {
+            self.result_cache.clear();
+        }
+        self.cache_ttl = ttl;
+    }
+
+    /// Drop every cached result
+    pub fn clear_cache(&mut self) {
+        self.result_cache.clear();
     }
 
-    pub fn execute(&self, connection: &mut Connection, query: &str) -> Result<()> {
+    /// A cached result for the query that is still within the TTL, with its age
+    fn cached_result(&mut self, query: &str) -> Option<(QueryResult, Duration)> {
+        let ttl = self.cache_ttl?;
+        let (result, fetched) = self.result_cache.get(query)?;
+        let age = fetched.elapsed();
+        if age <= ttl {
+            return Some((result.clone(), age));
+        }
+        self.result_cache.pop(query);
+        None
+    }
+
+    /// Switch between table and vertical (`\G`) output
+    pub fn set_vertical(&mut self, vertical: bool) {
+        self.vertical = vertical;
+    }
+
+    pub fn set_display_format(&mut self, format: DisplayFormat) {
+        self.display_format = format;
+    }
+
+    pub fn last_outcome(&self) -> Outcome {
+        self.last_outcome
+    }
+
+    pub fn last_insert_id(&self) -> Option<u64> {
+        self.last_insert_id
+    }
+
+    /// Client-configured execution time limit of the next statement, for its error message
+    pub fn set_time_limit(&mut self, ms: Option<u64>) {
+        self.time_limit = ms;
+    }
+
+    /// Forget the most recent result set
+    pub fn clear_last_result(&mut self) {
+        if let Ok(mut last_result) = self.last_result.lock() {
+            *last_result = None;
+        }
+    }
+
+    /// The most recent result set with columns, if any
+    pub fn last_result(&self) -> Option<QueryResult> {
+        self.last_result
+            .lock()
+            .ok()
+            .and_then(|result| result.clone())
+    }
+
+    /// Handle on the most recent result set for key bindings
+    pub fn shared_last_result(&self) -> Arc<Mutex<Option<QueryResult>>> {
+        self.last_result.clone()
+    }
+
+    /// Get the session `sql_select_limit`, querying the server only when not cached
+    pub fn sql_select_limit(&mut self, connection: &mut Connection) -> Option<u64> {
+        if self.sql_select_limit.is_none() {
+            // A failed lookup counts as no limit, so it isn't repeated for every SELECT
+            self.sql_select_limit = Some(connection.sql_select_limit().unwrap_or(u64::MAX));
+        }
+        self.sql_select_limit
+    }
+
+    /// Forget cached session variables (after reconnecting)
+    pub fn reset_session_cache(&mut self) {
+        self.sql_select_limit = None;
+    }
+
+    /// Whether the most recent statement ended with an error
+    pub fn last_failed(&self) -> bool {
+        self.last_failed
+    }
+
+    pub fn execute(&mut self, connection: &mut Connection, query: &str) -> Result<()> {
         let start_time = Instant::now();
 
         // Check if query is empty
@@ -18,55 +312,242 @@ impl QueryExecutor {
             return Ok(());
         }
 
-        match connection.execute_query(query) {
-            Ok(result) => {
+        // Serve a repeated SELECT from the cache; any other statement may change data
+        let query_upper = query.trim_start().to_uppercase();
+        let count_only = self.display_format == DisplayFormat::CountOnly;
+        let cacheable = self.cache_ttl.is_some() && !count_only && is_cacheable(&query_upper);
+        let cache_key = if cacheable {
+            util::normalize_query(query)
+        } else {
+            String::new()
+        };
+        if cacheable {
+            if let Some((result, age)) = self.cached_result(&cache_key) {
+                let timing = format!("cached, {} secs ago", age.as_secs());
+                self.last_failed = false;
+                self.show_result(connection, query, result, &timing, 0);
+                return Ok(());
+            }
+        } else {
+            self.result_cache.clear();
+        }
+
+        let (result, row_count) = if count_only {
+            match connection.count_query(query) {
+                Ok((outcome, rows)) => (Ok(outcome), rows),
+                Err(e) => (Err(e), 0),
+            }
+        } else {
+            (connection.execute_query(query), 0)
+        };
+        self.last_failed = result.is_err();
+
+        // A SET touching sql_select_limit invalidates the cached value
+        if query_upper.starts_with("SET") && query_upper.contains("SQL_SELECT_LIMIT") {
+            self.sql_select_limit = None;
+        }
+
+        match result {
+            Ok(ExecOutcome::Ok {
+                affected,
+                last_insert_id,
+                info,
+            }) => {
                 let duration = start_time.elapsed();
+                if last_insert_id.is_some_and(|id| id > 0) {
+                    self.last_insert_id = last_insert_id;
+                }
 
-                if result.rows.is_empty() && result.columns.is_empty() {
-                    // Non-SELECT query (INSERT, UPDATE, DELETE, etc.)
-                    println!(
-                        "Query OK, {} rows affected ({:.3} sec)",
-                        0,
+                let warnings = connection.get_conn_mut().warnings();
+                let outcome = if warnings > 0 {
+                    Outcome::Warning
+                } else {
+                    Outcome::Success
+                };
+                self.summary(
+                    outcome,
+                    &format!(
+                        "Query OK, {} {} affected{} ({:.3} sec)",
+                        affected,
+                        if affected == 1 { "row" } else { "rows" },
+                        warnings_note(warnings),
                         duration.as_secs_f64()
+                    ),
+                );
+                if !info.is_empty() && !self.silent {
+                    println!("{}", info);
+                }
+            }
+            Ok(ExecOutcome::ResultSet(_)) if count_only => {
+                let timing = format!("{:.3} sec", start_time.elapsed().as_secs_f64());
+                let warnings = connection.get_conn_mut().warnings();
+                self.row_summary(connection, row_count as usize, &timing, warnings);
+            }
+            Ok(ExecOutcome::ResultSet(result)) => {
+                let duration = start_time.elapsed();
+                if cacheable {
+                    self.result_cache
+                        .put(cache_key, (result.clone(), Instant::now()));
+                }
+                let timing = format!("{:.3} sec", duration.as_secs_f64());
+                let warnings = connection.get_conn_mut().warnings();
+                self.show_result(connection, query, result, &timing, warnings);
+            }
+            Err(e) => {
+                let message = match (self.time_limit, e.downcast_ref::<mysql::Error>()) {
+                    (Some(ms), Some(mysql::Error::MySqlError(err)))
+                        if err.code == ER_QUERY_TIMEOUT =>
+                    {
+                        format!(
+                            "ERROR: Query exceeded the {} ms client-configured limit (\\maxtime)",
+                            ms
+                        )
+                    }
+                    _ => format!("ERROR: {}", e),
+                };
+                self.summary(Outcome::Error, &message);
+                if let Some(mysql::Error::MySqlError(err)) = e.downcast_ref::<mysql::Error>() {
+                    if let Some(hint) = self.did_you_mean(connection, err.code, &err.message) {
+                        println!("{}", hint);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Display a result set with its row count and keep it as the last result
+    fn show_result(
+        &mut self,
+        connection: &mut Connection,
+        query: &str,
+        result: QueryResult,
+        timing: &str,
+        warnings: u16,
+    ) {
+        if self.display_format == DisplayFormat::Xml {
+            // No summary line in the document, but the outcome still counts
+            match xml::format(query, &result) {
+                Ok(text) => {
+                    print!("{}", text);
+                    self.last_outcome = if warnings > 0 {
+                        Outcome::Warning
+                    } else {
+                        Outcome::Success
+                    };
+                }
+                Err(e) => {
+                    self.last_failed = true;
+                    self.summary(Outcome::Error, &format!("ERROR: {}", e));
+                }
+            }
+            if let Ok(mut last_result) = self.last_result.lock() {
+                *last_result = Some(result);
+            }
+            return;
+        }
+        if is_explain_analyze(query, &result)
+            || (self.raw_single_column && result.columns.len() == 1)
+        {
+            self.display_raw(&result);
+        } else if self.vertical {
+            display_vertical(&result);
+        } else if !self.column_names {
+            self.display_results(&result);
+        } else {
+            let terminal_width = std::io::stdout()
+                .is_terminal()
+                .then(|| crossterm::terminal::size().ok())
+                .flatten()
+                .map(|(width, _)| width as usize);
+            let columns = result.columns.len();
+            match choose_layout(
+                columns,
+                table_width(&result),
+                terminal_width,
+                self.wide_result_columns,
+                self.auto_vertical,
+            ) {
+                Layout::Table => self.display_results(&result),
+                Layout::TableWithHint if self.silent => self.display_results(&result),
+                Layout::TableWithHint => {
+                    println!(
+                        "{} columns — consider \\G, \\v or \\set auto_vertical_output=1",
+                        columns
                     );
-                } else {
-                    // SELECT query with results
                     self.display_results(&result);
-                    let row_count = result.rows.len();
-                    if row_count == 1 {
-                        println!(
-                            "{} row in set ({:.3} sec)",
-                            row_count,
-                            duration.as_secs_f64()
-                        );
-                    } else {
+                }
+                Layout::Vertical { notice } => {
+                    if notice && !self.silent {
                         println!(
-                            "{} rows in set ({:.3} sec)",
-                            row_count,
-                            duration.as_secs_f64()
+                            "{} columns, too wide for the terminal — showing rows vertically",
+                            columns
                         );
                     }
+                    display_vertical(&result);
                 }
             }
-            Err(e) => {
-                println!("ERROR: {}", e);
+        }
+        if explain::is_explain_output(&result) && !self.silent {
+            for note in explain::annotate(&explain::parse_explain_output(&result)) {
+                println!("Note: {}", note);
             }
         }
+        self.row_summary(connection, result.rows.len(), timing, warnings);
+        if let Ok(mut last_result) = self.last_result.lock() {
+            *last_result = Some(result);
+        }
+    }
 
-        Ok(())
+    /// Print the `N rows in set` summary of a result set
+    fn row_summary(
+        &mut self,
+        connection: &mut Connection,
+        row_count: usize,
+        timing: &str,
+        warnings: u16,
+    ) {
+        let limit_note = match self.sql_select_limit(connection) {
+            Some(limit) if limit == row_count as u64 => {
+                format!(" (result may be truncated by sql_select_limit={})", limit)
+            }
+            _ => String::new(),
+        };
+        let outcome = if warnings > 0 {
+            Outcome::Warning
+        } else {
+            Outcome::Success
+        };
+        let rows = if row_count == 1 { "row" } else { "rows" };
+        self.summary(
+            outcome,
+            &format!(
+                "{} {} in set{} ({}){}",
+                row_count,
+                rows,
+                warnings_note(warnings),
+                timing,
+                limit_note
+            ),
+        );
     }
 
-    fn display_results(&self, result: &QueryResult) {
+    pub fn display_results(&self, result: &QueryResult) {
         if result.columns.is_empty() {
             return;
         }
+        if !self.column_names {
+            print!("{}", format_headerless(result, self.raw));
+            return;
+        }
 
         let mut table = Table::new();
         table.set_content_arrangement(ContentArrangement::Dynamic);
 
         // Add headers
         let mut header_cells = Vec::new();
-        for column in &result.columns {
+        for column in header_labels(result) {
             header_cells.push(Cell::new(column).add_attribute(Attribute::Bold));
         }
         table.set_header(header_cells);
@@ -82,4 +563,238 @@ impl QueryExecutor {
 
         println!("{}", table);
     }
+
+    /// Print a single-column result such as the EXPLAIN ANALYZE tree verbatim,
+    /// keeping its newlines and indentation
+    fn display_raw(&self, result: &QueryResult) {
+        let highlight = std::io::stdout().is_terminal();
+        for row in &result.rows {
+            for value in row {
+                println!("{}", render_raw(value, highlight, self.explain_slow_ms));
+            }
+        }
+    }
+}
+
+/// Summary line with an optional marker: `Some(true)` for plain `[OK]`/`[ERR]`
+/// text that can be grepped in logs, `Some(false)` for colored glyphs
+fn format_summary(outcome: Outcome, line: &str, marker: Option<bool>) -> String {
+    let prefix = match (marker, outcome) {
+        (None, _) => return line.to_string(),
+        (Some(true), Outcome::Success) => "[OK]",
+        (Some(true), Outcome::Warning) => "[WARN]",
+        (Some(true), Outcome::Error) => "[ERR]",
+        (Some(false), Outcome::Success) => "\x1b[32m✓\x1b[0m",
+        (Some(false), Outcome::Warning) => "\x1b[33m✓\x1b[0m",
+        (Some(false), Outcome::Error) => "\x1b[31m✗\x1b[0m",
+    };
+    format!("{} {}", prefix, line)
 }
+
+/// `, N warning(s)` for a summary line, empty without warnings
+fn warnings_note(warnings: u16) -> String {
+    match warnings {
+        0 => String::new(),
+        1 => ", 1 warning".to_string(),
+        n => format!(", {} warnings", n),
+    }
+}
+
+/// Print each row as a block of right-aligned `column: value` lines, like the mysql client's `\G`
+pub fn display_vertical(result: &QueryResult) {
+    print!("{}", format_vertical(result));
+}
+
+/// Width of the result drawn as a table without wrapping: each column as wide
+/// as its longest value plus the `| ` ... ` |` borders
+fn table_width(result: &QueryResult) -> usize {
+    header_labels(result)
+        .iter()
+        .enumerate()
+        .map(|(i, label)| {
+            let widest = result
+                .rows
+                .iter()
+                .filter_map(|row| row.get(i))
+                .flat_map(|value| value.lines())
+                .map(UnicodeWidthStr::width)
+                .max()
+                .unwrap_or(0);
+            widest.max(label.width()) + 3
+        })
+        .sum::<usize>()
+        + 1
+}
+
+/// Rows as tab-separated lines without header or border, for `--no-column-names`;
+/// unless `raw`, backslashes, newlines, tabs and NULs in values are escaped like
+/// mysql's batch output so each row stays on one line
+fn format_headerless(result: &QueryResult, raw: bool) -> String {
+    result
+        .rows
+        .iter()
+        .map(|row| {
+            let values: Vec<String> = if raw {
+                row.clone()
+            } else {
+                row.iter().map(|value| escape_tab_value(value)).collect()
+            };
+            format!("{}\n", values.join("\t"))
+        })
+        .collect()
+}
+
+fn escape_tab_value(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\t' => escaped.push_str("\\t"),
+            '\0' => escaped.push_str("\\0"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Column labels for display: the raw names, with the name structured formats
+/// use appended to duplicates, e.g. `id (b.id)`
+fn header_labels(result: &QueryResult) -> Vec<String> {
+    result
+        .columns
+        .iter()
+        .zip(result.unique_columns())
+        .map(|(column, unique)| {
+            if *column == unique {
+                unique
+            } else {
+                format!("{} ({})", column, unique)
+            }
+        })
+        .collect()
+}
+
+/// Render rows as vertical `column: value` blocks
+fn format_vertical(result: &QueryResult) -> String {
+    let labels = header_labels(result);
+    let width = labels.iter().map(|c| c.chars().count()).max().unwrap_or(0);
+
+    let mut out = String::new();
+    for (i, row) in result.rows.iter().enumerate() {
+        out.push_str(&format!(
+            "{} {}. row {}\n",
+            "*".repeat(27),
+            i + 1,
+            "*".repeat(27)
+        ));
+        for (column, value) in labels.iter().zip(row) {
+            out.push_str(&format!("{:>width$}: {}\n", column, value, width = width));
+        }
+    }
+    out
+}
+
+/// Key binding that re-displays the last result vertically at an empty prompt,
+/// without touching history or the server
+pub struct VerticalResultHandler {
+    last_result: Arc<Mutex<Option<QueryResult>>>,
+    printer: Mutex<Box<dyn ExternalPrinter + Send>>,
+}
+
+impl VerticalResultHandler {
+    pub fn new(
+        last_result: Arc<Mutex<Option<QueryResult>>>,
+        printer: Box<dyn ExternalPrinter + Send>,
+    ) -> Self {
+        Self {
+            last_result,
+            printer: Mutex::new(printer),
+        }
+    }
+}
+
+impl ConditionalEventHandler for VerticalResultHandler {
+    fn handle(&self, _: &Event, _: RepeatCount, _: bool, ctx: &EventContext) -> Option<Cmd> {
+        if !ctx.line().is_empty() {
+            return None;
+        }
+
+        let rendered = self
+            .last_result
+            .lock()
+            .ok()
+            .and_then(|result| result.as_ref().map(format_vertical));
+        match rendered {
+            Some(text) => {
+                if let Ok(mut printer) = self.printer.lock() {
+                    let _ = printer.print(text);
+                }
+            }
+            None => {
+                // Nothing to show: ring the bell
+                let mut out = std::io::stdout();
+                let _ = out.write_all(b"\x07");
+                let _ = out.flush();
+            }
+        }
+        Some(Cmd::Noop)
+    }
+}
+
+/// Raw cell text; with `highlight`, `(actual time=...)` figures are cyan, or red
+/// when the step took `slow_ms` milliseconds or more (0 turns red off)
+fn render_raw(text: &str, highlight: bool, slow_ms: f64) -> String {
+    if !highlight {
+        return text.to_string();
+    }
+    static ANNOTATION: OnceLock<Regex> = OnceLock::new();
+    let annotation =
+        ANNOTATION.get_or_init(|| Regex::new(r"\(actual time=[\d.]+\.\.([\d.]+)[^)]*\)").unwrap());
+    annotation
+        .replace_all(text, |captures: &regex::Captures| {
+            let last_row_ms: f64 = captures[1].parse().unwrap_or(0.0);
+            let color = if slow_ms > 0.0 && last_row_ms >= slow_ms {
+                31
+            } else {
+                36
+            };
+            format!("\x1b[{}m{}\x1b[0m", color, &captures[0])
+        })
+        .into_owned()
+}
+
+/// Whether the result of an upper-cased statement may be served from the query
+/// cache: a plain SELECT, not a locking read, a `SELECT ... INTO`, or one calling
+/// lock functions or functions whose value changes between runs
+fn is_cacheable(query_upper: &str) -> bool {
+    static UNCACHEABLE: OnceLock<Regex> = OnceLock::new();
+    let uncacheable = UNCACHEABLE.get_or_init(|| {
+        Regex::new(
+            r"\bFOR\s+(UPDATE|SHARE)\b|\bLOCK\s+IN\s+SHARE\s+MODE\b|\bINTO\b|\b(GET_LOCK|RELEASE_LOCK|RELEASE_ALL_LOCKS|IS_FREE_LOCK|IS_USED_LOCK|NOW|SYSDATE|CURDATE|CURTIME|UNIX_TIMESTAMP|UTC_TIMESTAMP|RAND|UUID|UUID_SHORT|SLEEP|BENCHMARK|LAST_INSERT_ID|FOUND_ROWS|ROW_COUNT)\s*\(|\bCURRENT_(TIMESTAMP|DATE|TIME)\b|\bLOCALTIME(STAMP)?\b",
+        )
+        .unwrap()
+    });
+    query_upper.starts_with("SELECT") && !uncacheable.is_match(query_upper)
+}
+
+/// Check whether a result is the single text column produced by EXPLAIN ANALYZE
+/// (or EXPLAIN FORMAT=TREE), whose column the server names `EXPLAIN`
+fn is_explain_analyze(query: &str, result: &QueryResult) -> bool {
+    if result.columns.len() == 1 && result.columns[0].eq_ignore_ascii_case("EXPLAIN") {
+        return true;
+    }
+    let mut words = query.split_whitespace();
+    let is_explain = words
+        .next()
+        .is_some_and(|w| w.eq_ignore_ascii_case("EXPLAIN") || w.eq_ignore_ascii_case("DESC"));
+    let is_analyze = words
+        .next()
+        .is_some_and(|w| w.eq_ignore_ascii_case("ANALYZE"));
+
+    is_explain && is_analyze && result.columns.len() == 1
+}
+
+#[cfg(test)]
+#[path = "./query_tests.rs"]
+mod query_tests;
@@ -0,0 +1,185 @@
+use super::*;
+
+#[test]
+fn test_cacheable_statements() {
+    for sql in [
+        "SELECT * FROM users WHERE id = 1",
+        "SELECT name, COUNT(*) FROM orders GROUP BY name",
+        "SELECT now_playing FROM songs",
+    ] {
+        assert!(is_cacheable(&sql.to_uppercase()), "{}", sql);
+    }
+    for sql in [
+        "UPDATE users SET name = 'x'",
+        "SELECT * FROM users WHERE id = 1 FOR UPDATE",
+        "SELECT * FROM users FOR SHARE NOWAIT",
+        "SELECT * FROM users LOCK IN SHARE MODE",
+        "SELECT GET_LOCK('job', 10)",
+        "SELECT NOW()",
+        "SELECT * FROM users ORDER BY RAND() LIMIT 1",
+        "SELECT CURRENT_TIMESTAMP",
+        "SELECT id INTO @id FROM users LIMIT 1",
+        "SELECT * FROM users INTO OUTFILE '/tmp/users.csv'",
+    ] {
+        assert!(!is_cacheable(&sql.to_uppercase()), "{}", sql);
+    }
+}
+
+#[test]
+fn test_summary_without_marker() {
+    assert_eq!(
+        format_summary(Outcome::Success, "1 row in set (0.001 sec)", None),
+        "1 row in set (0.001 sec)"
+    );
+}
+
+#[test]
+fn test_summary_plain_markers() {
+    assert_eq!(
+        format_summary(Outcome::Success, "2 rows in set (0.001 sec)", Some(true)),
+        "[OK] 2 rows in set (0.001 sec)"
+    );
+    assert_eq!(
+        format_summary(
+            Outcome::Warning,
+            "Query OK, 0 rows affected, 1 warning (0.002 sec)",
+            Some(true)
+        ),
+        "[WARN] Query OK, 0 rows affected, 1 warning (0.002 sec)"
+    );
+    assert_eq!(
+        format_summary(Outcome::Error, "ERROR: Table 'x' doesn't exist", Some(true)),
+        "[ERR] ERROR: Table 'x' doesn't exist"
+    );
+}
+
+#[test]
+fn test_summary_colored_markers() {
+    assert_eq!(
+        format_summary(Outcome::Success, "Query OK", Some(false)),
+        "\x1b[32m✓\x1b[0m Query OK"
+    );
+    assert_eq!(
+        format_summary(Outcome::Error, "ERROR: x", Some(false)),
+        "\x1b[31m✗\x1b[0m ERROR: x"
+    );
+}
+
+#[test]
+fn test_warnings_note() {
+    assert_eq!(warnings_note(0), "");
+    assert_eq!(warnings_note(1), ", 1 warning");
+    assert_eq!(warnings_note(3), ", 3 warnings");
+}
+
+#[test]
+fn test_vertical_marks_duplicate_columns() {
+    let result = QueryResult {
+        columns: vec!["id".to_string(), "id".to_string()],
+        rows: vec![vec!["1".to_string(), "2".to_string()]],
+        tables: vec!["a".to_string(), "b".to_string()],
+        nulls: Vec::new(),
+    };
+    assert_eq!(
+        format_vertical(&result),
+        format!(
+            "{0} 1. row {0}\nid (a.id): 1\nid (b.id): 2\n",
+            "*".repeat(27)
+        )
+    );
+}
+
+const EXPLAIN_ANALYZE: &str = "\
+-> Limit: 10 row(s)  (cost=1.75 rows=10) (actual time=0.045..0.052 rows=10 loops=1)
+    -> Nested loop inner join  (cost=1.75 rows=10) (actual time=0.044..250.31 rows=10 loops=1)
+        -> Table scan on o  (cost=1.25 rows=10) (actual time=0.030..0.036 rows=10 loops=1)
+        -> Single-row index lookup on u using PRIMARY (id=o.user_id)  (cost=0.26 rows=1) (never executed)";
+
+#[test]
+fn test_explain_analyze_rendered_raw() {
+    let result = QueryResult {
+        columns: vec!["EXPLAIN".to_string()],
+        rows: vec![vec![EXPLAIN_ANALYZE.to_string()]],
+        tables: Vec::new(),
+        nulls: Vec::new(),
+    };
+    // Detected by its column even when the statement text doesn't say EXPLAIN ANALYZE
+    assert!(is_explain_analyze("SELECT 1", &result));
+    assert_eq!(render_raw(EXPLAIN_ANALYZE, false, 100.0), EXPLAIN_ANALYZE);
+
+    let colored = render_raw(EXPLAIN_ANALYZE, true, 100.0);
+    let lines: Vec<&str> = colored.lines().collect();
+    assert!(lines[0].ends_with("\x1b[36m(actual time=0.045..0.052 rows=10 loops=1)\x1b[0m"));
+    assert!(lines[1].ends_with("\x1b[31m(actual time=0.044..250.31 rows=10 loops=1)\x1b[0m"));
+    assert!(lines[2].starts_with("        -> Table scan on o"));
+    assert!(lines[3].ends_with("(never executed)"));
+
+    // Threshold 0 never marks steps as slow
+    assert!(!render_raw(EXPLAIN_ANALYZE, true, 0.0).contains("\x1b[31m"));
+}
+
+#[test]
+fn test_choose_layout_for_wide_results() {
+    // Not a terminal: always a table
+    assert_eq!(choose_layout(120, 5000, None, 25, true), Layout::Table);
+    // Few columns, fitting or not
+    assert_eq!(choose_layout(5, 60, Some(80), 25, false), Layout::Table);
+    assert_eq!(choose_layout(10, 200, Some(80), 25, false), Layout::Table);
+    // Many columns: a hint, or vertical once more than three terminals wide
+    assert_eq!(
+        choose_layout(30, 200, Some(80), 25, false),
+        Layout::TableWithHint
+    );
+    assert_eq!(
+        choose_layout(120, 241, Some(80), 25, false),
+        Layout::Vertical { notice: true }
+    );
+    assert_eq!(choose_layout(120, 241, Some(80), 0, false), Layout::Table);
+    // auto_vertical_output switches anything wider than the terminal, quietly
+    assert_eq!(
+        choose_layout(3, 81, Some(80), 25, true),
+        Layout::Vertical { notice: false }
+    );
+    assert_eq!(
+        choose_layout(30, 80, Some(80), 25, true),
+        Layout::TableWithHint
+    );
+}
+
+#[test]
+fn test_table_width() {
+    let result = QueryResult {
+        columns: vec!["id".to_string(), "name".to_string()],
+        rows: vec![vec!["1".to_string(), "Zoë Smith".to_string()]],
+        tables: Vec::new(),
+        nulls: Vec::new(),
+    };
+    // | id | Zoë Smith |
+    assert_eq!(table_width(&result), 18);
+}
+
+#[test]
+fn test_format_headerless() {
+    let result = QueryResult {
+        columns: vec!["id".to_string(), "name".to_string()],
+        rows: vec![
+            vec!["1".to_string(), "alice".to_string()],
+            vec!["2".to_string(), "NULL".to_string()],
+        ],
+        tables: Vec::new(),
+        nulls: Vec::new(),
+    };
+    assert_eq!(format_headerless(&result, false), "1\talice\n2\tNULL\n");
+}
+
+#[test]
+fn test_format_headerless_escapes_unless_raw() {
+    let result = QueryResult {
+        columns: vec!["path".to_string(), "note".to_string()],
+        rows: vec![vec!["C:\\tmp".to_string(), "a\tb\nc".to_string()]],
+        tables: Vec::new(),
+        nulls: Vec::new(),
+    };
+    assert_eq!(format_headerless(&result, false), "C:\\\\tmp\ta\\tb\\nc\n");
+    assert_eq!(format_headerless(&result, true), "C:\\tmp\ta\tb\nc\n");
+}
@@ -0,0 +1,141 @@
+/*!
+ * Read-only server detection
+ *
+ * Tells a replica (`read_only` / `super_read_only`) from a writable primary for
+ * the prompt, `\s` and a warning before statements that would write
+ */
+
+use crate::commands::protect;
+use crate::completion::schema_change::SchemaChange;
+use crate::database::Connection;
+use std::time::{Duration, Instant};
+
+/// How long a detected mode is trusted before the server is asked again
+const RECHECK_INTERVAL: Duration = Duration::from_secs(300);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ServerMode {
+    ReadWrite,
+    ReadOnly,
+    /// `super_read_only`: not even SUPER users may write
+    SuperReadOnly,
+}
+
+impl ServerMode {
+    pub fn is_read_only(self) -> bool {
+        self != ServerMode::ReadWrite
+    }
+
+    /// Short form for the `\r` prompt escape
+    pub fn label(self) -> &'static str {
+        if self.is_read_only() {
+            "ro"
+        } else {
+            "rw"
+        }
+    }
+
+    pub fn describe(self) -> &'static str {
+        match self {
+            ServerMode::ReadWrite => "read-write",
+            ServerMode::ReadOnly => "read-only (read_only)",
+            ServerMode::SuperReadOnly => "read-only (super_read_only)",
+        }
+    }
+}
+
+/// Cached server mode, refreshed after `RECHECK_INTERVAL` or on demand
+#[derive(Debug, Default)]
+pub struct ReadOnlyCheck {
+    mode: Option<ServerMode>,
+    checked_at: Option<Instant>,
+}
+
+impl ReadOnlyCheck {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The last detected mode; `None` when the server couldn't be asked
+    pub fn mode(&self) -> Option<ServerMode> {
+        self.mode
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        self.mode.is_some_and(ServerMode::is_read_only)
+    }
+
+    /// Ask the server now
+    pub fn refresh(&mut self, connection: &mut Connection) {
+        self.mode = connection.read_only_flags().ok().map(|flags| match flags {
+            (_, true) => ServerMode::SuperReadOnly,
+            (true, false) => ServerMode::ReadOnly,
+            (false, false) => ServerMode::ReadWrite,
+        });
+        self.checked_at = Some(Instant::now());
+    }
+
+    /// Ask the server again once the cached mode is older than `RECHECK_INTERVAL`
+    pub fn refresh_if_stale(&mut self, connection: &mut Connection) {
+        if self
+            .checked_at
+            .is_none_or(|checked| checked.elapsed() >= RECHECK_INTERVAL)
+        {
+            self.refresh(connection);
+        }
+    }
+}
+
+/// Whether a statement writes data or changes the schema: the statements
+/// `\protect` guards, DDL found by schema change detection, and inserts
+pub fn is_write_statement(sql: &str) -> bool {
+    if !protect::destructive_targets(sql).is_empty() {
+        return true;
+    }
+    if SchemaChange::classify(sql)
+        .is_some_and(|change| change.databases || change.reload_all || !change.tables.is_empty())
+    {
+        return true;
+    }
+    let first_word = sql
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .to_uppercase();
+    matches!(
+        first_word.as_str(),
+        "INSERT" | "REPLACE" | "LOAD" | "UPDATE" | "DELETE"
+    )
+}
+
+/// Expand escapes in a custom prompt: `\d` database (`(none)` without one),
+/// `\r` `ro`/`rw` (`?` when unknown), `\v` server version, `\n` newline, `\\` backslash;
+/// other characters are kept as they are
+pub fn expand_prompt(
+    format: &str,
+    database: Option<&str>,
+    mode: Option<ServerMode>,
+    server_version: &str,
+) -> String {
+    let mut prompt = String::new();
+    let mut chars = format.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            prompt.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('d') => prompt.push_str(database.unwrap_or("(none)")),
+            Some('r') => prompt.push_str(mode.map_or("?", ServerMode::label)),
+            Some('v') => prompt.push_str(server_version),
+            Some('n') => prompt.push('\n'),
+            Some(other) => prompt.push(other),
+            None => prompt.push('\\'),
+        }
+    }
+    prompt
+}
+
+#[cfg(test)]
+#[path = "./read_only_tests.rs"]
+mod read_only_tests;
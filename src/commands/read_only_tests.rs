@@ -0,0 +1,33 @@
+use super::*;
+
+#[test]
+fn test_expand_prompt() {
+    assert_eq!(
+        expand_prompt(
+            "\\r@\\d [\\v]> ",
+            Some("shop"),
+            Some(ServerMode::SuperReadOnly),
+            "8.0.36"
+        ),
+        "ro@shop [8.0.36]> "
+    );
+    assert_eq!(
+        expand_prompt("\\r \\d\\\\> ", None, Some(ServerMode::ReadWrite), ""),
+        "rw (none)\\> "
+    );
+    assert_eq!(expand_prompt("(\\r) \\x", None, None, ""), "(?) x");
+}
+
+#[test]
+fn test_is_write_statement() {
+    assert!(is_write_statement(
+        "UPDATE users SET name = 'a' WHERE id = 1"
+    ));
+    assert!(is_write_statement("insert into users (id) values (1)"));
+    assert!(is_write_statement("DELETE FROM shop.orders"));
+    assert!(is_write_statement("ALTER TABLE users ADD COLUMN age INT"));
+    assert!(is_write_statement("CREATE DATABASE reporting"));
+    assert!(!is_write_statement("SELECT * FROM users"));
+    assert!(!is_write_statement("USE shop"));
+    assert!(!is_write_statement("SHOW TABLES"));
+}
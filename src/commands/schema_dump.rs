@@ -0,0 +1,88 @@
+/*!
+ * Schema dump
+ *
+ * `--dump-schema` loads databases, tables and columns with the completion
+ * metadata loader and prints them as JSON or as `CREATE` statements
+ */
+
+use crate::completion::metadata::DatabaseMetadata;
+use crate::database::Connection;
+use anyhow::{anyhow, Result};
+use regex::Regex;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DumpFormat {
+    Json,
+    Sql,
+}
+
+impl DumpFormat {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name.to_lowercase().as_str() {
+            "json" => Ok(DumpFormat::Json),
+            "sql" => Ok(DumpFormat::Sql),
+            _ => Err(anyhow!("Unknown dump format '{}' (json or sql)", name)),
+        }
+    }
+}
+
+/// Dump every accessible database, or only `database`
+pub fn dump(
+    connection: &mut Connection,
+    database: Option<&str>,
+    format: DumpFormat,
+) -> Result<String> {
+    let mut metadata = DatabaseMetadata::new();
+    match database {
+        Some(db) => {
+            metadata.refresh_tables(connection.get_conn_mut(), db, &[])?;
+            metadata.databases = vec![db.to_string()];
+        }
+        None => metadata.update_from_connection(connection.get_conn_mut())?,
+    }
+
+    if format == DumpFormat::Json {
+        return Ok(format!("{}\n", serde_json::to_string_pretty(&metadata)?));
+    }
+
+    let mut schemas = Vec::new();
+    for db in &metadata.databases {
+        // System databases have no table list loaded
+        let Some(tables) = metadata.tables.get(&db.to_lowercase()) else {
+            continue;
+        };
+        let mut statements = Vec::new();
+        for table in tables {
+            let result = connection
+                .execute_query(&format!("SHOW CREATE TABLE `{}`.`{}`", db, table))?
+                .into_result_set()?;
+            if let Some(create) = result.rows.first().and_then(|row| row.get(1)) {
+                statements.push(create.clone());
+            }
+        }
+        schemas.push((db.clone(), statements));
+    }
+    Ok(format_sql(&schemas))
+}
+
+/// `CREATE DATABASE` and `CREATE TABLE` statements per database; AUTO_INCREMENT
+/// counters are left out so dumps of different environments diff cleanly
+fn format_sql(schemas: &[(String, Vec<String>)]) -> String {
+    let counter = Regex::new(r" AUTO_INCREMENT=\d+").unwrap();
+    let mut out = String::new();
+    for (db, statements) in schemas {
+        let db = db.replace('`', "``");
+        out.push_str(&format!("-- Database: {}\n", db));
+        out.push_str(&format!("CREATE DATABASE IF NOT EXISTS `{}`;\n", db));
+        out.push_str(&format!("USE `{}`;\n\n", db));
+        for statement in statements {
+            out.push_str(&counter.replace_all(statement, ""));
+            out.push_str(";\n\n");
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+#[path = "./schema_dump_tests.rs"]
+mod schema_dump_tests;
@@ -0,0 +1,22 @@
+use super::*;
+
+#[test]
+fn test_format_sql_dump() {
+    let schemas = vec![(
+        "shop".to_string(),
+        vec![
+            "CREATE TABLE `orders` (\n  `id` int NOT NULL AUTO_INCREMENT,\n  PRIMARY KEY (`id`)\n) ENGINE=InnoDB AUTO_INCREMENT=42 DEFAULT CHARSET=utf8mb4".to_string(),
+        ],
+    )];
+    assert_eq!(
+        format_sql(&schemas),
+        "-- Database: shop\nCREATE DATABASE IF NOT EXISTS `shop`;\nUSE `shop`;\n\n\
+         CREATE TABLE `orders` (\n  `id` int NOT NULL AUTO_INCREMENT,\n  PRIMARY KEY (`id`)\n) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;\n\n"
+    );
+}
+
+#[test]
+fn test_parse_format() {
+    assert_eq!(DumpFormat::parse("SQL").unwrap(), DumpFormat::Sql);
+    assert!(DumpFormat::parse("xml").is_err());
+}
@@ -0,0 +1,186 @@
+/*!
+ * Session transcripts
+ *
+ * `\record` appends executed statements, each after a timestamp comment, to a
+ * plain SQL file; `\replay` reads such a file back statement by statement
+ */
+
+use crate::statement;
+use anyhow::{anyhow, Result};
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Prefix of the timestamp comment written before each entry
+const TIMESTAMP_PREFIX: &str = "-- [";
+
+/// Appends statements to a transcript file while `\record` is on
+pub struct Recorder {
+    file: File,
+    path: String,
+    /// Also record backslash commands (`\record <file> --all`)
+    include_commands: bool,
+    /// Delimiter the file's reader is at, changed with `DELIMITER` lines
+    delimiter: String,
+}
+
+impl Recorder {
+    /// Open `path` for appending
+    pub fn create(path: &str, include_commands: bool) -> Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| anyhow!("Failed to open file '{}': {}", path, e))?;
+        Ok(Self {
+            file,
+            path: path.to_string(),
+            include_commands,
+            delimiter: ";".to_string(),
+        })
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Append a statement run with `delimiter` in effect
+    pub fn record_statement(&mut self, sql: &str, delimiter: &str) -> Result<()> {
+        let text = entry(
+            &timestamp(SystemTime::now()),
+            sql,
+            delimiter,
+            &mut self.delimiter,
+        );
+        self.file.write_all(text.as_bytes())?;
+        Ok(())
+    }
+
+    /// Append a backslash command when commands are recorded
+    pub fn record_command(&mut self, command: &str) -> Result<()> {
+        if !self.include_commands {
+            return Ok(());
+        }
+        let delimiter = self.delimiter.clone();
+        self.record_statement(command, &delimiter)
+    }
+
+    /// Switch the file back to `;` so later appends and readers start from the default
+    pub fn finish(mut self) -> Result<()> {
+        if self.delimiter != ";" {
+            self.file.write_all(b"DELIMITER ;\n")?;
+        }
+        Ok(())
+    }
+}
+
+/// One transcript entry: the timestamp comment, a `DELIMITER` line when the
+/// delimiter changed, and the statement with its delimiter
+fn entry(timestamp: &str, sql: &str, delimiter: &str, file_delimiter: &mut String) -> String {
+    let mut text = format!("{}{}]\n", TIMESTAMP_PREFIX, timestamp);
+    if delimiter != file_delimiter {
+        text.push_str(&format!("DELIMITER {}\n", delimiter));
+        *file_delimiter = delimiter.to_string();
+    }
+    text.push_str(&format!("{}{}\n", sql.trim(), delimiter));
+    text
+}
+
+/// `YYYY-MM-DD HH:MM:SS UTC`
+fn timestamp(time: SystemTime) -> String {
+    let secs = time
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let (days, rest) = (secs / 86_400, secs % 86_400);
+
+    // Civil date from days since 1970-01-01
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02} UTC",
+        year,
+        month,
+        day,
+        rest / 3_600,
+        rest % 3_600 / 60,
+        rest % 60
+    )
+}
+
+/// Statements of a transcript, without their timestamp comments, each with the
+/// delimiter in effect for it, following `DELIMITER` lines from `delimiter`;
+/// also returns the delimiter at the end
+pub fn read_transcript(text: &str, delimiter: &str) -> (Vec<(String, String)>, String) {
+    let (statements, delimiter) = statement::split_script_delimited(text, delimiter);
+    let statements = statements
+        .into_iter()
+        .map(|(sql, delimiter)| {
+            let sql = sql
+                .lines()
+                .skip_while(|line| line.starts_with(TIMESTAMP_PREFIX))
+                .collect::<Vec<_>>()
+                .join("\n");
+            (sql, delimiter)
+        })
+        .filter(|(sql, _)| !sql.trim().is_empty())
+        .collect();
+    (statements, delimiter)
+}
+
+/// Options of `\replay <file> [--delay ms] [--step] [--force]`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplayOptions {
+    pub path: String,
+    /// Pause between statements
+    pub delay: Option<Duration>,
+    /// Wait for Enter before each statement
+    pub step: bool,
+    /// Keep going after a failed statement instead of stopping
+    pub force: bool,
+}
+
+impl ReplayOptions {
+    pub fn parse(args: &str) -> Result<Self> {
+        let usage = || anyhow!("Usage: \\replay <file> [--delay ms] [--step] [--force]");
+        let mut path = None;
+        let mut delay = None;
+        let mut step = false;
+        let mut force = false;
+        let mut args = args.split_whitespace();
+        while let Some(arg) = args.next() {
+            match arg {
+                "--step" => step = true,
+                "--force" => force = true,
+                "--delay" => {
+                    let ms = args.next().ok_or_else(usage)?;
+                    let ms: u64 = ms
+                        .parse()
+                        .map_err(|_| anyhow!("Invalid --delay value: {}", ms))?;
+                    delay = Some(Duration::from_millis(ms));
+                }
+                _ if arg.starts_with("--") => return Err(anyhow!("Unknown option: {}", arg)),
+                _ if path.is_none() => path = Some(arg.to_string()),
+                _ => return Err(usage()),
+            }
+        }
+        Ok(Self {
+            path: path.ok_or_else(usage)?,
+            delay,
+            step,
+            force,
+        })
+    }
+}
+
+#[cfg(test)]
+#[path = "./session_tests.rs"]
+mod session_tests;
@@ -0,0 +1,53 @@
+use super::*;
+
+#[test]
+fn test_timestamp_is_utc_date_time() {
+    let time = UNIX_EPOCH + Duration::from_secs(1_709_251_199);
+    assert_eq!(timestamp(time), "2024-02-29 23:59:59 UTC");
+    assert_eq!(timestamp(UNIX_EPOCH), "1970-01-01 00:00:00 UTC");
+}
+
+#[test]
+fn test_transcript_round_trip() {
+    let mut delimiter = ";".to_string();
+    let mut text = String::new();
+    text.push_str(&entry("t1", "SELECT ';' AS x", ";", &mut delimiter));
+    text.push_str(&entry("t2", "\\u shop", ";", &mut delimiter));
+    let routine = "CREATE PROCEDURE p()\nBEGIN\n  SELECT 1;\nEND";
+    text.push_str(&entry("t3", routine, "$$", &mut delimiter));
+    text.push_str(&entry("t4", "-- check\nCALL p()", "$$", &mut delimiter));
+    text.push_str(&entry("t5", "SELECT 2", ";", &mut delimiter));
+
+    assert!(
+        text.starts_with("-- [t1]\nSELECT ';' AS x;\n-- [t2]\n\\u shop;\n-- [t3]\nDELIMITER $$\n")
+    );
+    let (statements, end_delimiter) = read_transcript(&text, ";");
+    let expected = [
+        ("SELECT ';' AS x", ";"),
+        ("\\u shop", ";"),
+        (routine, "$$"),
+        ("-- check\nCALL p()", "$$"),
+        ("SELECT 2", ";"),
+    ];
+    let expected: Vec<(String, String)> = expected
+        .iter()
+        .map(|(sql, delimiter)| (sql.to_string(), delimiter.to_string()))
+        .collect();
+    assert_eq!(statements, expected);
+    assert_eq!(end_delimiter, ";");
+}
+
+#[test]
+fn test_parse_replay_options() {
+    assert_eq!(
+        ReplayOptions::parse("demo.sql --delay 500 --step").unwrap(),
+        ReplayOptions {
+            path: "demo.sql".to_string(),
+            delay: Some(Duration::from_millis(500)),
+            step: true,
+            force: false,
+        }
+    );
+    assert!(ReplayOptions::parse("--force").is_err());
+    assert!(ReplayOptions::parse("demo.sql --delay").is_err());
+}
@@ -0,0 +1,133 @@
+/*!
+ * Slow query log
+ *
+ * Parses slow query log entries (`# Time:`, `# User@Host:`, `# Query_time:`
+ * headers followed by the statement) for `\tail-slowlog`
+ */
+
+/// Queries slower than this many seconds are highlighted
+const SLOW_SECONDS: f64 = 1.0;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SlowLogEntry {
+    pub time: Option<String>,
+    pub user_host: Option<String>,
+    pub query_time: Option<f64>,
+    pub lock_time: Option<f64>,
+    pub rows_sent: Option<u64>,
+    pub rows_examined: Option<u64>,
+    pub sql: String,
+}
+
+/// Builds entries from log lines fed one at a time
+#[derive(Default)]
+pub struct SlowLogParser {
+    entry: SlowLogEntry,
+    /// Whether a header has been seen for the entry being built
+    in_entry: bool,
+    /// `# Time:` is only written when the second changes, so it carries over
+    last_time: Option<String>,
+}
+
+impl SlowLogParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed one line; returns the entry it completes. A statement is complete
+    /// at a line ending with `;`
+    pub fn feed(&mut self, line: &str) -> Option<SlowLogEntry> {
+        if let Some(time) = line.strip_prefix("# Time:") {
+            self.last_time = Some(time.trim().to_string());
+            self.start_entry();
+            return None;
+        }
+        if let Some(user_host) = line.strip_prefix("# User@Host:") {
+            if !self.in_entry || self.entry.user_host.is_some() {
+                self.start_entry();
+            }
+            self.entry.user_host = Some(user_host.trim().to_string());
+            return None;
+        }
+        if line.starts_with("# Query_time:") {
+            self.in_entry = true;
+            self.entry.query_time = header_value(line, "Query_time").and_then(|v| v.parse().ok());
+            self.entry.lock_time = header_value(line, "Lock_time").and_then(|v| v.parse().ok());
+            self.entry.rows_sent = header_value(line, "Rows_sent").and_then(|v| v.parse().ok());
+            self.entry.rows_examined =
+                header_value(line, "Rows_examined").and_then(|v| v.parse().ok());
+            return None;
+        }
+        if !self.in_entry || line.starts_with('#') {
+            return None;
+        }
+
+        // The server adds these before each statement
+        let trimmed = line.trim();
+        let lower = trimmed.to_lowercase();
+        if lower.starts_with("set timestamp=")
+            || (lower.starts_with("use ") && self.entry.sql.is_empty())
+        {
+            return None;
+        }
+
+        if !self.entry.sql.is_empty() {
+            self.entry.sql.push('\n');
+        }
+        self.entry.sql.push_str(line);
+        if trimmed.ends_with(';') {
+            let mut entry = std::mem::take(&mut self.entry);
+            entry.sql = entry.sql.trim().trim_end_matches(';').to_string();
+            self.in_entry = false;
+            return Some(entry);
+        }
+        None
+    }
+
+    fn start_entry(&mut self) {
+        self.entry = SlowLogEntry {
+            time: self.last_time.clone(),
+            ..Default::default()
+        };
+        self.in_entry = true;
+    }
+}
+
+/// Value following `name:` in a `# Query_time:` header line
+fn header_value<'a>(line: &'a str, name: &str) -> Option<&'a str> {
+    let mut words = line.split_whitespace();
+    words.find(|word| word.strip_suffix(':') == Some(name))?;
+    words.next()
+}
+
+/// Render an entry; queries slower than a second are red when `color` is set
+pub fn format_entry(entry: &SlowLogEntry, color: bool) -> String {
+    let mut header = Vec::new();
+    if let Some(time) = &entry.time {
+        header.push(time.clone());
+    }
+    if let Some(query_time) = entry.query_time {
+        header.push(format!("query {:.3}s", query_time));
+    }
+    if let Some(lock_time) = entry.lock_time {
+        header.push(format!("lock {:.3}s", lock_time));
+    }
+    if let (Some(sent), Some(examined)) = (entry.rows_sent, entry.rows_examined) {
+        header.push(format!("rows {}/{} sent/examined", sent, examined));
+    }
+    if let Some(user_host) = &entry.user_host {
+        header.push(user_host.clone());
+    }
+
+    let slow = entry.query_time.is_some_and(|t| t > SLOW_SECONDS);
+    let sql = if color && slow {
+        format!("\x1b[31m{}\x1b[0m", entry.sql)
+    } else {
+        entry.sql.clone()
+    };
+    format!("-- {}\n{};\n\n", header.join("  "), sql)
+}
+
+#[cfg(test)]
+#[path = "./slowlog_tests.rs"]
+mod slowlog_tests;
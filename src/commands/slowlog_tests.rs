@@ -0,0 +1,59 @@
+use super::*;
+
+const LOG: &str = "/usr/sbin/mysqld, Version: 8.0.36 (MySQL Community Server - GPL). started with:
+Tcp port: 3306  Unix socket: /var/run/mysqld/mysqld.sock
+Time                 Id Command    Argument
+# Time: 2024-01-02T03:04:05.123456Z
+# User@Host: app[app] @ localhost []  Id:     8
+# Query_time: 2.500100  Lock_time: 0.000020 Rows_sent: 1  Rows_examined: 120000
+use shop;
+SET timestamp=1704164645;
+SELECT *
+FROM orders
+WHERE note LIKE '%x%';
+# User@Host: root[root] @ localhost []  Id:     9
+# Query_time: 0.200000  Lock_time: 0.000000 Rows_sent: 0  Rows_examined: 0
+SET timestamp=1704164645;
+DELETE FROM sessions;
+";
+
+fn parse(log: &str) -> Vec<SlowLogEntry> {
+    let mut parser = SlowLogParser::new();
+    log.lines().filter_map(|line| parser.feed(line)).collect()
+}
+
+#[test]
+fn test_parse_slow_log_entries() {
+    let entries = parse(LOG);
+    assert_eq!(entries.len(), 2);
+
+    assert_eq!(
+        entries[0].time.as_deref(),
+        Some("2024-01-02T03:04:05.123456Z")
+    );
+    assert_eq!(entries[0].query_time, Some(2.5001));
+    assert_eq!(entries[0].lock_time, Some(0.00002));
+    assert_eq!(entries[0].rows_examined, Some(120000));
+    assert_eq!(
+        entries[0].sql,
+        "SELECT *\nFROM orders\nWHERE note LIKE '%x%'"
+    );
+
+    // No `# Time:` line: the previous timestamp still applies
+    assert_eq!(entries[1].time, entries[0].time);
+    assert_eq!(
+        entries[1].user_host.as_deref(),
+        Some("root[root] @ localhost []  Id:     9")
+    );
+    assert_eq!(entries[1].sql, "DELETE FROM sessions");
+}
+
+#[test]
+fn test_format_entry_highlights_slow_queries() {
+    let entries = parse(LOG);
+    assert!(format_entry(&entries[0], true).contains("\x1b[31mSELECT *"));
+    assert!(!format_entry(&entries[1], true).contains("\x1b[31m"));
+    assert!(format_entry(&entries[0], false).starts_with(
+        "-- 2024-01-02T03:04:05.123456Z  query 2.500s  lock 0.000s  rows 1/120000 sent/examined"
+    ));
+}
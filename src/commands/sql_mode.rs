@@ -0,0 +1,41 @@
+/*!
+ * sql_mode editing
+ *
+ * `\sqlmode +FLAG -FLAG` adds or removes single flags of the session sql_mode
+ */
+
+use anyhow::{anyhow, Result};
+
+/// Apply `+FLAG` / `-FLAG` changes to a comma-separated sql_mode, keeping flag order
+pub fn edit_sql_mode(current: &str, changes: &[&str]) -> Result<String> {
+    let mut flags: Vec<String> = current
+        .split(',')
+        .map(|flag| flag.trim().to_uppercase())
+        .filter(|flag| !flag.is_empty())
+        .collect();
+
+    for change in changes {
+        let (add, flag) = match change.split_at_checked(1) {
+            Some(("+", flag)) => (true, flag),
+            Some(("-", flag)) => (false, flag),
+            _ => return Err(anyhow!("Expected +FLAG or -FLAG, got '{}'", change)),
+        };
+        let flag = flag.trim().to_uppercase();
+        if flag.is_empty() || !flag.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return Err(anyhow!("Invalid sql_mode flag: '{}'", change));
+        }
+
+        if add {
+            if !flags.contains(&flag) {
+                flags.push(flag);
+            }
+        } else {
+            flags.retain(|f| *f != flag);
+        }
+    }
+    Ok(flags.join(","))
+}
+
+#[cfg(test)]
+#[path = "./sql_mode_tests.rs"]
+mod sql_mode_tests;
@@ -0,0 +1,23 @@
+use super::*;
+
+#[test]
+fn test_edit_sql_mode_adds_and_removes_flags() {
+    let current = "ONLY_FULL_GROUP_BY,STRICT_TRANS_TABLES,NO_ZERO_DATE";
+    assert_eq!(
+        edit_sql_mode(current, &["-only_full_group_by", "+ANSI_QUOTES"]).unwrap(),
+        "STRICT_TRANS_TABLES,NO_ZERO_DATE,ANSI_QUOTES"
+    );
+    // Adding a present flag or removing a missing one changes nothing
+    assert_eq!(
+        edit_sql_mode(current, &["+NO_ZERO_DATE", "-ANSI_QUOTES"]).unwrap(),
+        current
+    );
+    assert_eq!(edit_sql_mode("", &["+ANSI"]).unwrap(), "ANSI");
+}
+
+#[test]
+fn test_edit_sql_mode_rejects_bad_changes() {
+    assert!(edit_sql_mode("ANSI", &["ANSI_QUOTES"]).is_err());
+    assert!(edit_sql_mode("ANSI", &["+"]).is_err());
+    assert!(edit_sql_mode("ANSI", &["+A'B"]).is_err());
+}
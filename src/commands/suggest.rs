@@ -0,0 +1,124 @@
+/*!
+ * "Did you mean ...?" suggestions
+ *
+ * When the server reports an unknown table (1146) or column (1054), the name
+ * from the error message is compared with the cached metadata of the database
+ * and the closest names by edit distance are suggested
+ */
+
+use crate::completion::metadata::DatabaseMetadata;
+use regex::Regex;
+use std::sync::OnceLock;
+
+/// Table '%s' doesn't exist
+pub const ER_NO_SUCH_TABLE: u16 = 1146;
+/// Unknown column '%s' in '%s'
+pub const ER_BAD_FIELD_ERROR: u16 = 1054;
+
+/// Most names suggested for one error
+const MAX_SUGGESTIONS: usize = 3;
+
+/// A name the server didn't find
+#[derive(Debug, PartialEq)]
+pub enum UnknownName {
+    /// `database.table`, as the server qualifies it
+    Table { database: String, table: String },
+    /// A column name without its table qualifier
+    Column(String),
+}
+
+/// The unknown name in a server error, for the error codes that have one
+pub fn unknown_name(code: u16, message: &str) -> Option<UnknownName> {
+    match code {
+        ER_NO_SUCH_TABLE => {
+            static PATTERN: OnceLock<Regex> = OnceLock::new();
+            let pattern =
+                PATTERN.get_or_init(|| Regex::new(r"Table '([^']*)' doesn't exist").unwrap());
+            let name = pattern.captures(message)?.get(1)?.as_str();
+            let (database, table) = name.split_once('.')?;
+            Some(UnknownName::Table {
+                database: database.to_string(),
+                table: table.to_string(),
+            })
+        }
+        ER_BAD_FIELD_ERROR => {
+            static PATTERN: OnceLock<Regex> = OnceLock::new();
+            let pattern = PATTERN.get_or_init(|| Regex::new(r"Unknown column '([^']*)'").unwrap());
+            let name = pattern.captures(message)?.get(1)?.as_str();
+            let column = name.rsplit('.').next().unwrap_or(name);
+            Some(UnknownName::Column(column.to_string()))
+        }
+        _ => None,
+    }
+}
+
+/// `Did you mean: a, b?` for an unknown name, from the tables or columns of its
+/// database (the current one for columns); `None` without close matches
+pub fn did_you_mean(
+    meta: &DatabaseMetadata,
+    current_database: Option<&str>,
+    unknown: &UnknownName,
+) -> Option<String> {
+    let (name, candidates): (&str, Vec<&str>) = match unknown {
+        UnknownName::Table { database, table } => (
+            table,
+            meta.tables
+                .get(&database.to_lowercase())
+                .map(|tables| tables.iter().map(String::as_str).collect())
+                .unwrap_or_default(),
+        ),
+        UnknownName::Column(column) => {
+            let database = current_database?.to_lowercase();
+            let mut columns: Vec<&str> = Vec::new();
+            for table in meta.tables.get(&database).into_iter().flatten() {
+                let key = format!("{}.{}", database, table);
+                for column in meta.get_columns(&key.to_lowercase()).into_iter().flatten() {
+                    if !columns.contains(&column.as_str()) {
+                        columns.push(column);
+                    }
+                }
+            }
+            (column, columns)
+        }
+    };
+    let matches = closest(name, candidates);
+    (!matches.is_empty()).then(|| format!("Did you mean: {}?", matches.join(", ")))
+}
+
+/// Up to three candidates closest to `name`, ignoring case. A candidate may differ
+/// by one edit per three characters of `name`, at least one.
+pub fn closest<'a>(name: &str, candidates: impl IntoIterator<Item = &'a str>) -> Vec<&'a str> {
+    let name = name.to_lowercase();
+    let threshold = (name.chars().count() / 3).max(1);
+    let mut scored: Vec<(usize, &str)> = candidates
+        .into_iter()
+        .map(|candidate| (edit_distance(&name, &candidate.to_lowercase()), candidate))
+        .filter(|(distance, _)| *distance <= threshold)
+        .collect();
+    scored.sort();
+    scored.dedup_by(|a, b| a.1 == b.1);
+    scored
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, candidate)| candidate)
+        .collect()
+}
+
+/// Levenshtein distance between two strings, by characters
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+#[path = "./suggest_tests.rs"]
+mod suggest_tests;
@@ -0,0 +1,87 @@
+use super::*;
+
+#[test]
+fn test_edit_distance() {
+    assert_eq!(edit_distance("orders", "orders"), 0);
+    assert_eq!(edit_distance("ordrs", "orders"), 1);
+    assert_eq!(edit_distance("user_id", "usr_id"), 1);
+    assert_eq!(edit_distance("", "abc"), 3);
+}
+
+#[test]
+fn test_closest_scales_threshold_and_limits() {
+    let tables = [
+        "orders",
+        "order_items",
+        "customers",
+        "ordres",
+        "ORDERS",
+        "ordrs_",
+    ];
+    // Case is ignored; at most three, closest first
+    assert_eq!(
+        closest("ordrs", tables.iter().copied()),
+        vec!["ORDERS", "orders", "ordres"]
+    );
+    // Short names allow a single edit
+    assert_eq!(closest("id", ["ids", "uid", "name"]), vec!["ids", "uid"]);
+    assert!(closest("invoices", tables.iter().copied()).is_empty());
+    assert_eq!(
+        closest("order_iterms", tables.iter().copied()),
+        vec!["order_items"]
+    );
+}
+
+#[test]
+fn test_unknown_name_from_message() {
+    assert_eq!(
+        unknown_name(ER_NO_SUCH_TABLE, "Table 'shop.ordrs' doesn't exist"),
+        Some(UnknownName::Table {
+            database: "shop".to_string(),
+            table: "ordrs".to_string()
+        })
+    );
+    assert_eq!(
+        unknown_name(
+            ER_BAD_FIELD_ERROR,
+            "Unknown column 'o.usr_id' in 'where clause'"
+        ),
+        Some(UnknownName::Column("usr_id".to_string()))
+    );
+    assert_eq!(
+        unknown_name(1064, "You have an error in your SQL syntax"),
+        None
+    );
+}
+
+#[test]
+fn test_did_you_mean_uses_metadata() {
+    let mut meta = DatabaseMetadata::new();
+    meta.tables.insert(
+        "shop".to_string(),
+        vec!["orders".to_string(), "order_items".to_string()],
+    );
+    meta.columns.insert(
+        "shop.orders".to_string(),
+        vec!["id".to_string(), "user_id".to_string()],
+    );
+    meta.columns.insert(
+        "shop.order_items".to_string(),
+        vec!["id".to_string(), "order_id".to_string()],
+    );
+
+    let table = UnknownName::Table {
+        database: "shop".to_string(),
+        table: "ordrs".to_string(),
+    };
+    assert_eq!(
+        did_you_mean(&meta, None, &table).as_deref(),
+        Some("Did you mean: orders?")
+    );
+    let column = UnknownName::Column("usr_id".to_string());
+    assert_eq!(
+        did_you_mean(&meta, Some("shop"), &column).as_deref(),
+        Some("Did you mean: user_id?")
+    );
+    assert_eq!(did_you_mean(&meta, None, &column), None);
+}
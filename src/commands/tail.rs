@@ -0,0 +1,122 @@
+/*!
+ * Log file following
+ *
+ * `tail -f` for server log files: a reader thread polls the file for new lines
+ * while the terminal waits for `q`
+ */
+
+use anyhow::{anyhow, Result};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers};
+use crossterm::terminal;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Seek, SeekFrom, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+/// How often the file is checked for new data
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Follow `path` from its current end until `q` (or Esc, Ctrl-C) is pressed.
+/// Every complete new line is passed to `on_line`; text it returns is printed.
+pub fn follow(path: &str, mut on_line: impl FnMut(&str) -> Option<String>) -> Result<()> {
+    let mut file = File::open(path).map_err(|e| anyhow!("Failed to open '{}': {}", path, e))?;
+    let start = file.seek(SeekFrom::End(0))?;
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let (sender, receiver) = mpsc::channel();
+    let reader = {
+        let stop = stop.clone();
+        thread::spawn(move || read_new_lines(file, start, &stop, &sender))
+    };
+
+    println!("Following {} (press q to stop)", path);
+    let raw_mode = RawMode::enable()?;
+    let result = (|| -> Result<()> {
+        loop {
+            while let Ok(line) = receiver.try_recv() {
+                if let Some(text) = on_line(&line) {
+                    // Raw mode does not return the cursor to the line start
+                    let mut out = std::io::stdout();
+                    out.write_all(text.replace('\n', "\r\n").as_bytes())?;
+                    out.flush()?;
+                }
+            }
+            if event::poll(Duration::from_millis(100))? {
+                if let Event::Key(key) = event::read()? {
+                    if is_stop_key(&key) {
+                        return Ok(());
+                    }
+                }
+            }
+        }
+    })();
+    drop(raw_mode);
+
+    stop.store(true, Ordering::SeqCst);
+    let _ = reader.join();
+    result
+}
+
+fn is_stop_key(key: &KeyEvent) -> bool {
+    matches!(
+        key.code,
+        KeyCode::Char('q') | KeyCode::Char('Q') | KeyCode::Esc
+    ) || (key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL))
+}
+
+/// Send lines appended after `position` until `stop` is set; a truncated
+/// (rotated) file is read again from the start
+fn read_new_lines(file: File, position: u64, stop: &AtomicBool, sender: &mpsc::Sender<String>) {
+    let mut reader = BufReader::new(file);
+    let mut position = position;
+    let mut partial = String::new();
+
+    while !stop.load(Ordering::SeqCst) {
+        let length = reader.get_ref().metadata().map(|m| m.len()).unwrap_or(0);
+        if length < position {
+            position = 0;
+            partial.clear();
+            if reader.seek(SeekFrom::Start(0)).is_err() {
+                return;
+            }
+        }
+
+        let mut line = String::new();
+        match reader.read_line(&mut line) {
+            Ok(0) | Err(_) => thread::sleep(POLL_INTERVAL),
+            Ok(read) => {
+                position += read as u64;
+                partial.push_str(&line);
+                // Wait for the rest of a line that is still being written
+                if partial.ends_with('\n') {
+                    let complete = std::mem::take(&mut partial);
+                    if sender
+                        .send(complete.trim_end_matches(['\r', '\n']).to_string())
+                        .is_err()
+                    {
+                        return;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Raw terminal mode for reading single key presses, restored on drop
+struct RawMode;
+
+impl RawMode {
+    fn enable() -> Result<Self> {
+        terminal::enable_raw_mode()?;
+        Ok(RawMode)
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        let _ = terminal::disable_raw_mode();
+    }
+}
@@ -0,0 +1,95 @@
+/*!
+ * Transaction tracking
+ *
+ * Follows BEGIN, COMMIT, ROLLBACK and statements that commit implicitly to
+ * know whether the session has an open transaction, so `--rollback-on-error`
+ * can roll it back when a statement inside it fails
+ */
+
+use regex::Regex;
+use std::sync::OnceLock;
+
+/// How a statement changes the transaction state
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Effect {
+    /// BEGIN, START TRANSACTION, or COMMIT/ROLLBACK AND CHAIN
+    Open,
+    /// COMMIT, ROLLBACK or `SET autocommit=1`
+    Close,
+    /// DDL or LOCK TABLES, which commit before they run and so end the
+    /// transaction even when they fail
+    ImplicitCommit,
+}
+
+fn chained_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"\bAND\s+CHAIN\b").unwrap())
+}
+
+fn no_chain_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"\bAND\s+NO\s+CHAIN\b").unwrap())
+}
+
+fn autocommit_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"\bAUTOCOMMIT\s*=\s*(1|ON)\b").unwrap())
+}
+
+/// The transaction effect of a statement, `None` when it runs inside the
+/// current state (including ROLLBACK TO SAVEPOINT)
+pub fn effect(sql: &str) -> Option<Effect> {
+    let upper = sql.trim().trim_end_matches(';').trim().to_uppercase();
+    let words: Vec<&str> = upper.split_whitespace().collect();
+
+    match words.as_slice() {
+        ["BEGIN"] | ["BEGIN", "WORK"] | ["START", "TRANSACTION", ..] => Some(Effect::Open),
+        ["ROLLBACK", .., "SAVEPOINT", _]
+        | ["ROLLBACK", "TO", ..]
+        | ["ROLLBACK", "WORK", "TO", ..] => None,
+        ["COMMIT" | "ROLLBACK", ..]
+            if chained_regex().is_match(&upper) && !no_chain_regex().is_match(&upper) =>
+        {
+            Some(Effect::Open)
+        }
+        ["COMMIT" | "ROLLBACK", ..] => Some(Effect::Close),
+        // Temporary tables are created and dropped without a commit
+        ["CREATE" | "DROP", "TEMPORARY", ..] => None,
+        ["CREATE" | "ALTER" | "DROP" | "RENAME" | "TRUNCATE", ..] => Some(Effect::ImplicitCommit),
+        ["LOCK", "TABLES" | "TABLE", ..] => Some(Effect::ImplicitCommit),
+        ["SET", ..] if autocommit_regex().is_match(&upper) => Some(Effect::Close),
+        _ => None,
+    }
+}
+
+/// Whether the session has an explicitly opened transaction
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct TransactionState {
+    open: bool,
+}
+
+impl TransactionState {
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Follow a statement that ran; failed statements leave the state alone,
+    /// except those that commit implicitly before running
+    pub fn record(&mut self, sql: &str, succeeded: bool) {
+        match effect(sql) {
+            Some(Effect::ImplicitCommit) => self.open = false,
+            Some(Effect::Open) if succeeded => self.open = true,
+            Some(Effect::Close) if succeeded => self.open = false,
+            _ => {}
+        }
+    }
+
+    /// The transaction ended outside a statement, e.g. by a rollback or reconnect
+    pub fn reset(&mut self) {
+        self.open = false;
+    }
+}
+
+#[cfg(test)]
+#[path = "./transaction_tests.rs"]
+mod transaction_tests;
@@ -0,0 +1,58 @@
+use super::*;
+
+#[test]
+fn test_statement_effects() {
+    assert_eq!(effect("begin;"), Some(Effect::Open));
+    assert_eq!(effect("START TRANSACTION READ ONLY"), Some(Effect::Open));
+    assert_eq!(effect("COMMIT"), Some(Effect::Close));
+    assert_eq!(effect("rollback work"), Some(Effect::Close));
+    assert_eq!(effect("COMMIT AND CHAIN"), Some(Effect::Open));
+    assert_eq!(effect("COMMIT AND NO CHAIN"), Some(Effect::Close));
+    assert_eq!(effect("ROLLBACK TO SAVEPOINT s1"), None);
+    assert_eq!(effect("ROLLBACK TO s1"), None);
+    assert_eq!(
+        effect("ALTER TABLE t ADD c INT"),
+        Some(Effect::ImplicitCommit)
+    );
+    assert_eq!(effect("LOCK TABLES t WRITE"), Some(Effect::ImplicitCommit));
+    assert_eq!(effect("CREATE TEMPORARY TABLE tmp (id INT)"), None);
+    assert_eq!(effect("drop temporary table if exists tmp"), None);
+    assert_eq!(effect("SET autocommit = 1"), Some(Effect::Close));
+    assert_eq!(effect("SET autocommit = 0"), None);
+    assert_eq!(effect("UPDATE t SET begin = 1"), None);
+}
+
+#[test]
+fn test_state_transitions() {
+    let mut state = TransactionState::default();
+    state.record("INSERT INTO t VALUES (1)", true);
+    assert!(!state.is_open());
+
+    state.record("BEGIN", true);
+    assert!(state.is_open());
+    // A failing statement leaves the transaction open
+    state.record("INSERT INTO missing VALUES (1)", false);
+    assert!(state.is_open());
+    state.record("COMMIT", false);
+    assert!(state.is_open());
+    state.record("COMMIT", true);
+    assert!(!state.is_open());
+
+    state.record("START TRANSACTION", true);
+    state.record("CREATE INDEX i ON t (c)", true);
+    assert!(!state.is_open());
+
+    // DDL commits before it runs, so a failing one still ends the transaction
+    state.record("BEGIN", true);
+    state.record("ALTER TABLE missing ADD c INT", false);
+    assert!(!state.is_open());
+
+    state.record("BEGIN", true);
+    state.record("CREATE TEMPORARY TABLE tmp (id INT)", true);
+    state.record("DROP TEMPORARY TABLE tmp", true);
+    assert!(state.is_open());
+
+    state.record("BEGIN", true);
+    state.reset();
+    assert!(!state.is_open());
+}
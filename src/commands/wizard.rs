@@ -0,0 +1,932 @@
+/*!
+ * Table wizards
+ *
+ * `\create-table` walks through table name, columns, primary key and indexes
+ * one answer at a time and builds the CREATE TABLE statement from them;
+ * `\alter-table` collects column and index changes from a menu and builds
+ * the ALTER TABLE statement
+ */
+
+use super::literal;
+use crate::database::QueryResult;
+use anyhow::{anyhow, Result};
+use rustyline::completion::{Completer, Pair};
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::validate::Validator;
+use rustyline::{Context, Helper};
+
+/// Column types offered at the type prompt
+pub const COLUMN_TYPES: &[&str] = &[
+    "INT",
+    "TINYINT",
+    "SMALLINT",
+    "MEDIUMINT",
+    "BIGINT",
+    "INT UNSIGNED",
+    "BIGINT UNSIGNED",
+    "DECIMAL(10,2)",
+    "FLOAT",
+    "DOUBLE",
+    "BOOLEAN",
+    "CHAR(1)",
+    "VARCHAR(255)",
+    "TEXT",
+    "MEDIUMTEXT",
+    "LONGTEXT",
+    "BLOB",
+    "LONGBLOB",
+    "JSON",
+    "DATE",
+    "TIME",
+    "DATETIME",
+    "TIMESTAMP",
+    "YEAR",
+    "ENUM('a','b')",
+    "SET('a','b')",
+    "BINARY(16)",
+    "VARBINARY(255)",
+];
+
+/// Wizard steps, in the order they are asked
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step {
+    TableName,
+    ColumnName,
+    ColumnType,
+    Nullable,
+    Default,
+    Comment,
+    PrimaryKey,
+    Indexes,
+    Confirm,
+    Done,
+    Cancelled,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ColumnSpec {
+    pub name: String,
+    pub data_type: String,
+    pub nullable: bool,
+    pub default: Option<String>,
+    pub comment: Option<String>,
+    /// Attributes kept as reported by the server, e.g. `AUTO_INCREMENT`
+    pub extra: Option<String>,
+    /// `GENERATED ALWAYS AS (...)` clause of a generated column
+    pub generated: Option<String>,
+}
+
+impl ColumnSpec {
+    /// Column definition as used in CREATE and ALTER TABLE
+    pub fn definition(&self) -> String {
+        let mut definition = format!(
+            "{} {}",
+            literal::quote_identifier(&self.name),
+            self.data_type
+        );
+        if let Some(generated) = &self.generated {
+            definition.push_str(&format!(" {}", generated));
+        }
+        if !self.nullable {
+            definition.push_str(" NOT NULL");
+        }
+        if let Some(default) = &self.default {
+            definition.push_str(&format!(" DEFAULT {}", default));
+        }
+        if let Some(extra) = &self.extra {
+            definition.push_str(&format!(" {}", extra));
+        }
+        if let Some(comment) = &self.comment {
+            definition.push_str(&format!(" COMMENT {}", literal::quote_string(comment)));
+        }
+        definition
+    }
+}
+
+/// State machine behind `\create-table`
+pub struct TableWizard {
+    step: Step,
+    table: String,
+    columns: Vec<ColumnSpec>,
+    current: ColumnSpec,
+    primary_key: Vec<String>,
+    indexes: Vec<Vec<String>>,
+}
+
+impl Default for TableWizard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TableWizard {
+    pub fn new() -> Self {
+        Self {
+            step: Step::TableName,
+            table: String::new(),
+            columns: Vec::new(),
+            current: ColumnSpec::default(),
+            primary_key: Vec::new(),
+            indexes: Vec::new(),
+        }
+    }
+
+    pub fn step(&self) -> Step {
+        self.step
+    }
+
+    /// Whether the wizard is waiting for more answers
+    pub fn is_active(&self) -> bool {
+        !matches!(self.step, Step::Done | Step::Cancelled)
+    }
+
+    /// Prompt for the current step
+    pub fn prompt(&self) -> String {
+        match self.step {
+            Step::TableName => "Table name: ".to_string(),
+            Step::ColumnName => format!(
+                "Column {} name (empty to finish columns): ",
+                self.columns.len() + 1
+            ),
+            Step::ColumnType => format!("  Type of {} (Tab lists types): ", self.current.name),
+            Step::Nullable => "  Nullable? [Y/n]: ".to_string(),
+            Step::Default => "  Default (empty for none): ".to_string(),
+            Step::Comment => "  Comment (empty for none): ".to_string(),
+            Step::PrimaryKey => format!(
+                "Primary key columns, comma separated (empty for none) [{}]: ",
+                self.column_names().join(", ")
+            ),
+            Step::Indexes => format!(
+                "Index {} columns, comma separated (empty to finish): ",
+                self.indexes.len() + 1
+            ),
+            Step::Confirm => "Execute this statement? [y/N]: ".to_string(),
+            Step::Done | Step::Cancelled => String::new(),
+        }
+    }
+
+    /// Feed the answer to the current step; an error keeps the wizard on the same step
+    pub fn input(&mut self, answer: &str) -> Result<()> {
+        let answer = answer.trim();
+        if answer.eq_ignore_ascii_case("\\c") || answer.eq_ignore_ascii_case("\\q") {
+            self.step = Step::Cancelled;
+            return Ok(());
+        }
+
+        self.step = match self.step {
+            Step::TableName => {
+                if answer.is_empty() {
+                    return Err(anyhow!("A table name is required"));
+                }
+                self.table = answer.to_string();
+                Step::ColumnName
+            }
+            Step::ColumnName => {
+                if answer.is_empty() {
+                    if self.columns.is_empty() {
+                        return Err(anyhow!("Add at least one column"));
+                    }
+                    Step::PrimaryKey
+                } else if self.has_column(answer) {
+                    return Err(anyhow!("Column '{}' is already defined", answer));
+                } else {
+                    self.current = ColumnSpec {
+                        name: answer.to_string(),
+                        nullable: true,
+                        ..Default::default()
+                    };
+                    Step::ColumnType
+                }
+            }
+            Step::ColumnType => {
+                self.current.data_type = parse_type(answer)?;
+                Step::Nullable
+            }
+            Step::Nullable => {
+                self.current.nullable = yes_no(answer, true)?;
+                Step::Default
+            }
+            Step::Default => {
+                self.current.default = (!answer.is_empty()).then(|| default_literal(answer));
+                Step::Comment
+            }
+            Step::Comment => {
+                self.current.comment = (!answer.is_empty()).then(|| answer.to_string());
+                self.columns.push(std::mem::take(&mut self.current));
+                Step::ColumnName
+            }
+            Step::PrimaryKey => {
+                self.primary_key = self.parse_columns(answer)?;
+                for column in &mut self.columns {
+                    if self.primary_key.contains(&column.name) {
+                        column.nullable = false;
+                    }
+                }
+                Step::Indexes
+            }
+            Step::Indexes => {
+                if answer.is_empty() {
+                    Step::Confirm
+                } else {
+                    let columns = self.parse_columns(answer)?;
+                    self.indexes.push(columns);
+                    Step::Indexes
+                }
+            }
+            Step::Confirm => match answer.to_lowercase().as_str() {
+                "y" | "yes" => Step::Done,
+                _ => Step::Cancelled,
+            },
+            step @ (Step::Done | Step::Cancelled) => step,
+        };
+        Ok(())
+    }
+
+    /// The CREATE TABLE statement for the answers given so far
+    pub fn create_statement(&self) -> String {
+        let mut definitions: Vec<String> =
+            self.columns.iter().map(ColumnSpec::definition).collect();
+
+        if !self.primary_key.is_empty() {
+            definitions.push(format!("PRIMARY KEY ({})", quote_list(&self.primary_key)));
+        }
+        for columns in &self.indexes {
+            definitions.push(index_definition(columns));
+        }
+
+        format!(
+            "CREATE TABLE {} (\n  {}\n);",
+            literal::quote_identifier(&self.table),
+            definitions.join(",\n  ")
+        )
+    }
+
+    fn column_names(&self) -> Vec<&str> {
+        self.columns.iter().map(|c| c.name.as_str()).collect()
+    }
+
+    fn has_column(&self, name: &str) -> bool {
+        self.columns
+            .iter()
+            .any(|c| c.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Split a comma list of defined column names
+    fn parse_columns(&self, answer: &str) -> Result<Vec<String>> {
+        let mut columns = Vec::new();
+        for name in answer.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+            let column = self
+                .columns
+                .iter()
+                .find(|c| c.name.eq_ignore_ascii_case(name))
+                .ok_or_else(|| anyhow!("Unknown column '{}'", name))?;
+            columns.push(column.name.clone());
+        }
+        Ok(columns)
+    }
+}
+
+/// Columns and indexes of an existing table
+#[derive(Debug, Clone, Default)]
+pub struct TableStructure {
+    pub columns: Vec<ColumnSpec>,
+    /// Index name and its columns, in index order
+    pub indexes: Vec<(String, Vec<String>)>,
+}
+
+impl TableStructure {
+    /// Build from `SHOW FULL COLUMNS` and `SHOW INDEX` results
+    pub fn from_results(columns: &QueryResult, indexes: &QueryResult) -> Result<Self> {
+        let position = |result: &QueryResult, name: &str| {
+            result
+                .columns
+                .iter()
+                .position(|c| c.eq_ignore_ascii_case(name))
+                .ok_or_else(|| anyhow!("Missing '{}' in table description", name))
+        };
+
+        let field = position(columns, "Field")?;
+        let data_type = position(columns, "Type")?;
+        let null = position(columns, "Null")?;
+        let default = position(columns, "Default")?;
+        let extra = position(columns, "Extra")?;
+        let comment = position(columns, "Comment").ok();
+        let structure_columns = columns
+            .rows
+            .iter()
+            .map(|row| ColumnSpec {
+                name: row[field].clone(),
+                data_type: normalize_type(&row[data_type]),
+                nullable: row[null].eq_ignore_ascii_case("YES"),
+                default: (row[default] != "NULL").then(|| default_literal(&row[default])),
+                comment: comment
+                    .map(|i| row[i].clone())
+                    .filter(|comment| !comment.is_empty()),
+                extra: server_extra(&row[extra]),
+                generated: None,
+            })
+            .collect();
+
+        let key_name = position(indexes, "Key_name")?;
+        let column_name = position(indexes, "Column_name")?;
+        let mut structure_indexes: Vec<(String, Vec<String>)> = Vec::new();
+        for row in &indexes.rows {
+            match structure_indexes
+                .iter_mut()
+                .find(|(name, _)| *name == row[key_name])
+            {
+                Some((_, columns)) => columns.push(row[column_name].clone()),
+                None => {
+                    structure_indexes.push((row[key_name].clone(), vec![row[column_name].clone()]))
+                }
+            }
+        }
+
+        Ok(Self {
+            columns: structure_columns,
+            indexes: structure_indexes,
+        })
+    }
+
+    /// Add the generation clauses of generated columns from `COLUMN_NAME`,
+    /// `GENERATION_EXPRESSION` and `EXTRA` of `information_schema.COLUMNS`
+    pub fn set_generation_expressions(&mut self, generated: &QueryResult) -> Result<()> {
+        let position = |name: &str| {
+            generated
+                .columns
+                .iter()
+                .position(|c| c.eq_ignore_ascii_case(name))
+                .ok_or_else(|| anyhow!("Missing '{}' in generated columns", name))
+        };
+        let name = position("COLUMN_NAME")?;
+        let expression = position("GENERATION_EXPRESSION")?;
+        let extra = position("EXTRA")?;
+        for row in generated
+            .rows
+            .iter()
+            .filter(|row| !row[expression].is_empty())
+        {
+            let storage = if row[extra].to_uppercase().contains("STORED") {
+                "STORED"
+            } else {
+                "VIRTUAL"
+            };
+            if let Some(column) = self
+                .columns
+                .iter_mut()
+                .find(|c| c.name.eq_ignore_ascii_case(&row[name]))
+            {
+                column.generated = Some(format!(
+                    "GENERATED ALWAYS AS ({}) {}",
+                    row[expression], storage
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Column definitions and indexes, one per line
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines: Vec<String> = self.columns.iter().map(ColumnSpec::definition).collect();
+        for (name, columns) in &self.indexes {
+            lines.push(named_index_definition(name, columns));
+        }
+        lines
+    }
+
+    fn column(&self, name: &str) -> Option<&ColumnSpec> {
+        self.columns
+            .iter()
+            .find(|c| c.name.eq_ignore_ascii_case(name))
+    }
+
+    fn index(&self, name: &str) -> Option<&(String, Vec<String>)> {
+        self.indexes
+            .iter()
+            .find(|(index, _)| index.eq_ignore_ascii_case(name))
+    }
+}
+
+/// One change collected by `\alter-table`
+#[derive(Debug, Clone)]
+pub enum AlterChange {
+    AddColumn(ColumnSpec),
+    DropColumn(ColumnSpec),
+    ModifyColumn { old: ColumnSpec, new: ColumnSpec },
+    RenameColumn { old: ColumnSpec, new_name: String },
+    AddIndex(Vec<String>),
+    DropIndex(String, Vec<String>),
+}
+
+impl AlterChange {
+    /// Clause of the ALTER TABLE statement
+    fn clause(&self) -> String {
+        match self {
+            AlterChange::AddColumn(column) => format!("ADD COLUMN {}", column.definition()),
+            AlterChange::DropColumn(column) => {
+                format!("DROP COLUMN {}", literal::quote_identifier(&column.name))
+            }
+            AlterChange::ModifyColumn { old, new } if old.name != new.name => format!(
+                "CHANGE COLUMN {} {}",
+                literal::quote_identifier(&old.name),
+                new.definition()
+            ),
+            AlterChange::ModifyColumn { new, .. } => format!("MODIFY COLUMN {}", new.definition()),
+            AlterChange::RenameColumn { old, new_name } => format!(
+                "RENAME COLUMN {} TO {}",
+                literal::quote_identifier(&old.name),
+                literal::quote_identifier(new_name)
+            ),
+            AlterChange::AddIndex(columns) => format!("ADD {}", index_definition(columns)),
+            AlterChange::DropIndex(name, _) if name == "PRIMARY" => "DROP PRIMARY KEY".to_string(),
+            AlterChange::DropIndex(name, _) => {
+                format!("DROP INDEX {}", literal::quote_identifier(name))
+            }
+        }
+    }
+
+    /// Removed (`-`) and added (`+`) structure lines
+    fn diff(&self) -> Vec<String> {
+        match self {
+            AlterChange::AddColumn(column) => vec![format!("+ {}", column.definition())],
+            AlterChange::DropColumn(column) => vec![format!("- {}", column.definition())],
+            AlterChange::ModifyColumn { old, new } => vec![
+                format!("- {}", old.definition()),
+                format!("+ {}", new.definition()),
+            ],
+            AlterChange::RenameColumn { old, new_name } => {
+                let renamed = ColumnSpec {
+                    name: new_name.clone(),
+                    ..old.clone()
+                };
+                vec![
+                    format!("- {}", old.definition()),
+                    format!("+ {}", renamed.definition()),
+                ]
+            }
+            AlterChange::AddIndex(columns) => vec![format!("+ {}", index_definition(columns))],
+            AlterChange::DropIndex(name, columns) => {
+                vec![format!("- {}", named_index_definition(name, columns))]
+            }
+        }
+    }
+}
+
+/// Menu choices of `\alter-table` that ask for a column
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnAction {
+    Add,
+    Drop,
+    Modify,
+    Rename,
+}
+
+/// Steps of `\alter-table`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlterStep {
+    Menu,
+    ColumnName(ColumnAction),
+    ColumnType,
+    Nullable,
+    Default,
+    Comment,
+    NewName,
+    IndexColumns,
+    IndexName,
+    Confirm,
+    Done,
+    Cancelled,
+}
+
+/// State machine behind `\alter-table`
+pub struct AlterWizard {
+    step: AlterStep,
+    schema: String,
+    table: String,
+    /// Structure with the changes so far applied
+    working: TableStructure,
+    changes: Vec<AlterChange>,
+    /// Column being added, modified or renamed
+    current: ColumnSpec,
+    /// Definition before the modification in progress
+    modifying: Option<ColumnSpec>,
+}
+
+impl AlterWizard {
+    pub fn new(schema: &str, table: &str, structure: TableStructure) -> Self {
+        Self {
+            step: AlterStep::Menu,
+            schema: schema.to_string(),
+            table: table.to_string(),
+            working: structure,
+            changes: Vec::new(),
+            current: ColumnSpec::default(),
+            modifying: None,
+        }
+    }
+
+    pub fn step(&self) -> AlterStep {
+        self.step
+    }
+
+    /// Whether the wizard is waiting for more answers
+    pub fn is_active(&self) -> bool {
+        !matches!(self.step, AlterStep::Done | AlterStep::Cancelled)
+    }
+
+    /// Prompt for the current step
+    pub fn prompt(&self) -> String {
+        match self.step {
+            AlterStep::Menu => "[a]dd column, [d]rop column, [m]odify column, [r]ename column, \
+                                add [i]ndex, drop inde[x], [f]inish: "
+                .to_string(),
+            AlterStep::ColumnName(ColumnAction::Add) => "  New column name: ".to_string(),
+            AlterStep::ColumnName(_) => {
+                format!("  Column [{}]: ", self.column_names().join(", "))
+            }
+            AlterStep::ColumnType => format!("  Type of {} (Tab lists types): ", self.current.name),
+            AlterStep::Nullable if self.current.nullable => "  Nullable? [Y/n]: ".to_string(),
+            AlterStep::Nullable => "  Nullable? [y/N]: ".to_string(),
+            AlterStep::Default => "  Default (empty for none): ".to_string(),
+            AlterStep::Comment => "  Comment (empty for none): ".to_string(),
+            AlterStep::NewName => format!("  Rename {} to: ", self.current.name),
+            AlterStep::IndexColumns => format!(
+                "  Index columns, comma separated [{}]: ",
+                self.column_names().join(", ")
+            ),
+            AlterStep::IndexName => format!(
+                "  Drop index [{}]: ",
+                self.working
+                    .indexes
+                    .iter()
+                    .map(|(name, _)| name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            AlterStep::Confirm => "Execute this statement? [y/N]: ".to_string(),
+            AlterStep::Done | AlterStep::Cancelled => String::new(),
+        }
+    }
+
+    /// Text placed at the prompt for editing; a modified column starts from its current definition
+    pub fn initial_input(&self) -> Option<String> {
+        self.modifying.as_ref()?;
+        match self.step {
+            AlterStep::ColumnType => Some(self.current.data_type.clone()),
+            AlterStep::Default => self.current.default.clone(),
+            AlterStep::Comment => self.current.comment.clone(),
+            _ => None,
+        }
+    }
+
+    /// Feed the answer to the current step; an error keeps the wizard on the same step
+    pub fn input(&mut self, answer: &str) -> Result<()> {
+        let answer = answer.trim();
+        if answer.eq_ignore_ascii_case("\\c") || answer.eq_ignore_ascii_case("\\q") {
+            self.step = AlterStep::Cancelled;
+            return Ok(());
+        }
+
+        self.step = match self.step {
+            AlterStep::Menu => match answer.to_lowercase().as_str() {
+                "a" => AlterStep::ColumnName(ColumnAction::Add),
+                "d" => AlterStep::ColumnName(ColumnAction::Drop),
+                "m" => AlterStep::ColumnName(ColumnAction::Modify),
+                "r" => AlterStep::ColumnName(ColumnAction::Rename),
+                "i" => AlterStep::IndexColumns,
+                "x" => AlterStep::IndexName,
+                "f" if self.changes.is_empty() => {
+                    return Err(anyhow!("No changes yet (\\c cancels)"))
+                }
+                "f" => AlterStep::Confirm,
+                _ => return Err(anyhow!("Choose a, d, m, r, i, x or f")),
+            },
+            AlterStep::ColumnName(action) => self.choose_column(action, answer)?,
+            AlterStep::ColumnType => {
+                self.current.data_type = parse_type(answer)?;
+                AlterStep::Nullable
+            }
+            AlterStep::Nullable => {
+                self.current.nullable = yes_no(answer, self.current.nullable)?;
+                AlterStep::Default
+            }
+            AlterStep::Default => {
+                self.current.default = (!answer.is_empty()).then(|| default_literal(answer));
+                AlterStep::Comment
+            }
+            AlterStep::Comment => {
+                self.current.comment = (!answer.is_empty()).then(|| answer.to_string());
+                let column = std::mem::take(&mut self.current);
+                match self.modifying.take() {
+                    Some(old) => {
+                        self.replace_column(&old.name, column.clone());
+                        if !self.merge_column_change(&old.name, &column) {
+                            self.changes
+                                .push(AlterChange::ModifyColumn { old, new: column });
+                        }
+                    }
+                    None => {
+                        self.working.columns.push(column.clone());
+                        self.changes.push(AlterChange::AddColumn(column));
+                    }
+                }
+                AlterStep::Menu
+            }
+            AlterStep::NewName => {
+                if answer.is_empty() {
+                    return Err(anyhow!("A new name is required"));
+                }
+                if self.working.column(answer).is_some() {
+                    return Err(anyhow!("Column '{}' already exists", answer));
+                }
+                let old = std::mem::take(&mut self.current);
+                let renamed = ColumnSpec {
+                    name: answer.to_string(),
+                    ..old.clone()
+                };
+                self.replace_column(&old.name, renamed.clone());
+                if !self.merge_column_change(&old.name, &renamed) {
+                    self.changes.push(AlterChange::RenameColumn {
+                        old,
+                        new_name: answer.to_string(),
+                    });
+                }
+                AlterStep::Menu
+            }
+            AlterStep::IndexColumns => {
+                let mut columns = Vec::new();
+                for name in answer.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+                    let column = self
+                        .working
+                        .column(name)
+                        .ok_or_else(|| anyhow!("Unknown column '{}'", name))?;
+                    columns.push(column.name.clone());
+                }
+                if columns.is_empty() {
+                    return Err(anyhow!("Name at least one column"));
+                }
+                self.working
+                    .indexes
+                    .push((format!("idx_{}", columns.join("_")), columns.clone()));
+                self.changes.push(AlterChange::AddIndex(columns));
+                AlterStep::Menu
+            }
+            AlterStep::IndexName => {
+                let (name, columns) = self
+                    .working
+                    .index(answer)
+                    .cloned()
+                    .ok_or_else(|| anyhow!("Unknown index '{}'", answer))?;
+                self.working.indexes.retain(|(index, _)| *index != name);
+                self.changes.push(AlterChange::DropIndex(name, columns));
+                AlterStep::Menu
+            }
+            AlterStep::Confirm => match answer.to_lowercase().as_str() {
+                "y" | "yes" => AlterStep::Done,
+                _ => AlterStep::Cancelled,
+            },
+            step @ (AlterStep::Done | AlterStep::Cancelled) => step,
+        };
+        Ok(())
+    }
+
+    /// The ALTER TABLE statement for the changes collected so far
+    pub fn alter_statement(&self) -> String {
+        format!(
+            "ALTER TABLE {}.{}\n  {};",
+            literal::quote_identifier(&self.schema),
+            literal::quote_identifier(&self.table),
+            self.changes
+                .iter()
+                .map(AlterChange::clause)
+                .collect::<Vec<_>>()
+                .join(",\n  ")
+        )
+    }
+
+    /// Structure lines removed (`-`) and added (`+`) by the collected changes
+    pub fn diff(&self) -> Vec<String> {
+        self.changes.iter().flat_map(AlterChange::diff).collect()
+    }
+
+    fn choose_column(&mut self, action: ColumnAction, answer: &str) -> Result<AlterStep> {
+        if answer.is_empty() {
+            return Ok(AlterStep::Menu);
+        }
+        if action == ColumnAction::Add {
+            if self.working.column(answer).is_some() {
+                return Err(anyhow!("Column '{}' already exists", answer));
+            }
+            self.current = ColumnSpec {
+                name: answer.to_string(),
+                nullable: true,
+                ..Default::default()
+            };
+            return Ok(AlterStep::ColumnType);
+        }
+
+        let column = self
+            .working
+            .column(answer)
+            .cloned()
+            .ok_or_else(|| anyhow!("Unknown column '{}'", answer))?;
+        Ok(match action {
+            ColumnAction::Drop => {
+                self.working.columns.retain(|c| c.name != column.name);
+                self.changes.push(AlterChange::DropColumn(column));
+                AlterStep::Menu
+            }
+            ColumnAction::Modify => {
+                self.current = column.clone();
+                self.modifying = Some(column);
+                AlterStep::ColumnType
+            }
+            ColumnAction::Add | ColumnAction::Rename => {
+                self.current = column;
+                AlterStep::NewName
+            }
+        })
+    }
+
+    /// Fold a change of column `name` into the earlier change that added, modified
+    /// or renamed it: one ALTER TABLE can't refer to a column by the name an
+    /// earlier clause gives it
+    fn merge_column_change(&mut self, name: &str, column: &ColumnSpec) -> bool {
+        for change in self.changes.iter_mut().rev() {
+            match change {
+                AlterChange::AddColumn(added) if added.name == name => {
+                    *added = column.clone();
+                    return true;
+                }
+                AlterChange::ModifyColumn { new, .. } if new.name == name => {
+                    *new = column.clone();
+                    return true;
+                }
+                AlterChange::RenameColumn { old, new_name } if new_name == name => {
+                    let old = old.clone();
+                    *change = AlterChange::ModifyColumn {
+                        old,
+                        new: column.clone(),
+                    };
+                    return true;
+                }
+                _ => {}
+            }
+        }
+        false
+    }
+
+    fn replace_column(&mut self, name: &str, column: ColumnSpec) {
+        if let Some(existing) = self.working.columns.iter_mut().find(|c| c.name == name) {
+            *existing = column;
+        }
+    }
+
+    fn column_names(&self) -> Vec<&str> {
+        self.working
+            .columns
+            .iter()
+            .map(|c| c.name.as_str())
+            .collect()
+    }
+}
+
+/// Interpret a yes/no answer, empty meaning `default`
+fn yes_no(answer: &str, default: bool) -> Result<bool> {
+    match answer.to_lowercase().as_str() {
+        "" => Ok(default),
+        "y" | "yes" => Ok(true),
+        "n" | "no" => Ok(false),
+        _ => Err(anyhow!("Answer y or n")),
+    }
+}
+
+/// Uppercase a type name, keeping ENUM/SET values as written
+fn normalize_type(data_type: &str) -> String {
+    match (data_type.find('('), data_type.rfind(')')) {
+        (Some(open), Some(close)) if open < close => format!(
+            "{}{}{}",
+            data_type[..open].to_uppercase(),
+            &data_type[open..=close],
+            data_type[close + 1..].to_uppercase()
+        ),
+        _ => data_type.to_uppercase(),
+    }
+}
+
+/// `Extra` attributes that can be written back into a column definition; the
+/// generation clause of a generated column is rebuilt from its expression
+fn server_extra(extra: &str) -> Option<String> {
+    let extra = extra
+        .to_uppercase()
+        .replace("DEFAULT_GENERATED", "")
+        .replace("VIRTUAL GENERATED", "")
+        .replace("STORED GENERATED", "");
+    let extra = extra.trim();
+    (!extra.is_empty()).then(|| extra.to_string())
+}
+
+fn index_definition(columns: &[String]) -> String {
+    named_index_definition(&format!("idx_{}", columns.join("_")), columns)
+}
+
+fn named_index_definition(name: &str, columns: &[String]) -> String {
+    if name == "PRIMARY" {
+        format!("PRIMARY KEY ({})", quote_list(columns))
+    } else {
+        format!(
+            "INDEX {} ({})",
+            literal::quote_identifier(name),
+            quote_list(columns)
+        )
+    }
+}
+
+/// Accept a type whose base name is a known column type, e.g. `varchar(100)`
+fn parse_type(answer: &str) -> Result<String> {
+    let base = |t: &str| {
+        t.split(|c: char| c == '(' || c.is_whitespace())
+            .next()
+            .unwrap_or_default()
+            .to_uppercase()
+    };
+    let wanted = base(answer);
+    if wanted.is_empty() || !COLUMN_TYPES.iter().any(|t| base(t) == wanted) {
+        return Err(anyhow!(
+            "Unknown column type '{}' (press Tab for a list)",
+            answer
+        ));
+    }
+
+    Ok(normalize_type(answer))
+}
+
+/// Numbers, NULL, CURRENT_TIMESTAMP, expressions and quoted strings stay as typed;
+/// anything else becomes a string literal
+fn default_literal(answer: &str) -> String {
+    let upper = answer.to_uppercase();
+    if answer.parse::<f64>().is_ok()
+        || matches!(upper.as_str(), "NULL" | "TRUE" | "FALSE")
+        || upper.starts_with("CURRENT_TIMESTAMP")
+        || answer.starts_with('(')
+        || literal::is_quoted_string(answer)
+    {
+        answer.to_string()
+    } else {
+        literal::quote_string(answer)
+    }
+}
+
+fn quote_list(columns: &[String]) -> String {
+    columns
+        .iter()
+        .map(|c| literal::quote_identifier(c))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Line editor helper completing column types at the type prompt
+#[derive(Default)]
+pub struct WizardHelper {
+    pub complete_types: bool,
+}
+
+impl Completer for WizardHelper {
+    type Candidate = Pair;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<Pair>)> {
+        if !self.complete_types {
+            return Ok((pos, Vec::new()));
+        }
+        let typed = line[..pos].to_uppercase();
+        let candidates = COLUMN_TYPES
+            .iter()
+            .filter(|t| t.starts_with(typed.trim_start()))
+            .map(|t| Pair {
+                display: t.to_string(),
+                replacement: t.to_string(),
+            })
+            .collect();
+        Ok((0, candidates))
+    }
+}
+
+impl Hinter for WizardHelper {
+    type Hint = String;
+}
+
+impl Highlighter for WizardHelper {}
+
+impl Validator for WizardHelper {}
+
+impl Helper for WizardHelper {}
+
+#[cfg(test)]
+#[path = "./wizard_tests.rs"]
+mod wizard_tests;
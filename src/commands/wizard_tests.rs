@@ -0,0 +1,229 @@
+use super::*;
+
+fn answer_all(wizard: &mut TableWizard, answers: &[&str]) {
+    for answer in answers {
+        wizard.input(answer).unwrap();
+    }
+}
+
+#[test]
+fn test_wizard_builds_create_table() {
+    let mut wizard = TableWizard::new();
+    answer_all(
+        &mut wizard,
+        &[
+            "users",
+            "id",
+            "bigint unsigned",
+            "n",
+            "",
+            "",
+            "email",
+            "varchar(255)",
+            "",
+            "",
+            "login address",
+            "created_at",
+            "datetime",
+            "n",
+            "CURRENT_TIMESTAMP",
+            "",
+            "",
+            "id",
+            "email",
+            "",
+        ],
+    );
+
+    assert_eq!(wizard.step(), Step::Confirm);
+    assert_eq!(
+        wizard.create_statement(),
+        "CREATE TABLE `users` (\n  \
+         `id` BIGINT UNSIGNED NOT NULL,\n  \
+         `email` VARCHAR(255) COMMENT 'login address',\n  \
+         `created_at` DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,\n  \
+         PRIMARY KEY (`id`),\n  \
+         INDEX `idx_email` (`email`)\n);"
+    );
+
+    wizard.input("y").unwrap();
+    assert_eq!(wizard.step(), Step::Done);
+}
+
+#[test]
+fn test_wizard_rejects_bad_answers_and_stays_on_step() {
+    let mut wizard = TableWizard::new();
+    answer_all(&mut wizard, &["t", "name"]);
+
+    assert!(wizard.input("string").is_err());
+    assert_eq!(wizard.step(), Step::ColumnType);
+    answer_all(&mut wizard, &["varchar(20)", "", "", "", ""]);
+
+    assert!(wizard.input("missing").is_err());
+    assert_eq!(wizard.step(), Step::PrimaryKey);
+
+    wizard.input("\\c").unwrap();
+    assert!(!wizard.is_active());
+}
+
+#[test]
+fn test_default_literal_quotes_plain_text() {
+    assert_eq!(default_literal("active"), "'active'");
+    assert_eq!(default_literal("0"), "0");
+    assert_eq!(default_literal("NULL"), "NULL");
+    assert_eq!(default_literal("'x'"), "'x'");
+    assert_eq!(default_literal("'it''s'"), "'it''s'");
+    assert_eq!(default_literal("'a'b'"), "'''a''b'''");
+}
+
+fn structure() -> TableStructure {
+    let columns = QueryResult {
+        tables: Vec::new(),
+        nulls: Vec::new(),
+        columns: [
+            "Field", "Type", "Null", "Key", "Default", "Extra", "Comment",
+        ]
+        .map(String::from)
+        .to_vec(),
+        rows: vec![
+            [
+                "id",
+                "int unsigned",
+                "NO",
+                "PRI",
+                "NULL",
+                "auto_increment",
+                "",
+            ]
+            .map(String::from)
+            .to_vec(),
+            [
+                "name",
+                "varchar(50)",
+                "YES",
+                "MUL",
+                "NULL",
+                "",
+                "display name",
+            ]
+            .map(String::from)
+            .to_vec(),
+        ],
+    };
+    let indexes = QueryResult {
+        tables: Vec::new(),
+        nulls: Vec::new(),
+        columns: ["Key_name", "Column_name"].map(String::from).to_vec(),
+        rows: vec![
+            ["PRIMARY", "id"].map(String::from).to_vec(),
+            ["idx_name", "name"].map(String::from).to_vec(),
+        ],
+    };
+    TableStructure::from_results(&columns, &indexes).unwrap()
+}
+
+#[test]
+fn test_table_structure_from_server_description() {
+    assert_eq!(
+        structure().lines(),
+        vec![
+            "`id` INT UNSIGNED NOT NULL AUTO_INCREMENT",
+            "`name` VARCHAR(50) COMMENT 'display name'",
+            "PRIMARY KEY (`id`)",
+            "INDEX `idx_name` (`name`)",
+        ]
+    );
+}
+
+#[test]
+fn test_alter_wizard_collects_changes() {
+    let mut wizard = AlterWizard::new("shop", "users", structure());
+    for answer in [
+        "a",
+        "email",
+        "varchar(255)",
+        "n",
+        "",
+        "", // add column
+        "m",
+        "name",
+        "VARCHAR(100)",
+        "",
+        "",
+        "display name", // modify column
+        "r",
+        "name",
+        "full_name", // rename column
+        "x",
+        "idx_name", // drop index
+        "i",
+        "email", // add index
+        "f",
+    ] {
+        wizard.input(answer).unwrap();
+    }
+
+    assert_eq!(wizard.step(), AlterStep::Confirm);
+    assert_eq!(
+        wizard.alter_statement(),
+        "ALTER TABLE `shop`.`users`\n  \
+         ADD COLUMN `email` VARCHAR(255) NOT NULL,\n  \
+         CHANGE COLUMN `name` `full_name` VARCHAR(100) COMMENT 'display name',\n  \
+         DROP INDEX `idx_name`,\n  \
+         ADD INDEX `idx_email` (`email`);"
+    );
+    assert_eq!(
+        wizard.diff()[..3],
+        [
+            "+ `email` VARCHAR(255) NOT NULL",
+            "- `name` VARCHAR(50) COMMENT 'display name'",
+            "+ `full_name` VARCHAR(100) COMMENT 'display name'",
+        ]
+    );
+}
+
+#[test]
+fn test_generated_column_rebuilt_from_expression() {
+    let mut structure = structure();
+    structure.columns.push(ColumnSpec {
+        name: "initial".to_string(),
+        data_type: "CHAR(1)".to_string(),
+        nullable: true,
+        extra: server_extra("VIRTUAL GENERATED"),
+        ..Default::default()
+    });
+    let generated = QueryResult {
+        tables: Vec::new(),
+        nulls: Vec::new(),
+        columns: ["COLUMN_NAME", "GENERATION_EXPRESSION", "EXTRA"]
+            .map(String::from)
+            .to_vec(),
+        rows: vec![["initial", "left(`name`,1)", "VIRTUAL GENERATED"]
+            .map(String::from)
+            .to_vec()],
+    };
+    structure.set_generation_expressions(&generated).unwrap();
+
+    let mut wizard = AlterWizard::new("shop", "users", structure);
+    for answer in ["m", "initial", "CHAR(2)", "", "", "", "f"] {
+        wizard.input(answer).unwrap();
+    }
+    assert_eq!(
+        wizard.alter_statement(),
+        "ALTER TABLE `shop`.`users`\n  \
+         MODIFY COLUMN `initial` CHAR(2) GENERATED ALWAYS AS (left(`name`,1)) VIRTUAL;"
+    );
+}
+
+#[test]
+fn test_alter_wizard_rejects_unknown_columns() {
+    let mut wizard = AlterWizard::new("shop", "users", structure());
+    wizard.input("d").unwrap();
+    assert!(wizard.input("missing").is_err());
+    assert_eq!(wizard.step(), AlterStep::ColumnName(ColumnAction::Drop));
+
+    // An empty answer goes back to the menu, which cannot finish without changes
+    wizard.input("").unwrap();
+    assert_eq!(wizard.step(), AlterStep::Menu);
+    assert!(wizard.input("f").is_err());
+}
@@ -0,0 +1,119 @@
+/*!
+ * Excel export
+ *
+ * `\export-xlsx` writes a result set to an `.xlsx` workbook: a bold header row,
+ * then numbers, dates and text in cells of matching type
+ */
+
+use crate::database::QueryResult;
+use anyhow::Result;
+use regex::Regex;
+use rust_xlsxwriter::{ExcelDateTime, Format, Workbook};
+use std::sync::OnceLock;
+
+/// How a column's values are written
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CellKind {
+    Number,
+    Date,
+    DateTime,
+    Text,
+}
+
+/// Kind of a column from its values; NULLs are ignored, and a column is only
+/// numeric or a date when every other value is
+pub fn column_kind<'a>(values: impl Iterator<Item = &'a str>) -> CellKind {
+    static DATE: OnceLock<Regex> = OnceLock::new();
+    static DATETIME: OnceLock<Regex> = OnceLock::new();
+    let date = DATE.get_or_init(|| Regex::new(r"^\d{4}-\d{2}-\d{2}$").unwrap());
+    let datetime = DATETIME
+        .get_or_init(|| Regex::new(r"^\d{4}-\d{2}-\d{2} \d{2}:\d{2}:\d{2}(\.\d+)?$").unwrap());
+
+    let mut kind = None;
+    for value in values.filter(|value| *value != "NULL") {
+        let value_kind = if is_number(value) {
+            CellKind::Number
+        } else if date.is_match(value) {
+            CellKind::Date
+        } else if datetime.is_match(value) {
+            CellKind::DateTime
+        } else {
+            return CellKind::Text;
+        };
+        match kind {
+            None => kind = Some(value_kind),
+            Some(kind) if kind == value_kind => {}
+            // A column mixing dates and datetimes is written as datetimes
+            Some(CellKind::Date | CellKind::DateTime)
+                if matches!(value_kind, CellKind::Date | CellKind::DateTime) =>
+            {
+                kind = Some(CellKind::DateTime)
+            }
+            Some(_) => return CellKind::Text,
+        }
+    }
+    kind.unwrap_or(CellKind::Text)
+}
+
+/// Whether a value can be stored as an Excel number without changing it: codes
+/// with leading zeros and integers beyond double precision stay text
+fn is_number(value: &str) -> bool {
+    let digits = value.trim_start_matches('-');
+    let integer = digits.split('.').next().unwrap_or_default();
+    if integer.len() > 1 && integer.starts_with('0') {
+        return false;
+    }
+    if !digits.contains(['.', 'e', 'E']) && digits.len() > 15 {
+        return false;
+    }
+    value.parse::<f64>().is_ok_and(f64::is_finite)
+}
+
+/// Write a result to `path`, returning the number of data rows
+pub fn write(result: &QueryResult, path: &str) -> Result<usize> {
+    let mut workbook = Workbook::new();
+    let worksheet = workbook.add_worksheet();
+    let bold = Format::new().set_bold();
+    let date_format = Format::new().set_num_format("yyyy-mm-dd");
+    let datetime_format = Format::new().set_num_format("yyyy-mm-dd hh:mm:ss");
+
+    for (col, name) in result.unique_columns().iter().enumerate() {
+        worksheet.write_string_with_format(0, col as u16, name, &bold)?;
+    }
+
+    for (col, _) in result.columns.iter().enumerate() {
+        let kind = column_kind(result.rows.iter().map(|row| row[col].as_str()));
+        for (i, row) in result.rows.iter().enumerate() {
+            let (row_num, col_num) = (i as u32 + 1, col as u16);
+            let value = row[col].as_str();
+            if value == "NULL" {
+                continue;
+            }
+            match kind {
+                CellKind::Number => {
+                    worksheet.write_number(row_num, col_num, value.parse::<f64>()?)?;
+                }
+                CellKind::Date | CellKind::DateTime => {
+                    let format = if kind == CellKind::Date {
+                        &date_format
+                    } else {
+                        &datetime_format
+                    };
+                    let datetime = ExcelDateTime::parse_from_str(value)?;
+                    worksheet.write_datetime_with_format(row_num, col_num, &datetime, format)?;
+                }
+                CellKind::Text => {
+                    worksheet.write_string(row_num, col_num, value)?;
+                }
+            }
+        }
+    }
+
+    worksheet.autofit();
+    workbook.save(path)?;
+    Ok(result.rows.len())
+}
+
+#[cfg(test)]
+#[path = "./xlsx_tests.rs"]
+mod xlsx_tests;
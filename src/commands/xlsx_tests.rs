@@ -0,0 +1,44 @@
+use super::*;
+
+#[test]
+fn test_column_kind() {
+    assert_eq!(
+        column_kind(["1", "2.5", "NULL", "-3"].into_iter()),
+        CellKind::Number
+    );
+    assert_eq!(
+        column_kind(["2024-01-02", "NULL"].into_iter()),
+        CellKind::Date
+    );
+    assert_eq!(
+        column_kind(["2024-01-02", "2024-01-02 03:04:05"].into_iter()),
+        CellKind::DateTime
+    );
+    assert_eq!(column_kind(["1", "a"].into_iter()), CellKind::Text);
+    assert_eq!(column_kind(["NULL"].into_iter()), CellKind::Text);
+}
+
+#[test]
+fn test_codes_and_big_integers_stay_text() {
+    assert_eq!(column_kind(["0150", "2000"].into_iter()), CellKind::Text);
+    assert_eq!(
+        column_kind(["18446744073709551615"].into_iter()),
+        CellKind::Text
+    );
+    assert_eq!(column_kind(["0.5", "0"].into_iter()), CellKind::Number);
+}
+
+#[test]
+fn test_write_workbook() {
+    let result = QueryResult {
+        columns: vec!["id".to_string(), "created".to_string()],
+        rows: vec![vec!["1".to_string(), "2024-01-02".to_string()]],
+        tables: Vec::new(),
+        nulls: Vec::new(),
+    };
+    let path = std::env::temp_dir().join(format!("export-{}.xlsx", std::process::id()));
+    let path = path.to_str().unwrap();
+    assert_eq!(write(&result, path).unwrap(), 1);
+    assert!(std::fs::read(path).unwrap().starts_with(b"PK"));
+    std::fs::remove_file(path).unwrap();
+}
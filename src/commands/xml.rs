@@ -0,0 +1,50 @@
+/*!
+ * XML output
+ *
+ * `--xml` prints result sets like `mysql --xml`, for tools that read XML:
+ * a `<resultset>` of `<row>`s holding one `<field name="...">` per column
+ */
+
+use crate::database::QueryResult;
+use anyhow::Result;
+use quick_xml::events::{BytesDecl, BytesEnd, BytesStart, BytesText, Event};
+use quick_xml::Writer;
+
+const XSI_NAMESPACE: &str = "http://www.w3.org/2001/XMLSchema-instance";
+
+/// Format a result set as an XML document; NULL values are empty fields with
+/// `xsi:nil="true"`
+pub fn format(statement: &str, result: &QueryResult) -> Result<String> {
+    let mut writer = Writer::new_with_indent(Vec::new(), b' ', 2);
+    writer.write_event(Event::Decl(BytesDecl::new("1.0", None, None)))?;
+
+    let mut resultset = BytesStart::new("resultset");
+    resultset.push_attribute(("statement", statement));
+    resultset.push_attribute(("xmlns:xsi", XSI_NAMESPACE));
+    writer.write_event(Event::Start(resultset))?;
+    for (i, row) in result.rows.iter().enumerate() {
+        writer.write_event(Event::Start(BytesStart::new("row")))?;
+        for (j, (column, value)) in result.columns.iter().zip(row).enumerate() {
+            let mut field = BytesStart::new("field");
+            field.push_attribute(("name", column.as_str()));
+            if result.is_null(i, j) {
+                field.push_attribute(("xsi:nil", "true"));
+                writer.write_event(Event::Empty(field))?;
+            } else {
+                writer.write_event(Event::Start(field))?;
+                writer.write_event(Event::Text(BytesText::new(value)))?;
+                writer.write_event(Event::End(BytesEnd::new("field")))?;
+            }
+        }
+        writer.write_event(Event::End(BytesEnd::new("row")))?;
+    }
+    writer.write_event(Event::End(BytesEnd::new("resultset")))?;
+
+    let mut xml = String::from_utf8(writer.into_inner())?;
+    xml.push('\n');
+    Ok(xml)
+}
+
+#[cfg(test)]
+#[path = "./xml_tests.rs"]
+mod xml_tests;
@@ -0,0 +1,34 @@
+use super::*;
+
+#[test]
+fn test_format_escapes_values_and_marks_nulls() {
+    let result = QueryResult {
+        columns: vec!["id".to_string(), "note".to_string()],
+        rows: vec![
+            vec!["1".to_string(), "a < b & \"c\" 'd' > e".to_string()],
+            vec!["2".to_string(), "NULL".to_string()],
+            vec!["3".to_string(), "NULL".to_string()],
+        ],
+        tables: Vec::new(),
+        nulls: vec![vec![false, false], vec![false, true], vec![false, false]],
+    };
+    assert_eq!(
+        format("SELECT * FROM t WHERE x < 1", &result).unwrap(),
+        "<?xml version=\"1.0\"?>\n\
+         <resultset statement=\"SELECT * FROM t WHERE x &lt; 1\" \
+         xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\">\n  \
+           <row>\n    \
+             <field name=\"id\">1</field>\n    \
+             <field name=\"note\">a &lt; b &amp; &quot;c&quot; &apos;d&apos; &gt; e</field>\n  \
+           </row>\n  \
+           <row>\n    \
+             <field name=\"id\">2</field>\n    \
+             <field name=\"note\" xsi:nil=\"true\"/>\n  \
+           </row>\n  \
+           <row>\n    \
+             <field name=\"id\">3</field>\n    \
+             <field name=\"note\">NULL</field>\n  \
+           </row>\n\
+         </resultset>\n"
+    );
+}
@@ -7,12 +7,19 @@
  * - Sorting by relevance
  */
 
-use super::metadata::DatabaseMetadata;
+use super::metadata::{ColumnInfo, DatabaseMetadata};
+use super::special_commands::{self, Argument};
 use super::suggestion::Suggestion;
+use lru::LruCache;
+use regex::Regex;
 use sqlparser::ast::{Query, SetExpr, Statement};
 use sqlparser::dialect::MySqlDialect;
 use sqlparser::parser::Parser;
-use std::sync::{Arc, Mutex};
+use std::num::NonZeroUsize;
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// Number of parsed lines remembered between keystrokes
+const PARSE_CACHE_SIZE: usize = 32;
 
 /// Input context analysis result
 #[derive(Debug, PartialEq)]
@@ -37,6 +44,22 @@ pub enum InputContext {
     HavingClause,
     /// JOIN ON clause (column names for join conditions)
     JoinOnClause,
+    /// JSON path after `->`, `->>` or inside JSON_EXTRACT (path literal expected)
+    JsonPath,
+    /// Second argument of DATE_FORMAT or STR_TO_DATE (format string expected)
+    DateFormatClause,
+    /// After CHARSET=, CHARACTER SET or SET NAMES (character set name expected)
+    CharsetName,
+    /// After COLLATE (collation name expected)
+    CollationName,
+    /// After ENGINE= in CREATE/ALTER TABLE options (storage engine expected)
+    EngineName,
+    /// After PARTITION BY (partition type or column expected)
+    PartitionBy,
+    /// A backslash command name (`\st`)
+    SpecialCommand,
+    /// The first argument of the named backslash command (`\u sa`)
+    SpecialCommandArg(&'static str),
     /// General case
     General,
 }
@@ -46,6 +69,14 @@ pub struct SmartSuggestionEngine {
     metadata: Arc<Mutex<DatabaseMetadata>>,
     sql_keywords: Vec<String>,
     current_database: Arc<Mutex<Option<String>>>,
+    /// Databases whose tables are offered after the current one, in priority order
+    search_path: Mutex<Vec<String>>,
+    /// User-configured list size overriding the per-context default; 0 means unlimited
+    completion_limit: Mutex<Option<usize>>,
+    dialect: MySqlDialect,
+    /// Parse results by input text; `None` when the text does not parse.
+    /// Tab and hints ask about the same line repeatedly, and an edited line is a new key
+    parse_cache: Mutex<LruCache<String, Option<Vec<Statement>>>>,
 }
 
 impl SmartSuggestionEngine {
@@ -55,6 +86,17 @@ impl SmartSuggestionEngine {
             metadata,
             sql_keywords,
             current_database: Arc::new(Mutex::new(None)),
+            search_path: Mutex::new(Vec::new()),
+            completion_limit: Mutex::new(None),
+            dialect: MySqlDialect {},
+            parse_cache: Mutex::new(LruCache::new(NonZeroUsize::new(PARSE_CACHE_SIZE).unwrap())),
+        }
+    }
+
+    /// Override the suggestion list size (`None` restores per-context defaults, 0 is unlimited)
+    pub fn set_completion_limit(&self, limit: Option<usize>) {
+        if let Ok(mut completion_limit) = self.completion_limit.lock() {
+            *completion_limit = limit;
         }
     }
 
@@ -65,27 +107,68 @@ impl SmartSuggestionEngine {
         }
     }
 
-    /// Get smart suggestion list
+    /// Set the databases searched for unqualified table names after the current one
+    pub fn set_search_path(&self, databases: Vec<String>) {
+        if let Ok(mut search_path) = self.search_path.lock() {
+            *search_path = databases;
+        }
+    }
+
+    /// Get smart suggestion list, truncated to the list size
     pub fn get_suggestions(&self, line: &str, word: &str) -> Vec<Suggestion> {
+        let (mut suggestions, limit) = self.get_ranked_suggestions(line, word);
+        if let Some(limit) = limit {
+            suggestions.truncate(limit);
+        }
+        suggestions
+    }
+
+    /// Get every suggestion in rank order, with the list size for this context
+    /// (`None` when unlimited)
+    pub fn get_ranked_suggestions(
+        &self,
+        line: &str,
+        word: &str,
+    ) -> (Vec<Suggestion>, Option<usize>) {
+        self.get_ranked_suggestions_at(line, line.len(), word)
+    }
+
+    /// Like `get_ranked_suggestions`, with the caret at byte offset `caret` of `line`
+    pub fn get_ranked_suggestions_at(
+        &self,
+        line: &str,
+        caret: usize,
+        word: &str,
+    ) -> (Vec<Suggestion>, Option<usize>) {
         let mut suggestions = Vec::new();
         let line_upper = line.to_uppercase();
         let word_lower = word.to_lowercase();
 
         // Analyze current input context
-        let context = self.analyze_context(&line_upper);
+        let context = self.analyze_context_at(&line_upper, caret);
 
         // Generate suggestions based on context
         match context {
             InputContext::UseCommand => {
-                suggestions.extend(self.get_database_suggestions(&word_lower));
+                suggestions.extend(
+                    self.get_database_suggestions(&word_lower)
+                        .into_iter()
+                        .map(Suggestion::completing_statement),
+                );
                 // If no databases found and word is empty, still provide some indication
                 if suggestions.is_empty() && word.is_empty() {
                     // Add a placeholder suggestion to indicate no databases available
                     suggestions.push(Suggestion::command(
-                        "-- No databases available --".to_string(),
-                        "Connect to a MySQL server with databases".to_string(),
+                        "-- No databases visible --".to_string(),
+                        "The account may lack the SHOW DATABASES privilege; type a database name to use it".to_string(),
                         50,
                     ));
+                } else if word.is_empty() && self.database_list_limited() {
+                    suggestions.push(Suggestion::command(
+                        "-- Database list limited by privileges --".to_string(),
+                        "SHOW DATABASES is not permitted; only the current and -D databases are listed".to_string(),
+                        10,
+                    ));
                 }
             }
             InputContext::FromClause => {
@@ -102,7 +185,14 @@ impl SmartSuggestionEngine {
             }
             InputContext::SelectClause => {
                 // For SELECT without FROM, limit suggestions and prefer SQL functions/keywords
-                if !line_upper.contains("FROM") {
+                if line_upper
+                    .strip_suffix(&word.to_uppercase())
+                    .is_some_and(|before| before.ends_with("@@SESSION."))
+                {
+                    // `@@session.` is typed; the word is the variable name
+                    suggestions.extend(self.get_session_variable_suggestions(&word_lower, ""));
+                } else if !line_upper.contains("FROM") {
+                    suggestions.extend(self.get_session_suggestions(&word_lower));
                     // If it's just "SELECT" or "SELECT ", provide basic suggestions
                     if line_upper.trim() == "SELECT" || line_upper.trim() == "SELECT " {
                         suggestions.extend(self.get_sql_keyword_suggestions(&word_lower));
@@ -139,8 +229,33 @@ impl SmartSuggestionEngine {
                 }
             }
             InputContext::WhereClause | InputContext::HavingClause | InputContext::JoinOnClause => {
-                suggestions.extend(self.get_column_suggestions_for_query(&line_upper, &word_lower));
-                suggestions.extend(self.get_condition_suggestions(&word_lower));
+                let before_word = line_upper
+                    .trim_end()
+                    .strip_suffix(&word.to_uppercase())
+                    .unwrap_or(&line_upper);
+                let column = (word.is_empty() && line_upper.ends_with(char::is_whitespace))
+                    .then(|| self.preceding_column(&line_upper, before_word))
+                    .flatten();
+                if let Some(column) = column {
+                    // Right after a column an operator is expected; condition keywords rank below
+                    let operators = self.get_operator_suggestions(&column);
+                    let conditions: Vec<Suggestion> = self
+                        .get_condition_suggestions(&word_lower)
+                        .into_iter()
+                        .filter(|c| !operators.iter().any(|o| o.text.starts_with(&c.text)))
+                        .collect();
+                    suggestions.extend(operators);
+                    suggestions.extend(conditions);
+                } else {
+                    suggestions
+                        .extend(self.get_column_suggestions_for_query(&line_upper, &word_lower));
+                    // After `BETWEEN x AND` the upper bound is expected, not another condition
+                    if is_between_upper_bound(before_word) {
+                        suggestions.extend(self.get_function_suggestions(&word_lower));
+                    } else {
+                        suggestions.extend(self.get_condition_suggestions(&word_lower));
+                    }
+                }
             }
             InputContext::OrderByClause | InputContext::GroupByClause => {
                 suggestions.extend(self.get_column_suggestions_for_query(&line_upper, &word_lower));
@@ -148,6 +263,62 @@ impl SmartSuggestionEngine {
             InputContext::InsertIntoClause | InputContext::UpdateClause => {
                 suggestions.extend(self.get_table_suggestions(&word_lower));
             }
+            InputContext::JsonPath => {
+                suggestions.extend(self.get_json_path_suggestions(word));
+            }
+            InputContext::DateFormatClause => {
+                suggestions.extend(self.get_date_format_suggestions(word));
+            }
+            InputContext::CharsetName => {
+                suggestions.extend(self.get_charset_suggestions(&word_lower));
+            }
+            InputContext::CollationName => {
+                let charset = chosen_charset(&line_upper);
+                suggestions.extend(self.get_collation_suggestions(charset.as_deref(), &word_lower));
+            }
+            InputContext::EngineName => {
+                suggestions.extend(self.get_engine_suggestions(&word_lower));
+            }
+            InputContext::PartitionBy => {
+                suggestions.extend(self.get_partition_suggestions(line, &word_lower));
+            }
+            InputContext::SpecialCommand => {
+                suggestions.extend(
+                    special_commands::SPECIAL_COMMANDS
+                        .iter()
+                        .filter(|command| command.name.starts_with(&word_lower))
+                        .map(|command| {
+                            Suggestion::command(
+                                command.name.to_string(),
+                                command.description.to_string(),
+                                90,
+                            )
+                        }),
+                );
+            }
+            InputContext::SpecialCommandArg(name) => {
+                match special_commands::find(name).map(|command| command.argument) {
+                    Some(Argument::Database) => {
+                        suggestions.extend(self.get_database_suggestions(&word_lower))
+                    }
+                    Some(Argument::Table) => {
+                        suggestions.extend(self.get_table_suggestions(&word_lower))
+                    }
+                    Some(Argument::Subcommand(subcommands)) => suggestions.extend(
+                        subcommands
+                            .iter()
+                            .filter(|subcommand| subcommand.starts_with(&word_lower))
+                            .map(|subcommand| {
+                                Suggestion::command(
+                                    subcommand.to_string(),
+                                    format!("{} {}", name, subcommand),
+                                    90,
+                                )
+                            }),
+                    ),
+                    _ => {}
+                }
+            }
             InputContext::General => {
                 suggestions.extend(self.get_sql_keyword_suggestions(&word_lower));
                 if word.is_empty() {
@@ -156,8 +327,19 @@ impl SmartSuggestionEngine {
             }
         }
 
-        // Sort by relevance and limit quantity based on context
-        suggestions.sort_by(|a, b| b.relevance.cmp(&a.relevance));
+        // Sort deterministically
+        suggestions.sort_by(Suggestion::cmp_rank);
+
+        (suggestions, self.suggestion_limit(&context))
+    }
+
+    /// List size for a context: the configured limit, or a per-context default
+    fn suggestion_limit(&self, context: &InputContext) -> Option<usize> {
+        match self.completion_limit.lock().ok().and_then(|limit| *limit) {
+            Some(0) => return None,
+            Some(limit) => return Some(limit),
+            None => {}
+        }
 
         // Use different limits based on context
         let limit = match context {
@@ -170,13 +352,62 @@ impl SmartSuggestionEngine {
             | InputContext::HavingClause
             | InputContext::JoinOnClause
             | InputContext::OrderByClause
-            | InputContext::GroupByClause => 15, // Show more columns for filtering/sorting
-            InputContext::General => 10,    // Default limit for other contexts
+            | InputContext::GroupByClause
+            | InputContext::CharsetName
+            | InputContext::CollationName
+            | InputContext::EngineName
+            | InputContext::DateFormatClause
+            | InputContext::PartitionBy => 15, // Show more columns for filtering/sorting
+            InputContext::SpecialCommand => 50, // Every command at once
+            InputContext::SpecialCommandArg(_) => 20,
+            InputContext::JsonPath | InputContext::General => 10, // Default limit for other contexts
         };
 
-        suggestions.truncate(limit);
+        Some(limit)
+    }
 
-        suggestions
+    /// Analyze the context at the caret; inside a parenthesized SELECT (a derived
+    /// table or subquery) the subquery's own context applies
+    fn analyze_context_at(&self, line: &str, caret: usize) -> InputContext {
+        // Upper-casing may change byte lengths; fall back to the whole line then
+        let caret = if caret <= line.len() && line.is_char_boundary(caret) {
+            caret
+        } else {
+            line.len()
+        };
+        let subquery = enclosing_subquery(&line[..caret]);
+        let context = self.analyze_context(subquery.unwrap_or(line));
+
+        // Inside a function call (`WHERE DATE(cre`, `COUNT(DISTINCT na`) the clause
+        // around the outermost open call decides, unless the arguments have their
+        // own context such as a DATE_FORMAT format
+        let text = subquery.unwrap_or(&line[..caret]);
+        let takes_expression = matches!(
+            context,
+            InputContext::SelectClause
+                | InputContext::FromClause
+                | InputContext::WhereClause
+                | InputContext::HavingClause
+                | InputContext::JoinOnClause
+                | InputContext::OrderByClause
+                | InputContext::GroupByClause
+                | InputContext::General
+        );
+        if let Some(start) = enclosing_call(text).filter(|_| takes_expression) {
+            let outer = self.analyze_context(&text[..start]);
+            if matches!(
+                outer,
+                InputContext::SelectClause
+                    | InputContext::WhereClause
+                    | InputContext::HavingClause
+                    | InputContext::JoinOnClause
+                    | InputContext::OrderByClause
+                    | InputContext::GroupByClause
+            ) {
+                return outer;
+            }
+        }
+        context
     }
 
     /// Analyze input context using SQL parser for better accuracy
@@ -188,12 +419,44 @@ impl SmartSuggestionEngine {
             return InputContext::General;
         }
 
+        if special_commands::is_command_name(line) {
+            return InputContext::SpecialCommand;
+        }
+        if let Some(command) = special_commands::argument_command(line) {
+            return InputContext::SpecialCommandArg(command.name);
+        }
+
+        // JSON accessor on a known JSON column
+        if let Some(column) = json_accessor_column(line_trimmed) {
+            if let Ok(metadata) = self.metadata.try_lock() {
+                if metadata.is_json_column(&column) {
+                    return InputContext::JsonPath;
+                }
+            }
+        }
+
+        if is_date_format_argument(line_trimmed) {
+            return InputContext::DateFormatClause;
+        }
+
+        // Charset, collation and engine names and partitioning; checked before parsing
+        // because a partly typed name (`SET NAMES utf`) already parses as a complete statement
+        if let Some(context) =
+            charset_context(line_trimmed).or_else(|| table_option_context(line_trimmed))
+        {
+            return context;
+        }
+
+        // `\show columns <table>` takes a table name
+        if line_trimmed.to_uppercase().starts_with("\\SHOW COLUMNS") {
+            return InputContext::FromClause;
+        }
+
         // Quick check for specific commands first
         let words: Vec<&str> = line_trimmed.split_whitespace().collect();
         if let Some(first_word) = words.first() {
-            match first_word.to_uppercase().as_str() {
-                "USE" => return InputContext::UseCommand,
-                _ => {}
+            if first_word.to_uppercase() == "USE" {
+                return InputContext::UseCommand;
             }
         }
 
@@ -208,16 +471,14 @@ impl SmartSuggestionEngine {
 
     /// Analyze SQL context using sqlparser
     fn analyze_sql_context(&self, sql: &str) -> Result<InputContext, Box<dyn std::error::Error>> {
-        let dialect = MySqlDialect {};
-
         // Try to parse as a complete statement first
-        match Parser::parse_sql(&dialect, sql) {
-            Ok(statements) => {
+        match self.parse_cached(sql) {
+            Some(statements) => {
                 if let Some(stmt) = statements.first() {
                     return Ok(self.determine_context_from_statement(stmt));
                 }
             }
-            Err(_) => {
+            None => {
                 // If complete parsing fails, try to analyze incomplete queries
                 return self.analyze_incomplete_sql(sql);
             }
@@ -226,6 +487,17 @@ impl SmartSuggestionEngine {
         Err("Could not determine context".into())
     }
 
+    /// Parse `sql`, reusing the result when the same text was parsed recently
+    fn parse_cached(&self, sql: &str) -> Option<Vec<Statement>> {
+        let mut cache = self.parse_cache.lock().unwrap();
+        if let Some(parsed) = cache.get(sql) {
+            return parsed.clone();
+        }
+        let parsed = Parser::parse_sql(&self.dialect, sql).ok();
+        cache.put(sql.to_string(), parsed.clone());
+        parsed
+    }
+
     /// Determine context from a parsed SQL statement
     fn determine_context_from_statement(&self, stmt: &Statement) -> InputContext {
         match stmt {
@@ -262,6 +534,12 @@ impl SmartSuggestionEngine {
     ) -> Result<InputContext, Box<dyn std::error::Error>> {
         let sql_upper = sql.to_uppercase();
 
+        if let Some(context) =
+            charset_context(&sql_upper).or_else(|| table_option_context(&sql_upper))
+        {
+            return Ok(context);
+        }
+
         // Look for keyword patterns to determine context
         if sql_upper.ends_with("WHERE") {
             return Ok(InputContext::WhereClause);
@@ -392,6 +670,13 @@ impl SmartSuggestionEngine {
         }
     }
 
+    /// Whether the database list is the fallback for accounts without SHOW DATABASES
+    fn database_list_limited(&self) -> bool {
+        self.metadata
+            .try_lock()
+            .is_ok_and(|metadata| metadata.is_database_list_limited())
+    }
+
     /// Get database suggestions
     fn get_database_suggestions(&self, word: &str) -> Vec<Suggestion> {
         // Try to lock metadata with timeout to avoid hanging
@@ -437,8 +722,37 @@ impl SmartSuggestionEngine {
         };
 
         let current_db = self.current_database.lock().unwrap();
+        let search_path = self.search_path.lock().unwrap();
         let mut suggestions = Vec::new();
 
+        // Position of a search path database, ranked right after the current database
+        let search_rank = |db: &String| {
+            search_path
+                .iter()
+                .position(|p| p.eq_ignore_ascii_case(db))
+                .filter(|_| current_db.as_ref() != Some(db))
+                .map(|i| (i + 1).min(9) as u8)
+        };
+
+        // Bare names only resolve in the current database, so tables offered from
+        // the search path, or from anywhere without a default schema, are qualified
+        let table_suggestion = |table: &String, db: &String, relevance: u8| {
+            if current_db.is_none() || search_rank(db).is_some() {
+                Suggestion::qualified_table(table.clone(), db, relevance)
+            } else {
+                Suggestion::table(table.clone(), db, relevance)
+            }
+        };
+
+        // Tables outside the current database and search path rank below both
+        let other_relevance = |relevance: u8| {
+            if search_path.is_empty() {
+                relevance
+            } else {
+                relevance.min(80)
+            }
+        };
+
         // Separate current database tables and other tables
         let mut current_db_tables = Vec::new();
         let mut other_tables = Vec::new();
@@ -446,8 +760,12 @@ impl SmartSuggestionEngine {
         for (db, table) in metadata.get_all_tables() {
             if word.is_empty() {
                 // When no input, show all tables with current database first
-                let relevance = self.calculate_relevance(table, word, 85);
-                let suggestion = Suggestion::table(table.clone(), db, relevance);
+                let relevance = match search_rank(db) {
+                    Some(rank) => 85 - rank,
+                    None if current_db.as_ref() == Some(db) => 85,
+                    None => other_relevance(self.calculate_relevance(table, word, 85)),
+                };
+                let suggestion = table_suggestion(table, db, relevance);
 
                 if current_db.as_ref() == Some(db) {
                     current_db_tables.push(suggestion);
@@ -460,12 +778,14 @@ impl SmartSuggestionEngine {
                 let word_lower = word.to_lowercase();
 
                 if table_lower.starts_with(&word_lower) {
-                    let relevance = if current_db.as_ref() == Some(db) {
-                        95 // Higher relevance for current database tables
-                    } else {
-                        self.calculate_relevance(table, word, 85)
+                    let relevance = match search_rank(db) {
+                        Some(rank) => 95 - rank,
+                        None if current_db.as_ref() == Some(db) => {
+                            95 // Higher relevance for current database tables
+                        }
+                        None => other_relevance(self.calculate_relevance(table, word, 85)),
                     };
-                    suggestions.push(Suggestion::table(table.clone(), db, relevance));
+                    suggestions.push(table_suggestion(table, db, relevance));
                 }
             }
         }
@@ -507,17 +827,20 @@ impl SmartSuggestionEngine {
             // First try with current database
             if let Some(current_db_name) = current_db.as_ref() {
                 let full_table_key = format!("{}.{}", current_db_name, table_name).to_lowercase();
-                if let Some(columns) = metadata.columns.get(&full_table_key) {
+                if let Some(columns) = metadata.get_columns(&full_table_key) {
                     for column in columns {
                         if word.is_empty()
                             || column.to_lowercase().starts_with(&word.to_lowercase())
                         {
                             let relevance = self.calculate_relevance(column, word, 90);
-                            suggestions.push(Suggestion::column(
-                                column.clone(),
-                                &full_table_key,
-                                relevance,
-                            ));
+                            suggestions.push(
+                                Suggestion::column(column.clone(), &full_table_key, relevance)
+                                    .with_note(
+                                        metadata
+                                            .get_column_info(&full_table_key, column)
+                                            .and_then(ColumnInfo::insert_hint),
+                                    ),
+                            );
                         }
                     }
                 }
@@ -595,6 +918,63 @@ impl SmartSuggestionEngine {
         suggestions
     }
 
+    /// Session functions and `@@session.` variables, useful in a SELECT without FROM
+    fn get_session_suggestions(&self, word: &str) -> Vec<Suggestion> {
+        let functions = [
+            ("DATABASE", "Current database"),
+            ("USER", "User and host of this connection"),
+            ("CURRENT_USER", "Account used for privilege checks"),
+            ("VERSION", "Server version"),
+            ("CONNECTION_ID", "Id of this connection"),
+            ("LAST_INSERT_ID", "Last AUTO_INCREMENT value generated"),
+            ("ROW_COUNT", "Rows changed by the previous statement"),
+        ];
+
+        let mut suggestions = Vec::new();
+        for (func, desc) in &functions {
+            let relevance = self.calculate_relevance(func, word, 80);
+            if relevance > 50 || word.is_empty() {
+                suggestions.push(Suggestion::function(
+                    func.to_string(),
+                    desc.to_string(),
+                    relevance,
+                ));
+            }
+        }
+        if word.is_empty() || word.starts_with('@') {
+            suggestions.extend(self.get_session_variable_suggestions(word, "@@session."));
+        }
+        suggestions
+    }
+
+    /// Common session variables, written as `prefix` + name
+    fn get_session_variable_suggestions(&self, word: &str, prefix: &str) -> Vec<Suggestion> {
+        let variables = [
+            ("sql_mode", "SQL modes of this session"),
+            ("autocommit", "Whether statements commit automatically"),
+            ("time_zone", "Session time zone"),
+            ("transaction_isolation", "Transaction isolation level"),
+            ("character_set_client", "Character set of statements sent"),
+            ("collation_connection", "Collation of string literals"),
+            ("max_execution_time", "SELECT time limit in milliseconds"),
+            ("sql_select_limit", "Maximum rows returned by SELECT"),
+            (
+                "wait_timeout",
+                "Seconds before an idle connection is closed",
+            ),
+        ];
+
+        let mut suggestions = Vec::new();
+        for (variable, desc) in &variables {
+            let text = format!("{}{}", prefix, variable);
+            let relevance = self.calculate_relevance(&text, word, 70);
+            if relevance > 50 || word.is_empty() {
+                suggestions.push(Suggestion::command(text, desc.to_string(), relevance));
+            }
+        }
+        suggestions
+    }
+
     /// Get condition keyword suggestions
     fn get_condition_suggestions(&self, word: &str) -> Vec<Suggestion> {
         let conditions = [
@@ -625,6 +1005,250 @@ impl SmartSuggestionEngine {
         suggestions
     }
 
+    /// Column named by the last token of `before_word` when it belongs to a table of
+    /// the query; its type is unknown when the metadata has no details for it
+    fn preceding_column(&self, query: &str, before_word: &str) -> Option<ColumnInfo> {
+        let token = before_word.split_whitespace().last()?;
+        let name = token.rsplit('.').next().unwrap_or(token).trim_matches('`');
+        if name.is_empty() || self.is_sql_keyword(name) {
+            return None;
+        }
+
+        let metadata = self.metadata.try_lock().ok()?;
+        let current_db = self.current_database.lock().ok()?.clone()?;
+        self.extract_table_names_from_query(query)
+            .iter()
+            .find_map(|table| {
+                let table_key = format!("{}.{}", current_db, table).to_lowercase();
+                let column = metadata
+                    .get_columns(&table_key)?
+                    .iter()
+                    .find(|column| column.eq_ignore_ascii_case(name))?;
+                Some(
+                    metadata
+                        .get_column_info(&table_key, column)
+                        .cloned()
+                        .unwrap_or_default(),
+                )
+            })
+    }
+
+    /// Comparison operators after a column, with `IS NULL` first for nullable
+    /// columns and `LIKE` first for string columns
+    fn get_operator_suggestions(&self, column: &ColumnInfo) -> Vec<Suggestion> {
+        let (is_null, like) = match (column.nullable, column.is_string()) {
+            (true, _) => (99, if column.is_string() { 97 } else { 80 }),
+            (false, true) => (75, 99),
+            (false, false) => (75, 80),
+        };
+        let operators = [
+            ("IS NULL", "Is null value", is_null),
+            ("IS NOT NULL", "Is not null value", is_null - 1),
+            ("LIKE '%'", "Pattern matching", like),
+            ("=", "Equal", 96),
+            ("IN (", "Contains in list", 94),
+            ("<>", "Not equal", 93),
+            (">=", "Greater than or equal", 92),
+            ("<=", "Less than or equal", 92),
+            ("BETWEEN", "BETWEEN ... AND ... range", 91),
+        ];
+
+        operators
+            .iter()
+            .map(|(operator, desc, relevance)| {
+                Suggestion::sql_keyword(operator.to_string(), desc.to_string(), *relevance)
+            })
+            .collect()
+    }
+
+    /// Get JSON path starter suggestions
+    fn get_json_path_suggestions(&self, word: &str) -> Vec<Suggestion> {
+        let paths = [
+            ("'$.", "Object member path"),
+            ("'$[0]", "First array element"),
+            ("'$[*]", "All array elements"),
+            ("'$", "Whole document"),
+        ];
+
+        // Keep the listed order: later starters rank slightly lower
+        paths
+            .iter()
+            .enumerate()
+            .filter(|(_, (path, _))| path.starts_with(word))
+            .map(|(i, (path, desc))| {
+                let relevance = self.calculate_relevance(path, word, 90) - i as u8;
+                Suggestion::literal(path.to_string(), desc.to_string(), relevance)
+            })
+            .collect()
+    }
+
+    /// Get format string suggestions for DATE_FORMAT and STR_TO_DATE; specifiers
+    /// are case-sensitive, so the typed word is matched as is
+    fn get_date_format_suggestions(&self, word: &str) -> Vec<Suggestion> {
+        DATE_FORMATS
+            .iter()
+            .enumerate()
+            .filter(|(_, (format, _))| format.starts_with(word))
+            .map(|(i, (format, desc))| {
+                let relevance = self.calculate_relevance(format, word, 90) - i as u8;
+                Suggestion::literal(format.to_string(), desc.to_string(), relevance)
+            })
+            .collect()
+    }
+
+    /// Get character set suggestions
+    fn get_charset_suggestions(&self, word: &str) -> Vec<Suggestion> {
+        let metadata = match self.metadata.try_lock() {
+            Ok(metadata) => metadata,
+            Err(_) => return Vec::new(),
+        };
+
+        metadata
+            .charsets
+            .iter()
+            .filter(|c| c.name.to_lowercase().starts_with(word))
+            .map(|c| {
+                Suggestion::sql_keyword(
+                    c.name.clone(),
+                    format!(
+                        "Charset: {} (default {})",
+                        c.description, c.default_collation
+                    ),
+                    self.calculate_relevance(&c.name, word, 85),
+                )
+            })
+            .collect()
+    }
+
+    /// Get collation suggestions, limited to the chosen character set if any;
+    /// its default collation ranks first
+    fn get_collation_suggestions(&self, charset: Option<&str>, word: &str) -> Vec<Suggestion> {
+        let metadata = match self.metadata.try_lock() {
+            Ok(metadata) => metadata,
+            Err(_) => return Vec::new(),
+        };
+
+        let default = charset.and_then(|cs| metadata.default_collation(cs));
+        metadata
+            .get_collations(charset)
+            .into_iter()
+            .filter(|c| c.name.to_lowercase().starts_with(word))
+            .map(|c| {
+                let base = if Some(c.name.as_str()) == default {
+                    90
+                } else {
+                    85
+                };
+                Suggestion::sql_keyword(
+                    c.name.clone(),
+                    format!("Collation of {}", c.charset),
+                    self.calculate_relevance(&c.name, word, base),
+                )
+            })
+            .collect()
+    }
+
+    /// Get enabled storage engine suggestions, the default engine first
+    fn get_engine_suggestions(&self, word: &str) -> Vec<Suggestion> {
+        let metadata = match self.metadata.try_lock() {
+            Ok(metadata) => metadata,
+            Err(_) => return Vec::new(),
+        };
+
+        metadata
+            .engines
+            .iter()
+            .filter(|e| e.enabled && e.name.to_lowercase().starts_with(word))
+            .map(|e| {
+                let (base, default) = if e.is_default {
+                    (90, " (default)")
+                } else {
+                    (85, "")
+                };
+                Suggestion::sql_keyword(
+                    e.name.clone(),
+                    format!("Engine{}: {}", default, e.comment),
+                    self.calculate_relevance(&e.name, word, base).min(base),
+                )
+            })
+            .collect()
+    }
+
+    /// Get partition type and partitioning column suggestions
+    fn get_partition_suggestions(&self, line: &str, word: &str) -> Vec<Suggestion> {
+        let mut suggestions: Vec<Suggestion> = self
+            .partition_columns(line)
+            .into_iter()
+            .filter(|(_, column)| column.to_lowercase().starts_with(word))
+            .map(|(table, column)| {
+                let relevance = self.calculate_relevance(&column, word, 90);
+                Suggestion::column(column, &table, relevance)
+            })
+            .collect();
+
+        let kinds = [
+            ("RANGE", "Partition by value ranges"),
+            ("LIST", "Partition by value lists"),
+            ("HASH", "Partition by a hash of an expression"),
+            ("KEY", "Partition by the server's key hash"),
+        ];
+        for (kind, desc) in &kinds {
+            if kind.to_lowercase().starts_with(word) {
+                suggestions.push(Suggestion::sql_keyword(
+                    kind.to_string(),
+                    desc.to_string(),
+                    self.calculate_relevance(kind, word, 85),
+                ));
+            }
+        }
+
+        suggestions
+    }
+
+    /// Columns of the table being created or altered, as (table, column) pairs
+    fn partition_columns(&self, line: &str) -> Vec<(String, String)> {
+        static CREATE: OnceLock<Regex> = OnceLock::new();
+        let create = CREATE.get_or_init(|| {
+            Regex::new(
+                r"(?is)^\s*CREATE\s+(?:TEMPORARY\s+)?TABLE\s+(?:IF\s+NOT\s+EXISTS\s+)?([\w`.]+)\s*\((.*)$",
+            )
+            .unwrap()
+        });
+        if let Some(caps) = create.captures(line) {
+            let table = caps[1].replace('`', "");
+            return defined_columns(&caps[2])
+                .into_iter()
+                .map(|column| (table.clone(), column))
+                .collect();
+        }
+
+        static ALTER: OnceLock<Regex> = OnceLock::new();
+        let alter = ALTER.get_or_init(|| Regex::new(r"(?i)^\s*ALTER\s+TABLE\s+([\w`.]+)").unwrap());
+        let Some(caps) = alter.captures(line) else {
+            return Vec::new();
+        };
+        let table = caps[1].replace('`', "");
+        let table_key = if table.contains('.') {
+            table.to_lowercase()
+        } else {
+            match self.current_database.lock().unwrap().as_ref() {
+                Some(db) => format!("{}.{}", db, table).to_lowercase(),
+                None => return Vec::new(),
+            }
+        };
+
+        let metadata = match self.metadata.try_lock() {
+            Ok(metadata) => metadata,
+            Err(_) => return Vec::new(),
+        };
+        metadata
+            .get_columns(&table_key)
+            .into_iter()
+            .flatten()
+            .map(|column| (table_key.clone(), column.clone()))
+            .collect()
+    }
+
     /// Get SQL keyword suggestions
     fn get_sql_keyword_suggestions(&self, word: &str) -> Vec<Suggestion> {
         let mut suggestions = Vec::new();
@@ -654,12 +1278,14 @@ impl SmartSuggestionEngine {
                 "SHOW DATABASES".to_string(),
                 "Show all databases".to_string(),
                 90,
-            ),
+            )
+            .completing_statement(),
             Suggestion::command(
                 "SHOW TABLES".to_string(),
                 "Show all tables in current database".to_string(),
                 85,
-            ),
+            )
+            .completing_statement(),
             Suggestion::command(
                 "USE".to_string(),
                 "Switch to specified database".to_string(),
@@ -699,7 +1325,13 @@ impl SmartSuggestionEngine {
             let relevance = self.calculate_relevance(column, word, 80);
             // Only include columns with good relevance (starts with or contains typed text)
             if relevance > 70 {
-                suggestions.push(Suggestion::column(column.clone(), table, relevance));
+                suggestions.push(
+                    Suggestion::column(column.clone(), table, relevance).with_note(
+                        metadata
+                            .get_column_info(table, column)
+                            .and_then(ColumnInfo::insert_hint),
+                    ),
+                );
                 count += 1;
             }
         }
@@ -708,6 +1340,251 @@ impl SmartSuggestionEngine {
     }
 }
 
+/// Text of the innermost parenthesized SELECT still open at the end of `text`
+/// (the caret), from SELECT up to the caret
+fn enclosing_subquery(text: &str) -> Option<&str> {
+    let mut open = Vec::new();
+    let mut quote = None;
+    for (i, c) in text.char_indices() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None => match c {
+                '\'' | '"' | '`' => quote = Some(c),
+                '(' => open.push(i),
+                ')' => {
+                    open.pop();
+                }
+                _ => {}
+            },
+        }
+    }
+
+    open.iter().rev().find_map(|&i| {
+        let inner = text[i + 1..].trim_start();
+        let is_select = inner
+            .get(..6)
+            .is_some_and(|word| word.eq_ignore_ascii_case("SELECT"))
+            && inner[6..]
+                .chars()
+                .next()
+                .is_none_or(|c| !c.is_alphanumeric() && c != '_');
+        is_select.then_some(inner)
+    })
+}
+
+/// Start of the name of the outermost function call still open at the end of
+/// `text`, e.g. `DATE` in `WHERE DATE(created`; a `(` right after a word opens
+/// a call, while other parentheses only group
+fn enclosing_call(text: &str) -> Option<usize> {
+    let mut open = Vec::new();
+    let mut quote = None;
+    for (i, c) in text.char_indices() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None => match c {
+                '\'' | '"' | '`' => quote = Some(c),
+                '(' => {
+                    let name_start = text[..i]
+                        .trim_end_matches(|c: char| c.is_alphanumeric() || c == '_')
+                        .len();
+                    open.push((name_start < i).then_some(name_start));
+                }
+                ')' => {
+                    open.pop();
+                }
+                _ => {}
+            },
+        }
+    }
+    open.into_iter().flatten().next()
+}
+
+/// Column name before a JSON accessor at the end of the line (`col->`, `col->>'$.a`, `JSON_EXTRACT(col, `)
+fn json_accessor_column(line: &str) -> Option<String> {
+    static OPERATOR: OnceLock<Regex> = OnceLock::new();
+    static FUNCTION: OnceLock<Regex> = OnceLock::new();
+    let operator = OPERATOR.get_or_init(|| Regex::new(r"([\w`.]+)\s*->>?\s*('[^']*)?$").unwrap());
+    let function = FUNCTION.get_or_init(|| {
+        Regex::new(r"(?i)JSON_(?:EXTRACT|VALUE)\(\s*([\w`.]+)\s*,\s*('[^']*)?$").unwrap()
+    });
+
+    operator
+        .captures(line)
+        .or_else(|| function.captures(line))
+        .map(|caps| caps[1].to_string())
+}
+
+/// Common DATE_FORMAT / STR_TO_DATE format strings, most used first
+const DATE_FORMATS: &[(&str, &str)] = &[
+    ("'%Y-%m-%d'", "ISO 8601 date (2024-01-31)"),
+    (
+        "'%Y-%m-%d %H:%i:%s'",
+        "ISO 8601 date and time (2024-01-31 13:05:09)",
+    ),
+    ("'%H:%i:%s'", "24-hour time (13:05:09)"),
+    ("'%d/%m/%Y'", "Day/month/year (31/01/2024)"),
+    ("'%m/%d/%Y'", "US date (01/31/2024)"),
+    ("'%M %d, %Y'", "Month name, day, year (January 31, 2024)"),
+    (
+        "'%W, %M %e, %Y'",
+        "Weekday and date (Wednesday, January 31, 2024)",
+    ),
+    ("'%d.%m.%Y'", "Dotted date (31.01.2024)"),
+    ("'%Y%m%d'", "Compact date (20240131)"),
+    ("'%Y-%m'", "Year and month (2024-01)"),
+    ("'%x-W%v'", "ISO 8601 week (2024-W05)"),
+    ("'%h:%i %p'", "12-hour time (01:05 PM)"),
+    ("'%Y-%m-%dT%H:%i:%s'", "ISO 8601 timestamp with T separator"),
+];
+
+/// Whether the caret is in the second argument of DATE_FORMAT(...) or STR_TO_DATE(...),
+/// before or inside its format string
+fn is_date_format_argument(line: &str) -> bool {
+    static FUNCTION: OnceLock<Regex> = OnceLock::new();
+    static FORMAT: OnceLock<Regex> = OnceLock::new();
+    let function =
+        FUNCTION.get_or_init(|| Regex::new(r"(?i)\b(?:DATE_FORMAT|STR_TO_DATE)\s*\(").unwrap());
+    let Some(call) = function.find_iter(line).last() else {
+        return false;
+    };
+
+    // Find the top-level comma after the first argument; a `)` closes the call
+    let rest = &line[call.end()..];
+    let (mut depth, mut in_quote) = (0usize, None);
+    let mut second = None;
+    for (i, c) in rest.char_indices() {
+        match (in_quote, c) {
+            (Some(q), c) if c == q => in_quote = None,
+            (Some(_), _) => {}
+            (None, '\'' | '"') => in_quote = Some(c),
+            (None, '(') => depth += 1,
+            (None, ')') if depth == 0 => return false,
+            (None, ')') => depth -= 1,
+            (None, ',') if depth == 0 => {
+                second = Some(&rest[i + 1..]);
+                break;
+            }
+            _ => {}
+        }
+    }
+    let format = FORMAT.get_or_init(|| Regex::new(r"^\s*('[^']*)?$").unwrap());
+    second.is_some_and(|argument| format.is_match(argument))
+}
+
+/// Charset or collation name context at the end of the line
+/// (`DEFAULT CHARSET=`, `CHARACTER SET `, `SET NAMES `, `COLLATE `)
+fn charset_context(line: &str) -> Option<InputContext> {
+    static COLLATION: OnceLock<Regex> = OnceLock::new();
+    static CHARSET: OnceLock<Regex> = OnceLock::new();
+    let collation = COLLATION.get_or_init(|| Regex::new(r"(?i)\bCOLLATE\s*=?\s*'?\w*$").unwrap());
+    let charset = CHARSET.get_or_init(|| {
+        Regex::new(r"(?i)(\bCHARSET|\bCHARACTER\s+SET|\bSET\s+NAMES)\s*=?\s*'?\w*$").unwrap()
+    });
+
+    if collation.is_match(line) {
+        Some(InputContext::CollationName)
+    } else if charset.is_match(line) {
+        Some(InputContext::CharsetName)
+    } else {
+        None
+    }
+}
+
+/// Table option context at the end of the line (`ENGINE=`, `PARTITION BY `)
+fn table_option_context(line: &str) -> Option<InputContext> {
+    static ENGINE: OnceLock<Regex> = OnceLock::new();
+    static PARTITION: OnceLock<Regex> = OnceLock::new();
+    let engine = ENGINE.get_or_init(|| Regex::new(r"(?i)\bENGINE\s*=?\s*\w*$").unwrap());
+    let partition = PARTITION.get_or_init(|| {
+        Regex::new(
+            r"(?i)\bPARTITION\s+BY(?:\s+(?:LINEAR\s+)?(?:(?:RANGE|LIST)(?:\s+COLUMNS)?|HASH|KEY))?\s*\(?[\w`,\s]*$",
+        )
+        .unwrap()
+    });
+
+    if engine.is_match(line) {
+        Some(InputContext::EngineName)
+    } else if partition.is_match(line) {
+        Some(InputContext::PartitionBy)
+    } else {
+        None
+    }
+}
+
+/// Column names declared in the body of a CREATE TABLE column list
+fn defined_columns(body: &str) -> Vec<String> {
+    const NOT_COLUMNS: [&str; 9] = [
+        "PRIMARY",
+        "KEY",
+        "INDEX",
+        "UNIQUE",
+        "CONSTRAINT",
+        "FOREIGN",
+        "CHECK",
+        "FULLTEXT",
+        "SPATIAL",
+    ];
+
+    // Split the definitions at top-level commas, stopping at the closing parenthesis
+    let mut definitions = vec![String::new()];
+    let mut depth = 0;
+    for c in body.chars() {
+        match c {
+            '(' => depth += 1,
+            ')' if depth == 0 => break,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                definitions.push(String::new());
+                continue;
+            }
+            _ => {}
+        }
+        if let Some(current) = definitions.last_mut() {
+            current.push(c);
+        }
+    }
+
+    definitions
+        .iter()
+        .filter_map(|definition| definition.split_whitespace().next())
+        .filter(|name| !NOT_COLUMNS.contains(&name.to_uppercase().as_str()))
+        .map(|name| name.trim_matches('`').to_string())
+        .collect()
+}
+
+/// Character set chosen earlier in the statement, before a trailing COLLATE
+fn chosen_charset(line: &str) -> Option<String> {
+    static CHARSET: OnceLock<Regex> = OnceLock::new();
+    let charset = CHARSET.get_or_init(|| {
+        Regex::new(r"(?i)\b(?:CHARSET|CHARACTER\s+SET|NAMES)\s*=?\s*'?(\w+)").unwrap()
+    });
+    charset
+        .captures_iter(line)
+        .last()
+        .map(|caps| caps[1].to_lowercase())
+}
+
+/// Whether the text ends with the `AND` of a `BETWEEN x AND` range
+fn is_between_upper_bound(text: &str) -> bool {
+    let words: Vec<String> = text.split_whitespace().map(|w| w.to_uppercase()).collect();
+    let Some((last, rest)) = words.split_last() else {
+        return false;
+    };
+    if last != "AND" {
+        return false;
+    }
+    for (i, word) in rest.iter().enumerate().rev() {
+        match word.as_str() {
+            "BETWEEN" => return i + 1 < rest.len(),
+            "AND" | "OR" | "WHERE" | "ON" | "HAVING" => return false,
+            _ => {}
+        }
+    }
+    false
+}
+
 #[cfg(test)]
 #[path = "./engine_tests.rs"]
 mod engine_tests;
@@ -1,4 +1,5 @@
 use super::*;
+use crate::completion::metadata::ColumnInfo;
 use std::sync::{Arc, Mutex};
 
 fn create_test_engine() -> SmartSuggestionEngine {
@@ -206,5 +207,450 @@ fn test_select_column_suggestions() {
     let suggestions = engine.get_column_suggestions_for_query("select * from orders where", "");
 
     // display all columns from the orders table
-    assert_eq!(suggestions.len(), 6);
+    let texts: Vec<&str> = suggestions.iter().map(|s| s.text.as_str()).collect();
+    assert_eq!(texts, vec!["`order_id`", "`user_id`", "`amount`"]);
+}
+
+#[test]
+fn test_temporary_table_completion() {
+    let engine = create_test_engine();
+    engine.set_current_database(Some("test_db".to_string()));
+    engine.metadata.lock().unwrap().add_temporary_table(
+        "test_db",
+        "tmp_ids",
+        vec!["tmp_id".to_string()],
+    );
+
+    let suggestions = engine.get_suggestions("SELECT * FROM tm", "tm");
+    assert!(suggestions.iter().any(|s| s.text == "`tmp_ids`"));
+
+    let suggestions = engine.get_column_suggestions_for_query("SELECT * FROM TMP_IDS WHERE", "");
+    assert!(suggestions.iter().any(|s| s.text == "`tmp_id`"));
+
+    engine
+        .metadata
+        .lock()
+        .unwrap()
+        .remove_temporary_table("test_db", "tmp_ids");
+    let suggestions = engine.get_suggestions("SELECT * FROM tm", "tm");
+    assert!(!suggestions.iter().any(|s| s.text == "`tmp_ids`"));
+}
+
+#[test]
+fn test_json_path_context() {
+    let engine = create_test_engine();
+    engine.metadata.lock().unwrap().column_info.insert(
+        "test_db.users.profile".to_string(),
+        ColumnInfo {
+            data_type: "json".to_string(),
+            nullable: true,
+            ..Default::default()
+        },
+    );
+
+    assert_eq!(
+        engine.analyze_context("SELECT PROFILE->"),
+        InputContext::JsonPath
+    );
+    assert_eq!(
+        engine.analyze_context("SELECT U.PROFILE->>'$"),
+        InputContext::JsonPath
+    );
+    assert_eq!(
+        engine.analyze_context("SELECT JSON_EXTRACT(PROFILE, "),
+        InputContext::JsonPath
+    );
+    // Not a JSON column
+    assert_ne!(
+        engine.analyze_context("SELECT NAME->"),
+        InputContext::JsonPath
+    );
+
+    let suggestions = engine.get_suggestions("SELECT profile->>'", "'");
+    assert_eq!(suggestions.first().map(|s| s.text.as_str()), Some("'$."));
+}
+
+#[test]
+fn test_between_and_expects_value() {
+    let engine = create_test_engine();
+
+    let suggestions = engine.get_suggestions("SELECT * FROM users WHERE id BETWEEN 1 AND ", "");
+    assert!(!suggestions
+        .iter()
+        .any(|s| s.text == "AND" || s.text == "OR"));
+    assert!(suggestions.iter().any(|s| s.text == "NOW"));
+
+    // A complete range is followed by a fresh condition again
+    let suggestions =
+        engine.get_suggestions("SELECT * FROM users WHERE id BETWEEN 1 AND 5 AND ", "");
+    assert!(suggestions.iter().any(|s| s.text == "NOT"));
+
+    assert!(is_between_upper_bound(
+        "WHERE CREATED BETWEEN '2024-01-01' AND"
+    ));
+    assert!(!is_between_upper_bound("WHERE A = 1 AND"));
+    assert!(!is_between_upper_bound("WHERE A BETWEEN AND"));
+}
+
+#[test]
+fn test_suggestion_order_is_deterministic() {
+    // Separate engines get differently seeded HashMaps, so iteration order differs
+    let texts = |line: &str, word: &str| -> Vec<String> {
+        let engine = create_test_engine();
+        {
+            let mut md = engine.metadata.lock().unwrap();
+            md.tables.insert(
+                "sales".to_string(),
+                vec!["orders".to_string(), "refunds".to_string()],
+            );
+            for db in ["archive", "staging", "reporting"] {
+                md.databases.push(db.to_string());
+                md.tables.insert(
+                    db.to_string(),
+                    vec!["orders".to_string(), "users".to_string()],
+                );
+            }
+        }
+        engine
+            .get_suggestions(line, word)
+            .into_iter()
+            .map(|s| s.text)
+            .collect()
+    };
+
+    for (line, word) in [("SELECT * FROM ", ""), ("SELECT * FROM o", "o")] {
+        let first = texts(line, word);
+        for _ in 0..5 {
+            assert_eq!(texts(line, word), first, "{}", line);
+        }
+    }
+}
+
+#[test]
+fn test_charset_and_collation_context() {
+    let engine = create_test_engine();
+    assert_eq!(
+        engine.analyze_context("CREATE TABLE T (ID INT) DEFAULT CHARSET="),
+        InputContext::CharsetName
+    );
+    assert_eq!(
+        engine.analyze_context("SET NAMES UTF"),
+        InputContext::CharsetName
+    );
+    assert_eq!(
+        engine.analyze_context("SELECT NAME COLLATE "),
+        InputContext::CollationName
+    );
+
+    let suggestions = engine.get_suggestions("SET NAMES ", "");
+    assert!(suggestions.iter().any(|s| s.text == "utf8mb4"));
+
+    // Collations follow the charset chosen earlier, default collation first
+    let suggestions = engine.get_suggestions("ALTER TABLE users CHARACTER SET latin1 COLLATE ", "");
+    assert_eq!(
+        suggestions.first().map(|s| s.text.as_str()),
+        Some("latin1_swedish_ci")
+    );
+    assert!(suggestions.iter().all(|s| s.text.starts_with("latin1_")));
+}
+
+#[test]
+fn test_tables_qualified_without_current_database() {
+    let engine = create_test_engine();
+
+    let suggestions = engine.get_suggestions("SELECT * FROM us", "us");
+    assert!(suggestions.iter().any(|s| s.text == "`test_db`.`users`"));
+
+    engine.set_current_database(Some("test_db".to_string()));
+    let suggestions = engine.get_suggestions("SELECT * FROM us", "us");
+    assert!(suggestions.iter().any(|s| s.text == "`users`"));
+}
+
+#[test]
+fn test_engine_completion_filters_by_prefix() {
+    let engine = create_test_engine();
+    assert_eq!(
+        engine.analyze_context("CREATE TABLE T (ID INT) ENGINE=IN"),
+        InputContext::EngineName
+    );
+
+    let suggestions = engine.get_suggestions("CREATE TABLE t (id INT) ENGINE=In", "In");
+    let texts: Vec<&str> = suggestions.iter().map(|s| s.text.as_str()).collect();
+    assert_eq!(texts, vec!["InnoDB"]);
+
+    // Only enabled engines, default first
+    engine.metadata.lock().unwrap().engines[2].enabled = false;
+    let suggestions = engine.get_suggestions("ALTER TABLE orders ENGINE=", "");
+    let texts: Vec<&str> = suggestions.iter().map(|s| s.text.as_str()).collect();
+    assert_eq!(texts, vec!["InnoDB", "MEMORY"]);
+}
+
+#[test]
+fn test_partition_by_columns() {
+    let engine = create_test_engine();
+    engine.set_current_database(Some("test_db".to_string()));
+    assert_eq!(
+        engine.analyze_context("ALTER TABLE ORDERS PARTITION BY "),
+        InputContext::PartitionBy
+    );
+
+    let suggestions = engine.get_suggestions("ALTER TABLE orders PARTITION BY HASH(", "");
+    assert!(suggestions.iter().any(|s| s.text == "`order_id`"));
+    assert!(suggestions.iter().any(|s| s.text == "RANGE"));
+
+    let suggestions = engine.get_suggestions(
+        "CREATE TABLE logs (id INT, created DATE, PRIMARY KEY (id, created)) PARTITION BY RANGE (cr",
+        "cr",
+    );
+    let texts: Vec<&str> = suggestions.iter().map(|s| s.text.as_str()).collect();
+    assert_eq!(texts, vec!["`created`"]);
+}
+
+#[test]
+fn test_search_path_tables_follow_current_database() {
+    let engine = create_test_engine();
+    engine.metadata.lock().unwrap().tables.insert(
+        "sales".to_string(),
+        vec!["orders".to_string(), "invoices".to_string()],
+    );
+    engine.set_current_database(Some("test_db".to_string()));
+    engine.set_search_path(vec!["sales".to_string()]);
+
+    let suggestions = engine.get_suggestions("SELECT * FROM o", "o");
+    let texts: Vec<&str> = suggestions.iter().map(|s| s.text.as_str()).collect();
+    assert_eq!(texts, vec!["`orders`", "`sales`.`orders`"]);
+
+    let suggestions = engine.get_suggestions("SELECT * FROM ", "");
+    assert!(suggestions.iter().any(|s| s.text == "`sales`.`invoices`"));
+}
+
+#[test]
+fn test_parse_results_are_reused_per_line() {
+    let engine = create_test_engine();
+    let sql = "SELECT * FROM users WHERE id = 1";
+
+    let first = engine.analyze_context(sql);
+    assert_eq!(engine.parse_cache.lock().unwrap().len(), 1);
+    assert_eq!(engine.analyze_context(sql), first);
+    assert_eq!(engine.parse_cache.lock().unwrap().len(), 1);
+
+    // An edited line is parsed on its own
+    engine.analyze_context("SELECT * FROM users WHERE id = 12");
+    assert_eq!(engine.parse_cache.lock().unwrap().len(), 2);
+}
+
+#[test]
+fn test_session_function_suggestions_without_from() {
+    let engine = create_test_engine();
+
+    let suggestions = engine.get_suggestions("SELECT ", "");
+    let texts: Vec<&str> = suggestions.iter().map(|s| s.text.as_str()).collect();
+    assert!(texts.contains(&"DATABASE"));
+
+    let suggestions = engine.get_suggestions("SELECT conn", "conn");
+    assert_eq!(suggestions[0].text, "CONNECTION_ID");
+
+    let suggestions = engine.get_suggestions("SELECT @@sess", "@@sess");
+    assert!(suggestions
+        .iter()
+        .any(|s| s.text == "@@session.sql_mode" && !s.description.is_empty()));
+
+    // After `@@session.` only the variable name is completed
+    let suggestions = engine.get_suggestions("SELECT @@session.sql", "sql");
+    assert_eq!(suggestions[0].text, "sql_mode");
+}
+
+#[test]
+fn test_context_inside_from_subquery() {
+    let engine = create_test_engine();
+
+    assert_eq!(
+        engine.analyze_context_at("SELECT * FROM (SELECT ", 22),
+        InputContext::SelectClause
+    );
+    assert_eq!(
+        engine.analyze_context_at("SELECT * FROM (SELECT id FROM ", 30),
+        InputContext::FromClause
+    );
+
+    // Caret inside a closed derived table
+    let line = "SELECT * FROM (SELECT  FROM users) AS u WHERE";
+    let caret = line.find("SELECT  ").unwrap() + 7;
+    assert_eq!(
+        engine.analyze_context_at(line, caret),
+        InputContext::SelectClause
+    );
+    // Past the derived table the outer query applies again
+    assert_eq!(
+        engine.analyze_context_at(line, line.len()),
+        InputContext::WhereClause
+    );
+    // A function call is not a subquery
+    assert_eq!(
+        engine.analyze_context_at("SELECT COUNT(", 13),
+        InputContext::SelectClause
+    );
+}
+
+#[test]
+fn test_operator_suggestions_after_where_column() {
+    let engine = create_test_engine();
+    engine.set_current_database(Some("test_db".to_string()));
+    {
+        let mut metadata = engine.metadata.lock().unwrap();
+        for (column, data_type, nullable) in [
+            ("id", "int", false),
+            ("name", "varchar(100)", false),
+            ("email", "varchar(255)", true),
+        ] {
+            metadata.column_info.insert(
+                format!("test_db.users.{}", column),
+                ColumnInfo {
+                    data_type: data_type.to_string(),
+                    nullable,
+                    ..Default::default()
+                },
+            );
+        }
+    }
+    let top = |line: &str| -> Vec<String> {
+        let (suggestions, _) = engine.get_ranked_suggestions(line, "");
+        suggestions.into_iter().take(3).map(|s| s.text).collect()
+    };
+
+    assert_eq!(
+        top("SELECT * FROM users WHERE id "),
+        vec!["=", "IN (", "<>"]
+    );
+    assert_eq!(
+        top("SELECT * FROM users WHERE name "),
+        vec!["LIKE '%'", "=", "IN ("]
+    );
+    assert_eq!(
+        top("SELECT * FROM users u WHERE u.email "),
+        vec!["IS NULL", "IS NOT NULL", "LIKE '%'"]
+    );
+
+    // Operators replace column suggestions and rank above condition keywords
+    let (suggestions, _) = engine.get_ranked_suggestions("SELECT * FROM users WHERE id ", "");
+    assert!(!suggestions.iter().any(|s| s.text == "`name`"));
+    let position = |text: &str| suggestions.iter().position(|s| s.text == text).unwrap();
+    assert!(position("BETWEEN") < position("AND"));
+    assert_eq!(
+        suggestions.iter().filter(|s| s.text == "IS NULL").count(),
+        1
+    );
+
+    // Without a column before the caret, columns are still offered
+    let (suggestions, _) = engine.get_ranked_suggestions("SELECT * FROM users WHERE ", "");
+    assert!(suggestions.iter().any(|s| s.text == "`name`"));
+}
+
+#[test]
+fn test_column_descriptions_show_defaults_and_required() {
+    let engine = create_test_engine();
+    engine.set_current_database(Some("test_db".to_string()));
+    {
+        let mut metadata = engine.metadata.lock().unwrap();
+        for (column, nullable, default, generated) in [
+            ("order_id", false, None, true),
+            ("user_id", false, None, false),
+            ("amount", false, Some("CURRENT_TIMESTAMP"), false),
+        ] {
+            metadata.column_info.insert(
+                format!("test_db.orders.{}", column),
+                ColumnInfo {
+                    data_type: "int".to_string(),
+                    nullable,
+                    default: default.map(str::to_string),
+                    generated,
+                },
+            );
+        }
+    }
+    let (suggestions, _) = engine.get_ranked_suggestions("SELECT * FROM orders WHERE ", "");
+    let description = |text: &str| {
+        suggestions
+            .iter()
+            .find(|s| s.text == text)
+            .map(|s| s.description.clone())
+            .unwrap()
+    };
+
+    assert!(description("`user_id`").ends_with("(required)"));
+    assert!(description("`amount`").ends_with("(default: CURRENT_TIMESTAMP)"));
+    assert!(description("`order_id`").ends_with("(from table test_db.orders)"));
+}
+
+#[test]
+fn test_date_format_context() {
+    let engine = create_test_engine();
+
+    assert_eq!(
+        engine.analyze_context("SELECT DATE_FORMAT(CREATED_AT, "),
+        InputContext::DateFormatClause
+    );
+    assert_eq!(
+        engine.analyze_context("SELECT STR_TO_DATE(CONCAT(D, ' ', T), '%Y"),
+        InputContext::DateFormatClause
+    );
+    // Still in the first argument, or past the closed call
+    assert_ne!(
+        engine.analyze_context("SELECT DATE_FORMAT(NOW("),
+        InputContext::DateFormatClause
+    );
+    assert_ne!(
+        engine.analyze_context("SELECT DATE_FORMAT(D, '%Y') FROM "),
+        InputContext::DateFormatClause
+    );
+
+    let suggestions = engine.get_suggestions("SELECT DATE_FORMAT(created_at, '%d", "'%d");
+    assert_eq!(
+        suggestions.first().map(|s| s.text.as_str()),
+        Some("'%d/%m/%Y'")
+    );
+    assert!(suggestions.iter().all(|s| s.text.starts_with("'%d")));
+}
+
+#[test]
+fn test_function_arguments_keep_clause_context() {
+    let engine = create_test_engine();
+    engine.set_current_database(Some("test_db".to_string()));
+    let context = |line: &str| engine.analyze_context_at(&line.to_uppercase(), line.len());
+    let texts = |line: &str, caret: usize, word: &str| -> Vec<String> {
+        let (suggestions, _) = engine.get_ranked_suggestions_at(line, caret, word);
+        suggestions.into_iter().map(|s| s.text).collect()
+    };
+
+    assert_eq!(
+        context("SELECT id FROM users GROUP BY YEAR(na"),
+        InputContext::GroupByClause
+    );
+    assert_eq!(
+        context("SELECT id FROM users ORDER BY LOWER("),
+        InputContext::OrderByClause
+    );
+    // Nested calls, and grouping parentheses around them
+    assert_eq!(
+        context("SELECT id FROM users WHERE (id > 1 OR UPPER(TRIM(na"),
+        InputContext::WhereClause
+    );
+    assert!(
+        texts("SELECT id FROM users ORDER BY LOWER(TRIM(em", 43, "em")
+            .contains(&"`email`".to_string())
+    );
+
+    // DISTINCT before the partial column, with the caret before a typed FROM
+    let line = "SELECT COUNT(DISTINCT na) FROM users";
+    assert_eq!(
+        engine.analyze_context_at(&line.to_uppercase(), 24),
+        InputContext::SelectClause
+    );
+    assert!(texts(line, 24, "na").contains(&"`name`".to_string()));
+
+    // Arguments with a context of their own keep it
+    assert_eq!(
+        context("SELECT DATE_FORMAT(created_at, '%"),
+        InputContext::DateFormatClause
+    );
 }
@@ -6,6 +6,10 @@
 
 use super::engine::SmartSuggestionEngine;
 use super::metadata::DatabaseMetadata;
+use super::paging::{self, CompletionPaging};
+use super::special_commands;
+use super::status_bar::{self, StatusBar};
+use super::suggestion::{self, SuggestionCategory};
 use anyhow::Result;
 use rustyline::completion::{Completer, Pair};
 use rustyline::error::ReadlineError;
@@ -13,12 +17,21 @@ use rustyline::highlight::{Highlighter, MatchingBracketHighlighter};
 use rustyline::hint::{Hinter, HistoryHinter};
 use rustyline::validate::{self, MatchingBracketValidator, Validator};
 use rustyline::Context;
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
 /// MySQL Completer
 pub struct MySQLCompleter {
     sql_keywords: Vec<String>,
     suggestion_engine: SmartSuggestionEngine,
+    /// Show descriptions in the status bar instead of the completion list
+    use_status_bar: AtomicBool,
+    status_bar: StatusBar,
+    paging: CompletionPaging,
+    /// Delimiter appended to candidates that finish a statement, e.g. `USE db`
+    statement_terminator: Mutex<Option<String>>,
 }
 
 impl MySQLCompleter {
@@ -30,6 +43,19 @@ impl MySQLCompleter {
         Self {
             sql_keywords,
             suggestion_engine,
+            use_status_bar: AtomicBool::new(false),
+            status_bar: StatusBar::default(),
+            paging: CompletionPaging::default(),
+            statement_terminator: Mutex::new(None),
+        }
+    }
+
+    /// Format a completion list entry, with the description unless the status bar shows it
+    fn display(&self, icon: &str, text: &str, description: &str) -> String {
+        if self.use_status_bar.load(Ordering::Relaxed) {
+            format!("{} {}", icon, text)
+        } else {
+            format!("{} {} - {}", icon, text, description)
         }
     }
 
@@ -231,21 +257,108 @@ impl MySQLCompleter {
             "RESTRICT",
         ];
 
-        keywords.iter().map(|s| s.to_string()).collect()
+        // YEAR and friends are both types and functions; list each once
+        let mut seen = HashSet::new();
+        keywords
+            .iter()
+            .filter(|keyword| seen.insert(**keyword))
+            .map(|s| s.to_string())
+            .collect()
     }
 
     /// Get current word start position
     fn get_word_start(&self, line: &str, pos: usize) -> usize {
         line[..pos]
-            .rfind(|c: char| c.is_whitespace() || c == '(' || c == ',' || c == '.' || c == ';')
+            .rfind(|c: char| {
+                c.is_whitespace() || c == '(' || c == ',' || c == '.' || c == ';' || c == '>'
+            })
             .map(|i| i + 1)
             .unwrap_or(0)
     }
 
+    /// Start of the identifier token before `pos`, which completion replaces as a
+    /// whole: a name with any `db.` qualifiers, where backtick-quoted segments may
+    /// contain spaces and other punctuation
+    fn get_token_start(&self, line: &str, pos: usize) -> usize {
+        let before = &line[..pos];
+        // A format string or JSON path is completed from its opening quote
+        if before.matches('\'').count() % 2 == 1 {
+            return before.rfind('\'').unwrap_or(pos);
+        }
+        let mut in_quote = before.matches('`').count() % 2 == 1;
+        let mut start = pos;
+        for (i, c) in before.char_indices().rev() {
+            if c == '`' {
+                in_quote = !in_quote;
+            } else if !(in_quote || c.is_alphanumeric() || c == '_' || c == '$' || c == '.') {
+                break;
+            }
+            start = i;
+        }
+        start
+    }
+
     /// Update current database for better context-aware suggestions
     pub fn set_current_database(&self, database: Option<String>) {
         self.suggestion_engine.set_current_database(database);
     }
+
+    /// Delimiter to append when a candidate finishes a statement; `None` appends nothing
+    pub fn set_statement_terminator(&self, terminator: Option<String>) {
+        if let Ok(mut current) = self.statement_terminator.lock() {
+            *current = terminator;
+        }
+    }
+
+    /// Set the databases searched for unqualified table names
+    pub fn set_search_path(&self, databases: Vec<String>) {
+        self.suggestion_engine.set_search_path(databases);
+    }
+}
+
+/// Split an identifier token after its last unquoted `.` into the qualifier
+/// (with the dot) and the name being typed
+fn split_qualifier(token: &str) -> (&str, &str) {
+    let mut in_quote = false;
+    let mut split = 0;
+    for (i, c) in token.char_indices() {
+        match c {
+            '`' => in_quote = !in_quote,
+            '.' if !in_quote => split = i + 1,
+            _ => {}
+        }
+    }
+    token.split_at(split)
+}
+
+/// Functions that take no arguments, completed with `()`
+const NO_ARGUMENT_FUNCTIONS: &[&str] = &[
+    "NOW",
+    "CURDATE",
+    "CURTIME",
+    "UTC_TIMESTAMP",
+    "UUID",
+    "DATABASE",
+    "USER",
+    "CURRENT_USER",
+    "VERSION",
+    "CONNECTION_ID",
+    "LAST_INSERT_ID",
+    "FOUND_ROWS",
+    "ROW_COUNT",
+    "PI",
+];
+
+/// Completion text for a function: `name(` ready for arguments, or `name()`
+fn function_call(name: &str) -> String {
+    if NO_ARGUMENT_FUNCTIONS
+        .iter()
+        .any(|f| f.eq_ignore_ascii_case(name))
+    {
+        format!("{}()", name)
+    } else {
+        format!("{}(", name)
+    }
 }
 
 impl Completer for MySQLCompleter {
@@ -257,32 +370,103 @@ impl Completer for MySQLCompleter {
         pos: usize,
         _ctx: &Context<'_>,
     ) -> Result<(usize, Vec<Pair>), ReadlineError> {
-        let start = self.get_word_start(line, pos);
-        let word = &line[start..pos];
+        // `users\G`: the word is completed in front of a glued terminator, which
+        // every candidate keeps
+        let suffix = glued_terminator(&line[..pos]);
+        let line = if suffix.is_empty() {
+            line
+        } else {
+            &line[..pos - suffix.len()]
+        };
+        let pos = pos - suffix.len();
+
+        // Candidates replace the whole token, so a typed qualifier is kept in front
+        // of bare names and replaced by qualified ones
+        let mut start = self.get_token_start(line, pos);
+        // A backslash command name is completed from its backslash
+        if special_commands::is_command_name(&line[..pos]) {
+            start = pos - line[..pos].trim_start().len();
+        }
+        let (qualifier, word) = if line[start..].starts_with('\'') {
+            ("", &line[start..pos])
+        } else {
+            split_qualifier(&line[start..pos])
+        };
+
+        // A word opened with a backtick matches unquoted names and only identifiers
+        let quoted = word.starts_with('`');
+        let match_word = word.trim_start_matches('`');
 
         // Use smart suggestion engine to get suggestions
-        let suggestions = self.suggestion_engine.get_suggestions(line, word);
+        let (suggestions, limit) = self
+            .suggestion_engine
+            .get_ranked_suggestions_at(line, pos, match_word);
+        // Only at the end of the input, so text after the cursor is never split
+        let terminator = self
+            .statement_terminator
+            .lock()
+            .ok()
+            .and_then(|terminator| terminator.clone())
+            .filter(|_| suffix.is_empty() && line[pos..].trim().is_empty());
 
         let mut completions = Vec::new();
+        let mut descriptions = Vec::new();
 
         // Convert smart suggestions to rustyline Pair format
         for suggestion in suggestions {
+            // Identifier suggestions are the backtick-quoted ones
+            let is_identifier = suggestion.text.starts_with('`');
+            if quoted && !is_identifier {
+                continue;
+            }
+
             // Extract clean text for replacement (remove backticks)
-            let clean_text = suggestion.text.trim_matches('`').to_string();
+            let clean_text = suggestion.text.replace('`', "");
+
+            // Functions open their argument list, unless one is already typed;
+            // identifiers keep their backticks when the user opened one
+            let replacement = if suggestion.category == SuggestionCategory::Function
+                && !line[pos..].starts_with('(')
+            {
+                function_call(&clean_text)
+            } else if quoted
+                || (is_identifier
+                    && suggestion
+                        .text
+                        .trim_matches('`')
+                        .split("`.`")
+                        .any(suggestion::needs_quoting))
+            {
+                suggestion.text.clone()
+            } else {
+                clean_text.clone()
+            };
+            let mut replacement = if clean_text.contains('.') {
+                replacement
+            } else {
+                format!("{}{}", qualifier, replacement)
+            };
+            if let Some(terminator) = terminator
+                .as_ref()
+                .filter(|_| suggestion.completes_statement)
+            {
+                replacement.push_str(terminator);
+            }
+            replacement.push_str(suffix);
 
             completions.push(Pair {
-                display: format!(
-                    "{} {} - {}",
+                display: self.display(
                     suggestion.category.icon(),
-                    clean_text,
-                    suggestion.description
+                    &clean_text,
+                    &suggestion.description,
                 ),
-                replacement: clean_text,
+                replacement,
             });
+            descriptions.push((clean_text, suggestion.description));
         }
 
         // If no smart suggestions, check if we're in a specific context where we shouldn't show SQL keywords
-        if completions.is_empty() {
+        if completions.is_empty() && !quoted && qualifier.is_empty() {
             let line_upper = line.to_uppercase();
             let should_show_keywords = !line_upper.ends_with("FROM ")
                 && !line_upper.ends_with("JOIN ")
@@ -293,30 +477,78 @@ impl Completer for MySQLCompleter {
                 for keyword in &self.sql_keywords {
                     if keyword.to_lowercase().starts_with(&word_lower) {
                         completions.push(Pair {
-                            display: format!("🔵 {} - SQL keyword", keyword),
-                            replacement: keyword.clone(),
+                            display: self.display("🔵", keyword, "SQL keyword"),
+                            replacement: format!("{}{}", keyword, suffix),
                         });
+                        descriptions.push((keyword.clone(), "SQL keyword".to_string()));
                     }
                 }
             }
         }
 
-        // Limit result count based on context
-        let line_upper = line.to_uppercase();
-        let limit = if line_upper.contains("USE ") {
-            20 // Show more databases for USE command
-        } else if line_upper.ends_with("FROM ") || line_upper.ends_with("JOIN ") {
-            15 // Show more tables for FROM/JOIN
-        } else {
-            10 // Default limit
-        };
+        // Limit result count; a second Tab on the same word shows everything
+        let limit = limit.filter(|_| !self.paging.is_repeat(line, pos));
+        let hidden = limit.map_or(0, |limit| completions.len().saturating_sub(limit));
+        if let Some(limit) = limit {
+            completions.truncate(limit);
+            descriptions.truncate(limit);
+        }
+        self.paging.record(line, pos, hidden > 0);
 
-        completions.truncate(limit);
+        // Keep each entry on one line; the terminal may have been resized since the last Tab
+        if let Ok((columns, _)) = crossterm::terminal::size() {
+            for pair in &mut completions {
+                pair.display = fit_width(&pair.display, usize::from(columns).saturating_sub(1));
+            }
+        }
+
+        // The hidden count goes on the status line, not into the candidates,
+        // so it can't take part in the common-prefix completion
+        if self.use_status_bar.load(Ordering::Relaxed) {
+            let mut status = status_bar::describe(&descriptions);
+            if hidden > 0 {
+                status = format!("{}  |  {}", status, paging::more_note(hidden));
+            }
+            self.status_bar.show(line, &status);
+        }
 
         Ok((start, completions))
     }
 }
 
+/// `\G` or `\g` typed right after a word, as in `SELECT * FROM users\G`;
+/// empty when the text doesn't end that way
+fn glued_terminator(before: &str) -> &'static str {
+    ["\\G", "\\g"]
+        .into_iter()
+        .find(|terminator| {
+            before.strip_suffix(terminator).is_some_and(|word| {
+                word.ends_with(|c: char| c.is_alphanumeric() || c == '_' || c == '$' || c == '`')
+            })
+        })
+        .unwrap_or("")
+}
+
+/// Shorten text to a display width, ending with an ellipsis when cut
+fn fit_width(text: &str, width: usize) -> String {
+    if text.width() <= width {
+        return text.to_string();
+    }
+
+    let mut fitted = String::new();
+    let mut used = 0;
+    for c in text.chars() {
+        let char_width = c.width().unwrap_or(0);
+        if used + char_width + 1 > width {
+            break;
+        }
+        fitted.push(c);
+        used += char_width;
+    }
+    fitted.push('…');
+    fitted
+}
+
 /// MySQL Helper (integrating all functionality)
 pub struct MySQLHelper {
     completer: MySQLCompleter,
@@ -340,6 +572,36 @@ impl MySQLHelper {
     pub fn set_current_database(&self, database: Option<String>) {
         self.completer.set_current_database(database);
     }
+
+    /// Set the databases searched for unqualified table names after the current one
+    pub fn set_search_path(&self, databases: Vec<String>) {
+        self.completer.set_search_path(databases);
+    }
+
+    /// Delimiter appended to candidates that finish a statement; `None` turns it off
+    pub fn set_statement_terminator(&self, terminator: Option<String>) {
+        self.completer.set_statement_terminator(terminator);
+    }
+
+    /// Override the completion list size (`None` for per-context defaults, 0 for unlimited)
+    pub fn set_completion_limit(&self, limit: Option<usize>) {
+        self.completer.suggestion_engine.set_completion_limit(limit);
+    }
+
+    /// Show completion descriptions in a bottom status line instead of the list
+    pub fn set_status_bar(&self, enabled: bool) {
+        self.completer
+            .use_status_bar
+            .store(enabled, Ordering::Relaxed);
+        if !enabled {
+            self.completer.status_bar.clear();
+        }
+    }
+
+    /// Clear the completion status line, e.g. when the input is accepted
+    pub fn clear_status_bar(&self) {
+        self.completer.status_bar.clear();
+    }
 }
 
 impl Completer for MySQLHelper {
@@ -359,6 +621,8 @@ impl Hinter for MySQLHelper {
     type Hint = String;
 
     fn hint(&self, line: &str, pos: usize, ctx: &Context<'_>) -> Option<String> {
+        self.completer.status_bar.clear_if_changed(line);
+
         // First try history hints
         if let Some(history_hint) = self.hinter.hint(line, pos, ctx) {
             return Some(history_hint);
@@ -467,3 +731,7 @@ impl Validator for MySQLHelper {
 }
 
 impl rustyline::Helper for MySQLHelper {}
+
+#[cfg(test)]
+#[path = "./helper_tests.rs"]
+mod helper_tests;
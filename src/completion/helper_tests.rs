@@ -0,0 +1,222 @@
+use super::*;
+use crate::completion::metadata::ColumnInfo;
+use rustyline::history::DefaultHistory;
+
+fn create_test_completer() -> MySQLCompleter {
+    let mut md = DatabaseMetadata::new();
+    md.databases = vec!["test_db".to_string()];
+    md.tables.insert(
+        "test_db".to_string(),
+        vec!["orders".to_string(), "users".to_string()],
+    );
+    let completer = MySQLCompleter::with_metadata(Arc::new(Mutex::new(md)));
+    completer.set_current_database(Some("test_db".to_string()));
+    completer
+}
+
+fn replacements(completer: &MySQLCompleter, line: &str) -> Vec<String> {
+    let history = DefaultHistory::new();
+    let ctx = Context::new(&history);
+    let (_, pairs) = completer.complete(line, line.len(), &ctx).unwrap();
+    pairs.into_iter().map(|pair| pair.replacement).collect()
+}
+
+#[test]
+fn test_backtick_word_completes_quoted_identifier() {
+    let completer = create_test_completer();
+
+    assert_eq!(
+        replacements(&completer, "SELECT * FROM `ord"),
+        vec!["`orders`"]
+    );
+    assert_eq!(
+        replacements(&completer, "SELECT * FROM ord"),
+        vec!["orders"]
+    );
+}
+
+#[test]
+fn test_qualified_table_replacement_without_current_database() {
+    let completer = create_test_completer();
+    completer.set_current_database(None);
+
+    assert_eq!(
+        replacements(&completer, "SELECT * FROM ord"),
+        vec!["test_db.orders"]
+    );
+    assert_eq!(
+        replacements(&completer, "SELECT * FROM `ord"),
+        vec!["`test_db`.`orders`"]
+    );
+}
+
+#[test]
+fn test_fit_width_truncates_with_ellipsis() {
+    assert_eq!(fit_width("orders - Table", 20), "orders - Table");
+    assert_eq!(
+        fit_width("Column: x (from table very.long.name)", 12),
+        "Column: x (…"
+    );
+    // Wide characters count double
+    assert_eq!(fit_width("表格表格表格", 7), "表格表…");
+}
+
+fn completion(completer: &MySQLCompleter, line: &str) -> (usize, Vec<String>) {
+    let history = DefaultHistory::new();
+    let ctx = Context::new(&history);
+    let (start, pairs) = completer.complete(line, line.len(), &ctx).unwrap();
+    (
+        start,
+        pairs.into_iter().map(|pair| pair.replacement).collect(),
+    )
+}
+
+#[test]
+fn test_completion_replaces_whole_qualified_token() {
+    let completer = create_test_completer();
+
+    // The qualifier is kept in front of bare table names
+    assert_eq!(
+        completion(&completer, "SELECT * FROM test_db.ord"),
+        (14, vec!["test_db.orders".to_string()])
+    );
+    assert_eq!(
+        completion(&completer, "SELECT * FROM `test_db`.`ord"),
+        (14, vec!["`test_db`.`orders`".to_string()])
+    );
+
+    // Qualified candidates replace the typed qualifier instead of repeating it
+    completer.set_current_database(None);
+    assert_eq!(
+        completion(&completer, "SELECT * FROM test_db.ord"),
+        (14, vec!["test_db.orders".to_string()])
+    );
+    assert_eq!(
+        completion(&completer, "SELECT * FROM `test_db`.`ord"),
+        (14, vec!["`test_db`.`orders`".to_string()])
+    );
+}
+
+#[test]
+fn test_token_start_covers_partial_backtick() {
+    let completer = create_test_completer();
+    assert_eq!(completion(&completer, "SELECT * FROM `or").0, 14);
+    // A space inside an open backtick belongs to the identifier
+    assert_eq!(completer.get_token_start("SELECT * FROM `my ta", 20), 14);
+    assert_eq!(completer.get_token_start("SELECT a, `b c`.d", 17), 10);
+    assert_eq!(split_qualifier("`a.b`.`c"), ("`a.b`.", "`c"));
+}
+
+#[test]
+fn test_use_completion_inserts_bare_name_and_delimiter() {
+    let mut md = DatabaseMetadata::new();
+    md.databases = vec!["test_db".to_string(), "my-app".to_string()];
+    let completer = MySQLCompleter::with_metadata(Arc::new(Mutex::new(md)));
+
+    assert_eq!(replacements(&completer, "USE te"), vec!["test_db"]);
+    assert_eq!(replacements(&completer, "USE my"), vec!["`my-app`"]);
+
+    completer.set_statement_terminator(Some(";".to_string()));
+    assert_eq!(replacements(&completer, "USE te"), vec!["test_db;"]);
+    assert_eq!(replacements(&completer, "USE `te"), vec!["`test_db`;"]);
+    let general = replacements(&completer, "");
+    assert!(general.contains(&"SHOW DATABASES;".to_string()));
+    assert!(general.contains(&"SHOW TABLES;".to_string()));
+    assert!(general.contains(&"SELECT * FROM".to_string()));
+
+    completer.set_statement_terminator(Some("$$".to_string()));
+    assert_eq!(replacements(&completer, "USE te"), vec!["test_db$$"]);
+}
+
+#[test]
+fn test_backslash_completes_special_commands() {
+    let completer = create_test_completer();
+    let history = DefaultHistory::new();
+    let ctx = Context::new(&history);
+
+    let (start, pairs) = completer.complete("\\st", 3, &ctx).unwrap();
+    assert_eq!(start, 0);
+    let names: Vec<String> = pairs.into_iter().map(|pair| pair.replacement).collect();
+    assert_eq!(names, vec!["\\status"]);
+
+    let all = replacements(&completer, "\\");
+    assert!(all.contains(&"\\quit".to_string()));
+    assert!(all.contains(&"\\tables".to_string()));
+    assert_eq!(all.len(), special_commands::SPECIAL_COMMANDS.len());
+
+    assert_eq!(completion(&completer, "  \\impor").0, 2);
+}
+
+#[test]
+fn test_special_command_arguments() {
+    let completer = create_test_completer();
+
+    assert_eq!(
+        completion(&completer, "\\u te"),
+        (3, vec!["test_db".to_string()])
+    );
+    assert_eq!(replacements(&completer, "\\check us"), vec!["users"]);
+    assert_eq!(replacements(&completer, "\\d+ or"), vec!["orders"]);
+    // Only the first argument is completed
+    assert!(!replacements(&completer, "\\clone-row orders us").contains(&"users".to_string()));
+}
+
+#[test]
+fn test_completion_before_glued_vertical_terminator() {
+    let completer = create_test_completer();
+
+    assert_eq!(glued_terminator("SELECT * FROM users\\G"), "\\G");
+    assert_eq!(glued_terminator("SELECT 1 \\G"), "");
+    assert_eq!(
+        replacements(&completer, "SELECT * FROM ord\\G"),
+        vec!["orders\\G"]
+    );
+}
+
+#[test]
+fn test_date_format_inserted_as_is() {
+    let completer = create_test_completer();
+
+    let formats = replacements(&completer, "SELECT DATE_FORMAT(d, '%Y");
+    assert_eq!(formats.first().map(String::as_str), Some("'%Y-%m-%d'"));
+    assert!(formats
+        .iter()
+        .all(|f| f.starts_with("'%Y") && f.ends_with('\'')));
+}
+
+#[test]
+fn test_json_path_inserted_as_is() {
+    let mut md = DatabaseMetadata::new();
+    md.column_info.insert(
+        "test_db.users.profile".to_string(),
+        ColumnInfo {
+            data_type: "json".to_string(),
+            ..Default::default()
+        },
+    );
+    let completer = MySQLCompleter::with_metadata(Arc::new(Mutex::new(md)));
+    completer.set_current_database(Some("test_db".to_string()));
+
+    assert_eq!(
+        replacements(&completer, "SELECT profile->>'$.").first(),
+        Some(&"'$.".to_string())
+    );
+    assert_eq!(
+        replacements(&completer, "SELECT profile->>'"),
+        vec!["'$.", "'$[0]", "'$[*]", "'$"]
+    );
+}
+
+#[test]
+fn test_keywords_listed_once() {
+    let completer = create_test_completer();
+
+    assert_eq!(
+        completer
+            .sql_keywords
+            .iter()
+            .filter(|k| *k == "YEAR")
+            .count(),
+        1
+    );
+}
@@ -8,23 +8,179 @@
  * - Cache refresh logic
  */
 
+use crate::database::ConnectionIdentity;
 use anyhow::Result;
 use mysql::prelude::*;
-use std::collections::HashMap;
+use serde::{Serialize, Serializer};
+use sqlparser::ast::{ObjectName, ObjectType, Statement};
+use sqlparser::dialect::MySqlDialect;
+use sqlparser::parser::Parser;
+use std::collections::{BTreeMap, HashMap};
+
+/// Index definition of a table
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TableIndex {
+    /// Index name (PRIMARY for the primary key)
+    pub name: String,
+    /// Indexed columns in index order
+    pub columns: Vec<String>,
+    /// Whether the index enforces uniqueness
+    pub unique: bool,
+}
+
+/// Column details from SHOW COLUMNS
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ColumnInfo {
+    /// Column type as reported by the server (e.g. `varchar(255)`, `json`)
+    pub data_type: String,
+    /// Whether the column accepts NULL
+    pub nullable: bool,
+    /// Default value, e.g. `CURRENT_TIMESTAMP`; `None` without one (or for a NULL default)
+    pub default: Option<String>,
+    /// Filled in by the server: auto_increment or a generated column
+    pub generated: bool,
+}
+
+impl ColumnInfo {
+    /// What an INSERT gets when it leaves the column out: `(default: X)`, or
+    /// `(required)` for NOT NULL columns without a default
+    pub fn insert_hint(&self) -> Option<String> {
+        match &self.default {
+            Some(default) => Some(format!("(default: {})", default)),
+            None if !self.nullable && !self.generated => Some("(required)".to_string()),
+            None => None,
+        }
+    }
+
+    /// Whether the column holds JSON documents
+    pub fn is_json(&self) -> bool {
+        self.data_type.eq_ignore_ascii_case("json")
+    }
+
+    /// Whether the column holds text (CHAR, VARCHAR, TEXT, ENUM or SET)
+    pub fn is_string(&self) -> bool {
+        let data_type = self.data_type.to_lowercase();
+        let name = data_type.split('(').next().unwrap_or_default().trim();
+        matches!(
+            name,
+            "char" | "varchar" | "tinytext" | "text" | "mediumtext" | "longtext" | "enum" | "set"
+        )
+    }
+}
+
+/// Foreign key constraint of a table
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ForeignKey {
+    /// Constraint name
+    pub name: String,
+    /// Referencing columns
+    pub columns: Vec<String>,
+    /// Referenced table as `db.table`
+    pub referenced_table: String,
+    /// Referenced columns, matching `columns` by position
+    pub referenced_columns: Vec<String>,
+}
+
+/// Character set from SHOW CHARACTER SET
+#[derive(Debug, Clone, PartialEq)]
+pub struct CharsetInfo {
+    /// Character set name (e.g. `utf8mb4`)
+    pub name: String,
+    /// Collation used when none is given
+    pub default_collation: String,
+    /// Description as reported by the server
+    pub description: String,
+}
+
+/// Collation from SHOW COLLATION
+#[derive(Debug, Clone, PartialEq)]
+pub struct CollationInfo {
+    /// Collation name (e.g. `utf8mb4_general_ci`)
+    pub name: String,
+    /// Character set the collation belongs to
+    pub charset: String,
+}
+
+/// Storage engine from SHOW ENGINES
+#[derive(Debug, Clone, PartialEq)]
+pub struct EngineInfo {
+    /// Engine name (e.g. `InnoDB`)
+    pub name: String,
+    /// Comment as reported by the server
+    pub comment: String,
+    /// Whether the engine can be used (Support is YES or DEFAULT)
+    pub enabled: bool,
+    /// Whether this is the default storage engine
+    pub is_default: bool,
+}
+
+/// Where the database list comes from: the server connection, or a mock in tests
+pub trait DatabaseSource {
+    fn show_databases(&mut self) -> Result<Vec<String>>;
+    fn current_database(&mut self) -> Result<Option<String>>;
+}
+
+impl DatabaseSource for mysql::Conn {
+    fn show_databases(&mut self) -> Result<Vec<String>> {
+        Ok(self.query("SHOW DATABASES")?)
+    }
+
+    fn current_database(&mut self) -> Result<Option<String>> {
+        let database: Option<Option<String>> = self.query_first("SELECT DATABASE()")?;
+        Ok(database.flatten())
+    }
+}
 
 /// Database metadata cache
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct DatabaseMetadata {
     /// Database list
     pub databases: Vec<String>,
     /// Table information: database name -> table name list
+    #[serde(serialize_with = "sorted_map")]
     pub tables: HashMap<String, Vec<String>>,
     /// Field information: table name -> field list
+    #[serde(serialize_with = "sorted_map")]
     pub columns: HashMap<String, Vec<String>>,
+    /// Column details: `db.table.column` -> column info
+    #[serde(serialize_with = "sorted_map")]
+    pub column_info: HashMap<String, ColumnInfo>,
+    /// Index information: table name -> index list
+    #[serde(serialize_with = "sorted_map")]
+    pub indexes: HashMap<String, Vec<TableIndex>>,
+    /// Foreign key information: table name -> foreign key list
+    #[serde(serialize_with = "sorted_map")]
+    pub foreign_keys: HashMap<String, Vec<ForeignKey>>,
+    /// Character sets known to the server
+    #[serde(skip)]
+    pub charsets: Vec<CharsetInfo>,
+    /// Collations known to the server
+    #[serde(skip)]
+    pub collations: Vec<CollationInfo>,
+    /// Storage engines known to the server
+    #[serde(skip)]
+    pub engines: Vec<EngineInfo>,
+    /// Session temporary tables: database name -> table name list
+    #[serde(skip)]
+    temporary_tables: HashMap<String, Vec<String>>,
+    /// Session temporary table fields: table name -> field list
+    #[serde(skip)]
+    temporary_columns: HashMap<String, Vec<String>>,
     /// Last update time
+    #[serde(skip)]
     last_update: std::time::Instant,
     /// Whether data has been loaded at least once
+    #[serde(skip)]
     has_loaded: bool,
+    /// Database given with `-D`, loaded even when SHOW DATABASES doesn't list it
+    #[serde(skip)]
+    requested_database: Option<String>,
+    /// SHOW DATABASES failed or listed only system databases
+    #[serde(skip)]
+    database_list_limited: bool,
+    /// Server and account the metadata was loaded from
+    #[serde(skip)]
+    identity: Option<ConnectionIdentity>,
 }
 
 impl DatabaseMetadata {
@@ -34,16 +190,94 @@ impl DatabaseMetadata {
             databases: Vec::new(),
             tables: HashMap::new(),
             columns: HashMap::new(),
+            column_info: HashMap::new(),
+            indexes: HashMap::new(),
+            foreign_keys: HashMap::new(),
+            charsets: builtin_charsets(),
+            collations: builtin_collations(),
+            engines: builtin_engines(),
+            temporary_tables: HashMap::new(),
+            temporary_columns: HashMap::new(),
             last_update: std::time::Instant::now(),
             has_loaded: false,
+            requested_database: None,
+            database_list_limited: false,
+            identity: None,
         }
     }
 
+    /// Tie the metadata to the server it is loaded from. When the server or user
+    /// changes, everything loaded so far (temporary tables included) is dropped,
+    /// so completion offers keywords only until the new server's metadata is loaded.
+    /// Returns whether the identity changed
+    pub fn bind_to(&mut self, identity: &ConnectionIdentity) -> bool {
+        match &self.identity {
+            Some(current) if current == identity => false,
+            Some(_) => {
+                let requested_database = self.requested_database.take();
+                *self = Self::new();
+                self.requested_database = requested_database;
+                self.identity = Some(identity.clone());
+                true
+            }
+            None => {
+                self.identity = Some(identity.clone());
+                false
+            }
+        }
+    }
+
+    /// Remember the database given with `-D` for accounts that can't list databases
+    pub fn set_requested_database(&mut self, database: Option<String>) {
+        self.requested_database = database;
+    }
+
+    /// Whether the database list comes from the fallback because the account
+    /// can't see its databases with SHOW DATABASES
+    pub fn is_database_list_limited(&self) -> bool {
+        self.database_list_limited
+    }
+
+    /// Databases to load, from SHOW DATABASES. Accounts without the SHOW DATABASES
+    /// privilege may get an error or only system databases; the current database
+    /// and the one requested with `-D` are added then, and the list is marked limited
+    fn accessible_databases(&mut self, source: &mut impl DatabaseSource) -> Vec<String> {
+        let mut databases = source.show_databases().unwrap_or_default();
+        self.database_list_limited = databases.iter().all(|db| self.is_system_database(db));
+        if self.database_list_limited {
+            let current = source.current_database().ok().flatten();
+            for db in current.into_iter().chain(self.requested_database.clone()) {
+                if !databases
+                    .iter()
+                    .any(|known| known.eq_ignore_ascii_case(&db))
+                {
+                    databases.push(db);
+                }
+            }
+        }
+        databases
+    }
+
     /// Check if cache needs refresh (5 minute expiry)
     pub fn needs_refresh(&self) -> bool {
         !self.has_loaded || self.last_update.elapsed().as_secs() > 300
     }
 
+    /// Whether the metadata has been loaded from a server at least once
+    pub fn is_loaded(&self) -> bool {
+        self.has_loaded
+    }
+
+    /// Whether tables were loaded for every non-system database
+    pub fn is_complete(&self) -> bool {
+        self.has_loaded
+            && self
+                .databases
+                .iter()
+                .filter(|db| !self.is_system_database(db))
+                .all(|db| self.tables.contains_key(&db.to_lowercase()))
+    }
+
     /// Update metadata from database connection
     pub fn update_from_connection(&mut self, conn: &mut mysql::Conn) -> Result<()> {
         if !self.needs_refresh() {
@@ -51,12 +285,19 @@ impl DatabaseMetadata {
         }
 
         // Get database list
-        let databases: Vec<String> = conn.query("SHOW DATABASES")?;
+        let databases = self.accessible_databases(conn);
         self.databases = databases.clone();
 
+        // Character sets and collations are a few hundred rows; keep the built-in list on failure
+        let _ = self.load_charsets(conn);
+        let _ = self.load_engines(conn);
+
         // Clear old table and column information
         self.tables.clear();
         self.columns.clear();
+        self.column_info.clear();
+        self.indexes.clear();
+        self.foreign_keys.clear();
 
         // Get table information for each database
         for db in &databases {
@@ -71,18 +312,12 @@ impl DatabaseMetadata {
 
                 // Get column information for each table
                 for table in &tables {
-                    let query = format!("SHOW COLUMNS FROM `{}`.`{}`", db, table);
-                    if let Ok(rows) = conn.query::<mysql::Row, _>(query) {
-                        let mut columns = Vec::new();
-                        for row in rows {
-                            if let Some(field_name) = row.get::<String, _>(0) {
-                                columns.push(field_name);
-                            }
-                        }
-                        let table_key = format!("{}.{}", db, table);
-                        self.columns.insert(table_key.to_lowercase(), columns);
-                    }
+                    let _ = self.load_columns(conn, db, table);
                 }
+
+                // Get index and foreign key information for the whole database in one query each
+                let _ = self.load_indexes(conn, db);
+                let _ = self.load_foreign_keys(conn, db);
             }
         }
 
@@ -91,6 +326,205 @@ impl DatabaseMetadata {
         Ok(())
     }
 
+    /// Load column names and types of one table
+    fn load_columns(&mut self, conn: &mut mysql::Conn, db: &str, table: &str) -> Result<()> {
+        let query = format!("SHOW COLUMNS FROM `{}`.`{}`", db, table);
+        let rows = conn.query::<mysql::Row, _>(query)?;
+        let mut columns = Vec::new();
+        for row in rows {
+            if let Some(field_name) = row.get::<String, _>(0) {
+                let data_type = row.get::<String, _>(1).unwrap_or_default();
+                let nullable = row.get::<String, _>(2).is_some_and(|null| null == "YES");
+                let default = row.get::<Option<String>, _>(4).flatten();
+                let extra = row
+                    .get::<Option<String>, _>(5)
+                    .flatten()
+                    .unwrap_or_default()
+                    .to_lowercase();
+                // `DEFAULT_GENERATED` only marks an expression default
+                let generated = extra.contains("auto_increment") || extra.contains(" generated");
+                let column_key = format!("{}.{}.{}", db, table, field_name);
+                self.column_info.insert(
+                    column_key.to_lowercase(),
+                    ColumnInfo {
+                        data_type,
+                        nullable,
+                        default,
+                        generated,
+                    },
+                );
+                columns.push(field_name);
+            }
+        }
+        let table_key = format!("{}.{}", db, table);
+        self.columns.insert(table_key.to_lowercase(), columns);
+        Ok(())
+    }
+
+    /// Reload the database list, forgetting objects of databases that no longer exist
+    pub fn refresh_databases(&mut self, conn: &mut mysql::Conn) -> Result<()> {
+        let databases = self.accessible_databases(conn);
+        let known: Vec<String> = databases.iter().map(|db| db.to_lowercase()).collect();
+        let is_known = |key: &String| {
+            let db = key.split('.').next().unwrap_or(key);
+            known.iter().any(|k| k == db)
+        };
+
+        self.tables.retain(|db, _| is_known(db));
+        self.columns.retain(|key, _| is_known(key));
+        self.column_info.retain(|key, _| is_known(key));
+        self.indexes.retain(|key, _| is_known(key));
+        self.foreign_keys.retain(|key, _| is_known(key));
+
+        for db in &databases {
+            if !self.is_system_database(db) && !self.tables.contains_key(&db.to_lowercase()) {
+                let _ = self.refresh_tables(conn, db, &[]);
+            }
+        }
+        self.databases = databases;
+        Ok(())
+    }
+
+    /// Reload the table list of a database and the columns of the given tables;
+    /// indexes and foreign keys of the database are reloaded with one query each
+    pub fn refresh_tables(
+        &mut self,
+        conn: &mut mysql::Conn,
+        db: &str,
+        changed: &[String],
+    ) -> Result<()> {
+        let db_key = db.to_lowercase();
+        let tables: Vec<String> = conn.query(format!("SHOW TABLES FROM `{}`", db))?;
+
+        // Forget columns of changed tables and of tables that no longer exist
+        let stale: Vec<String> = self
+            .tables
+            .get(&db_key)
+            .into_iter()
+            .flatten()
+            .filter(|t| !tables.contains(t))
+            .chain(changed)
+            .map(|t| format!("{}.{}", db_key, t.to_lowercase()))
+            .collect();
+        for table_key in stale {
+            let column_prefix = format!("{}.", table_key);
+            self.columns.remove(&table_key);
+            self.column_info
+                .retain(|key, _| !key.starts_with(&column_prefix));
+        }
+
+        // Load columns of changed tables and of tables not cached yet
+        for table in &tables {
+            let table_key = format!("{}.{}", db_key, table.to_lowercase());
+            if !self.columns.contains_key(&table_key) {
+                let _ = self.load_columns(conn, db, table);
+            }
+        }
+
+        let table_prefix = format!("{}.", db_key);
+        self.indexes
+            .retain(|key, _| !key.starts_with(&table_prefix));
+        self.foreign_keys
+            .retain(|key, _| !key.starts_with(&table_prefix));
+        let _ = self.load_indexes(conn, db);
+        let _ = self.load_foreign_keys(conn, db);
+
+        self.tables.insert(db_key, tables);
+        Ok(())
+    }
+
+    /// Force a full reload on the next update
+    pub fn invalidate(&mut self) {
+        self.has_loaded = false;
+    }
+
+    /// Load character sets and collations with their default pairing
+    fn load_charsets(&mut self, conn: &mut mysql::Conn) -> Result<()> {
+        let charsets = conn.query_map("SHOW CHARACTER SET", |row: mysql::Row| CharsetInfo {
+            name: row.get(0).unwrap_or_default(),
+            description: row.get(1).unwrap_or_default(),
+            default_collation: row.get(2).unwrap_or_default(),
+        })?;
+        let collations = conn.query_map("SHOW COLLATION", |row: mysql::Row| CollationInfo {
+            name: row.get(0).unwrap_or_default(),
+            charset: row.get(1).unwrap_or_default(),
+        })?;
+
+        self.charsets = charsets;
+        self.charsets.sort_by(|a, b| a.name.cmp(&b.name));
+        self.collations = collations;
+        self.collations.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(())
+    }
+
+    /// Load storage engines and which one is the default
+    fn load_engines(&mut self, conn: &mut mysql::Conn) -> Result<()> {
+        self.engines = conn.query_map("SHOW ENGINES", |row: mysql::Row| {
+            let support: String = row.get(1).unwrap_or_default();
+            EngineInfo {
+                name: row.get(0).unwrap_or_default(),
+                comment: row.get(2).unwrap_or_default(),
+                enabled: support == "YES" || support == "DEFAULT",
+                is_default: support == "DEFAULT",
+            }
+        })?;
+        Ok(())
+    }
+
+    /// Load index definitions for every table of a database
+    fn load_indexes(&mut self, conn: &mut mysql::Conn, db: &str) -> Result<()> {
+        let rows: Vec<(String, String, String, u8)> = conn.exec(
+            "SELECT TABLE_NAME, INDEX_NAME, COLUMN_NAME, NON_UNIQUE \
+             FROM information_schema.STATISTICS WHERE TABLE_SCHEMA = ? \
+             ORDER BY TABLE_NAME, INDEX_NAME, SEQ_IN_INDEX",
+            (db,),
+        )?;
+
+        for (table, index_name, column, non_unique) in rows {
+            let table_key = format!("{}.{}", db, table).to_lowercase();
+            let indexes = self.indexes.entry(table_key).or_default();
+            match indexes.last_mut() {
+                Some(index) if index.name == index_name => index.columns.push(column),
+                _ => indexes.push(TableIndex {
+                    name: index_name,
+                    columns: vec![column],
+                    unique: non_unique == 0,
+                }),
+            }
+        }
+        Ok(())
+    }
+
+    /// Load foreign key definitions for every table of a database
+    fn load_foreign_keys(&mut self, conn: &mut mysql::Conn, db: &str) -> Result<()> {
+        let rows: Vec<(String, String, String, String, String, String)> = conn.exec(
+            "SELECT TABLE_NAME, CONSTRAINT_NAME, COLUMN_NAME, REFERENCED_TABLE_SCHEMA, \
+             REFERENCED_TABLE_NAME, REFERENCED_COLUMN_NAME \
+             FROM information_schema.KEY_COLUMN_USAGE \
+             WHERE TABLE_SCHEMA = ? AND REFERENCED_TABLE_NAME IS NOT NULL \
+             ORDER BY TABLE_NAME, CONSTRAINT_NAME, ORDINAL_POSITION",
+            (db,),
+        )?;
+
+        for (table, name, column, ref_db, ref_table, ref_column) in rows {
+            let table_key = format!("{}.{}", db, table).to_lowercase();
+            let foreign_keys = self.foreign_keys.entry(table_key).or_default();
+            match foreign_keys.last_mut() {
+                Some(fk) if fk.name == name => {
+                    fk.columns.push(column);
+                    fk.referenced_columns.push(ref_column);
+                }
+                _ => foreign_keys.push(ForeignKey {
+                    name,
+                    columns: vec![column],
+                    referenced_table: format!("{}.{}", ref_db, ref_table),
+                    referenced_columns: vec![ref_column],
+                }),
+            }
+        }
+        Ok(())
+    }
+
     /// Check if it's a system database
     fn is_system_database(&self, db: &str) -> bool {
         matches!(
@@ -104,27 +538,260 @@ impl DatabaseMetadata {
         &self.databases
     }
 
-    /// Get all table names (across databases)
+    /// Get all table names (across databases), including session temporary tables,
+    /// sorted by database and table name
     pub fn get_all_tables(&self) -> Vec<(&String, &String)> {
         let mut tables = Vec::new();
         for (db, table_list) in &self.tables {
+            for table in table_list {
+                if !self.is_temporary_table(db, table) {
+                    tables.push((db, table));
+                }
+            }
+        }
+        for (db, table_list) in &self.temporary_tables {
             for table in table_list {
                 tables.push((db, table));
             }
         }
+        tables.sort();
         tables
     }
 
-    /// Get all column names (across tables)
+    /// Get all column names (across tables), including session temporary tables,
+    /// sorted by table key and then in table order
     pub fn get_all_columns(&self) -> Vec<(&String, &String)> {
         let mut columns = Vec::new();
         for (table, column_list) in &self.columns {
+            if self.temporary_columns.contains_key(table) {
+                continue;
+            }
             for column in column_list {
                 columns.push((table, column));
             }
         }
+        for (table, column_list) in &self.temporary_columns {
+            for column in column_list {
+                columns.push((table, column));
+            }
+        }
+        // Stable sort keeps each table's columns in their defined order
+        columns.sort_by(|a, b| a.0.cmp(b.0));
         columns
     }
+
+    /// Get columns for a `db.table` key; temporary tables shadow regular ones
+    pub fn get_columns(&self, table_key: &str) -> Option<&Vec<String>> {
+        self.temporary_columns
+            .get(table_key)
+            .or_else(|| self.columns.get(table_key))
+    }
+
+    /// Get details of a column of a table
+    pub fn get_column_info(&self, table_key: &str, column: &str) -> Option<&ColumnInfo> {
+        self.column_info
+            .get(&format!("{}.{}", table_key, column).to_lowercase())
+    }
+
+    /// Check whether any known column with this name (optionally `table.column`) is JSON
+    pub fn is_json_column(&self, column: &str) -> bool {
+        let name = column.rsplit('.').next().unwrap_or(column);
+        let suffix = format!(".{}", name.trim_matches('`').to_lowercase());
+        self.column_info
+            .iter()
+            .any(|(key, info)| key.ends_with(&suffix) && info.is_json())
+    }
+
+    /// Get collations, restricted to one character set when given
+    pub fn get_collations(&self, charset: Option<&str>) -> Vec<&CollationInfo> {
+        self.collations
+            .iter()
+            .filter(|c| charset.is_none_or(|cs| c.charset.eq_ignore_ascii_case(cs)))
+            .collect()
+    }
+
+    /// Get the default collation of a character set
+    pub fn default_collation(&self, charset: &str) -> Option<&str> {
+        self.charsets
+            .iter()
+            .find(|c| c.name.eq_ignore_ascii_case(charset))
+            .map(|c| c.default_collation.as_str())
+    }
+
+    /// Get indexes for a `db.table` key
+    pub fn get_indexes(&self, table_key: &str) -> &[TableIndex] {
+        self.indexes
+            .get(table_key)
+            .map(|indexes| indexes.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// Get foreign keys for a `db.table` key
+    pub fn get_foreign_keys(&self, table_key: &str) -> &[ForeignKey] {
+        self.foreign_keys
+            .get(table_key)
+            .map(|foreign_keys| foreign_keys.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// Record a temporary table created in this session
+    pub fn add_temporary_table(&mut self, database: &str, table: &str, columns: Vec<String>) {
+        let db_key = database.to_lowercase();
+        let tables = self.temporary_tables.entry(db_key).or_default();
+        if !tables.iter().any(|t| t.eq_ignore_ascii_case(table)) {
+            tables.push(table.to_string());
+        }
+        self.temporary_columns
+            .insert(format!("{}.{}", database, table).to_lowercase(), columns);
+    }
+
+    /// Forget a temporary table dropped in this session
+    pub fn remove_temporary_table(&mut self, database: &str, table: &str) {
+        let db_key = database.to_lowercase();
+        if let Some(tables) = self.temporary_tables.get_mut(&db_key) {
+            tables.retain(|t| !t.eq_ignore_ascii_case(table));
+            if tables.is_empty() {
+                self.temporary_tables.remove(&db_key);
+            }
+        }
+        self.temporary_columns
+            .remove(&format!("{}.{}", database, table).to_lowercase());
+    }
+
+    /// Drop the session overlay (temporary tables die with the connection)
+    pub fn clear_session_overlay(&mut self) {
+        self.temporary_tables.clear();
+        self.temporary_columns.clear();
+    }
+
+    /// Check if a table is a session temporary table
+    fn is_temporary_table(&self, database: &str, table: &str) -> bool {
+        self.temporary_tables
+            .get(&database.to_lowercase())
+            .is_some_and(|tables| tables.iter().any(|t| t.eq_ignore_ascii_case(table)))
+    }
+}
+
+/// Character sets offered before the server list is loaded or when it cannot be read
+fn builtin_charsets() -> Vec<CharsetInfo> {
+    [
+        ("latin1", "latin1_swedish_ci", "cp1252 West European"),
+        ("utf8mb4", "utf8mb4_0900_ai_ci", "UTF-8 Unicode"),
+    ]
+    .iter()
+    .map(|(name, default_collation, description)| CharsetInfo {
+        name: name.to_string(),
+        default_collation: default_collation.to_string(),
+        description: description.to_string(),
+    })
+    .collect()
+}
+
+/// Common collations of the built-in character sets
+fn builtin_collations() -> Vec<CollationInfo> {
+    [
+        ("latin1_bin", "latin1"),
+        ("latin1_general_ci", "latin1"),
+        ("latin1_swedish_ci", "latin1"),
+        ("utf8mb4_0900_ai_ci", "utf8mb4"),
+        ("utf8mb4_bin", "utf8mb4"),
+        ("utf8mb4_general_ci", "utf8mb4"),
+        ("utf8mb4_unicode_ci", "utf8mb4"),
+    ]
+    .iter()
+    .map(|(name, charset)| CollationInfo {
+        name: name.to_string(),
+        charset: charset.to_string(),
+    })
+    .collect()
+}
+
+/// Storage engines offered before the server list is loaded or when it cannot be read
+fn builtin_engines() -> Vec<EngineInfo> {
+    [
+        (
+            "InnoDB",
+            "Supports transactions, row-level locking, and foreign keys",
+            true,
+        ),
+        ("MEMORY", "Hash based, stored in memory", false),
+        ("MyISAM", "MyISAM storage engine", false),
+    ]
+    .iter()
+    .map(|(name, comment, is_default)| EngineInfo {
+        name: name.to_string(),
+        comment: comment.to_string(),
+        enabled: true,
+        is_default: *is_default,
+    })
+    .collect()
+}
+
+/// Serialize a HashMap with keys in sorted order for stable output
+fn sorted_map<V: Serialize, S: Serializer>(
+    map: &HashMap<String, V>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    map.iter().collect::<BTreeMap<_, _>>().serialize(serializer)
+}
+
+/// Session temporary table change detected from a DDL statement
+#[derive(Debug, PartialEq)]
+pub enum TemporaryTableChange {
+    /// CREATE TEMPORARY TABLE; columns are None when they must be fetched from the server
+    Created {
+        database: Option<String>,
+        table: String,
+        columns: Option<Vec<String>>,
+    },
+    /// DROP [TEMPORARY] TABLE (a plain DROP also removes a shadowing temporary table)
+    Dropped(Vec<(Option<String>, String)>),
+}
+
+impl TemporaryTableChange {
+    /// Detect a temporary table change in a SQL statement
+    pub fn parse(sql: &str) -> Option<Self> {
+        let statements = Parser::parse_sql(&MySqlDialect {}, sql).ok()?;
+        match statements.first()? {
+            Statement::CreateTable {
+                temporary: true,
+                name,
+                columns,
+                query,
+                like,
+                ..
+            } => {
+                let (database, table) = split_object_name(name)?;
+                let columns = if columns.is_empty() || query.is_some() || like.is_some() {
+                    None
+                } else {
+                    Some(columns.iter().map(|c| c.name.value.clone()).collect())
+                };
+                Some(TemporaryTableChange::Created {
+                    database,
+                    table,
+                    columns,
+                })
+            }
+            Statement::Drop {
+                object_type: ObjectType::Table,
+                names,
+                ..
+            } => Some(TemporaryTableChange::Dropped(
+                names.iter().filter_map(split_object_name).collect(),
+            )),
+            _ => None,
+        }
+    }
+}
+
+/// Split an optionally qualified object name into (database, name)
+fn split_object_name(name: &ObjectName) -> Option<(Option<String>, String)> {
+    match name.0.as_slice() {
+        [table] => Some((None, table.value.clone())),
+        [database, table] => Some((Some(database.value.clone()), table.value.clone())),
+        _ => None,
+    }
 }
 
 impl Default for DatabaseMetadata {
@@ -132,3 +799,7 @@ impl Default for DatabaseMetadata {
         Self::new()
     }
 }
+
+#[cfg(test)]
+#[path = "./metadata_tests.rs"]
+mod metadata_tests;
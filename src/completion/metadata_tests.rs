@@ -0,0 +1,169 @@
+use super::*;
+
+#[test]
+fn test_parse_create_temporary_table() {
+    assert_eq!(
+        TemporaryTableChange::parse("CREATE TEMPORARY TABLE tmp_ids (id INT, label VARCHAR(20))"),
+        Some(TemporaryTableChange::Created {
+            database: None,
+            table: "tmp_ids".to_string(),
+            columns: Some(vec!["id".to_string(), "label".to_string()]),
+        })
+    );
+    assert_eq!(
+        TemporaryTableChange::parse("CREATE TEMPORARY TABLE sales.tmp AS SELECT id FROM orders"),
+        Some(TemporaryTableChange::Created {
+            database: Some("sales".to_string()),
+            table: "tmp".to_string(),
+            columns: None,
+        })
+    );
+    assert_eq!(
+        TemporaryTableChange::parse("CREATE TABLE regular (id INT)"),
+        None
+    );
+}
+
+#[test]
+fn test_parse_drop_table() {
+    assert_eq!(
+        TemporaryTableChange::parse("DROP TEMPORARY TABLE IF EXISTS tmp_ids, test_db.tmp2"),
+        Some(TemporaryTableChange::Dropped(vec![
+            (None, "tmp_ids".to_string()),
+            (Some("test_db".to_string()), "tmp2".to_string()),
+        ]))
+    );
+}
+
+#[test]
+fn test_temporary_table_overlay() {
+    let mut md = DatabaseMetadata::new();
+    md.tables
+        .insert("test_db".to_string(), vec!["users".to_string()]);
+
+    md.add_temporary_table("test_db", "tmp_ids", vec!["id".to_string()]);
+    assert!(md
+        .get_all_tables()
+        .iter()
+        .any(|(_, table)| table.as_str() == "tmp_ids"));
+    assert_eq!(
+        md.get_columns("test_db.tmp_ids"),
+        Some(&vec!["id".to_string()])
+    );
+
+    md.remove_temporary_table("test_db", "TMP_IDS");
+    assert_eq!(md.get_all_tables().len(), 1);
+    assert_eq!(md.get_columns("test_db.tmp_ids"), None);
+
+    md.add_temporary_table("test_db", "tmp_ids", vec!["id".to_string()]);
+    md.clear_session_overlay();
+    assert_eq!(md.get_all_tables().len(), 1);
+}
+
+struct MockConnection {
+    databases: Result<Vec<String>, String>,
+    current: Option<String>,
+}
+
+impl MockConnection {
+    fn identity(host: &str) -> ConnectionIdentity {
+        ConnectionIdentity {
+            host: host.to_string(),
+            port: 3306,
+            user: "app".to_string(),
+        }
+    }
+}
+
+impl DatabaseSource for MockConnection {
+    fn show_databases(&mut self) -> Result<Vec<String>> {
+        self.databases.clone().map_err(anyhow::Error::msg)
+    }
+
+    fn current_database(&mut self) -> Result<Option<String>> {
+        Ok(self.current.clone())
+    }
+}
+
+#[test]
+fn test_database_list_falls_back_without_show_databases_privilege() {
+    let mut metadata = DatabaseMetadata::new();
+    metadata.set_requested_database(Some("reports".to_string()));
+
+    let mut denied = MockConnection {
+        databases: Err("Access denied; you need the SHOW DATABASES privilege".to_string()),
+        current: Some("shop".to_string()),
+    };
+    assert_eq!(
+        metadata.accessible_databases(&mut denied),
+        vec!["shop", "reports"]
+    );
+    assert!(metadata.is_database_list_limited());
+
+    // Only information_schema is visible; the current database is also the requested one
+    let mut system_only = MockConnection {
+        databases: Ok(vec!["information_schema".to_string()]),
+        current: Some("Reports".to_string()),
+    };
+    assert_eq!(
+        metadata.accessible_databases(&mut system_only),
+        vec!["information_schema", "Reports"]
+    );
+
+    let mut privileged = MockConnection {
+        databases: Ok(vec!["information_schema".to_string(), "shop".to_string()]),
+        current: None,
+    };
+    assert_eq!(
+        metadata.accessible_databases(&mut privileged),
+        vec!["information_schema", "shop"]
+    );
+    assert!(!metadata.is_database_list_limited());
+}
+
+#[test]
+fn test_switching_servers_drops_old_metadata() {
+    use crate::completion::engine::SmartSuggestionEngine;
+    use std::sync::{Arc, Mutex};
+
+    let metadata = Arc::new(Mutex::new(DatabaseMetadata::new()));
+    {
+        let mut meta = metadata.lock().unwrap();
+        assert!(!meta.bind_to(&MockConnection::identity("db-a")));
+        let mut server_a = MockConnection {
+            databases: Ok(vec!["shop".to_string()]),
+            current: Some("shop".to_string()),
+        };
+        meta.databases = meta.accessible_databases(&mut server_a);
+        meta.tables
+            .insert("shop".to_string(), vec!["orders".to_string()]);
+        meta.add_temporary_table("shop", "tmp_ids", vec!["id".to_string()]);
+        meta.has_loaded = true;
+    }
+    let engine = SmartSuggestionEngine::new(metadata.clone(), vec!["SELECT".to_string()]);
+    engine.set_current_database(Some("shop".to_string()));
+    let tables = |engine: &SmartSuggestionEngine| -> Vec<String> {
+        engine
+            .get_suggestions("SELECT * FROM ", "")
+            .into_iter()
+            .map(|s| s.text)
+            .collect()
+    };
+    assert!(tables(&engine).contains(&"`orders`".to_string()));
+
+    // Reconnecting to the same server keeps the snapshot
+    assert!(!metadata
+        .lock()
+        .unwrap()
+        .bind_to(&MockConnection::identity("db-a")));
+    assert!(tables(&engine).contains(&"`orders`".to_string()));
+
+    // Another server: tables and temporary tables of the old one are gone
+    let mut meta = metadata.lock().unwrap();
+    assert!(meta.bind_to(&MockConnection::identity("db-b")));
+    assert!(meta.needs_refresh());
+    drop(meta);
+    let suggestions = tables(&engine);
+    assert!(!suggestions.contains(&"`orders`".to_string()));
+    assert!(!suggestions.contains(&"`tmp_ids`".to_string()));
+}
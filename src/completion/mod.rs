@@ -11,6 +11,10 @@
 pub mod engine;
 pub mod helper;
 pub mod metadata;
+pub mod paging;
+pub mod schema_change;
+pub mod special_commands;
+pub mod status_bar;
 pub mod suggestion;
 
 // Re-export main interfaces
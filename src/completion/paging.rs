@@ -0,0 +1,40 @@
+/*!
+ * Completion paging
+ *
+ * Remembers whether the last completion list was truncated, so that a
+ * second consecutive Tab on the same word shows every candidate
+ */
+
+use std::sync::Mutex;
+
+/// State of the previous completion request
+#[derive(Default)]
+pub struct CompletionPaging {
+    /// Line and cursor position of the last truncated request
+    last_truncated: Mutex<Option<(String, usize)>>,
+}
+
+impl CompletionPaging {
+    /// Whether this request repeats the previous truncated one on the same input
+    pub fn is_repeat(&self, line: &str, pos: usize) -> bool {
+        self.last_truncated
+            .lock()
+            .is_ok_and(|last| matches!(&*last, Some((l, p)) if l == line && *p == pos))
+    }
+
+    /// Record whether the list for this request was truncated
+    pub fn record(&self, line: &str, pos: usize, truncated: bool) {
+        if let Ok(mut last) = self.last_truncated.lock() {
+            *last = truncated.then(|| (line.to_string(), pos));
+        }
+    }
+}
+
+/// Status line note below a truncated list
+pub fn more_note(hidden: usize) -> String {
+    format!("… and {} more (press Tab again)", hidden)
+}
+
+#[cfg(test)]
+#[path = "./paging_tests.rs"]
+mod paging_tests;
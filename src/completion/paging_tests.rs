@@ -0,0 +1,32 @@
+use super::*;
+
+#[test]
+fn test_repeat_on_same_word_after_truncation() {
+    let paging = CompletionPaging::default();
+    assert!(!paging.is_repeat("SELECT * FROM o", 15));
+
+    paging.record("SELECT * FROM o", 15, true);
+    assert!(paging.is_repeat("SELECT * FROM o", 15));
+}
+
+#[test]
+fn test_no_repeat_after_input_changed() {
+    let paging = CompletionPaging::default();
+    paging.record("SELECT * FROM o", 15, true);
+    assert!(!paging.is_repeat("SELECT * FROM or", 16));
+    assert!(!paging.is_repeat("SELECT * FROM o", 14));
+}
+
+#[test]
+fn test_full_list_resets_state() {
+    let paging = CompletionPaging::default();
+    paging.record("SELECT * FROM o", 15, true);
+    // The expanded list is not truncated, so a third Tab starts over
+    paging.record("SELECT * FROM o", 15, false);
+    assert!(!paging.is_repeat("SELECT * FROM o", 15));
+}
+
+#[test]
+fn test_more_note() {
+    assert_eq!(more_note(37), "… and 37 more (press Tab again)");
+}
@@ -0,0 +1,169 @@
+/*!
+ * Schema change detection
+ *
+ * Classifies executed statements by the schema objects they may have changed,
+ * so that only the affected part of the metadata cache is reloaded
+ */
+
+use crate::statement;
+use sqlparser::ast::{AlterTableOperation, ObjectName, ObjectType, SchemaName, Statement};
+use sqlparser::dialect::MySqlDialect;
+use sqlparser::keywords::Keyword;
+use sqlparser::parser::Parser;
+use sqlparser::tokenizer::{Token, Tokenizer, Word};
+
+/// Schema objects changed by one or more statements
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct SchemaChange {
+    /// Tables or views created, dropped, altered or renamed, as (database, name)
+    pub tables: Vec<(Option<String>, String)>,
+    /// A database was created or dropped
+    pub databases: bool,
+    /// Database selected with USE
+    pub use_database: Option<String>,
+    /// DDL whose targets could not be determined; everything must be reloaded
+    pub reload_all: bool,
+}
+
+impl SchemaChange {
+    /// Classify SQL text, which may hold several statements, into a single change
+    pub fn classify(sql: &str) -> Option<Self> {
+        let mut change = SchemaChange::default();
+        for statement in statement::split_statements(sql) {
+            match Parser::parse_sql(&MySqlDialect {}, &statement) {
+                Ok(parsed) => parsed.iter().for_each(|s| change.add_statement(s)),
+                Err(_) => change.add_tokens(&statement),
+            }
+        }
+        (!change.is_empty()).then_some(change)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tables.is_empty() && !self.databases && self.use_database.is_none() && !self.reload_all
+    }
+
+    fn add_table(&mut self, name: &ObjectName) {
+        match name.0.as_slice() {
+            [table] => self.add_name(None, &table.value),
+            [database, table] => self.add_name(Some(&database.value), &table.value),
+            _ => self.reload_all = true,
+        }
+    }
+
+    fn add_name(&mut self, database: Option<&str>, table: &str) {
+        let entry = (database.map(|d| d.to_string()), table.to_string());
+        if !self.tables.contains(&entry) {
+            self.tables.push(entry);
+        }
+    }
+
+    fn add_statement(&mut self, statement: &Statement) {
+        match statement {
+            // Temporary tables are tracked by the session overlay
+            Statement::CreateTable {
+                temporary: true, ..
+            }
+            | Statement::Drop {
+                temporary: true, ..
+            } => {}
+            Statement::CreateTable { name, .. } | Statement::CreateView { name, .. } => {
+                self.add_table(name)
+            }
+            Statement::CreateIndex { table_name, .. } => self.add_table(table_name),
+            Statement::AlterTable {
+                name, operations, ..
+            } => {
+                self.add_table(name);
+                for operation in operations {
+                    if let AlterTableOperation::RenameTable { table_name } = operation {
+                        self.add_table(table_name);
+                    }
+                }
+            }
+            Statement::Drop {
+                object_type: ObjectType::Table | ObjectType::View,
+                names,
+                ..
+            } => names.iter().for_each(|name| self.add_table(name)),
+            Statement::Drop {
+                object_type: ObjectType::Schema,
+                ..
+            }
+            | Statement::CreateDatabase { .. }
+            | Statement::CreateSchema {
+                schema_name: SchemaName::Simple(_),
+                ..
+            } => self.databases = true,
+            Statement::Use { db_name } => self.use_database = Some(db_name.value.clone()),
+            _ => {}
+        }
+    }
+
+    /// Fallback for MySQL statements the parser does not support, such as
+    /// `RENAME TABLE` and `DROP INDEX ... ON`
+    fn add_tokens(&mut self, sql: &str) {
+        let dialect = MySqlDialect {};
+        let Ok(tokens) = Tokenizer::new(&dialect, sql).tokenize() else {
+            return;
+        };
+        let tokens: Vec<Token> = tokens
+            .into_iter()
+            .filter(|t| !matches!(t, Token::Whitespace(_)))
+            .collect();
+        let keyword = |i: usize| match tokens.get(i) {
+            Some(Token::Word(Word {
+                keyword,
+                quote_style: None,
+                ..
+            })) => *keyword,
+            _ => Keyword::NoKeyword,
+        };
+
+        match (keyword(0), keyword(1)) {
+            (Keyword::RENAME, Keyword::TABLE) => {
+                // RENAME TABLE a TO b [, c TO d ...]
+                let mut i = 2;
+                while let Some((database, table, next)) = object_name_at(&tokens, i) {
+                    self.add_name(database.as_deref(), &table);
+                    i = match tokens.get(next) {
+                        Some(Token::Word(w)) if w.keyword == Keyword::TO => next + 1,
+                        Some(Token::Comma) => next + 1,
+                        _ => break,
+                    };
+                }
+            }
+            (Keyword::DROP, Keyword::INDEX) => {
+                let on = (2..tokens.len()).find(|&i| keyword(i) == Keyword::ON);
+                match on.and_then(|i| object_name_at(&tokens, i + 1)) {
+                    Some((database, table, _)) => self.add_name(database.as_deref(), &table),
+                    None => self.reload_all = true,
+                }
+            }
+            (Keyword::DROP | Keyword::CREATE, Keyword::DATABASE | Keyword::SCHEMA) => {
+                self.databases = true
+            }
+            (Keyword::CREATE | Keyword::DROP | Keyword::ALTER | Keyword::RENAME, _) => {
+                self.reload_all = true
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Read a possibly qualified object name starting at token `i`,
+/// returning (database, name, index of the next token)
+fn object_name_at(tokens: &[Token], i: usize) -> Option<(Option<String>, String, usize)> {
+    let Some(Token::Word(first)) = tokens.get(i) else {
+        return None;
+    };
+    match (tokens.get(i + 1), tokens.get(i + 2)) {
+        (Some(Token::Period), Some(Token::Word(second))) => {
+            Some((Some(first.value.clone()), second.value.clone(), i + 3))
+        }
+        _ => Some((None, first.value.clone(), i + 1)),
+    }
+}
+
+#[cfg(test)]
+#[path = "./schema_change_tests.rs"]
+mod schema_change_tests;
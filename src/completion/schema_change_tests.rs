@@ -0,0 +1,97 @@
+use super::*;
+
+fn tables(sql: &str) -> Vec<(Option<String>, String)> {
+    SchemaChange::classify(sql).unwrap().tables
+}
+
+fn name(database: Option<&str>, table: &str) -> (Option<String>, String) {
+    (database.map(|d| d.to_string()), table.to_string())
+}
+
+#[test]
+fn test_rename_table() {
+    assert_eq!(
+        tables("RENAME TABLE users TO members, shop.a TO shop.b"),
+        vec![
+            name(None, "users"),
+            name(None, "members"),
+            name(Some("shop"), "a"),
+            name(Some("shop"), "b"),
+        ]
+    );
+    assert_eq!(
+        tables("ALTER TABLE users RENAME TO members"),
+        vec![name(None, "users"), name(None, "members")]
+    );
+}
+
+#[test]
+fn test_views() {
+    assert_eq!(
+        tables("CREATE OR REPLACE VIEW v_users AS SELECT id FROM users"),
+        vec![name(None, "v_users")]
+    );
+    assert_eq!(
+        tables("DROP VIEW IF EXISTS shop.v_orders"),
+        vec![name(Some("shop"), "v_orders")]
+    );
+}
+
+#[test]
+fn test_indexes() {
+    assert_eq!(
+        tables("CREATE INDEX idx_name ON users (name)"),
+        vec![name(None, "users")]
+    );
+    assert_eq!(
+        tables("DROP INDEX idx_name ON shop.users"),
+        vec![name(Some("shop"), "users")]
+    );
+}
+
+#[test]
+fn test_databases() {
+    for sql in [
+        "DROP DATABASE shop",
+        "CREATE DATABASE shop",
+        "DROP SCHEMA shop",
+    ] {
+        let change = SchemaChange::classify(sql).unwrap();
+        assert!(change.databases, "{}", sql);
+        assert!(change.tables.is_empty());
+    }
+}
+
+#[test]
+fn test_use_and_non_ddl() {
+    assert_eq!(
+        SchemaChange::classify("USE shop").unwrap().use_database,
+        Some("shop".to_string())
+    );
+    assert_eq!(SchemaChange::classify("SELECT * FROM users"), None);
+    assert_eq!(
+        SchemaChange::classify("CREATE TEMPORARY TABLE t (id INT)"),
+        None
+    );
+}
+
+#[test]
+fn test_statements_wrapped_in_comments() {
+    assert_eq!(
+        tables("/* migrate */ RENAME TABLE -- old name\n users TO members"),
+        vec![name(None, "users"), name(None, "members")]
+    );
+    assert_eq!(
+        tables("-- cleanup\nDROP /* really */ TABLE users /* done */"),
+        vec![name(None, "users")]
+    );
+}
+
+#[test]
+fn test_multiple_statements_merge_into_one_change() {
+    let change =
+        SchemaChange::classify("CREATE TABLE a (id INT); DROP TABLE a; DROP DATABASE old").unwrap();
+    assert_eq!(change.tables, vec![name(None, "a")]);
+    assert!(change.databases);
+    assert!(!change.reload_all);
+}
@@ -0,0 +1,221 @@
+/*!
+ * Special command registry
+ *
+ * Names and descriptions of the backslash commands, offered when completing
+ * a word that starts with `\`, and the arguments they need
+ */
+
+pub struct SpecialCommand {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// What the first argument names, for completion
+    pub argument: Argument,
+    pub arity: Arity,
+}
+
+/// Whether a command can run without arguments
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Arity {
+    /// Runs bare or with optional arguments
+    Optional,
+    /// Needs arguments, described by the synopsis shown in its usage line
+    Required(&'static str),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Argument {
+    None,
+    Database,
+    Table,
+    /// One of the listed subcommands, e.g. `columns` of `\\show columns`
+    Subcommand(&'static [&'static str]),
+}
+
+const fn command(name: &'static str, description: &'static str) -> SpecialCommand {
+    SpecialCommand {
+        name,
+        description,
+        argument: Argument::None,
+        arity: Arity::Optional,
+    }
+}
+
+const fn with_argument(
+    name: &'static str,
+    description: &'static str,
+    argument: Argument,
+) -> SpecialCommand {
+    SpecialCommand {
+        name,
+        description,
+        argument,
+        arity: Arity::Optional,
+    }
+}
+
+impl SpecialCommand {
+    const fn requires(mut self, synopsis: &'static str) -> Self {
+        self.arity = Arity::Required(synopsis);
+        self
+    }
+}
+
+/// Every backslash command handled by the CLI, by its long name
+pub const SPECIAL_COMMANDS: &[SpecialCommand] = &[
+    command("\\admin", "List or run admin task templates"),
+    command("\\alias", "List or define aliases"),
+    with_argument(
+        "\\alter-table",
+        "Change columns and indexes from a menu",
+        Argument::Table,
+    )
+    .requires("<table>"),
+    command(
+        "\\charset-check",
+        "Check that the session character sets agree",
+    ),
+    with_argument(
+        "\\check",
+        "Check a table and report fragmentation",
+        Argument::Table,
+    )
+    .requires("<table>"),
+    command("\\clear", "Clear the current input statement"),
+    command("\\clear-cache", "Flush cached SELECT results"),
+    with_argument(
+        "\\clone-row",
+        "Put an INSERT copying a row at the prompt",
+        Argument::Table,
+    )
+    .requires("<table> <where> [--first]"),
+    with_argument(
+        "\\config",
+        "Show the effective configuration and its sources",
+        Argument::Subcommand(&["show"]),
+    )
+    .requires("show"),
+    command(
+        "\\create-table",
+        "Build a CREATE TABLE statement step by step",
+    ),
+    command("\\databases", "List databases"),
+    with_argument(
+        "\\describe+",
+        "Describe a table with indexes and foreign keys",
+        Argument::Table,
+    )
+    .requires("<table>"),
+    command("\\edit-row", "Edit the single row of the last SELECT"),
+    command("\\export-metadata", "Write cached schema metadata as JSON").requires("<path>"),
+    command(
+        "\\export-xlsx",
+        "Write the next result to an Excel workbook",
+    )
+    .requires("<path>"),
+    command("\\find", "Search databases, tables and columns").requires("<pattern>"),
+    command("\\help", "Display help"),
+    command("\\hist", "Search history or run an entry")
+        .requires("grep <pattern> | run <n> | stats"),
+    command("\\import-csv", "Load a CSV file into a table")
+        .requires("<path> <table> [--skip-rows=N] [--delimiter=C]"),
+    command("\\import-json", "Load a JSON array of objects into a table")
+        .requires("<path> <table>"),
+    with_argument(
+        "\\index-stats",
+        "Show index cardinality and usage",
+        Argument::Table,
+    )
+    .requires("<table>"),
+    command("\\innodb-status", "Summarize SHOW ENGINE INNODB STATUS"),
+    command("\\load-settings", "Load settings from a file"),
+    command("\\marker", "Mark statement summaries with ✓/✗").requires("on|off"),
+    command("\\maxtime", "Show or set a time limit for SELECTs"),
+    with_argument(
+        "\\protect",
+        "List or add protected databases",
+        Argument::Database,
+    ),
+    command("\\pset", "Change a setting psql-style"),
+    command("\\quit", "Quit"),
+    command("\\reconnect", "Reconnect to the server"),
+    command("\\record", "Append executed statements to a file"),
+    command("\\replay", "Run a recorded file").requires("<file> [--delay ms] [--step] [--force]"),
+    command("\\save-settings", "Save settings to a file"),
+    command("\\set", "Show or change settings"),
+    with_argument(
+        "\\show",
+        "Show full column details of a table",
+        Argument::Subcommand(&["columns"]),
+    )
+    .requires("columns <table>"),
+    command("\\source", "Execute an SQL script file").requires("<file>"),
+    command("\\sqlmode", "Show or change sql_mode flags"),
+    command("\\status", "Get status information from the server"),
+    command("\\tables", "List tables in the current database"),
+    command("\\tail-generallog", "Follow a general query log").requires("[--type=T] <path>"),
+    command("\\tail-slowlog", "Follow a slow query log").requires("<path>"),
+    with_argument("\\u", "Use a database", Argument::Database).requires("<database>"),
+    command("\\unalias", "Remove an alias").requires("<name>"),
+    command("\\useids", "Collect a column of the last result for ${ids}").requires("<column>"),
+    command("\\var", "List or set variables"),
+    command("\\vertical", "Show the last result vertically"),
+];
+
+/// Short forms of commands that take arguments
+const ALIASES: &[(&str, &str)] = &[("\\d+", "\\describe+"), ("\\.", "\\source")];
+
+/// The command whose first argument is being typed, e.g. `\u` for `\u sa`
+pub fn argument_command(line: &str) -> Option<&'static SpecialCommand> {
+    let line = line.trim_start();
+    let (name, argument) = line.split_once(char::is_whitespace)?;
+    if !name.starts_with('\\') || argument.trim_start().contains(char::is_whitespace) {
+        return None;
+    }
+    find(name).filter(|command| command.argument != Argument::None)
+}
+
+/// A command by its name or short form
+pub fn find(name: &str) -> Option<&'static SpecialCommand> {
+    let name = ALIASES
+        .iter()
+        .find(|(alias, _)| alias.eq_ignore_ascii_case(name))
+        .map_or(name, |(_, command)| command);
+    SPECIAL_COMMANDS
+        .iter()
+        .find(|command| command.name.eq_ignore_ascii_case(name))
+}
+
+/// Tidy a typed backslash command: trailing semicolons and whitespace are
+/// dropped and the name is separated from its arguments by a single space,
+/// so `\u  mydb ;` becomes `\u mydb`
+pub fn normalize(line: &str) -> String {
+    let line = line
+        .trim()
+        .trim_end_matches(|c: char| c == ';' || c.is_whitespace());
+    match line.split_once(char::is_whitespace) {
+        Some((name, arguments)) => format!("{} {}", name, arguments.trim_start()),
+        None => line.to_string(),
+    }
+}
+
+/// The usage line of a normalized command that needs arguments but has none
+pub fn missing_arguments(command: &str) -> Option<String> {
+    let name = ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == command)
+        .map_or(command, |(_, name)| name);
+    match SPECIAL_COMMANDS.iter().find(|c| c.name == name)?.arity {
+        Arity::Required(synopsis) => Some(format!("Usage: {} {}", command, synopsis)),
+        Arity::Optional => None,
+    }
+}
+
+/// Whether the input up to the caret is a backslash command name being typed
+pub fn is_command_name(line: &str) -> bool {
+    let line = line.trim_start();
+    line.starts_with('\\') && !line.contains(char::is_whitespace)
+}
+
+#[cfg(test)]
+#[path = "./special_commands_tests.rs"]
+mod special_commands_tests;
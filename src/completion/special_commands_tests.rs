@@ -0,0 +1,47 @@
+use super::*;
+
+#[test]
+fn test_normalize_messy_commands() {
+    assert_eq!(normalize("\\u  mydb ;"), "\\u mydb");
+    assert_eq!(normalize("\\d;"), "\\d");
+    assert_eq!(normalize("\\q "), "\\q");
+    assert_eq!(normalize("  \\d+\tusers;;"), "\\d+ users");
+    // Whitespace inside the arguments is kept
+    assert_eq!(
+        normalize("\\alias t =  SHOW TABLES ;"),
+        "\\alias t =  SHOW TABLES"
+    );
+}
+
+#[test]
+fn test_missing_arguments_from_registry() {
+    assert_eq!(
+        missing_arguments(&normalize("\\u ;")).as_deref(),
+        Some("Usage: \\u <database>")
+    );
+    assert_eq!(
+        missing_arguments("\\d+").as_deref(),
+        Some("Usage: \\d+ <table>")
+    );
+    assert_eq!(missing_arguments("\\u mydb"), None);
+    assert_eq!(missing_arguments("\\tables"), None);
+    assert_eq!(missing_arguments("\\record"), None);
+    // Unknown names are left to the alias table
+    assert_eq!(missing_arguments("\\nope"), None);
+}
+
+#[test]
+fn test_subcommand_commands_complete_their_argument() {
+    assert_eq!(
+        argument_command("\\show col").map(|command| command.argument),
+        Some(Argument::Subcommand(&["columns"]))
+    );
+    assert_eq!(
+        argument_command("\\config ").map(|command| command.name),
+        Some("\\config")
+    );
+    assert_eq!(
+        missing_arguments("\\show").as_deref(),
+        Some("Usage: \\show columns <table>")
+    );
+}
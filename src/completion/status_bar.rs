@@ -0,0 +1,79 @@
+/*!
+ * Completion status bar
+ *
+ * Shows completion descriptions on the bottom terminal line instead of
+ * inline in the completion list
+ */
+
+use crossterm::cursor::{MoveTo, RestorePosition, SavePosition};
+use crossterm::style::Print;
+use crossterm::terminal::{self, Clear, ClearType};
+use crossterm::QueueableCommand;
+use std::io::{self, Write};
+use std::sync::Mutex;
+
+/// Bottom-line status bar for completion descriptions
+#[derive(Default)]
+pub struct StatusBar {
+    /// Input line at the time the bar was drawn, while it is visible
+    drawn_for: Mutex<Option<String>>,
+}
+
+impl StatusBar {
+    /// Draw `text` on the last terminal line for the given input line
+    pub fn show(&self, line: &str, text: &str) {
+        if let Ok(mut drawn_for) = self.drawn_for.lock() {
+            if draw(text).is_ok() {
+                *drawn_for = Some(line.to_string());
+            }
+        }
+    }
+
+    /// Clear the bar once the input line changed, e.g. after a completion was selected
+    pub fn clear_if_changed(&self, line: &str) {
+        let changed = self
+            .drawn_for
+            .lock()
+            .is_ok_and(|drawn_for| drawn_for.as_deref().is_some_and(|drawn| drawn != line));
+        if changed {
+            self.clear();
+        }
+    }
+
+    /// Clear the bar if it is visible
+    pub fn clear(&self) {
+        if let Ok(mut drawn_for) = self.drawn_for.lock() {
+            if drawn_for.take().is_some() {
+                let _ = draw("");
+            }
+        }
+    }
+}
+
+/// Format completion descriptions for the bar: a single match shows its
+/// description, several matches show `text: description` pairs
+pub fn describe(entries: &[(String, String)]) -> String {
+    match entries {
+        [] => String::new(),
+        [(text, description)] => format!("{} - {}", text, description),
+        _ => entries
+            .iter()
+            .map(|(text, description)| format!("{}: {}", text, description))
+            .collect::<Vec<_>>()
+            .join("  |  "),
+    }
+}
+
+/// Write text on the last line, keeping the cursor where it was
+fn draw(text: &str) -> io::Result<()> {
+    let (columns, rows) = terminal::size()?;
+    let text: String = text.chars().take(columns as usize).collect();
+
+    let mut out = io::stdout();
+    out.queue(SavePosition)?
+        .queue(MoveTo(0, rows.saturating_sub(1)))?
+        .queue(Clear(ClearType::CurrentLine))?
+        .queue(Print(text))?
+        .queue(RestorePosition)?;
+    out.flush()
+}
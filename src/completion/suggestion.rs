@@ -4,6 +4,9 @@
  * Defines data structures and classification system for completion suggestions
  */
 
+use crate::commands::literal;
+use std::cmp::Ordering;
+
 /// Smart suggestion item
 #[derive(Clone, Debug)]
 pub struct Suggestion {
@@ -15,6 +18,18 @@ pub struct Suggestion {
     pub category: SuggestionCategory,
     /// Relevance score (0-100)
     pub relevance: u8,
+    /// Accepting it finishes the statement, so the delimiter may be appended
+    pub completes_statement: bool,
+}
+
+/// Whether an identifier needs backticks: anything but letters, digits, `_` and `$`,
+/// or digits only
+pub fn needs_quoting(name: &str) -> bool {
+    name.is_empty()
+        || name.chars().all(|c| c.is_ascii_digit())
+        || !name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '$')
 }
 
 /// Suggestion category enum
@@ -32,6 +47,8 @@ pub enum SuggestionCategory {
     Function,
     /// Command
     Command,
+    /// Literal value such as a format string, inserted as is
+    Literal,
 }
 
 impl Suggestion {
@@ -47,7 +64,22 @@ impl Suggestion {
             description,
             category,
             relevance: relevance.min(100), // Ensure not exceeding 100
+            completes_statement: false,
+        }
+    }
+
+    /// Mark the suggestion as the last word of a statement, e.g. the database of `USE`
+    pub fn completing_statement(mut self) -> Self {
+        self.completes_statement = true;
+        self
+    }
+
+    /// Append a note such as `(required)` to the description
+    pub fn with_note(mut self, note: Option<String>) -> Self {
+        if let Some(note) = note {
+            self.description = format!("{} {}", self.description, note);
         }
+        self
     }
 
     /// Create database suggestion
@@ -70,6 +102,20 @@ impl Suggestion {
         )
     }
 
+    /// Create a `db`.`table` qualified table suggestion
+    pub fn qualified_table(name: String, database: &str, relevance: u8) -> Self {
+        Self::new(
+            format!(
+                "{}.{}",
+                literal::quote_identifier(database),
+                literal::quote_identifier(&name)
+            ),
+            format!("Table: {} (in {} database)", name, database),
+            SuggestionCategory::Table,
+            relevance,
+        )
+    }
+
     /// Create column suggestion
     pub fn column(name: String, table: &str, relevance: u8) -> Self {
         Self::new(
@@ -95,13 +141,41 @@ impl Suggestion {
         Self::new(name, description, SuggestionCategory::Function, relevance)
     }
 
+    /// Create literal value suggestion
+    pub fn literal(text: String, description: String, relevance: u8) -> Self {
+        Self::new(text, description, SuggestionCategory::Literal, relevance)
+    }
+
     /// Create command suggestion
     pub fn command(command: String, description: String, relevance: u8) -> Self {
         Self::new(command, description, SuggestionCategory::Command, relevance)
     }
+
+    /// Ranking order: relevance descending, then category priority, then name
+    pub fn cmp_rank(&self, other: &Self) -> Ordering {
+        other
+            .relevance
+            .cmp(&self.relevance)
+            .then_with(|| self.category.priority().cmp(&other.category.priority()))
+            .then_with(|| self.text.to_lowercase().cmp(&other.text.to_lowercase()))
+            .then_with(|| self.text.cmp(&other.text))
+    }
 }
 
 impl SuggestionCategory {
+    /// Tie-breaking priority among equally relevant suggestions (lower first)
+    pub fn priority(&self) -> u8 {
+        match self {
+            SuggestionCategory::Column => 0,
+            SuggestionCategory::Table => 1,
+            SuggestionCategory::Database => 2,
+            SuggestionCategory::Function => 3,
+            SuggestionCategory::SqlKeyword => 4,
+            SuggestionCategory::Command => 5,
+            SuggestionCategory::Literal => 6,
+        }
+    }
+
     /// Get emoji icon for category
     pub fn icon(&self) -> &'static str {
         match self {
@@ -111,6 +185,7 @@ impl SuggestionCategory {
             SuggestionCategory::SqlKeyword => "🔵",
             SuggestionCategory::Function => "⚡",
             SuggestionCategory::Command => "⚙️",
+            SuggestionCategory::Literal => "🔤",
         }
     }
 }
@@ -0,0 +1,431 @@
+/*!
+ * Configuration file
+ *
+ * Reads and writes `~/.config/mysql-cli-rust/config.toml`, and reads the
+ * `[mysql-cli-rust]` section of the MySQL option files (`~/.my.cnf`, or the
+ * files chosen with `--defaults-file` / `--defaults-extra-file`). Runtime
+ * settings live in their own file, `~/.mysql_cli_settings.toml`; `Provenance`
+ * records which file, variable or flag set each value for `\config show`
+ */
+
+use anyhow::{anyhow, Result};
+use serde::de::{self, Deserialize, Deserializer, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use toml::Spanned;
+
+/// Sections of the configuration file
+const SECTIONS: [&str; 2] = ["aliases", "admin"];
+
+/// Which option and configuration files are read, from `--no-defaults`,
+/// `--defaults-file` and `--defaults-extra-file`
+#[derive(Debug, Clone, Default)]
+pub struct OptionFiles {
+    /// Read no option, configuration or settings files
+    pub no_defaults: bool,
+    /// Read only this option file instead of the default ones
+    pub defaults_file: Option<PathBuf>,
+    /// Read this option file after the others
+    pub extra_file: Option<PathBuf>,
+}
+
+impl OptionFiles {
+    /// Option files in reading order; later files override earlier ones
+    pub fn paths(&self) -> Vec<PathBuf> {
+        if self.no_defaults {
+            return Vec::new();
+        }
+        let mut paths: Vec<PathBuf> = match &self.defaults_file {
+            Some(path) => vec![path.clone()],
+            None => my_cnf_path().into_iter().collect(),
+        };
+        paths.extend(self.extra_file.clone());
+        paths
+    }
+
+    /// Fail when a file given on the command line doesn't exist
+    pub fn check(&self) -> Result<()> {
+        for path in self.defaults_file.iter().chain(&self.extra_file) {
+            if !path.is_file() {
+                return Err(anyhow!(
+                    "Could not open required defaults file: {}",
+                    path.display()
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+static OPTION_FILES: OnceLock<OptionFiles> = OnceLock::new();
+
+/// Choose the option files for the rest of the run; called once at startup
+pub fn set_option_files(files: OptionFiles) {
+    let _ = OPTION_FILES.set(files);
+}
+
+fn option_files() -> OptionFiles {
+    OPTION_FILES.get().cloned().unwrap_or_default()
+}
+
+/// Whether configuration files are read at all (false with `--no-defaults`)
+pub fn defaults_enabled() -> bool {
+    !option_files().no_defaults
+}
+
+/// Get the user's home directory
+pub fn home_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .map(PathBuf::from)
+}
+
+/// Get the configuration directory
+pub fn config_dir() -> Option<PathBuf> {
+    home_dir().map(|home| home.join(".config").join("mysql-cli-rust"))
+}
+
+/// Get the configuration file path
+pub fn config_path() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("config.toml"))
+}
+
+/// Get the default settings file path used by `\save-settings` and at startup
+pub fn settings_path() -> Option<PathBuf> {
+    home_dir().map(|home| home.join(".mysql_cli_settings.toml"))
+}
+
+/// Get the history file path, `~/.mysql_cli_history`
+pub fn history_path() -> Option<PathBuf> {
+    home_dir().map(|home| home.join(".mysql_cli_history"))
+}
+
+/// Load the configuration file as a TOML table (empty when missing or with `--no-defaults`)
+pub fn load() -> Result<toml::Table> {
+    let Some(path) = config_path() else {
+        return Ok(toml::Table::new());
+    };
+    if !path.exists() || !defaults_enabled() {
+        return Ok(toml::Table::new());
+    }
+
+    let content = fs::read_to_string(&path)?;
+    content
+        .parse::<toml::Table>()
+        .map_err(|e| anyhow!("{}: {}", path.display(), e))
+}
+
+/// The configuration file
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Config {
+    /// `[aliases]`: expansions of `\name` shortcuts
+    pub aliases: BTreeMap<String, String>,
+    /// `[admin]`: statement templates added to or replacing the built-in ones
+    pub admin: BTreeMap<String, String>,
+}
+
+/// An entry of the configuration file that was skipped
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub file: PathBuf,
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.file.display(), self.line, self.message)
+    }
+}
+
+impl Config {
+    /// Load the configuration file (empty when missing or with `--no-defaults`),
+    /// with diagnostics for the entries that were skipped
+    pub fn load() -> Result<(Self, Vec<Diagnostic>)> {
+        match config_path() {
+            Some(path) if path.exists() && defaults_enabled() => {
+                let content = fs::read_to_string(&path)?;
+                Self::parse(&content, &path)
+            }
+            _ => Ok((Self::default(), Vec::new())),
+        }
+    }
+
+    /// Parse configuration text; syntax errors fail, while unknown keys and
+    /// values of the wrong type are skipped with a diagnostic
+    pub fn parse(content: &str, file: &Path) -> Result<(Self, Vec<Diagnostic>)> {
+        let raw: BTreeMap<Spanned<String>, Spanned<RawValue>> =
+            toml::from_str(content).map_err(|e| anyhow!("{}: {}", file.display(), e))?;
+
+        let mut config = Self::default();
+        let mut diagnostics = Vec::new();
+        let mut skip = |offset: usize, message: String| {
+            diagnostics.push(Diagnostic {
+                file: file.to_path_buf(),
+                line: line_at(content, offset),
+                message,
+            })
+        };
+        for (key, value) in raw {
+            let section = match key.get_ref().as_str() {
+                "aliases" => &mut config.aliases,
+                "admin" => &mut config.admin,
+                name => {
+                    skip(
+                        key.span().start,
+                        format!(
+                            "unknown key `{}` (expected one of: {})",
+                            name,
+                            SECTIONS.join(", ")
+                        ),
+                    );
+                    continue;
+                }
+            };
+            let entries = match value.get_ref() {
+                RawValue::Table(entries) => entries,
+                RawValue::Other(kind) => {
+                    skip(
+                        key.span().start,
+                        format!("`{}` must be a table, found {}", key.get_ref(), kind),
+                    );
+                    continue;
+                }
+            };
+            for (name, entry) in entries {
+                match entry.get_ref() {
+                    toml::Value::String(text) => {
+                        section.insert(name.get_ref().clone(), text.clone());
+                    }
+                    other => skip(
+                        entry.span().start,
+                        format!(
+                            "`{}.{}` must be a string, found {}",
+                            key.get_ref(),
+                            name.get_ref(),
+                            other.type_str()
+                        ),
+                    ),
+                }
+            }
+        }
+        diagnostics.sort_by_key(|diagnostic| diagnostic.line);
+        Ok((config, diagnostics))
+    }
+}
+
+/// A top-level value of the configuration file; tables keep the spans of their entries
+enum RawValue {
+    Table(Vec<(Spanned<String>, Spanned<toml::Value>)>),
+    Other(&'static str),
+}
+
+impl<'de> Deserialize<'de> for RawValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(RawValueVisitor)
+    }
+}
+
+struct RawValueVisitor;
+
+impl<'de> Visitor<'de> for RawValueVisitor {
+    type Value = RawValue;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a TOML value")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<RawValue, A::Error> {
+        let mut entries = Vec::new();
+        while let Some(entry) = map.next_entry()? {
+            entries.push(entry);
+        }
+        Ok(RawValue::Table(entries))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<RawValue, A::Error> {
+        while seq.next_element::<IgnoredAny>()?.is_some() {}
+        Ok(RawValue::Other("array"))
+    }
+
+    fn visit_str<E: de::Error>(self, _: &str) -> Result<RawValue, E> {
+        Ok(RawValue::Other("string"))
+    }
+
+    fn visit_i64<E: de::Error>(self, _: i64) -> Result<RawValue, E> {
+        Ok(RawValue::Other("integer"))
+    }
+
+    fn visit_u64<E: de::Error>(self, _: u64) -> Result<RawValue, E> {
+        Ok(RawValue::Other("integer"))
+    }
+
+    fn visit_f64<E: de::Error>(self, _: f64) -> Result<RawValue, E> {
+        Ok(RawValue::Other("float"))
+    }
+
+    fn visit_bool<E: de::Error>(self, _: bool) -> Result<RawValue, E> {
+        Ok(RawValue::Other("boolean"))
+    }
+}
+
+/// 1-based line number of a byte offset
+fn line_at(content: &str, offset: usize) -> usize {
+    content[..offset.min(content.len())].matches('\n').count() + 1
+}
+
+/// Where the effective value of a setting comes from
+#[derive(Debug, Clone, PartialEq)]
+pub enum Source {
+    Default,
+    File(PathBuf),
+    Env(&'static str),
+    Flag(&'static str),
+    /// `\set` and other commands typed at the prompt
+    Session,
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Source::Default => write!(f, "default"),
+            Source::File(path) => write!(f, "{}", path.display()),
+            Source::Env(name) => write!(f, "${}", name),
+            Source::Flag(flag) => write!(f, "{}", flag),
+            Source::Session => write!(f, "session"),
+        }
+    }
+}
+
+/// A setting name and value with where the value comes from
+pub type Annotated = (String, String, Source);
+
+/// The source of each value, layered as they are applied: defaults, then files,
+/// environment and flags, then the session; the last one to set a value wins
+#[derive(Debug, Clone, Default)]
+pub struct Provenance {
+    sources: BTreeMap<String, Source>,
+}
+
+impl Provenance {
+    /// Record that `source` set `name`
+    pub fn record(&mut self, name: &str, source: Source) {
+        self.sources.insert(name.to_string(), source);
+    }
+
+    /// Where `name` was set last; `Default` when nothing set it
+    pub fn source(&self, name: &str) -> Source {
+        self.sources.get(name).cloned().unwrap_or(Source::Default)
+    }
+
+    /// Values paired with their sources
+    pub fn annotate<N: AsRef<str>>(
+        &self,
+        entries: impl IntoIterator<Item = (N, String)>,
+    ) -> Vec<Annotated> {
+        entries
+            .into_iter()
+            .map(|(name, value)| {
+                let source = self.source(name.as_ref());
+                (name.as_ref().to_string(), value, source)
+            })
+            .collect()
+    }
+}
+
+/// Format sections of annotated values for `\config show`, with the source of
+/// each value in an aligned `#` comment
+pub fn format_effective(sections: &[(&str, Vec<Annotated>)]) -> String {
+    let mut text = String::new();
+    for (section, entries) in sections {
+        if !text.is_empty() {
+            text.push('\n');
+        }
+        text.push_str(&format!("[{}]\n", section));
+        let assignments: Vec<String> = entries
+            .iter()
+            .map(|(name, value, _)| format!("{} = {}", name, value))
+            .collect();
+        let width = assignments
+            .iter()
+            .map(|a| a.chars().count())
+            .max()
+            .unwrap_or(0);
+        for (assignment, (_, _, source)) in assignments.iter().zip(entries) {
+            let padding = width - assignment.chars().count();
+            text.push_str(&format!(
+                "{}{}  # {}\n",
+                assignment,
+                " ".repeat(padding),
+                source
+            ));
+        }
+    }
+    text
+}
+
+/// Replace a section of string values in the configuration file, keeping the rest
+pub fn save_string_section(section: &str, values: &BTreeMap<String, String>) -> Result<PathBuf> {
+    let path = config_path().ok_or_else(|| anyhow!("Cannot determine home directory"))?;
+    let mut config = load()?;
+
+    let table: toml::Table = values
+        .iter()
+        .map(|(key, value)| (key.clone(), toml::Value::String(value.clone())))
+        .collect();
+    config.insert(section.to_string(), toml::Value::Table(table));
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(&path, toml::to_string(&config)?)?;
+    Ok(path)
+}
+
+/// Get the MySQL option file path
+pub fn my_cnf_path() -> Option<PathBuf> {
+    home_dir().map(|home| home.join(".my.cnf"))
+}
+
+/// Read the `name = value` options of a section of the option files, in reading
+/// order (empty when none exist); dashes in names are read as underscores
+pub fn my_cnf_section(section: &str) -> Result<Vec<(String, String)>> {
+    let mut options = Vec::new();
+    for path in option_files().paths() {
+        if !path.exists() {
+            continue;
+        }
+        let content =
+            fs::read_to_string(&path).map_err(|e| anyhow!("{}: {}", path.display(), e))?;
+        options.extend(parse_option_section(&content, section));
+    }
+    Ok(options)
+}
+
+/// Options of one `[section]` of an option file, in file order
+fn parse_option_section(content: &str, section: &str) -> Vec<(String, String)> {
+    let mut in_section = false;
+    let mut options = Vec::new();
+    for line in content.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            in_section = name.trim() == section;
+            continue;
+        }
+        if in_section {
+            let (name, value) = line.split_once('=').unwrap_or((line, ""));
+            let value = value.trim().trim_matches(|c| c == '"' || c == '\'');
+            options.push((name.trim().replace('-', "_"), value.to_string()));
+        }
+    }
+    options
+}
+
+#[cfg(test)]
+#[path = "./config_tests.rs"]
+mod config_tests;
@@ -0,0 +1,143 @@
+use super::*;
+
+#[test]
+fn test_option_file_order() {
+    // `~/.my.cnf`, or nothing without a home directory
+    let defaults = OptionFiles::default().paths();
+    assert_eq!(defaults, my_cnf_path().into_iter().collect::<Vec<_>>());
+
+    let files = OptionFiles {
+        defaults_file: Some(PathBuf::from("/srv/app/my.cnf")),
+        extra_file: Some(PathBuf::from("/srv/app/extra.cnf")),
+        ..Default::default()
+    };
+    assert_eq!(
+        files.paths(),
+        vec![
+            PathBuf::from("/srv/app/my.cnf"),
+            PathBuf::from("/srv/app/extra.cnf")
+        ]
+    );
+
+    let files = OptionFiles {
+        no_defaults: true,
+        ..Default::default()
+    };
+    assert!(files.paths().is_empty());
+}
+
+#[test]
+fn test_missing_given_file_is_an_error() {
+    let files = OptionFiles {
+        extra_file: Some(PathBuf::from("/nonexistent/extra.cnf")),
+        ..Default::default()
+    };
+    assert!(files.check().is_err());
+    assert!(OptionFiles::default().check().is_ok());
+}
+
+#[test]
+fn test_parse_option_section() {
+    let content = "[client]\nuser = app\n\n[mysql-cli-rust]\n# comment\nprotected-databases = \"prod, billing\"\n";
+    assert_eq!(
+        parse_option_section(content, "mysql-cli-rust"),
+        vec![(
+            "protected_databases".to_string(),
+            "prod, billing".to_string()
+        )]
+    );
+}
+
+#[test]
+fn test_parse_config_sections() {
+    let content = "[aliases]\ntables = \"SHOW TABLES\"\n\n[admin]\nlock-user = \"ALTER USER {user} ACCOUNT LOCK\"\n";
+    let (config, diagnostics) = Config::parse(content, Path::new("config.toml")).unwrap();
+    assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+    assert_eq!(config.aliases["tables"], "SHOW TABLES");
+    assert_eq!(config.admin["lock-user"], "ALTER USER {user} ACCOUNT LOCK");
+}
+
+#[test]
+fn test_parse_config_reports_unknown_keys_and_types_with_lines() {
+    let content = "pager = \"less\"\nadmin = [1, 2]\n\n[alias]\nt = \"SHOW TABLES\"\n\n[aliases]\nok = \"SELECT 1\"\nbad = 3\n";
+    let (config, diagnostics) = Config::parse(content, Path::new("config.toml")).unwrap();
+    assert_eq!(config.aliases.len(), 1);
+    assert_eq!(config.aliases["ok"], "SELECT 1");
+    assert!(config.admin.is_empty());
+
+    let lines: Vec<usize> = diagnostics.iter().map(|d| d.line).collect();
+    assert_eq!(lines, vec![1, 2, 4, 9]);
+    assert_eq!(
+        diagnostics[0].to_string(),
+        "config.toml:1: unknown key `pager` (expected one of: aliases, admin)"
+    );
+    assert_eq!(
+        diagnostics[1].message,
+        "`admin` must be a table, found array"
+    );
+    assert!(diagnostics[2].message.contains("`alias`"));
+    assert_eq!(
+        diagnostics[3].message,
+        "`aliases.bad` must be a string, found integer"
+    );
+}
+
+#[test]
+fn test_parse_config_syntax_error_fails() {
+    let error = Config::parse("[aliases\n", Path::new("config.toml")).unwrap_err();
+    assert!(error.to_string().starts_with("config.toml: "));
+}
+
+#[test]
+fn test_provenance_last_source_wins() {
+    let mut provenance = Provenance::default();
+    provenance.record("marker", Source::File(PathBuf::from("settings.toml")));
+    provenance.record("column_names", Source::Flag("--no-column-names"));
+    provenance.record("marker", Source::Session);
+
+    assert_eq!(provenance.source("marker"), Source::Session);
+    assert_eq!(
+        provenance.source("column_names"),
+        Source::Flag("--no-column-names")
+    );
+    assert_eq!(provenance.source("prompt"), Source::Default);
+
+    let annotated =
+        provenance.annotate(vec![("marker", "1".to_string()), ("prompt", String::new())]);
+    assert_eq!(
+        annotated,
+        vec![
+            ("marker".to_string(), "1".to_string(), Source::Session),
+            ("prompt".to_string(), String::new(), Source::Default),
+        ]
+    );
+}
+
+#[test]
+fn test_format_effective_aligns_sources() {
+    let text = format_effective(&[
+        (
+            "settings",
+            vec![
+                ("marker".to_string(), "1".to_string(), Source::Session),
+                (
+                    "column_names".to_string(),
+                    "0".to_string(),
+                    Source::Flag("-ss"),
+                ),
+            ],
+        ),
+        (
+            "environment",
+            vec![(
+                "pager".to_string(),
+                "less".to_string(),
+                Source::Env("PAGER"),
+            )],
+        ),
+    ]);
+    assert_eq!(
+        text,
+        "[settings]\nmarker = 1        # session\ncolumn_names = 0  # -ss\n\n[environment]\npager = less  # $PAGER\n"
+    );
+}
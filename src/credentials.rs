@@ -0,0 +1,237 @@
+/*!
+ * Encrypted credential store
+ *
+ * Keeps passwords per profile in `~/.config/mysql-cli-rust/credentials`,
+ * like the login paths of `mysql_config_editor`. Each password is sealed with
+ * ChaCha20-Poly1305 under a key derived from a master passphrase (Argon2id)
+ * or kept in the OS keyring; profile names stay readable so `list` and
+ * lookups don't need the key.
+ */
+
+use crate::config;
+use crate::platform;
+use anyhow::{anyhow, Context, Result};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Nonce};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Service name of the OS keyring entry holding the store key
+const KEYRING_SERVICE: &str = "mysql-cli-rust";
+const KEYRING_USER: &str = "credentials";
+
+/// Where the key that seals the passwords comes from
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeySource {
+    /// Derived from a master passphrase typed when the store is used
+    Passphrase,
+    /// A random key saved in the OS keyring
+    Keyring,
+}
+
+/// A 256-bit key for sealing passwords
+pub struct StoreKey([u8; 32]);
+
+impl StoreKey {
+    /// Derive the key from a master passphrase with Argon2id
+    fn from_passphrase(passphrase: &str, salt: &[u8]) -> Result<Self> {
+        let mut key = [0u8; 32];
+        argon2::Argon2::default()
+            .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+            .map_err(|e| anyhow!("Cannot derive key from passphrase: {}", e))?;
+        Ok(Self(key))
+    }
+
+    /// The key saved in the OS keyring; a new one is created and saved when
+    /// `create` is set and none exists yet
+    pub fn from_keyring(create: bool) -> Result<Self> {
+        let entry = keyring::Entry::new(KEYRING_SERVICE, KEYRING_USER)?;
+        match entry.get_password() {
+            Ok(encoded) => {
+                let bytes = BASE64.decode(encoded.trim())?;
+                let bytes: [u8; 32] = bytes
+                    .try_into()
+                    .map_err(|_| anyhow!("Invalid credential key in the OS keyring"))?;
+                Ok(Self(bytes))
+            }
+            Err(keyring::Error::NoEntry) if create => {
+                let key = ChaCha20Poly1305::generate_key(&mut OsRng);
+                entry.set_password(&BASE64.encode(key))?;
+                Ok(Self(key.into()))
+            }
+            Err(keyring::Error::NoEntry) => {
+                Err(anyhow!("No credential key found in the OS keyring"))
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn cipher(&self) -> ChaCha20Poly1305 {
+        ChaCha20Poly1305::new(&self.0.into())
+    }
+}
+
+/// A sealed password
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Sealed {
+    nonce: String,
+    password: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct StoreFile {
+    key_source: Option<KeySource>,
+    /// Argon2 salt for passphrase-derived keys
+    salt: Option<String>,
+    #[serde(default)]
+    profiles: BTreeMap<String, Sealed>,
+}
+
+/// Passwords by profile, read from and saved to one file
+pub struct CredentialStore {
+    path: PathBuf,
+    file: StoreFile,
+}
+
+impl CredentialStore {
+    /// `~/.config/mysql-cli-rust/credentials`
+    pub fn default_path() -> Option<PathBuf> {
+        config::config_dir().map(|dir| dir.join("credentials"))
+    }
+
+    /// Open the store at `path`; a missing file is an empty store
+    pub fn open(path: &Path) -> Result<Self> {
+        let file = if path.exists() {
+            let content = fs::read_to_string(path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            toml::from_str(&content).map_err(|e| anyhow!("{}: {}", path.display(), e))?
+        } else {
+            StoreFile::default()
+        };
+        Ok(Self {
+            path: path.to_path_buf(),
+            file,
+        })
+    }
+
+    /// How the store's key is obtained; `None` until the first password is saved
+    pub fn key_source(&self) -> Option<KeySource> {
+        self.file.key_source
+    }
+
+    /// Choose how the key of a new store is obtained
+    pub fn set_key_source(&mut self, source: KeySource) -> Result<()> {
+        match self.file.key_source {
+            Some(current) if current != source && !self.file.profiles.is_empty() => Err(anyhow!(
+                "Credentials in {} are already protected by the {}",
+                self.path.display(),
+                match current {
+                    KeySource::Passphrase => "master passphrase",
+                    KeySource::Keyring => "OS keyring",
+                }
+            )),
+            _ => {
+                self.file.key_source = Some(source);
+                Ok(())
+            }
+        }
+    }
+
+    /// The key for a master passphrase, creating the store's salt when needed
+    pub fn passphrase_key(&mut self, passphrase: &str) -> Result<StoreKey> {
+        let salt = match &self.file.salt {
+            Some(salt) => BASE64.decode(salt)?,
+            None => {
+                let mut salt = [0u8; 16];
+                OsRng.fill_bytes(&mut salt);
+                self.file.salt = Some(BASE64.encode(salt));
+                salt.to_vec()
+            }
+        };
+        StoreKey::from_passphrase(passphrase, &salt)
+    }
+
+    /// Profile names, sorted
+    pub fn profiles(&self) -> Vec<&str> {
+        self.file.profiles.keys().map(String::as_str).collect()
+    }
+
+    pub fn contains(&self, profile: &str) -> bool {
+        self.file.profiles.contains_key(profile)
+    }
+
+    /// Seal and add (or replace) the password of a profile
+    pub fn set(&mut self, profile: &str, password: &str, key: &StoreKey) -> Result<()> {
+        // Another entry's password must still open with the same key
+        if let Some(other) = self.file.profiles.keys().find(|name| *name != profile) {
+            self.get(&other.clone(), key)?;
+        }
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let sealed = key
+            .cipher()
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: password.as_bytes(),
+                    aad: profile.as_bytes(),
+                },
+            )
+            .map_err(|_| anyhow!("Cannot encrypt the password"))?;
+        self.file.profiles.insert(
+            profile.to_string(),
+            Sealed {
+                nonce: BASE64.encode(nonce),
+                password: BASE64.encode(sealed),
+            },
+        );
+        Ok(())
+    }
+
+    /// The password of a profile, `None` when the profile isn't stored
+    pub fn get(&self, profile: &str, key: &StoreKey) -> Result<Option<String>> {
+        let Some(sealed) = self.file.profiles.get(profile) else {
+            return Ok(None);
+        };
+        let nonce = BASE64.decode(&sealed.nonce)?;
+        if nonce.len() != 12 {
+            return Err(anyhow!("Corrupt credential entry '{}'", profile));
+        }
+        let password = key
+            .cipher()
+            .decrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: &BASE64.decode(&sealed.password)?,
+                    aad: profile.as_bytes(),
+                },
+            )
+            .map_err(|_| anyhow!("Cannot decrypt credentials (wrong passphrase or key)"))?;
+        Ok(Some(String::from_utf8(password)?))
+    }
+
+    /// Remove a profile; false when it wasn't stored
+    pub fn remove(&mut self, profile: &str) -> bool {
+        self.file.profiles.remove(profile).is_some()
+    }
+
+    /// Write the store, readable only by the owner
+    pub fn save(&self) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&self.path, toml::to_string(&self.file)?)
+            .with_context(|| format!("Failed to write {}", self.path.display()))?;
+        platform::restrict_to_owner(&self.path)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[path = "./credentials_tests.rs"]
+mod credentials_tests;
@@ -0,0 +1,69 @@
+use super::*;
+
+fn test_key() -> StoreKey {
+    StoreKey([7; 32])
+}
+
+#[test]
+fn test_round_trip_through_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("credentials");
+
+    let mut store = CredentialStore::open(&path).unwrap();
+    store.set_key_source(KeySource::Keyring).unwrap();
+    store.set("app@db1", "s3cret pass", &test_key()).unwrap();
+    store.set("admin", "other", &test_key()).unwrap();
+    store.save().unwrap();
+
+    let content = fs::read_to_string(&path).unwrap();
+    assert!(!content.contains("s3cret"));
+
+    let store = CredentialStore::open(&path).unwrap();
+    assert_eq!(store.key_source(), Some(KeySource::Keyring));
+    assert_eq!(store.profiles(), vec!["admin", "app@db1"]);
+    assert_eq!(
+        store.get("app@db1", &test_key()).unwrap().as_deref(),
+        Some("s3cret pass")
+    );
+    assert_eq!(store.get("missing", &test_key()).unwrap(), None);
+}
+
+#[test]
+fn test_wrong_key_and_swapped_entries_fail() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut store = CredentialStore::open(&dir.path().join("credentials")).unwrap();
+    store.set("a", "first", &test_key()).unwrap();
+    store.set("b", "second", &test_key()).unwrap();
+
+    let wrong = StoreKey([8; 32]);
+    assert!(store.get("a", &wrong).is_err());
+    // Adding under a different key than the existing entries is refused
+    assert!(store.set("c", "third", &wrong).is_err());
+
+    // An entry copied to another profile name doesn't decrypt
+    let sealed = store.file.profiles["a"].clone();
+    store.file.profiles.insert("b".to_string(), sealed);
+    assert!(store.get("b", &test_key()).is_err());
+}
+
+#[test]
+fn test_passphrase_key_uses_stored_salt() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("credentials");
+
+    let mut store = CredentialStore::open(&path).unwrap();
+    store.set_key_source(KeySource::Passphrase).unwrap();
+    let key = store.passphrase_key("master").unwrap();
+    store.set("app", "pw", &key).unwrap();
+    store.save().unwrap();
+
+    let mut store = CredentialStore::open(&path).unwrap();
+    let key = store.passphrase_key("master").unwrap();
+    assert_eq!(store.get("app", &key).unwrap().as_deref(), Some("pw"));
+    let wrong = store.passphrase_key("Master").unwrap();
+    assert!(store.get("app", &wrong).is_err());
+
+    assert!(store.remove("app"));
+    assert!(!store.remove("app"));
+    assert!(store.profiles().is_empty());
+}
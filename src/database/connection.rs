@@ -1,11 +1,85 @@
-use anyhow::Result;
+use crate::commands::literal;
+use crate::statement;
+use anyhow::{anyhow, Result};
+use mysql::consts::ColumnType;
 use mysql::prelude::*;
-use mysql::{Conn, OptsBuilder, Value};
+use mysql::{Compression, Conn, Opts, OptsBuilder, Value};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+
+/// Ask for protocol compression on the first connection, from `--compress`
+static COMPRESS: AtomicBool = AtomicBool::new(false);
+
+pub fn set_compress(compress: bool) {
+    COMPRESS.store(compress, Ordering::Relaxed);
+}
+
+/// User-defined connection attributes from `--connection-attribute`
+static CONNECT_ATTRIBUTES: OnceLock<Vec<(String, String)>> = OnceLock::new();
+
+/// Send these attributes with every connection besides the defaults; called
+/// once at startup
+pub fn set_connect_attributes(attributes: Vec<(String, String)>) {
+    let _ = CONNECT_ATTRIBUTES.set(attributes);
+}
+
+/// Connection attributes shown in `performance_schema.session_connect_attrs`:
+/// the program name and version, then the user's, which may replace them. The
+/// driver adds `_client_version`, `_os`, `_platform` and `_pid` itself and drops
+/// other names starting with `_`
+fn connect_attributes(extra: &[(String, String)]) -> HashMap<String, String> {
+    let mut attributes = HashMap::from([
+        ("program_name".to_string(), "mysql-cli-rust".to_string()),
+        (
+            "program_version".to_string(),
+            env!("CARGO_PKG_VERSION").to_string(),
+        ),
+    ]);
+    attributes.extend(extra.iter().cloned());
+    attributes
+}
+
+/// Parse a `--connection-attribute key=value` argument
+pub fn parse_connect_attribute(argument: &str) -> Result<(String, String), String> {
+    let (key, value) = argument
+        .split_once('=')
+        .ok_or_else(|| format!("expected key=value, got '{}'", argument))?;
+    let key = key.trim();
+    if key.is_empty() {
+        return Err("the attribute name is empty".to_string());
+    }
+    if key.starts_with('_') {
+        return Err(format!(
+            "attribute names starting with '_' are reserved: {}",
+            key
+        ));
+    }
+    Ok((key.to_string(), value.trim().to_string()))
+}
+
+/// Server and account a connection talks to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConnectionIdentity {
+    pub host: String,
+    pub port: u16,
+    pub user: String,
+}
 
 pub struct Connection {
     conn: Conn,
+    opts: Opts,
     connection_id: u32,
     server_version: String,
+    /// Server `lower_case_table_names`: 0 compares database and table names case-sensitively
+    lower_case_table_names: u8,
+    /// `--query-comment` template put in front of every statement
+    query_comment: Option<String>,
+    /// Statements sent with a query comment, for its `{counter}`
+    comment_counter: u64,
+    /// Whether the current session asked for protocol compression; `opts` may
+    /// differ until the next reconnect
+    compressed: bool,
 }
 
 impl Connection {
@@ -20,25 +94,90 @@ impl Connection {
             .ip_or_hostname(Some(host))
             .tcp_port(port)
             .user(Some(user))
-            .pass(Some(password));
+            .pass(Some(password))
+            .connect_attrs(Some(connect_attributes(
+                CONNECT_ATTRIBUTES.get().map_or(&[], Vec::as_slice),
+            )));
+        let compressed = COMPRESS.load(Ordering::Relaxed);
+        if compressed {
+            opts_builder = opts_builder.compress(Some(Compression::default()));
+        }
 
         if let Some(db) = database {
             opts_builder = opts_builder.db_name(Some(db));
         }
 
-        let mut conn = Conn::new(opts_builder)?;
+        let opts = Opts::from(opts_builder);
+        let mut conn = Conn::new(opts.clone())?;
 
         // Get connection info
         let connection_id: u32 = conn.query_first("SELECT CONNECTION_ID()")?.unwrap_or(0);
         let server_version: String = conn.query_first("SELECT VERSION()")?.unwrap_or_default();
+        let lower_case_table_names = fetch_lower_case_table_names(&mut conn);
 
         Ok(Self {
             conn,
+            opts,
             connection_id,
             server_version,
+            lower_case_table_names,
+            query_comment: None,
+            comment_counter: 0,
+            compressed,
         })
     }
 
+    /// Open a fresh session with the original connection options
+    pub fn reconnect(&mut self) -> Result<()> {
+        let mut conn = Conn::new(self.opts.clone())?;
+        self.connection_id = conn.query_first("SELECT CONNECTION_ID()")?.unwrap_or(0);
+        self.lower_case_table_names = fetch_lower_case_table_names(&mut conn);
+        self.conn = conn;
+        self.compressed = self.opts.get_compress().is_some();
+        Ok(())
+    }
+
+    /// Whether the session asked for protocol compression (the server may not support it)
+    pub fn compressed(&self) -> bool {
+        self.compressed
+    }
+
+    /// Ask for protocol compression from the next reconnect on
+    pub fn set_compress(&mut self, compress: bool) {
+        self.opts = OptsBuilder::from_opts(self.opts.clone())
+            .compress(compress.then(Compression::default))
+            .into();
+    }
+
+    /// A compression change is waiting for a reconnect
+    pub fn compress_pending(&self) -> bool {
+        self.opts.get_compress().is_some() != self.compressed
+    }
+
+    /// Bytes the server received from and sent to this session, as counted on
+    /// the wire (after compression)
+    pub fn traffic(&mut self) -> Result<(u64, u64)> {
+        let rows: Vec<(String, String)> = self
+            .conn
+            .query("SHOW SESSION STATUS WHERE Variable_name IN ('Bytes_received', 'Bytes_sent')")?;
+        let value = |name: &str| {
+            rows.iter()
+                .find(|(variable, _)| variable.eq_ignore_ascii_case(name))
+                .and_then(|(_, value)| value.parse().ok())
+                .unwrap_or(0)
+        };
+        Ok((value("Bytes_received"), value("Bytes_sent")))
+    }
+
+    /// Host, port and user of the connection options
+    pub fn identity(&self) -> ConnectionIdentity {
+        ConnectionIdentity {
+            host: self.opts.get_ip_or_hostname().to_lowercase(),
+            port: self.opts.get_tcp_port(),
+            user: self.opts.get_user().unwrap_or_default().to_string(),
+        }
+    }
+
     pub fn connection_id(&self) -> u32 {
         self.connection_id
     }
@@ -47,10 +186,106 @@ impl Connection {
         &self.server_version
     }
 
-    pub fn execute_query(&mut self, query: &str) -> Result<QueryResult> {
+    /// A database name as the server compares it, lower-cased unless names are case-sensitive
+    pub fn normalize_database_name(&self, name: &str) -> String {
+        normalize_name(name, self.lower_case_table_names)
+    }
+
+    /// Ask the server which database is currently selected
+    pub fn current_database(&mut self) -> Result<Option<String>> {
+        let database: Option<Option<String>> = self.conn.query_first("SELECT DATABASE()")?;
+        Ok(database.flatten())
+    }
+
+    /// Read the session `sql_select_limit`
+    pub fn sql_select_limit(&mut self) -> Result<u64> {
+        let limit: Option<u64> = self.conn.query_first("SELECT @@SESSION.sql_select_limit")?;
+        Ok(limit.unwrap_or(u64::MAX))
+    }
+
+    /// Read a session system variable as text (empty when NULL)
+    pub fn session_variable(&mut self, name: &str) -> Result<String> {
+        let value: Option<Option<String>> = self
+            .conn
+            .query_first(format!("SELECT @@SESSION.{}", name))?;
+        Ok(value.flatten().unwrap_or_default())
+    }
+
+    /// Read the global `read_only` and `super_read_only` flags; servers without
+    /// `super_read_only` (MariaDB, MySQL before 5.7.8) report it off
+    pub fn read_only_flags(&mut self) -> Result<(bool, bool)> {
+        let read_only: Option<u8> = self.conn.query_first("SELECT @@GLOBAL.read_only")?;
+        let super_read_only: Option<u8> = self
+            .conn
+            .query_first("SELECT @@GLOBAL.super_read_only")
+            .ok()
+            .flatten();
+        Ok((
+            read_only.unwrap_or(0) != 0,
+            super_read_only.unwrap_or(0) != 0,
+        ))
+    }
+
+    /// List column names of `database`.`table`
+    pub fn table_columns(&mut self, database: &str, table: &str) -> Result<Vec<String>> {
+        let columns: Vec<String> = self.conn.query_map(
+            format!(
+                "SHOW COLUMNS FROM {}.{}",
+                literal::quote_identifier(database),
+                literal::quote_identifier(table)
+            ),
+            |row: mysql::Row| row.get::<String, _>(0).unwrap_or_default(),
+        )?;
+        Ok(columns)
+    }
+
+    /// Run a statement, returning its rows or, for statements without a result set,
+    /// the OK packet details
+    pub fn execute_query(&mut self, query: &str) -> Result<ExecOutcome> {
+        Ok(self.run(query, true)?.0)
+    }
+
+    /// Run a statement and count the rows it returns as they arrive, without keeping
+    /// them; a result set comes back with its columns only
+    pub fn count_query(&mut self, query: &str) -> Result<(ExecOutcome, u64)> {
+        self.run(query, false)
+    }
+
+    /// Prefix statements with `/* text */`, where `{user}`, `{pid}` and `{counter}`
+    /// are expanded for each statement; `None` sends statements unchanged
+    pub fn set_query_comment(&mut self, template: Option<String>) {
+        self.query_comment = template;
+    }
+
+    fn run(&mut self, query: &str, keep_rows: bool) -> Result<(ExecOutcome, u64)> {
+        let tagged;
+        let query = match &self.query_comment {
+            Some(template) => {
+                self.comment_counter += 1;
+                let comment = statement::expand_query_comment(
+                    template,
+                    self.opts.get_user().unwrap_or_default(),
+                    std::process::id(),
+                    self.comment_counter,
+                );
+                tagged = statement::prepend_query_comment(query, &comment);
+                tagged.as_str()
+            }
+            None => query,
+        };
         let result = self.conn.query_iter(query)?;
 
+        if result.columns().as_ref().is_empty() {
+            let outcome = ExecOutcome::Ok {
+                affected: result.affected_rows(),
+                last_insert_id: result.last_insert_id(),
+                info: result.info_str().into_owned(),
+            };
+            return Ok((outcome, 0));
+        }
+
         let mut rows = Vec::new();
+        let mut nulls = Vec::new();
 
         // Get column information
         let columns: Vec<String> = result
@@ -59,24 +294,55 @@ impl Connection {
             .iter()
             .map(|col| col.name_str().to_string())
             .collect();
+        let tables: Vec<String> = result
+            .columns()
+            .as_ref()
+            .iter()
+            .map(|col| col.table_str().to_string())
+            .collect();
 
         // Collect all rows
+        let mut row_count = 0;
         for row in result {
             let row = row?;
+            row_count += 1;
+            if !keep_rows {
+                continue;
+            }
             let mut row_values = Vec::new();
+            let mut row_nulls = Vec::new();
 
             for i in 0..row.len() {
+                let column_type = row.columns_ref()[i].column_type();
                 let value = match row.get_opt::<Value, usize>(i) {
-                    Some(Ok(value)) => format_value(&value),
-                    Some(Err(_)) => "ERROR".to_string(),
-                    None => "NULL".to_string(),
+                    Some(Ok(value)) => {
+                        row_nulls.push(value == Value::NULL);
+                        format_value(&value, column_type)
+                    }
+                    Some(Err(_)) => {
+                        row_nulls.push(false);
+                        "ERROR".to_string()
+                    }
+                    None => {
+                        row_nulls.push(true);
+                        "NULL".to_string()
+                    }
                 };
                 row_values.push(value);
             }
             rows.push(row_values);
+            nulls.push(row_nulls);
         }
 
-        Ok(QueryResult { columns, rows })
+        Ok((
+            ExecOutcome::ResultSet(QueryResult {
+                columns,
+                rows,
+                tables,
+                nulls,
+            }),
+            row_count,
+        ))
     }
 
     pub fn get_conn_mut(&mut self) -> &mut Conn {
@@ -84,12 +350,124 @@ impl Connection {
     }
 }
 
+#[derive(Clone, Default)]
 pub struct QueryResult {
     pub columns: Vec<String>,
     pub rows: Vec<Vec<String>>,
+    /// Table or alias each column comes from, as reported by the server; empty
+    /// for results built by the client
+    pub tables: Vec<String>,
+    /// Which cells are SQL NULL, as opposed to the text `NULL`; empty for
+    /// results built by the client
+    pub nulls: Vec<Vec<bool>>,
 }
 
-fn format_value(value: &Value) -> String {
+impl QueryResult {
+    /// Column names made unique for formats keyed by name
+    pub fn unique_columns(&self) -> Vec<String> {
+        unique_column_names(&self.columns, &self.tables)
+    }
+
+    /// Whether the cell at `row`, `column` is SQL NULL
+    pub fn is_null(&self, row: usize, column: usize) -> bool {
+        self.nulls
+            .get(row)
+            .and_then(|nulls| nulls.get(column))
+            .copied()
+            .unwrap_or(false)
+    }
+}
+
+/// Disambiguate duplicate column names: duplicates become `table.name` when each
+/// comes from a different known table, otherwise the first keeps its name and
+/// later ones get `name_2`, `name_3`, ... skipping names already in use
+pub fn unique_column_names(columns: &[String], tables: &[String]) -> Vec<String> {
+    let is_duplicate = |name: &String| columns.iter().filter(|c| *c == name).count() > 1;
+    let table_of = |i: usize| tables.get(i).filter(|t| !t.is_empty());
+
+    let mut names: Vec<String> = Vec::with_capacity(columns.len());
+    for (i, name) in columns.iter().enumerate() {
+        if !is_duplicate(name) {
+            names.push(name.clone());
+            continue;
+        }
+
+        let positions: Vec<usize> = (0..columns.len())
+            .filter(|&j| columns[j] == *name)
+            .collect();
+        let mut qualifiers: Vec<&String> = positions.iter().filter_map(|&j| table_of(j)).collect();
+        qualifiers.sort();
+        qualifiers.dedup();
+        if qualifiers.len() == positions.len() {
+            names.push(format!("{}.{}", table_of(i).unwrap(), name));
+            continue;
+        }
+
+        let occurrence = positions.iter().position(|&j| j == i).unwrap_or(0);
+        if occurrence == 0 {
+            names.push(name.clone());
+            continue;
+        }
+        let mut suffix = occurrence + 1;
+        loop {
+            let candidate = format!("{}_{}", name, suffix);
+            if !columns.contains(&candidate) && !names.contains(&candidate) {
+                names.push(candidate);
+                break;
+            }
+            suffix += 1;
+        }
+    }
+    names
+}
+
+/// What the server sent back for a statement
+#[derive(Clone)]
+pub enum ExecOutcome {
+    /// Column definitions and rows, possibly none
+    ResultSet(QueryResult),
+    /// OK packet of a statement without a result set
+    Ok {
+        affected: u64,
+        last_insert_id: Option<u64>,
+        /// Server info such as `Records: 3  Duplicates: 0  Warnings: 0`
+        info: String,
+    },
+}
+
+impl ExecOutcome {
+    /// The rows of a statement that is expected to return a result set
+    pub fn into_result_set(self) -> Result<QueryResult> {
+        match self {
+            ExecOutcome::ResultSet(result) => Ok(result),
+            ExecOutcome::Ok { .. } => Err(anyhow!("Statement returned no result set")),
+        }
+    }
+}
+
+/// Read `lower_case_table_names`, assuming case-sensitive names when it can't be read
+fn fetch_lower_case_table_names(conn: &mut Conn) -> u8 {
+    conn.query_first("SELECT @@lower_case_table_names")
+        .ok()
+        .flatten()
+        .unwrap_or(0)
+}
+
+/// Normalize a database or table name for a `lower_case_table_names` setting:
+/// with 1 or 2 the server compares names in lower case
+fn normalize_name(name: &str, lower_case_table_names: u8) -> String {
+    let name = name.trim().trim_matches('`');
+    if lower_case_table_names == 0 {
+        name.to_string()
+    } else {
+        name.to_lowercase()
+    }
+}
+
+/// Display text of a value. Text-protocol results arrive as the server's own
+/// bytes; binary temporal values are printed component by component, so zero
+/// dates (`0000-00-00`) and zero months or days come out exactly as stored
+fn format_value(value: &Value, column_type: ColumnType) -> String {
     match value {
         Value::NULL => "NULL".to_string(),
         Value::Bytes(bytes) => String::from_utf8_lossy(bytes).to_string(),
@@ -98,18 +476,37 @@ fn format_value(value: &Value) -> String {
         Value::Float(f) => f.to_string(),
         Value::Double(d) => d.to_string(),
         Value::Date(year, month, day, hour, minute, second, micro) => {
-            if *hour == 0 && *minute == 0 && *second == 0 && *micro == 0 {
-                format!("{:04}-{:02}-{:02}", year, month, day)
-            } else {
-                format!(
-                    "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
-                    year, month, day, hour, minute, second
-                )
+            let date = format!("{:04}-{:02}-{:02}", year, month, day);
+            let time = format_time(*hour as u32, *minute, *second, *micro);
+            match column_type {
+                ColumnType::MYSQL_TYPE_DATE | ColumnType::MYSQL_TYPE_NEWDATE => date,
+                // DATETIME and TIMESTAMP keep their time, also at midnight
+                ColumnType::MYSQL_TYPE_DATETIME
+                | ColumnType::MYSQL_TYPE_DATETIME2
+                | ColumnType::MYSQL_TYPE_TIMESTAMP
+                | ColumnType::MYSQL_TYPE_TIMESTAMP2 => format!("{} {}", date, time),
+                _ if *hour == 0 && *minute == 0 && *second == 0 && *micro == 0 => date,
+                _ => format!("{} {}", date, time),
             }
         }
-        Value::Time(neg, _days, hours, minutes, seconds, _micro) => {
+        Value::Time(neg, days, hours, minutes, seconds, micro) => {
             let sign = if *neg { "-" } else { "" };
-            format!("{}{:02}:{:02}:{:02}", sign, hours, minutes, seconds)
+            // TIME spans -838:59:59 to 838:59:59; whole days are folded into the hours
+            let hours = days * 24 + *hours as u32;
+            format!("{}{}", sign, format_time(hours, *minutes, *seconds, *micro))
         }
     }
 }
+
+/// `hh:mm:ss`, with `.ffffff` only when there are microseconds
+fn format_time(hours: u32, minutes: u8, seconds: u8, micro: u32) -> String {
+    if micro == 0 {
+        format!("{:02}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{:02}:{:02}:{:02}.{:06}", hours, minutes, seconds, micro)
+    }
+}
+
+#[cfg(test)]
+#[path = "./connection_tests.rs"]
+mod connection_tests;
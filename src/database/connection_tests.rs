@@ -0,0 +1,178 @@
+use super::*;
+
+#[test]
+fn test_into_result_set() {
+    let rows = ExecOutcome::ResultSet(QueryResult {
+        tables: Vec::new(),
+        nulls: Vec::new(),
+        columns: vec!["id".to_string()],
+        rows: Vec::new(),
+    });
+    assert_eq!(rows.into_result_set().unwrap().columns, vec!["id"]);
+
+    let ok = ExecOutcome::Ok {
+        affected: 2,
+        last_insert_id: None,
+        info: String::new(),
+    };
+    assert!(ok.into_result_set().is_err());
+}
+
+#[test]
+fn test_normalize_name_case_sensitive_server() {
+    assert_eq!(normalize_name("MyDB", 0), "MyDB");
+    assert_eq!(normalize_name("`Sales_2024`", 0), "Sales_2024");
+}
+
+#[test]
+fn test_normalize_name_case_insensitive_server() {
+    assert_eq!(normalize_name("MyDB", 1), "mydb");
+    assert_eq!(normalize_name(" `MyDB` ", 2), "mydb");
+}
+
+fn names(names: &[&str]) -> Vec<String> {
+    names.iter().map(|name| name.to_string()).collect()
+}
+
+#[test]
+fn test_unique_column_names_use_tables() {
+    assert_eq!(
+        unique_column_names(&names(&["id", "id", "total"]), &names(&["a", "b", "b"])),
+        names(&["a.id", "b.id", "total"])
+    );
+    // Without distinct tables the duplicates are numbered, skipping taken names
+    assert_eq!(
+        unique_column_names(&names(&["id", "id_2", "id", "id"]), &[]),
+        names(&["id", "id_2", "id_3", "id_4"])
+    );
+    assert_eq!(
+        unique_column_names(&names(&["n", "n"]), &names(&["t", "t"])),
+        names(&["n", "n_2"])
+    );
+    assert_eq!(
+        unique_column_names(&names(&["a", "b"]), &[]),
+        names(&["a", "b"])
+    );
+}
+
+#[test]
+fn test_format_value() {
+    assert_eq!(
+        format_value(&Value::NULL, ColumnType::MYSQL_TYPE_NULL),
+        "NULL"
+    );
+    assert_eq!(
+        format_value(
+            &Value::Date(2024, 1, 2, 0, 0, 0, 0),
+            ColumnType::MYSQL_TYPE_DATE
+        ),
+        "2024-01-02"
+    );
+    assert_eq!(
+        format_value(
+            &Value::Time(true, 0, 1, 2, 3, 0),
+            ColumnType::MYSQL_TYPE_TIME
+        ),
+        "-01:02:03"
+    );
+}
+
+#[test]
+fn test_format_value_zero_dates_verbatim() {
+    assert_eq!(
+        format_value(
+            &Value::Date(0, 0, 0, 0, 0, 0, 0),
+            ColumnType::MYSQL_TYPE_DATETIME
+        ),
+        "0000-00-00 00:00:00"
+    );
+    assert_eq!(
+        format_value(
+            &Value::Date(0, 0, 0, 0, 0, 0, 0),
+            ColumnType::MYSQL_TYPE_DATE
+        ),
+        "0000-00-00"
+    );
+    // Text-protocol values are the server's own text
+    assert_eq!(
+        format_value(
+            &Value::Bytes(b"0000-00-00 00:00:00".to_vec()),
+            ColumnType::MYSQL_TYPE_DATETIME
+        ),
+        "0000-00-00 00:00:00"
+    );
+}
+
+#[test]
+fn test_format_value_partial_and_short_dates() {
+    // Zero month or day next to a non-zero time
+    assert_eq!(
+        format_value(
+            &Value::Date(2024, 0, 15, 13, 5, 9, 0),
+            ColumnType::MYSQL_TYPE_DATETIME
+        ),
+        "2024-00-15 13:05:09"
+    );
+    assert_eq!(
+        format_value(
+            &Value::Date(2024, 3, 0, 0, 0, 1, 0),
+            ColumnType::MYSQL_TYPE_TIMESTAMP
+        ),
+        "2024-03-00 00:00:01"
+    );
+    // Two-digit years are stored as years 0-99 and shown zero-padded
+    assert_eq!(
+        format_value(
+            &Value::Date(99, 12, 31, 0, 0, 0, 0),
+            ColumnType::MYSQL_TYPE_DATE
+        ),
+        "0099-12-31"
+    );
+    assert_eq!(
+        format_value(
+            &Value::Date(2024, 1, 31, 0, 0, 0, 250),
+            ColumnType::MYSQL_TYPE_DATETIME
+        ),
+        "2024-01-31 00:00:00.000250"
+    );
+}
+
+#[test]
+fn test_format_value_time_with_days() {
+    assert_eq!(
+        format_value(
+            &Value::Time(true, 34, 22, 59, 59, 0),
+            ColumnType::MYSQL_TYPE_TIME
+        ),
+        "-838:59:59"
+    );
+    assert_eq!(
+        format_value(
+            &Value::Time(false, 0, 0, 0, 0, 0),
+            ColumnType::MYSQL_TYPE_TIME
+        ),
+        "00:00:00"
+    );
+}
+
+#[test]
+fn test_connect_attributes() {
+    let defaults = connect_attributes(&[]);
+    assert_eq!(defaults["program_name"], "mysql-cli-rust");
+    assert_eq!(defaults["program_version"], env!("CARGO_PKG_VERSION"));
+
+    let attributes = connect_attributes(&[
+        ("team".to_string(), "billing".to_string()),
+        ("program_name".to_string(), "nightly-report".to_string()),
+    ]);
+    assert_eq!(attributes["team"], "billing");
+    assert_eq!(attributes["program_name"], "nightly-report");
+
+    assert_eq!(
+        parse_connect_attribute("env = staging"),
+        Ok(("env".to_string(), "staging".to_string()))
+    );
+    assert!(parse_connect_attribute("no-value").is_err());
+    assert!(parse_connect_attribute("=x").is_err());
+    assert!(parse_connect_attribute("_os=linux").is_err());
+}
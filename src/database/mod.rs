@@ -1,2 +1,6 @@
 pub mod connection;
-pub use connection::{Connection, QueryResult};
+pub mod session;
+pub use connection::{
+    parse_connect_attribute, set_compress, set_connect_attributes, Connection, ConnectionIdentity,
+    ExecOutcome, QueryResult,
+};
@@ -0,0 +1,111 @@
+/*!
+ * Session state
+ *
+ * Session-scoped settings that a fresh server connection does not inherit:
+ * init commands, SET NAMES, autocommit, safe-updates, sql_mode and the current database.
+ * They are recorded as statements run and replayed onto a new connection
+ * in one place after a reconnect.
+ */
+
+use super::Connection;
+use anyhow::Result;
+use mysql::prelude::*;
+use regex::Regex;
+use std::sync::OnceLock;
+
+/// A connection that session statements can be replayed onto
+pub trait SessionTarget {
+    fn execute(&mut self, sql: &str) -> Result<()>;
+}
+
+impl SessionTarget for Connection {
+    fn execute(&mut self, sql: &str) -> Result<()> {
+        self.get_conn_mut().query_drop(sql)?;
+        Ok(())
+    }
+}
+
+/// Session settings to restore on a new connection
+#[derive(Debug, Default, Clone)]
+pub struct SessionState {
+    /// Database selected with USE
+    pub database: Option<String>,
+    /// Statements run first on every connection (`--init-command`)
+    init_commands: Vec<String>,
+    /// Recorded SET statements keyed by setting, in the order they were last run
+    settings: Vec<(String, String)>,
+    /// Whether the state was replayed onto a new connection
+    restored: bool,
+}
+
+impl SessionState {
+    pub fn new(database: Option<String>, init_commands: Vec<String>) -> Self {
+        Self {
+            database,
+            init_commands,
+            ..Default::default()
+        }
+    }
+
+    /// Remember a statement if it changes a tracked session setting
+    pub fn record(&mut self, sql: &str) -> bool {
+        let Some(key) = setting_key(sql) else {
+            return false;
+        };
+        let statement = sql.trim().trim_end_matches(';').trim().to_string();
+        self.settings.retain(|(k, _)| *k != key);
+        self.settings.push((key, statement));
+        true
+    }
+
+    /// Statements that rebuild this state, in replay order
+    pub fn statements(&self) -> Vec<String> {
+        let mut statements = self.init_commands.clone();
+        statements.extend(self.settings.iter().map(|(_, s)| s.clone()));
+        if let Some(db) = &self.database {
+            statements.push(format!("USE `{}`", db.replace('`', "``")));
+        }
+        statements
+    }
+
+    /// Re-apply every recorded setting onto a new connection, in order
+    pub fn replay(&mut self, target: &mut impl SessionTarget) -> Result<()> {
+        for statement in self.statements() {
+            target.execute(&statement)?;
+        }
+        self.restored = true;
+        Ok(())
+    }
+
+    /// Number of recorded settings, excluding the current database
+    pub fn setting_count(&self) -> usize {
+        self.init_commands.len() + self.settings.len()
+    }
+
+    pub fn was_restored(&self) -> bool {
+        self.restored
+    }
+}
+
+/// Key of the session setting a statement changes, if it is one we track
+fn setting_key(sql: &str) -> Option<String> {
+    static CHARSET: OnceLock<Regex> = OnceLock::new();
+    static VARIABLE: OnceLock<Regex> = OnceLock::new();
+    let charset = CHARSET
+        .get_or_init(|| Regex::new(r"(?i)^\s*SET\s+(NAMES|CHARACTER\s+SET|CHARSET)\b").unwrap());
+    if charset.is_match(sql) {
+        return Some("names".to_string());
+    }
+
+    let variable = VARIABLE.get_or_init(|| {
+        Regex::new(
+            r"(?i)^\s*SET\s+(?:SESSION\s+|LOCAL\s+|@@SESSION\.|@@LOCAL\.|@@)?(AUTOCOMMIT|SQL_SAFE_UPDATES|SQL_MODE)\s*(?:=|:=)",
+        )
+        .unwrap()
+    });
+    variable.captures(sql).map(|caps| caps[1].to_lowercase())
+}
+
+#[cfg(test)]
+#[path = "./session_tests.rs"]
+mod session_tests;
@@ -0,0 +1,56 @@
+use super::*;
+
+/// Records executed statements instead of talking to a server
+#[derive(Default)]
+struct MockConnection {
+    executed: Vec<String>,
+}
+
+impl SessionTarget for MockConnection {
+    fn execute(&mut self, sql: &str) -> Result<()> {
+        self.executed.push(sql.to_string());
+        Ok(())
+    }
+}
+
+#[test]
+fn test_replay_reapplies_settings_in_order() {
+    let mut session = SessionState::new(None, vec!["SET time_zone = '+00:00'".to_string()]);
+    assert!(session.record("SET NAMES utf8mb4;"));
+    assert!(session.record("SET autocommit = 0"));
+    assert!(session.record("SET SESSION sql_safe_updates = 1"));
+    assert!(!session.record("SELECT 1"));
+    session.database = Some("shop".to_string());
+
+    let mut conn = MockConnection::default();
+    session.replay(&mut conn).unwrap();
+
+    assert_eq!(
+        conn.executed,
+        vec![
+            "SET time_zone = '+00:00'",
+            "SET NAMES utf8mb4",
+            "SET autocommit = 0",
+            "SET SESSION sql_safe_updates = 1",
+            "USE `shop`",
+        ]
+    );
+    assert!(session.was_restored());
+}
+
+#[test]
+fn test_record_keeps_latest_value_of_each_setting() {
+    let mut session = SessionState::default();
+    session.record("SET autocommit = 0");
+    session.record("SET NAMES latin1");
+    session.record("set @@autocommit := 1");
+
+    let mut conn = MockConnection::default();
+    session.replay(&mut conn).unwrap();
+
+    assert_eq!(
+        conn.executed,
+        vec!["SET NAMES latin1", "set @@autocommit := 1"]
+    );
+    assert_eq!(session.setting_count(), 2);
+}
@@ -0,0 +1,279 @@
+/*!
+ * Inline SQL keyword help
+ *
+ * Short syntax reminders shown by `\help <keyword>` and the F1 key
+ */
+
+use rustyline::{Cmd, ConditionalEventHandler, Event, EventContext, ExternalPrinter, RepeatCount};
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+/// Help texts keyed by upper-case keyword
+fn help_texts() -> &'static HashMap<&'static str, &'static str> {
+    static HELP: OnceLock<HashMap<&'static str, &'static str>> = OnceLock::new();
+    HELP.get_or_init(|| {
+        HashMap::from([
+            (
+                "SELECT",
+                "SELECT retrieves rows from one or more tables.\n\
+                 SELECT [DISTINCT] expr [, expr ...] FROM table\n\
+                 \x20 [WHERE cond] [GROUP BY ...] [HAVING cond] [ORDER BY ...] [LIMIT n]\n\
+                 Example: SELECT id, name FROM users WHERE active = 1;",
+            ),
+            (
+                "INSERT",
+                "INSERT adds new rows to a table.\n\
+                 INSERT INTO table (col, ...) VALUES (val, ...) [, (val, ...)]\n\
+                 INSERT INTO table (col, ...) SELECT ...\n\
+                 Example: INSERT INTO users (name) VALUES ('alice');",
+            ),
+            (
+                "UPDATE",
+                "UPDATE modifies existing rows.\n\
+                 UPDATE table SET col = expr [, col = expr] [WHERE cond] [ORDER BY ...] [LIMIT n]\n\
+                 Without WHERE every row is changed.\n\
+                 Example: UPDATE users SET active = 0 WHERE id = 42;",
+            ),
+            (
+                "DELETE",
+                "DELETE removes rows from a table.\n\
+                 DELETE FROM table [WHERE cond] [ORDER BY ...] [LIMIT n]\n\
+                 Without WHERE every row is removed.\n\
+                 Example: DELETE FROM sessions WHERE expires_at < NOW();",
+            ),
+            (
+                "REPLACE",
+                "REPLACE works like INSERT, but first deletes rows with the same\n\
+                 primary or unique key.\n\
+                 REPLACE INTO table (col, ...) VALUES (val, ...)",
+            ),
+            (
+                "FROM",
+                "FROM names the tables a query reads.\n\
+                 FROM table [AS alias] [, table ...] | FROM table JOIN table ON cond\n\
+                 Example: SELECT * FROM orders o JOIN users u ON u.id = o.user_id;",
+            ),
+            (
+                "WHERE",
+                "WHERE filters rows before grouping.\n\
+                 WHERE cond [AND|OR cond]\n\
+                 Example: WHERE created_at >= '2024-01-01' AND status IN ('new', 'paid')",
+            ),
+            (
+                "JOIN",
+                "JOIN combines rows of two tables.\n\
+                 [INNER | LEFT [OUTER] | RIGHT [OUTER] | CROSS] JOIN table ON cond | USING (col)\n\
+                 Example: SELECT * FROM a LEFT JOIN b ON b.a_id = a.id;",
+            ),
+            (
+                "GROUP",
+                "GROUP BY collapses rows sharing the same values into one.\n\
+                 GROUP BY expr [, expr ...] [WITH ROLLUP]\n\
+                 Example: SELECT status, COUNT(*) FROM orders GROUP BY status;",
+            ),
+            (
+                "HAVING",
+                "HAVING filters groups after GROUP BY.\n\
+                 HAVING cond\n\
+                 Example: SELECT user_id, COUNT(*) c FROM orders GROUP BY user_id HAVING c > 5;",
+            ),
+            (
+                "ORDER",
+                "ORDER BY sorts the result.\n\
+                 ORDER BY expr [ASC|DESC] [, expr [ASC|DESC] ...]\n\
+                 Example: SELECT * FROM users ORDER BY created_at DESC;",
+            ),
+            (
+                "LIMIT",
+                "LIMIT restricts the number of rows returned.\n\
+                 LIMIT count | LIMIT offset, count | LIMIT count OFFSET offset\n\
+                 Example: SELECT * FROM logs ORDER BY id DESC LIMIT 10;",
+            ),
+            (
+                "UNION",
+                "UNION combines the results of several SELECTs.\n\
+                 SELECT ... UNION [ALL | DISTINCT] SELECT ...\n\
+                 UNION removes duplicate rows unless ALL is given.",
+            ),
+            (
+                "DISTINCT",
+                "DISTINCT removes duplicate rows from the result.\n\
+                 SELECT DISTINCT col [, col ...] FROM table\n\
+                 Example: SELECT DISTINCT country FROM users;",
+            ),
+            (
+                "CREATE",
+                "CREATE defines a new object.\n\
+                 CREATE TABLE name (col type [constraints], ...) [ENGINE=InnoDB]\n\
+                 CREATE [UNIQUE] INDEX name ON table (col, ...)\n\
+                 CREATE DATABASE name | CREATE [OR REPLACE] VIEW name AS SELECT ...",
+            ),
+            (
+                "ALTER",
+                "ALTER changes the definition of an existing table.\n\
+                 ALTER TABLE name ADD [COLUMN] col type | DROP [COLUMN] col\n\
+                 \x20 | MODIFY col type | RENAME TO new_name | ADD INDEX idx (col)\n\
+                 Example: ALTER TABLE users ADD COLUMN email VARCHAR(255);",
+            ),
+            (
+                "DROP",
+                "DROP removes an object and its data.\n\
+                 DROP TABLE [IF EXISTS] name [, name ...]\n\
+                 DROP DATABASE name | DROP VIEW name | DROP INDEX idx ON table",
+            ),
+            (
+                "TRUNCATE",
+                "TRUNCATE empties a table quickly and resets AUTO_INCREMENT.\n\
+                 TRUNCATE [TABLE] name\n\
+                 It cannot be rolled back.",
+            ),
+            (
+                "RENAME",
+                "RENAME TABLE renames one or more tables atomically.\n\
+                 RENAME TABLE old TO new [, old2 TO new2 ...]",
+            ),
+            (
+                "USE",
+                "USE selects the default database for the session.\n\
+                 USE db_name",
+            ),
+            (
+                "SHOW",
+                "SHOW displays server and schema information.\n\
+                 SHOW DATABASES | SHOW TABLES | SHOW COLUMNS FROM table\n\
+                 SHOW CREATE TABLE table | SHOW INDEX FROM table | SHOW PROCESSLIST",
+            ),
+            (
+                "DESCRIBE",
+                "DESCRIBE shows the columns of a table.\n\
+                 DESCRIBE table [col] (same as SHOW COLUMNS FROM table)",
+            ),
+            (
+                "EXPLAIN",
+                "EXPLAIN shows how MySQL executes a statement.\n\
+                 EXPLAIN [FORMAT=TREE|JSON] SELECT ...\n\
+                 EXPLAIN ANALYZE SELECT ... runs the query and reports actual timings.",
+            ),
+            (
+                "SET",
+                "SET assigns variables.\n\
+                 SET [SESSION|GLOBAL] var = value | SET @user_var = expr\n\
+                 Example: SET SESSION sql_mode = 'STRICT_ALL_TABLES';",
+            ),
+            (
+                "BEGIN",
+                "BEGIN (or START TRANSACTION) opens a transaction.\n\
+                 Finish it with COMMIT to keep the changes or ROLLBACK to discard them.",
+            ),
+            (
+                "COMMIT",
+                "COMMIT makes the changes of the current transaction permanent.\n\
+                 COMMIT [WORK]",
+            ),
+            (
+                "ROLLBACK",
+                "ROLLBACK discards the changes of the current transaction.\n\
+                 ROLLBACK [WORK] | ROLLBACK TO SAVEPOINT name",
+            ),
+            (
+                "GRANT",
+                "GRANT gives privileges to an account.\n\
+                 GRANT priv [, priv] ON db.table TO 'user'@'host'\n\
+                 Example: GRANT SELECT ON shop.* TO 'report'@'%';",
+            ),
+            (
+                "COUNT",
+                "COUNT(expr) counts non-NULL values; COUNT(*) counts rows.\n\
+                 COUNT([DISTINCT] expr)\n\
+                 Example: SELECT COUNT(DISTINCT user_id) FROM orders;",
+            ),
+            (
+                "CONCAT",
+                "CONCAT(str1, str2, ...) joins strings; NULL if any argument is NULL.\n\
+                 CONCAT_WS(sep, str1, str2, ...) joins with a separator and skips NULLs.",
+            ),
+            (
+                "COALESCE",
+                "COALESCE(v1, v2, ...) returns the first non-NULL argument.\n\
+                 Example: SELECT COALESCE(nickname, name) FROM users;",
+            ),
+            (
+                "IFNULL",
+                "IFNULL(expr, fallback) returns fallback when expr is NULL.\n\
+                 Example: SELECT IFNULL(discount, 0) FROM orders;",
+            ),
+            (
+                "NOW",
+                "NOW() returns the current date and time as 'YYYY-MM-DD hh:mm:ss'.\n\
+                 See also CURDATE(), CURTIME(), UTC_TIMESTAMP().",
+            ),
+            (
+                "DATE_FORMAT",
+                "DATE_FORMAT(date, format) formats a date.\n\
+                 Common specifiers: %Y year, %m month, %d day, %H hour, %i minute, %s second\n\
+                 Example: SELECT DATE_FORMAT(NOW(), '%Y-%m-%d');",
+            ),
+            (
+                "JSON_EXTRACT",
+                "JSON_EXTRACT(doc, path [, path ...]) returns data from a JSON document.\n\
+                 col->'$.path' is shorthand; col->>'$.path' also unquotes the result.\n\
+                 Example: SELECT profile->>'$.email' FROM users;",
+            ),
+        ])
+    })
+}
+
+/// Look up the help text for a keyword, case-insensitively
+pub fn lookup(keyword: &str) -> Option<&'static str> {
+    let key = keyword.trim().trim_end_matches(['(', ';']).to_uppercase();
+    help_texts().get(key.as_str()).copied()
+}
+
+/// The word around the cursor position, or the one right before it
+pub fn word_at(line: &str, pos: usize) -> Option<&str> {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    let pos = pos.min(line.len());
+    let start = line[..pos]
+        .rfind(|c: char| !is_word(c))
+        .map(|i| i + 1)
+        .unwrap_or(0);
+    let end = line[pos..]
+        .find(|c: char| !is_word(c))
+        .map(|i| pos + i)
+        .unwrap_or(line.len());
+    let word = &line[start..end];
+    (!word.is_empty()).then_some(word)
+}
+
+/// Key handler printing help for the keyword under the cursor above the prompt
+pub struct KeywordHelpHandler {
+    printer: Mutex<Box<dyn ExternalPrinter + Send>>,
+}
+
+impl KeywordHelpHandler {
+    pub fn new(printer: Box<dyn ExternalPrinter + Send>) -> Self {
+        Self {
+            printer: Mutex::new(printer),
+        }
+    }
+}
+
+impl ConditionalEventHandler for KeywordHelpHandler {
+    fn handle(&self, _: &Event, _: RepeatCount, _: bool, ctx: &EventContext) -> Option<Cmd> {
+        let message = match word_at(ctx.line(), ctx.pos()) {
+            Some(word) => match lookup(word) {
+                Some(text) => text.to_string(),
+                None => format!("No help available for '{}'.", word),
+            },
+            None => "Place the cursor on a keyword and press F1 for help.".to_string(),
+        };
+        if let Ok(mut printer) = self.printer.lock() {
+            let _ = printer.print(format!("{}\n", message));
+        }
+        Some(Cmd::Noop)
+    }
+}
+
+#[cfg(test)]
+#[path = "./help_tests.rs"]
+mod help_tests;
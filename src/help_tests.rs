@@ -0,0 +1,20 @@
+use super::*;
+
+#[test]
+fn test_lookup_ignores_case_and_call_syntax() {
+    assert!(lookup("select").is_some());
+    assert_eq!(lookup("ifnull("), lookup("IFNULL"));
+    assert!(lookup("ifnull(").is_some());
+    assert!(lookup("no_such_keyword").is_none());
+}
+
+#[test]
+fn test_word_at() {
+    let line = "SELECT IFNULL(a, 0) FROM t";
+    assert_eq!(word_at(line, 0), Some("SELECT"));
+    assert_eq!(word_at(line, 9), Some("IFNULL"));
+    assert_eq!(word_at(line, 13), Some("IFNULL"));
+    assert_eq!(word_at(line, line.len()), Some("t"));
+    assert_eq!(word_at(line, 100), Some("t"));
+    assert_eq!(word_at("a  b", 2), None);
+}
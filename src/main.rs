@@ -1,17 +1,52 @@
-use clap::{Arg, Command};
-use std::io::{self, Write};
+use clap::{Arg, ArgAction, Command};
+use std::io::{self, IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
 
 mod cli;
 mod commands;
 mod completion;
+mod config;
+mod credentials;
 mod database;
+mod help;
+mod pager;
+mod parsers;
+mod platform;
+mod settings;
+mod statement;
+mod util;
 
 use cli::Cli;
+use commands::{credential, schema_dump, DisplayFormat};
+use database::Connection;
 
 fn main() -> anyhow::Result<()> {
     let matches = Command::new("mysql-cli-rust")
         .version("0.1.0")
         .about("A MySQL client CLI written in Rust")
+        .subcommand_negates_reqs(true)
+        .subcommand(
+            Command::new("credential")
+                .about("Manage passwords in the encrypted credential store")
+                .subcommand_required(true)
+                .subcommand(
+                    Command::new("set")
+                        .about("Store the password of a profile")
+                        .arg(Arg::new("profile").required(true))
+                        .arg(
+                            Arg::new("keyring")
+                                .long("keyring")
+                                .action(ArgAction::SetTrue)
+                                .help("Keep the store key in the OS keyring (macOS, Windows) instead of deriving it from a master passphrase"),
+                        ),
+                )
+                .subcommand(
+                    Command::new("remove")
+                        .about("Remove the password of a profile")
+                        .arg(Arg::new("profile").required(true)),
+                )
+                .subcommand(Command::new("list").about("List stored profiles")),
+        )
         .arg(
             Arg::new("host")
                 .long("host")
@@ -44,6 +79,27 @@ fn main() -> anyhow::Result<()> {
                 .num_args(0..=1)
                 .require_equals(true),
         )
+        .arg(
+            Arg::new("profile")
+                .long("profile")
+                .value_name("NAME")
+                .help("Read the password from this credential store profile (default user@host)"),
+        )
+        .arg(
+            Arg::new("compress")
+                .short('C')
+                .long("compress")
+                .action(ArgAction::SetTrue)
+                .help("Use protocol compression, for large results over slow networks"),
+        )
+        .arg(
+            Arg::new("connection-attribute")
+                .long("connection-attribute")
+                .value_name("KEY=VALUE")
+                .action(ArgAction::Append)
+                .value_parser(database::parse_connect_attribute)
+                .help("Connection attribute shown in performance_schema.session_connect_attrs (repeatable)"),
+        )
         .arg(
             Arg::new("database")
                 .short('D')
@@ -51,8 +107,186 @@ fn main() -> anyhow::Result<()> {
                 .value_name("DATABASE")
                 .help("Database to use"),
         )
+        .arg(
+            Arg::new("execute")
+                .short('e')
+                .long("execute")
+                .value_name("STATEMENTS")
+                .help("Execute the statements and quit"),
+        )
+        .arg(
+            Arg::new("init-command")
+                .long("init-command")
+                .value_name("STATEMENTS")
+                .help("Statements to run after connecting and after every reconnect"),
+        )
+        .arg(
+            Arg::new("sql-mode")
+                .long("sql-mode")
+                .value_name("MODES")
+                .help("Set the session sql_mode after connecting, e.g. ONLY_FULL_GROUP_BY,STRICT_TRANS_TABLES"),
+        )
+        .arg(
+            Arg::new("charset")
+                .long("charset")
+                .alias("default-character-set")
+                .value_name("NAME")
+                .help("Run SET NAMES NAME after connecting and warn when the session uses another character set"),
+        )
+        .arg(
+            Arg::new("delimiter")
+                .long("delimiter")
+                .value_name("TOKEN")
+                .help("Statement delimiter to start with, e.g. $$ for routine dumps"),
+        )
+        .arg(
+            Arg::new("max-execution-time")
+                .long("max-execution-time")
+                .value_name("MS")
+                .value_parser(clap::value_parser!(u64))
+                .help("Stop interactive SELECTs after MS milliseconds of server execution time"),
+        )
+        .arg(
+            Arg::new("query-comment")
+                .long("query-comment")
+                .value_name("TEXT")
+                .help("Prefix statements with /* TEXT */; {user}, {pid} and {counter} are expanded"),
+        )
+        .arg(
+            Arg::new("search-path")
+                .long("search-path")
+                .value_name("DATABASES")
+                .help("Comma-separated databases whose tables are completed after the current one"),
+        )
+        .arg(
+            Arg::new("history-size")
+                .long("history-size")
+                .value_name("N")
+                .value_parser(clap::value_parser!(usize))
+                .default_value("10000")
+                .help("Keep the newest N entries of the line history and ~/.mysql_cli_history"),
+        )
+        .arg(
+            Arg::new("batch")
+                .short('B')
+                .long("batch")
+                .action(ArgAction::SetTrue)
+                .help("Read statements from stdin and run them without the interactive prompt"),
+        )
+        .arg(
+            Arg::new("rollback-on-error")
+                .long("rollback-on-error")
+                .action(ArgAction::SetTrue)
+                .help("Roll back the open transaction when a statement inside it fails"),
+        )
+        .arg(
+            Arg::new("dump-schema")
+                .long("dump-schema")
+                .value_name("FORMAT")
+                .num_args(0..=1)
+                .default_missing_value("json")
+                .help("Print the schema (all databases, or --database) as json or sql and exit"),
+        )
+        .arg(
+            Arg::new("no-defaults")
+                .long("no-defaults")
+                .action(ArgAction::SetTrue)
+                .conflicts_with_all(["defaults-file", "defaults-extra-file"])
+                .help("Don't read option files, the configuration file, the settings file or stored user@host credentials"),
+        )
+        .arg(
+            Arg::new("defaults-file")
+                .long("defaults-file")
+                .value_name("PATH")
+                .value_parser(clap::value_parser!(PathBuf))
+                .help("Read only this option file instead of ~/.my.cnf and the system ones"),
+        )
+        .arg(
+            Arg::new("defaults-extra-file")
+                .long("defaults-extra-file")
+                .value_name("PATH")
+                .value_parser(clap::value_parser!(PathBuf))
+                .help("Read this option file after the others"),
+        )
+        .arg(
+            Arg::new("settings-file")
+                .long("settings-file")
+                .value_name("PATH")
+                .value_parser(clap::value_parser!(PathBuf))
+                .help("Settings file loaded at startup and used by \\save-settings (default ~/.mysql_cli_settings.toml)"),
+        )
+        .arg(
+            Arg::new("no-column-names")
+                .short('N')
+                .long("no-column-names")
+                .action(ArgAction::SetTrue)
+                .overrides_with("column-names")
+                .help("Don't print column names; rows are printed tab-separated without borders"),
+        )
+        .arg(
+            Arg::new("column-names")
+                .long("column-names")
+                .action(ArgAction::SetTrue)
+                .overrides_with("no-column-names")
+                .help("Print column names in results (the default)"),
+        )
+        .arg(
+            Arg::new("silent")
+                .short('s')
+                .long("silent")
+                .action(ArgAction::Count)
+                .help("Print only results and errors; give twice (-ss) to also leave out column names"),
+        )
+        .arg(
+            Arg::new("raw")
+                .short('r')
+                .long("raw")
+                .action(ArgAction::SetTrue)
+                .help("Don't escape backslashes, newlines and tabs in tab-separated output"),
+        )
+        .arg(
+            Arg::new("xml")
+                .short('X')
+                .long("xml")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("count-only")
+                .help("Print result sets as XML like mysql --xml"),
+        )
+        .arg(
+            Arg::new("count-only")
+                .long("count-only")
+                .action(ArgAction::SetTrue)
+                .help("Print only the row count and timing of result sets, not the rows"),
+        )
+        .arg(
+            Arg::new("skip-banner")
+                .long("skip-banner")
+                .alias("no-banner")
+                .action(ArgAction::SetTrue)
+                .overrides_with("banner")
+                .help("Don't print the welcome banner (implied by --batch, -e and non-TTY stdin)"),
+        )
+        .arg(
+            Arg::new("banner")
+                .long("banner")
+                .action(ArgAction::SetTrue)
+                .overrides_with("skip-banner")
+                .help("Print the welcome banner even in batch mode"),
+        )
         .get_matches();
 
+    if let Some(("credential", credential_matches)) = matches.subcommand() {
+        return credential::run(credential_matches);
+    }
+
+    let option_files = config::OptionFiles {
+        no_defaults: matches.get_flag("no-defaults"),
+        defaults_file: matches.get_one::<PathBuf>("defaults-file").cloned(),
+        extra_file: matches.get_one::<PathBuf>("defaults-extra-file").cloned(),
+    };
+    option_files.check()?;
+    config::set_option_files(option_files);
+
     let host = matches.get_one::<String>("host").unwrap();
     let port: u16 = matches
         .get_one::<String>("port")
@@ -61,25 +295,191 @@ fn main() -> anyhow::Result<()> {
         .expect("Invalid port number");
     let user = matches.get_one::<String>("user").unwrap();
 
-    let password = if matches.contains_id("password") {
-        match matches.get_one::<String>("password") {
-            Some(p) => p.clone(),
-            None => {
-                print!("Enter password: ");
-                io::stdout().flush().unwrap();
-                rpassword::read_password().unwrap_or_default()
+    // Without --password=..., a stored profile (default user@host) comes before the prompt
+    let profile = matches.get_one::<String>("profile");
+    let password = match matches.get_one::<String>("password") {
+        Some(p) => p.clone(),
+        None => {
+            let name = match profile {
+                Some(profile) => Some(profile.clone()),
+                None if config::defaults_enabled() => Some(format!("{}@{}", user, host)),
+                None => None,
+            };
+            // Only an explicit --profile makes an unreadable store fatal
+            let stored = match (&name, profile) {
+                (Some(name), Some(_)) => credential::stored_password(name)?,
+                (Some(name), None) => credential::stored_password(name).unwrap_or_else(|e| {
+                    eprintln!("Warning: could not read stored credentials: {}", e);
+                    None
+                }),
+                (None, _) => None,
+            };
+            match (stored, profile) {
+                (Some(p), _) => p,
+                (None, Some(profile)) => {
+                    return Err(anyhow::anyhow!("No stored credentials for '{}'", profile));
+                }
+                (None, None) => {
+                    print!("Enter password: ");
+                    io::stdout().flush().unwrap();
+                    rpassword::read_password().unwrap_or_default()
+                }
             }
         }
-    } else {
-        print!("Enter password: ");
-        io::stdout().flush().unwrap();
-        rpassword::read_password().unwrap_or_default()
     };
 
     let database = matches.get_one::<String>("database").cloned();
+    // The settings file is applied after connecting, so a saved compress=1 is
+    // read here to compress the first connection too
+    let settings_file = matches.get_one::<PathBuf>("settings-file").cloned();
+    let settings_path = settings_file
+        .clone()
+        .or_else(config::settings_path)
+        .filter(|_| config::defaults_enabled() || settings_file.is_some());
+    database::set_compress(
+        matches.get_flag("compress") || settings_path.is_some_and(|path| saved_compress(&path)),
+    );
+    database::set_connect_attributes(
+        matches
+            .get_many::<(String, String)>("connection-attribute")
+            .map(|attributes| attributes.cloned().collect())
+            .unwrap_or_default(),
+    );
 
-    let mut cli = Cli::new(host, port, user, &password, database.as_deref())?;
-    cli.run()?;
+    // One-shot schema dump: no prompt, banner or init commands
+    if let Some(format) = matches.get_one::<String>("dump-schema") {
+        let format = schema_dump::DumpFormat::parse(format)?;
+        let mut connection = Connection::new(host, port, user, &password, database.as_deref())?;
+        print!(
+            "{}",
+            schema_dump::dump(&mut connection, database.as_deref(), format)?
+        );
+        return Ok(());
+    }
+
+    let execute = matches.get_one::<String>("execute").cloned();
+
+    // Statements given with -e or piped through stdin run without the REPL
+    let batch_input = match execute {
+        Some(statements) => Some(statements),
+        None if matches.get_flag("batch") || !io::stdin().is_terminal() => {
+            let mut input = String::new();
+            io::stdin().read_to_string(&mut input)?;
+            Some(input)
+        }
+        None => None,
+    };
+    let show_banner = cli::banner_enabled(
+        matches.get_flag("banner"),
+        matches.get_flag("skip-banner"),
+        batch_input.is_some(),
+    );
+
+    // --sql-mode runs with the init commands, so it is also restored after a reconnect
+    let mut init_command = matches.get_one::<String>("init-command").cloned();
+    if let Some(modes) = matches.get_one::<String>("sql-mode") {
+        let set_mode = format!("SET SESSION sql_mode = '{}'", modes.replace('\'', "''"));
+        init_command = Some(match init_command {
+            Some(commands) => format!("{}; {}", commands, set_mode),
+            None => set_mode,
+        });
+    }
+    let charset = matches.get_one::<String>("charset");
+    if let Some(charset) = charset {
+        if !charset
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_')
+        {
+            return Err(anyhow::anyhow!("Invalid character set name: {}", charset));
+        }
+        let set_names = format!("SET NAMES {}", charset);
+        init_command = Some(match init_command {
+            Some(commands) => format!("{}; {}", commands, set_names),
+            None => set_names,
+        });
+    }
+
+    let mut cli = Cli::new(
+        host,
+        port,
+        user,
+        &password,
+        database.as_deref(),
+        show_banner,
+        init_command.as_deref(),
+    )?;
+    if config::defaults_enabled() || settings_file.is_some() {
+        cli.set_settings_file(settings_file)?;
+    }
+    if let Some(search_path) = matches.get_one::<String>("search-path") {
+        cli.set_search_path(
+            search_path
+                .split(',')
+                .map(|db| db.trim().to_string())
+                .filter(|db| !db.is_empty())
+                .collect(),
+        );
+    }
+    cli.set_rollback_on_error(matches.get_flag("rollback-on-error"));
+    if matches.get_flag("compress") {
+        cli.set_compress_flag();
+    }
+    if let Some(delimiter) = matches.get_one::<String>("delimiter") {
+        if delimiter.trim().is_empty() || delimiter.contains(char::is_whitespace) {
+            return Err(anyhow::anyhow!(
+                "--delimiter must be a non-empty token without spaces"
+            ));
+        }
+        cli.set_delimiter(delimiter);
+    }
+    if let Some(template) = matches.get_one::<String>("query-comment") {
+        cli.set_query_comment(template);
+    }
+    if let Some(&ms) = matches.get_one::<u64>("max-execution-time") {
+        cli.set_max_execution_time(ms);
+    }
+    cli.set_silent_level(matches.get_count("silent"));
+    if matches.get_flag("no-column-names") {
+        cli.set_column_names(false);
+    } else if matches.get_flag("column-names") {
+        cli.set_column_names(true);
+    }
+    if matches.get_flag("raw") {
+        cli.set_raw(true);
+    }
+    if matches.get_flag("count-only") {
+        cli.set_display_format(DisplayFormat::CountOnly);
+    }
+    if matches.get_flag("xml") {
+        cli.set_display_format(DisplayFormat::Xml);
+    }
+    if let Some(charset) = charset {
+        cli.set_charset(charset);
+    }
+    // On stderr, so -X, -N -e and -s output stays clean
+    match cli.charset_warning() {
+        Ok(Some(warning)) => eprintln!("{}", warning),
+        Ok(None) => {}
+        Err(e) => eprintln!("Warning: could not check the character sets: {}", e),
+    }
+    cli.set_history_size(*matches.get_one::<usize>("history-size").unwrap())?;
+    match batch_input {
+        Some(input) => cli.run_batch(&input)?,
+        None => {
+            if config::defaults_enabled() {
+                if let Some(path) = config::history_path() {
+                    cli.set_history_file(path)?;
+                }
+            }
+            cli.run()?
+        }
+    }
 
     Ok(())
 }
+
+/// Whether the settings file at `path` turns on compression
+fn saved_compress(path: &Path) -> bool {
+    let mut saved = settings::Settings::default();
+    path.exists() && saved.load(path).is_ok() && saved.compress
+}
@@ -0,0 +1,51 @@
+/*!
+ * Output paging
+ *
+ * Long listings go through `$PAGER` (default `less -FRX`, `more` on Windows)
+ * when stdout is a terminal
+ */
+
+use crate::config::Source;
+use crate::platform::DEFAULT_PAGER;
+use std::io::{IsTerminal, Write};
+use std::process::{Command, Stdio};
+
+/// Print `text`, through the pager when it does not fit on the terminal
+pub fn page(text: &str) {
+    let rows = crossterm::terminal::size()
+        .map(|(_, rows)| rows as usize)
+        .unwrap_or(usize::MAX);
+    if !std::io::stdout().is_terminal() || text.lines().count() < rows || !run_pager(text) {
+        print!("{}", text);
+    }
+}
+
+/// The pager command, from `$PAGER` unless it is unset or empty
+pub fn pager_command() -> (String, Source) {
+    match std::env::var("PAGER") {
+        Ok(pager) if !pager.trim().is_empty() => (pager, Source::Env("PAGER")),
+        _ => (DEFAULT_PAGER.to_string(), Source::Default),
+    }
+}
+
+/// Pipe text into the pager, returning false when it could not be started
+fn run_pager(text: &str) -> bool {
+    let (pager, _) = pager_command();
+    let mut parts = pager.split_whitespace();
+    let Some(program) = parts.next() else {
+        return false;
+    };
+
+    let Ok(mut child) = Command::new(program)
+        .args(parts)
+        .stdin(Stdio::piped())
+        .spawn()
+    else {
+        return false;
+    };
+    if let Some(mut stdin) = child.stdin.take() {
+        // The pager may quit before reading everything
+        let _ = stdin.write_all(text.as_bytes());
+    }
+    child.wait().is_ok()
+}
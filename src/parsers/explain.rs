@@ -0,0 +1,138 @@
+/*!
+ * EXPLAIN output
+ *
+ * Turns the rows of a tabular `EXPLAIN` into typed plan rows and points out
+ * full table scans, temporary tables and filesorts
+ */
+
+use crate::database::QueryResult;
+
+/// One row of a tabular EXPLAIN; columns missing from older servers stay `None`
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ExplainRow {
+    pub id: Option<u64>,
+    pub select_type: String,
+    pub table: Option<String>,
+    pub partitions: Option<String>,
+    /// The `type` column: `ALL`, `index`, `range`, `ref`, `eq_ref`, `const`, ...
+    pub join_type: Option<String>,
+    pub possible_keys: Option<String>,
+    pub key: Option<String>,
+    pub key_len: Option<String>,
+    pub ref_: Option<String>,
+    pub rows: Option<u64>,
+    pub filtered: Option<f64>,
+    pub extra: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PlanIssue {
+    /// `type` is ALL: every row of the table is read
+    FullScan,
+    /// `Using temporary`: an internal temporary table is built
+    TemporaryTable,
+    /// `Using filesort`: rows are sorted after reading
+    Filesort,
+}
+
+impl ExplainRow {
+    pub fn issues(&self) -> Vec<PlanIssue> {
+        let mut issues = Vec::new();
+        if self.join_type.as_deref() == Some("ALL") {
+            issues.push(PlanIssue::FullScan);
+        }
+        let extra = self.extra.as_deref().unwrap_or_default();
+        if extra.contains("Using temporary") {
+            issues.push(PlanIssue::TemporaryTable);
+        }
+        if extra.contains("Using filesort") {
+            issues.push(PlanIssue::Filesort);
+        }
+        issues
+    }
+}
+
+/// Whether a result has the columns of a tabular EXPLAIN
+pub fn is_explain_output(result: &QueryResult) -> bool {
+    ["select_type", "type", "extra"]
+        .iter()
+        .all(|name| column_index(result, name).is_some())
+}
+
+/// Typed rows of a tabular EXPLAIN, with columns looked up by name
+pub fn parse_explain_output(result: &QueryResult) -> Vec<ExplainRow> {
+    let index = |name: &str| column_index(result, name);
+    let (id, select_type, table, partitions, join_type) = (
+        index("id"),
+        index("select_type"),
+        index("table"),
+        index("partitions"),
+        index("type"),
+    );
+    let (possible_keys, key, key_len, ref_, rows, filtered, extra) = (
+        index("possible_keys"),
+        index("key"),
+        index("key_len"),
+        index("ref"),
+        index("rows"),
+        index("filtered"),
+        index("extra"),
+    );
+
+    result
+        .rows
+        .iter()
+        .map(|row| {
+            let text = |column: Option<usize>| {
+                column
+                    .and_then(|i| row.get(i))
+                    .filter(|value| *value != "NULL")
+                    .cloned()
+            };
+            ExplainRow {
+                id: text(id).and_then(|v| v.parse().ok()),
+                select_type: text(select_type).unwrap_or_default(),
+                table: text(table),
+                partitions: text(partitions),
+                join_type: text(join_type),
+                possible_keys: text(possible_keys),
+                key: text(key),
+                key_len: text(key_len),
+                ref_: text(ref_),
+                rows: text(rows).and_then(|v| v.parse().ok()),
+                filtered: text(filtered).and_then(|v| v.parse().ok()),
+                extra: text(extra),
+            }
+        })
+        .collect()
+}
+
+/// One line per problematic plan row, e.g. `Full table scan on orders (~1200 rows)`
+pub fn annotate(rows: &[ExplainRow]) -> Vec<String> {
+    let mut notes = Vec::new();
+    for row in rows {
+        let table = row.table.as_deref().unwrap_or("?");
+        for issue in row.issues() {
+            notes.push(match issue {
+                PlanIssue::FullScan => match row.rows {
+                    Some(count) => format!("Full table scan on {} (~{} rows)", table, count),
+                    None => format!("Full table scan on {}", table),
+                },
+                PlanIssue::TemporaryTable => format!("Temporary table for {}", table),
+                PlanIssue::Filesort => format!("Filesort for {}", table),
+            });
+        }
+    }
+    notes
+}
+
+fn column_index(result: &QueryResult, name: &str) -> Option<usize> {
+    result
+        .columns
+        .iter()
+        .position(|column| column.eq_ignore_ascii_case(name))
+}
+
+#[cfg(test)]
+#[path = "./explain_tests.rs"]
+mod explain_tests;
@@ -0,0 +1,92 @@
+use super::*;
+
+fn strings(values: &[&str]) -> Vec<String> {
+    values.iter().map(|value| value.to_string()).collect()
+}
+
+fn plan() -> QueryResult {
+    QueryResult {
+        columns: strings(&[
+            "id",
+            "select_type",
+            "table",
+            "partitions",
+            "type",
+            "possible_keys",
+            "key",
+            "key_len",
+            "ref",
+            "rows",
+            "filtered",
+            "Extra",
+        ]),
+        rows: vec![
+            strings(&[
+                "1",
+                "SIMPLE",
+                "o",
+                "NULL",
+                "ALL",
+                "NULL",
+                "NULL",
+                "NULL",
+                "NULL",
+                "1200",
+                "10.00",
+                "Using where; Using temporary; Using filesort",
+            ]),
+            strings(&[
+                "1",
+                "SIMPLE",
+                "u",
+                "NULL",
+                "eq_ref",
+                "PRIMARY",
+                "PRIMARY",
+                "4",
+                "shop.o.user_id",
+                "1",
+                "100.00",
+                "NULL",
+            ]),
+        ],
+        tables: Vec::new(),
+        nulls: Vec::new(),
+    }
+}
+
+#[test]
+fn test_parse_explain_output() {
+    let rows = parse_explain_output(&plan());
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0].id, Some(1));
+    assert_eq!(rows[0].join_type.as_deref(), Some("ALL"));
+    assert_eq!(rows[0].key, None);
+    assert_eq!(rows[0].rows, Some(1200));
+    assert_eq!(rows[0].filtered, Some(10.0));
+    assert_eq!(rows[1].ref_.as_deref(), Some("shop.o.user_id"));
+    assert_eq!(rows[1].extra, None);
+    assert!(is_explain_output(&plan()));
+}
+
+#[test]
+fn test_plan_issues_and_annotations() {
+    let rows = parse_explain_output(&plan());
+    assert_eq!(
+        rows[0].issues(),
+        vec![
+            PlanIssue::FullScan,
+            PlanIssue::TemporaryTable,
+            PlanIssue::Filesort
+        ]
+    );
+    assert!(rows[1].issues().is_empty());
+    assert_eq!(
+        annotate(&rows),
+        vec![
+            "Full table scan on o (~1200 rows)",
+            "Temporary table for o",
+            "Filesort for o"
+        ]
+    );
+}
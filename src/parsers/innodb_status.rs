@@ -0,0 +1,316 @@
+/*!
+ * InnoDB monitor output
+ *
+ * Extracts structured data from the text block returned by
+ * `SHOW ENGINE INNODB STATUS`, section by section
+ */
+
+use regex::Regex;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct InnoDBStatus {
+    pub semaphores: Semaphores,
+    pub transactions: Transactions,
+    pub file_io: FileIo,
+    pub buffer_pool_stats: BufferPoolStats,
+    pub row_operations: RowOperations,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct Semaphores {
+    pub reservation_count: Option<u64>,
+    pub signal_count: Option<u64>,
+    /// `--Thread ... has waited at ...` lines of threads waiting on a semaphore
+    pub waiting_threads: Vec<String>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct Transactions {
+    pub trx_id_counter: Option<u64>,
+    pub history_list_length: Option<u64>,
+    pub transactions: Vec<Transaction>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct Transaction {
+    pub id: String,
+    /// e.g. `ACTIVE 7 sec starting index read` or `not started`
+    pub state: String,
+    pub active_seconds: Option<u64>,
+    pub thread_id: Option<u64>,
+    pub query_id: Option<u64>,
+    pub query: Option<String>,
+    pub lock_wait: Option<LockWait>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct LockWait {
+    pub seconds: Option<u64>,
+    /// The lock being waited for, e.g. `RECORD LOCKS space id 2 page no 4 ...`
+    pub lock: String,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct FileIo {
+    pub os_file_reads: Option<u64>,
+    pub os_file_writes: Option<u64>,
+    pub os_fsyncs: Option<u64>,
+    pub reads_per_sec: Option<f64>,
+    pub writes_per_sec: Option<f64>,
+    pub fsyncs_per_sec: Option<f64>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct BufferPoolStats {
+    /// Sizes in pages
+    pub buffer_pool_size: Option<u64>,
+    pub free_buffers: Option<u64>,
+    pub database_pages: Option<u64>,
+    pub modified_db_pages: Option<u64>,
+    /// Hits per 1000 page requests
+    pub hit_rate: Option<u64>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct RowOperations {
+    pub queries_inside_innodb: Option<u64>,
+    pub queries_in_queue: Option<u64>,
+    pub rows_inserted: Option<u64>,
+    pub rows_updated: Option<u64>,
+    pub rows_deleted: Option<u64>,
+    pub rows_read: Option<u64>,
+    pub inserts_per_sec: Option<f64>,
+    pub updates_per_sec: Option<f64>,
+    pub deletes_per_sec: Option<f64>,
+    pub reads_per_sec: Option<f64>,
+}
+
+/// Parse `SHOW ENGINE INNODB STATUS` text; sections that are missing stay empty
+pub fn parse_innodb_status(text: &str) -> InnoDBStatus {
+    let text = text.replace("\r\n", "\n");
+    let sections = split_sections(&text);
+    let section = |name: &str| sections.get(name).copied().unwrap_or_default();
+
+    InnoDBStatus {
+        semaphores: parse_semaphores(section("SEMAPHORES")),
+        transactions: parse_transactions(section("TRANSACTIONS")),
+        file_io: parse_file_io(section("FILE I/O")),
+        buffer_pool_stats: parse_buffer_pool(section("BUFFER POOL AND MEMORY")),
+        row_operations: parse_row_operations(section("ROW OPERATIONS")),
+    }
+}
+
+/// Section bodies keyed by header, where a header is a name between two dashed lines
+fn split_sections(text: &str) -> HashMap<&str, &str> {
+    static HEADER: OnceLock<Regex> = OnceLock::new();
+    let header =
+        HEADER.get_or_init(|| Regex::new(r"(?m)^-{3,}\n([A-Z][A-Z /]+)\n-{3,}\n").unwrap());
+    let headers: Vec<_> = header.captures_iter(text).collect();
+    let mut sections = HashMap::new();
+    for (i, captures) in headers.iter().enumerate() {
+        let start = captures.get(0).unwrap().end();
+        let end = headers
+            .get(i + 1)
+            .map_or(text.len(), |next| next.get(0).unwrap().start());
+        sections.insert(captures.get(1).unwrap().as_str().trim(), &text[start..end]);
+    }
+    sections
+}
+
+/// First capture of `pattern` in `text`, parsed; patterns are compiled once
+fn number<T: std::str::FromStr>(text: &str, pattern: &'static str) -> Option<T> {
+    static PATTERNS: OnceLock<Mutex<HashMap<&'static str, Regex>>> = OnceLock::new();
+    let regex = PATTERNS
+        .get_or_init(Default::default)
+        .lock()
+        .ok()?
+        .entry(pattern)
+        .or_insert_with(|| Regex::new(pattern).unwrap())
+        .clone();
+    regex
+        .captures(text)
+        .and_then(|captures| captures.get(1))
+        .and_then(|value| value.as_str().parse().ok())
+}
+
+fn parse_semaphores(text: &str) -> Semaphores {
+    Semaphores {
+        reservation_count: number(text, r"reservation count (\d+)"),
+        signal_count: number(text, r"signal count (\d+)"),
+        waiting_threads: text
+            .lines()
+            .filter(|line| line.starts_with("--Thread "))
+            .map(|line| line.trim_start_matches('-').to_string())
+            .collect(),
+    }
+}
+
+fn parse_transactions(text: &str) -> Transactions {
+    let mut transactions = Transactions {
+        trx_id_counter: number(text, r"(?m)^Trx id counter (\d+)"),
+        history_list_length: number(text, r"(?m)^History list length (\d+)"),
+        transactions: Vec::new(),
+    };
+
+    static HEADER: OnceLock<Regex> = OnceLock::new();
+    static THREAD: OnceLock<Regex> = OnceLock::new();
+    static WAITING: OnceLock<Regex> = OnceLock::new();
+    let header = HEADER.get_or_init(|| Regex::new(r"^---TRANSACTION (\w+), (.*)$").unwrap());
+    let thread =
+        THREAD.get_or_init(|| Regex::new(r"^MySQL thread id (\d+), .*query id (\d+)").unwrap());
+    let waiting =
+        WAITING.get_or_init(|| Regex::new(r"^------- TRX HAS BEEN WAITING (\d+) SEC").unwrap());
+
+    let lines: Vec<&str> = text.lines().collect();
+    let mut current: Option<Transaction> = None;
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+        if let Some(captures) = header.captures(line) {
+            transactions.transactions.extend(current.take());
+            let state = captures[2].trim().to_string();
+            current = Some(Transaction {
+                id: captures[1].to_string(),
+                active_seconds: number(&state, r"^ACTIVE (?:\(PREPARED\) )?(\d+) sec"),
+                state,
+                ..Default::default()
+            });
+        } else if let Some(trx) = current.as_mut() {
+            if let Some(captures) = thread.captures(line) {
+                trx.thread_id = captures[1].parse().ok();
+                trx.query_id = captures[2].parse().ok();
+                // The statement, when there is one, follows the thread line
+                if let Some(next) = lines.get(i + 1).filter(|next| is_query_line(next)) {
+                    trx.query = Some(next.to_string());
+                    i += 1;
+                }
+            } else if let Some(captures) = waiting.captures(line) {
+                trx.lock_wait = Some(LockWait {
+                    seconds: captures[1].parse().ok(),
+                    lock: lines.get(i + 1).map_or(String::new(), |l| l.to_string()),
+                });
+                i += 1;
+            }
+        }
+        i += 1;
+    }
+    transactions.transactions.extend(current);
+    transactions
+}
+
+/// Lines that are part of the transaction report rather than its statement
+fn is_query_line(line: &str) -> bool {
+    const REPORT_PREFIXES: &[&str] = &[
+        "---",
+        "Trx read view",
+        "TABLE LOCK",
+        "RECORD LOCKS",
+        "Record lock",
+        "mysql tables in use",
+    ];
+    !line.trim().is_empty() && !REPORT_PREFIXES.iter().any(|p| line.starts_with(p))
+}
+
+fn parse_file_io(text: &str) -> FileIo {
+    FileIo {
+        os_file_reads: number(text, r"(\d+) OS file reads"),
+        os_file_writes: number(text, r"(\d+) OS file writes"),
+        os_fsyncs: number(text, r"(\d+) OS fsyncs"),
+        reads_per_sec: number(text, r"([\d.]+) reads/s"),
+        writes_per_sec: number(text, r"([\d.]+) writes/s"),
+        fsyncs_per_sec: number(text, r"([\d.]+) fsyncs/s"),
+    }
+}
+
+fn parse_buffer_pool(text: &str) -> BufferPoolStats {
+    BufferPoolStats {
+        buffer_pool_size: number(text, r"(?m)^Buffer pool size\s+(\d+)"),
+        free_buffers: number(text, r"(?m)^Free buffers\s+(\d+)"),
+        database_pages: number(text, r"(?m)^Database pages\s+(\d+)"),
+        modified_db_pages: number(text, r"(?m)^Modified db pages\s+(\d+)"),
+        hit_rate: number(text, r"Buffer pool hit rate (\d+) / 1000"),
+    }
+}
+
+fn parse_row_operations(text: &str) -> RowOperations {
+    RowOperations {
+        queries_inside_innodb: number(text, r"(\d+) queries inside InnoDB"),
+        queries_in_queue: number(text, r"(\d+) queries in queue"),
+        rows_inserted: number(text, r"Number of rows inserted (\d+)"),
+        rows_updated: number(text, r"Number of rows inserted \d+, updated (\d+)"),
+        rows_deleted: number(text, r"deleted (\d+), read"),
+        rows_read: number(text, r"Number of rows inserted .*, read (\d+)"),
+        inserts_per_sec: number(text, r"([\d.]+) inserts/s"),
+        updates_per_sec: number(text, r"([\d.]+) updates/s"),
+        deletes_per_sec: number(text, r"([\d.]+) deletes/s"),
+        reads_per_sec: number(text, r"([\d.]+) reads/s"),
+    }
+}
+
+/// A few lines summing up the parsed status
+pub fn format_summary(status: &InnoDBStatus) -> String {
+    let show = |value: Option<u64>| value.map_or("?".to_string(), |v| v.to_string());
+    let trx = &status.transactions;
+    let waiting: Vec<&Transaction> = trx
+        .transactions
+        .iter()
+        .filter(|t| t.lock_wait.is_some())
+        .collect();
+
+    let mut lines = vec![
+        format!(
+            "Semaphores:      reservation count {}, signal count {}, {} waiting thread(s)",
+            show(status.semaphores.reservation_count),
+            show(status.semaphores.signal_count),
+            status.semaphores.waiting_threads.len()
+        ),
+        format!(
+            "Transactions:    {} ({} waiting for a lock), history list length {}",
+            trx.transactions.len(),
+            waiting.len(),
+            show(trx.history_list_length)
+        ),
+    ];
+    for t in waiting {
+        let wait = t.lock_wait.as_ref().unwrap();
+        lines.push(format!(
+            "  trx {} (thread {}) waiting {} sec: {}",
+            t.id,
+            show(t.thread_id),
+            show(wait.seconds),
+            t.query.as_deref().unwrap_or("")
+        ));
+        lines.push(format!("    {}", wait.lock));
+    }
+    let io = &status.file_io;
+    lines.push(format!(
+        "File I/O:        {} reads, {} writes, {} fsyncs",
+        show(io.os_file_reads),
+        show(io.os_file_writes),
+        show(io.os_fsyncs)
+    ));
+    let pool = &status.buffer_pool_stats;
+    lines.push(format!(
+        "Buffer pool:     {} pages, {} free, {} modified, hit rate {} / 1000",
+        show(pool.buffer_pool_size),
+        show(pool.free_buffers),
+        show(pool.modified_db_pages),
+        show(pool.hit_rate)
+    ));
+    let rows = &status.row_operations;
+    lines.push(format!(
+        "Row operations:  {} inserted, {} updated, {} deleted, {} read",
+        show(rows.rows_inserted),
+        show(rows.rows_updated),
+        show(rows.rows_deleted),
+        show(rows.rows_read)
+    ));
+    lines.join("\n")
+}
+
+#[cfg(test)]
+#[path = "./innodb_status_tests.rs"]
+mod innodb_status_tests;
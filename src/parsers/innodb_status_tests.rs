@@ -0,0 +1,109 @@
+use super::*;
+
+const STATUS: &str = "
+=====================================
+2024-05-02 10:15:00 0x7f3a INNODB MONITOR OUTPUT
+=====================================
+Per second averages calculated from the last 12 seconds
+----------
+SEMAPHORES
+----------
+OS WAIT ARRAY INFO: reservation count 1234
+--Thread 140 has waited at buf0buf.cc line 4321 for 2 seconds the semaphore:
+OS WAIT ARRAY INFO: signal count 1180
+RW-shared spins 0, rounds 0, OS waits 0
+------------
+TRANSACTIONS
+------------
+Trx id counter 5012
+Purge done for trx's n:o < 5000 undo n:o < 0 state: running but idle
+History list length 17
+LIST OF TRANSACTIONS FOR EACH SESSION:
+---TRANSACTION 421873, not started
+0 lock struct(s), heap size 1128, 0 row lock(s)
+---TRANSACTION 5011, ACTIVE 7 sec starting index read
+mysql tables in use 1, locked 1
+LOCK WAIT 2 lock struct(s), heap size 1128, 1 row lock(s)
+MySQL thread id 12, OS thread handle 1400, query id 88 localhost root updating
+UPDATE accounts SET balance = 0 WHERE id = 1
+------- TRX HAS BEEN WAITING 7 SEC FOR THIS LOCK TO BE GRANTED:
+RECORD LOCKS space id 2 page no 4 n bits 72 index PRIMARY of table `bank`.`accounts` trx id 5011 lock_mode X locks rec but not gap waiting
+Record lock, heap no 2 PHYSICAL RECORD: n_fields 4; compact format; info bits 0
+------------------
+---TRANSACTION 5010, ACTIVE 30 sec
+2 lock struct(s), heap size 1128, 1 row lock(s), undo log entries 1
+MySQL thread id 11, OS thread handle 1399, query id 80 localhost root
+--------
+FILE I/O
+--------
+I/O thread 0 state: waiting for completed aio requests (insert buffer thread)
+Pending flushes (fsync) log: 0; buffer pool: 0
+850 OS file reads, 1200 OS file writes, 400 OS fsyncs
+0.00 reads/s, 0 avg bytes/read, 0.42 writes/s, 0.17 fsyncs/s
+----------------------
+BUFFER POOL AND MEMORY
+----------------------
+Total large memory allocated 137363456
+Buffer pool size   8192
+Free buffers       7000
+Database pages     1180
+Modified db pages  3
+Buffer pool hit rate 998 / 1000, young-making rate 0 / 1000 not 0 / 1000
+--------------
+ROW OPERATIONS
+--------------
+1 queries inside InnoDB, 0 queries in queue
+Number of rows inserted 100, updated 5, deleted 2, read 5000
+0.50 inserts/s, 0.08 updates/s, 0.00 deletes/s, 12.25 reads/s
+----------------------------
+END OF INNODB MONITOR OUTPUT
+============================
+";
+
+#[test]
+fn test_parse_sections() {
+    let status = parse_innodb_status(STATUS);
+    assert_eq!(status.semaphores.reservation_count, Some(1234));
+    assert_eq!(status.semaphores.signal_count, Some(1180));
+    assert_eq!(status.semaphores.waiting_threads.len(), 1);
+    assert_eq!(status.file_io.os_fsyncs, Some(400));
+    assert_eq!(status.file_io.writes_per_sec, Some(0.42));
+    assert_eq!(status.buffer_pool_stats.buffer_pool_size, Some(8192));
+    assert_eq!(status.buffer_pool_stats.modified_db_pages, Some(3));
+    assert_eq!(status.buffer_pool_stats.hit_rate, Some(998));
+    assert_eq!(status.row_operations.rows_updated, Some(5));
+    assert_eq!(status.row_operations.rows_read, Some(5000));
+    assert_eq!(status.row_operations.reads_per_sec, Some(12.25));
+}
+
+#[test]
+fn test_parse_transactions_with_lock_wait() {
+    let trx = parse_innodb_status(STATUS).transactions;
+    assert_eq!(trx.trx_id_counter, Some(5012));
+    assert_eq!(trx.history_list_length, Some(17));
+    assert_eq!(trx.transactions.len(), 3);
+
+    let waiting = &trx.transactions[1];
+    assert_eq!(waiting.id, "5011");
+    assert_eq!(waiting.active_seconds, Some(7));
+    assert_eq!(waiting.thread_id, Some(12));
+    assert_eq!(
+        waiting.query.as_deref(),
+        Some("UPDATE accounts SET balance = 0 WHERE id = 1")
+    );
+    let wait = waiting.lock_wait.as_ref().unwrap();
+    assert_eq!(wait.seconds, Some(7));
+    assert!(wait.lock.starts_with("RECORD LOCKS space id 2"));
+
+    let idle = &trx.transactions[2];
+    assert_eq!(idle.query, None);
+    assert_eq!(idle.lock_wait, None);
+    assert_eq!(trx.transactions[0].state, "not started");
+}
+
+#[test]
+fn test_missing_sections_stay_empty() {
+    let status = parse_innodb_status("no monitor output here");
+    assert_eq!(status, InnoDBStatus::default());
+    assert!(serde_json::to_string(&status).is_ok());
+}
@@ -0,0 +1,6 @@
+/*!
+ * Parsers for free-form server output
+ */
+
+pub mod explain;
+pub mod innodb_status;
@@ -0,0 +1,16 @@
+/*!
+ * Platform differences
+ *
+ * The few places where Unix and Windows behave differently: system option
+ * files, the default pager and restricting a file to its owner
+ */
+
+#[cfg(unix)]
+mod unix;
+#[cfg(unix)]
+pub use unix::*;
+
+#[cfg(windows)]
+mod windows;
+#[cfg(windows)]
+pub use windows::*;
@@ -0,0 +1,12 @@
+use std::fs;
+use std::io;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+
+/// Pager used when `$PAGER` is not set
+pub const DEFAULT_PAGER: &str = "less -FRX";
+
+/// Make a file readable and writable by its owner only
+pub fn restrict_to_owner(path: &Path) -> io::Result<()> {
+    fs::set_permissions(path, fs::Permissions::from_mode(0o600))
+}
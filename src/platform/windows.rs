@@ -0,0 +1,10 @@
+use std::io;
+use std::path::Path;
+
+/// Pager used when `$PAGER` is not set; `less` is rarely installed on Windows
+pub const DEFAULT_PAGER: &str = "more";
+
+/// Files under the user profile already inherit an ACL limited to the user
+pub fn restrict_to_owner(_path: &Path) -> io::Result<()> {
+    Ok(())
+}
@@ -0,0 +1,341 @@
+/*!
+ * Runtime settings
+ *
+ * Session-level options changed with `\set name=value`, saved to and
+ * loaded from a TOML settings file with `\save-settings` / `\load-settings`
+ */
+
+use anyhow::{anyhow, Context, Result};
+use rustyline::CompletionType;
+use std::fs;
+use std::path::Path;
+
+/// Runtime settings adjustable from the prompt
+#[derive(Debug, Clone)]
+pub struct Settings {
+    /// Fragmentation percentage above which `\check` suggests OPTIMIZE TABLE
+    pub fragmentation_threshold: f64,
+    /// Run EXPLAIN after SELECTs and suggest covering indexes for full scans; this
+    /// sends an extra EXPLAIN to the server for every SELECT
+    pub index_advice: bool,
+    /// Tab shows all matches (`list`) or cycles through them (`circular`)
+    pub completion_type: CompletionType,
+    /// Show completion descriptions in a bottom status line instead of the list
+    pub completion_status_bar: bool,
+    /// Completion list size; `None` uses per-context defaults, 0 is unlimited
+    pub completion_limit: Option<usize>,
+    /// Run single-line statements that parse as complete SQL without a trailing `;`
+    pub auto_semicolon: bool,
+    /// Append the delimiter when a completion finishes a statement (`USE db`, `SHOW TABLES`)
+    pub completion_semicolon: bool,
+    /// Alt-<key> re-displays the last result vertically
+    pub vertical_key: char,
+    /// Show repeated identical SELECTs from a result cache; locking reads, `SELECT ... INTO`
+    /// and SELECTs calling non-deterministic or lock functions always run
+    pub query_cache: bool,
+    /// Seconds a cached result stays valid
+    pub query_cache_ttl: u64,
+    /// Prefix statement summaries with a success/error marker
+    pub marker: bool,
+    /// Statements larger than this many bytes are checked for pasted data; 0 disables
+    pub paste_guard_bytes: usize,
+    /// Statements with more than this many lines are checked for pasted data; 0 disables
+    pub paste_guard_lines: usize,
+    /// Print every single-column result as raw text, like EXPLAIN ANALYZE trees
+    pub raw_single_column: bool,
+    /// EXPLAIN ANALYZE timings of at least this many milliseconds are shown in red
+    pub explain_slow_ms: u64,
+    /// Show results wider than the terminal vertically
+    pub auto_vertical_output: bool,
+    /// Print the header row of result tables; off prints tab-separated rows
+    pub column_names: bool,
+    /// Print backslashes, newlines and tabs in tab-separated rows as they are
+    pub raw: bool,
+    /// Ask for protocol compression; changes take effect on the next connection
+    pub compress: bool,
+    /// Results with more columns get a hint to show them vertically; 0 disables
+    pub wide_result_columns: usize,
+    /// Custom prompt with `\d`, `\r`, `\v` escapes; empty uses the built-in prompt
+    pub prompt: String,
+    /// Don't add a line to history when it repeats the previous entry
+    pub history_ignore_dups: bool,
+    /// Don't add lines typed with a leading space to history
+    pub history_ignore_space: bool,
+    /// Mask password literals before lines are added to history
+    pub history_scrub_passwords: bool,
+    /// Entries longer than this many bytes aren't saved to the history file; 0 saves any length
+    pub history_max_entry_bytes: usize,
+}
+
+impl Settings {
+    /// Create settings with default values
+    pub fn new() -> Self {
+        Self {
+            fragmentation_threshold: 30.0,
+            index_advice: false,
+            completion_type: CompletionType::List,
+            completion_status_bar: false,
+            completion_limit: None,
+            auto_semicolon: false,
+            completion_semicolon: true,
+            vertical_key: 'v',
+            query_cache: false,
+            query_cache_ttl: 60,
+            marker: false,
+            paste_guard_bytes: 512 * 1024,
+            paste_guard_lines: 500,
+            raw_single_column: false,
+            explain_slow_ms: 100,
+            auto_vertical_output: false,
+            column_names: true,
+            raw: false,
+            compress: false,
+            wide_result_columns: 25,
+            prompt: String::new(),
+            history_ignore_dups: true,
+            history_ignore_space: false,
+            history_scrub_passwords: true,
+            history_max_entry_bytes: 64 * 1024,
+        }
+    }
+
+    /// Apply a `name=value` assignment
+    pub fn apply(&mut self, assignment: &str) -> Result<()> {
+        let (name, value) = assignment
+            .split_once('=')
+            .ok_or_else(|| anyhow!("Usage: \\set <name>=<value>"))?;
+        self.set(name.trim(), value.trim())
+    }
+
+    /// Set a single setting by name
+    pub fn set(&mut self, name: &str, value: &str) -> Result<()> {
+        match name {
+            "fragmentation_threshold" => {
+                let threshold: f64 = value
+                    .parse()
+                    .map_err(|_| anyhow!("Invalid number for {}: {}", name, value))?;
+                if !(0.0..=100.0).contains(&threshold) {
+                    return Err(anyhow!("{} must be between 0 and 100", name));
+                }
+                self.fragmentation_threshold = threshold;
+            }
+            "index_advice" => self.index_advice = parse_bool(name, value)?,
+            "completion_limit" => {
+                self.completion_limit = match value {
+                    "auto" => None,
+                    _ => Some(value.parse().map_err(|_| {
+                        anyhow!("Invalid value for {}: {} (number or 'auto')", name, value)
+                    })?),
+                }
+            }
+            "auto_semicolon" => self.auto_semicolon = parse_bool(name, value)?,
+            "completion_semicolon" => self.completion_semicolon = parse_bool(name, value)?,
+            "query_cache" => self.query_cache = parse_bool(name, value)?,
+            "marker" => self.marker = parse_bool(name, value)?,
+            "raw_single_column" => self.raw_single_column = parse_bool(name, value)?,
+            "auto_vertical_output" => self.auto_vertical_output = parse_bool(name, value)?,
+            "column_names" => self.column_names = parse_bool(name, value)?,
+            "raw" => self.raw = parse_bool(name, value)?,
+            "compress" => self.compress = parse_bool(name, value)?,
+            "wide_result_columns" => {
+                self.wide_result_columns = value
+                    .parse()
+                    .map_err(|_| anyhow!("Invalid number for {}: {}", name, value))?;
+            }
+            "explain_slow_ms" => {
+                self.explain_slow_ms = value.parse().map_err(|_| {
+                    anyhow!("Invalid number of milliseconds for {}: {}", name, value)
+                })?;
+            }
+            "history_ignore_dups" => self.history_ignore_dups = parse_bool(name, value)?,
+            "history_ignore_space" => self.history_ignore_space = parse_bool(name, value)?,
+            "history_scrub_passwords" => self.history_scrub_passwords = parse_bool(name, value)?,
+            "history_max_entry_bytes" => {
+                self.history_max_entry_bytes = value
+                    .parse()
+                    .map_err(|_| anyhow!("Invalid number for {}: {}", name, value))?;
+            }
+            "query_cache_ttl" => {
+                self.query_cache_ttl = value
+                    .parse()
+                    .map_err(|_| anyhow!("Invalid number of seconds for {}: {}", name, value))?;
+            }
+            "paste_guard_bytes" | "paste_guard_lines" => {
+                let limit = value
+                    .parse()
+                    .map_err(|_| anyhow!("Invalid number for {}: {}", name, value))?;
+                if name == "paste_guard_bytes" {
+                    self.paste_guard_bytes = limit;
+                } else {
+                    self.paste_guard_lines = limit;
+                }
+            }
+            "completion_status_bar" => self.completion_status_bar = parse_bool(name, value)?,
+            "prompt" => {
+                // Quotes keep trailing spaces, which `\set` otherwise trims
+                let unquoted = ['\'', '"']
+                    .iter()
+                    .find_map(|q| value.strip_prefix(*q).and_then(|v| v.strip_suffix(*q)));
+                self.prompt = unquoted.unwrap_or(value).to_string();
+            }
+            "vertical_key" => {
+                let lower = value.to_lowercase();
+                let key = lower.strip_prefix("alt-").unwrap_or(&lower);
+                let mut chars = key.chars();
+                self.vertical_key = match (chars.next(), chars.next()) {
+                    (Some(c), None) if c.is_ascii_alphanumeric() => c,
+                    _ => return Err(anyhow!("{} must be a letter or digit, e.g. alt-v", name)),
+                };
+            }
+            "completion_type" => {
+                self.completion_type = match value.to_lowercase().as_str() {
+                    "list" => CompletionType::List,
+                    "circular" => CompletionType::Circular,
+                    _ => return Err(anyhow!("{} must be 'list' or 'circular'", name)),
+                }
+            }
+            _ => return Err(anyhow!("Unknown setting: {}", name)),
+        }
+        Ok(())
+    }
+
+    /// List all settings as (name, value) pairs
+    pub fn entries(&self) -> Vec<(&'static str, String)> {
+        vec![
+            (
+                "fragmentation_threshold",
+                self.fragmentation_threshold.to_string(),
+            ),
+            ("index_advice", format_bool(self.index_advice)),
+            (
+                "completion_type",
+                match self.completion_type {
+                    CompletionType::Circular => "circular",
+                    _ => "list",
+                }
+                .to_string(),
+            ),
+            (
+                "completion_status_bar",
+                format_bool(self.completion_status_bar),
+            ),
+            ("auto_semicolon", format_bool(self.auto_semicolon)),
+            (
+                "completion_semicolon",
+                format_bool(self.completion_semicolon),
+            ),
+            ("vertical_key", format!("alt-{}", self.vertical_key)),
+            ("query_cache", format_bool(self.query_cache)),
+            ("query_cache_ttl", self.query_cache_ttl.to_string()),
+            ("marker", format_bool(self.marker)),
+            ("paste_guard_bytes", self.paste_guard_bytes.to_string()),
+            ("paste_guard_lines", self.paste_guard_lines.to_string()),
+            ("raw_single_column", format_bool(self.raw_single_column)),
+            ("explain_slow_ms", self.explain_slow_ms.to_string()),
+            (
+                "auto_vertical_output",
+                format_bool(self.auto_vertical_output),
+            ),
+            ("wide_result_columns", self.wide_result_columns.to_string()),
+            ("column_names", format_bool(self.column_names)),
+            ("raw", format_bool(self.raw)),
+            ("compress", format_bool(self.compress)),
+            ("prompt", format!("'{}'", self.prompt)),
+            ("history_ignore_dups", format_bool(self.history_ignore_dups)),
+            (
+                "history_ignore_space",
+                format_bool(self.history_ignore_space),
+            ),
+            (
+                "history_scrub_passwords",
+                format_bool(self.history_scrub_passwords),
+            ),
+            (
+                "history_max_entry_bytes",
+                self.history_max_entry_bytes.to_string(),
+            ),
+            (
+                "completion_limit",
+                self.completion_limit
+                    .map_or("auto".to_string(), |limit| limit.to_string()),
+            ),
+        ]
+    }
+
+    /// Serialize all settings as a TOML table of `name = "value"` strings
+    pub fn to_toml(&self) -> Result<String> {
+        let table: toml::Table = self
+            .entries()
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), toml::Value::String(value)))
+            .collect();
+        Ok(toml::to_string(&table)?)
+    }
+
+    /// Apply settings from TOML text; entries that can't be applied are skipped
+    /// and returned as warnings
+    pub fn apply_toml(&mut self, text: &str) -> Result<Applied> {
+        let table: toml::Table = text.parse()?;
+        let mut applied = Applied::default();
+        for (name, value) in table {
+            let value = match value {
+                toml::Value::String(value) => value,
+                other => other.to_string(),
+            };
+            match self.set(&name, &value) {
+                Ok(()) => applied.names.push(name),
+                Err(e) => applied.warnings.push(e.to_string()),
+            }
+        }
+        Ok(applied)
+    }
+
+    /// Write all settings to a TOML file
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, self.to_toml()?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Apply settings from a TOML file
+    pub fn load(&mut self, path: &Path) -> Result<Applied> {
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        self.apply_toml(&text)
+            .with_context(|| format!("Invalid settings file {}", path.display()))
+    }
+}
+
+/// Settings applied from a file
+#[derive(Debug, Default)]
+pub struct Applied {
+    /// Names of the settings that were set
+    pub names: Vec<String>,
+    /// Why the other entries were skipped
+    pub warnings: Vec<String>,
+}
+
+/// Parse a boolean setting value (1/0, on/off, true/false)
+fn parse_bool(name: &str, value: &str) -> Result<bool> {
+    match value.to_lowercase().as_str() {
+        "1" | "on" | "true" | "yes" => Ok(true),
+        "0" | "off" | "false" | "no" => Ok(false),
+        _ => Err(anyhow!("Invalid boolean for {}: {}", name, value)),
+    }
+}
+
+fn format_bool(value: bool) -> String {
+    if value { "1" } else { "0" }.to_string()
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+#[path = "./settings_tests.rs"]
+mod settings_tests;
@@ -0,0 +1,37 @@
+use super::*;
+
+#[test]
+fn test_toml_round_trip_keeps_every_setting() {
+    let mut settings = Settings::new();
+    settings.apply("completion_type=circular").unwrap();
+    settings.apply("completion_limit=40").unwrap();
+    settings.apply("prompt=' \\d> '").unwrap();
+    settings.apply("vertical_key=alt-x").unwrap();
+    settings.apply("fragmentation_threshold=12.5").unwrap();
+
+    let mut loaded = Settings::new();
+    let applied = loaded.apply_toml(&settings.to_toml().unwrap()).unwrap();
+    assert!(applied.warnings.is_empty(), "{:?}", applied.warnings);
+    assert_eq!(applied.names.len(), settings.entries().len());
+    assert_eq!(loaded.entries(), settings.entries());
+    assert_eq!(loaded.prompt, " \\d> ");
+}
+
+#[test]
+fn test_apply_toml_accepts_native_values_and_reports_bad_entries() {
+    let mut settings = Settings::new();
+    let applied = settings
+        .apply_toml(
+            "marker = true\nquery_cache_ttl = 30\nno_such_setting = 1\nindex_advice = \"maybe\"\n",
+        )
+        .unwrap();
+    let warnings = applied.warnings;
+    assert_eq!(applied.names, vec!["marker", "query_cache_ttl"]);
+    assert!(settings.marker);
+    assert_eq!(settings.query_cache_ttl, 30);
+    assert!(!settings.index_advice);
+    assert_eq!(warnings.len(), 2);
+    assert!(warnings[0].contains("index_advice") || warnings[1].contains("index_advice"));
+
+    assert!(settings.apply_toml("marker = ").is_err());
+}
@@ -0,0 +1,522 @@
+/*!
+ * Statement text utilities
+ *
+ * Quote-aware scanning of SQL text shared by the REPL and script execution
+ */
+
+use sqlparser::dialect::MySqlDialect;
+use sqlparser::keywords::Keyword;
+use sqlparser::parser::Parser;
+use sqlparser::tokenizer::{Token, Tokenizer, Whitespace};
+
+/// Lexical state while scanning SQL text
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ScanState {
+    Normal,
+    SingleQuote,
+    DoubleQuote,
+    Backtick,
+    LineComment,
+    BlockComment,
+}
+
+/// Scan SQL text and call `on_code` for every byte offset outside quotes and comments
+fn scan_code(sql: &str, mut on_code: impl FnMut(usize)) {
+    let bytes = sql.as_bytes();
+    let mut state = ScanState::Normal;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let c = bytes[i];
+        let next = bytes.get(i + 1).copied();
+        match state {
+            ScanState::Normal => match c {
+                b'\'' => state = ScanState::SingleQuote,
+                b'"' => state = ScanState::DoubleQuote,
+                b'`' => state = ScanState::Backtick,
+                b'#' => state = ScanState::LineComment,
+                b'-' if next == Some(b'-')
+                    && bytes.get(i + 2).is_none_or(|b| b.is_ascii_whitespace()) =>
+                {
+                    state = ScanState::LineComment
+                }
+                b'/' if next == Some(b'*') => {
+                    state = ScanState::BlockComment;
+                    i += 1;
+                }
+                _ => on_code(i),
+            },
+            ScanState::SingleQuote | ScanState::DoubleQuote => {
+                let quote = if state == ScanState::SingleQuote {
+                    b'\''
+                } else {
+                    b'"'
+                };
+                if c == b'\\' {
+                    i += 1;
+                } else if c == quote {
+                    state = ScanState::Normal;
+                }
+            }
+            ScanState::Backtick => {
+                if c == b'`' {
+                    state = ScanState::Normal;
+                }
+            }
+            ScanState::LineComment => {
+                if c == b'\n' {
+                    state = ScanState::Normal;
+                }
+            }
+            ScanState::BlockComment => {
+                if c == b'*' && next == Some(b'/') {
+                    state = ScanState::Normal;
+                    i += 1;
+                }
+            }
+        }
+        i += 1;
+    }
+}
+
+/// Split SQL text into statements on `;` outside quotes and comments
+pub fn split_statements(sql: &str) -> Vec<String> {
+    split_script(sql, ";").0
+}
+
+/// Split a script into statements on `delimiter` outside quotes and comments,
+/// following `DELIMITER` lines as the mysql client does; returns the statements
+/// and the delimiter in effect at the end
+pub fn split_script(sql: &str, delimiter: &str) -> (Vec<String>, String) {
+    let (statements, delimiter) = split_script_delimited(sql, delimiter);
+    let statements = statements.into_iter().map(|(sql, _)| sql).collect();
+    (statements, delimiter)
+}
+
+/// Like `split_script`, pairing each statement with the delimiter that ended it
+pub fn split_script_delimited(sql: &str, delimiter: &str) -> (Vec<(String, String)>, String) {
+    let bytes = sql.as_bytes();
+    let mut code = vec![false; bytes.len()];
+    scan_code(sql, |i| code[i] = true);
+
+    let mut delimiter = delimiter.to_string();
+    let mut statements = Vec::new();
+    let mut start = 0;
+    let mut at_start = true;
+    let mut i = 0;
+    while i < bytes.len() {
+        if !code[i] {
+            i += 1;
+            continue;
+        }
+        if at_start {
+            let line_end = sql[i..].find('\n').map_or(sql.len(), |end| i + end);
+            if let Some(new_delimiter) = delimiter_command(&sql[i..line_end]) {
+                delimiter = new_delimiter;
+                i = line_end;
+                start = i;
+                continue;
+            }
+        }
+        if !delimiter.is_empty() && bytes[i..].starts_with(delimiter.as_bytes()) {
+            statements.push((sql[start..i].trim().to_string(), delimiter.clone()));
+            i += delimiter.len();
+            start = i;
+            at_start = true;
+            continue;
+        }
+        if !bytes[i].is_ascii_whitespace() {
+            at_start = false;
+        }
+        i += 1;
+    }
+    statements.push((sql[start..].trim().to_string(), delimiter.clone()));
+
+    statements.retain(|(s, _)| !s.is_empty());
+    (statements, delimiter)
+}
+
+/// The new delimiter when a line is a `DELIMITER <token>` command
+pub fn delimiter_command(line: &str) -> Option<String> {
+    let line = line.trim();
+    let keyword = line.get(..9)?;
+    let rest = &line[9..];
+    if !keyword.eq_ignore_ascii_case("DELIMITER") || !rest.starts_with([' ', '\t']) {
+        return None;
+    }
+    rest.split_whitespace().next().map(str::to_string)
+}
+
+/// How a buffered statement was terminated
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Terminator {
+    /// The active delimiter, `;` by default
+    Delimiter,
+    /// `\g`: send the statement
+    Go,
+    /// `\G`: send the statement and print rows vertically
+    Vertical,
+    /// `\E`: send the statement wrapped in EXPLAIN
+    Explain,
+}
+
+/// Check whether an input buffer, possibly spanning several lines, ends with
+/// `\g`, `\G`, `\E` or the delimiter outside quotes and comments; returns the
+/// statement without its terminator
+pub fn complete_statement(buffer: &str, delimiter: &str) -> Option<(String, Terminator)> {
+    let trimmed = buffer.trim_end();
+    let mut code = vec![false; trimmed.len()];
+    scan_code(trimmed, |i| code[i] = true);
+
+    let terminators = [
+        ("\\G", Terminator::Vertical),
+        ("\\g", Terminator::Go),
+        ("\\E", Terminator::Explain),
+        (delimiter, Terminator::Delimiter),
+    ];
+    terminators.into_iter().find_map(|(text, terminator)| {
+        let start = trimmed.len().checked_sub(text.len())?;
+        let is_code =
+            !text.is_empty() && trimmed.ends_with(text) && code[start..].iter().all(|c| *c);
+        is_code.then(|| (trimmed[..start].trim().to_string(), terminator))
+    })
+}
+
+/// Split a script statement at `\g` and `\G` outside quotes and comments;
+/// like the mysql client they end a statement wherever they appear, also
+/// glued to a word (`SELECT * FROM users\G`). The last part ends with the
+/// delimiter
+pub fn split_terminators(sql: &str) -> Vec<(String, Terminator)> {
+    let bytes = sql.as_bytes();
+    let mut code = vec![false; bytes.len()];
+    scan_code(sql, |i| code[i] = true);
+
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut i = 0;
+    while i + 1 < bytes.len() {
+        let terminator = match bytes[i + 1] {
+            b'G' => Terminator::Vertical,
+            b'g' => Terminator::Go,
+            _ => {
+                i += 1;
+                continue;
+            }
+        };
+        if bytes[i] == b'\\' && code[i] && code[i + 1] {
+            parts.push((sql[start..i].trim().to_string(), terminator));
+            start = i + 2;
+            i += 2;
+        } else {
+            i += 1;
+        }
+    }
+    parts.push((sql[start..].trim().to_string(), Terminator::Delimiter));
+    parts.retain(|(statement, _)| !statement.is_empty());
+    parts
+}
+
+/// Wrap a statement in EXPLAIN; only SELECT and DML statements have a plan
+pub fn explain(sql: &str) -> Option<String> {
+    let first_word = sql.split_whitespace().next()?.to_uppercase();
+    let explainable = matches!(
+        first_word.as_str(),
+        "SELECT" | "WITH" | "TABLE" | "INSERT" | "UPDATE" | "DELETE" | "REPLACE"
+    ) || sql.trim_start().starts_with('(');
+    explainable.then(|| format!("EXPLAIN {}", sql.trim()))
+}
+
+/// How a client-side time limit applies to a statement
+#[derive(Debug, PartialEq)]
+pub enum TimeLimit {
+    /// The statement with a `MAX_EXECUTION_TIME` optimizer hint added
+    Hint(String),
+    /// A SELECT where no hint can be placed; use the session variable instead
+    Session,
+    /// Not a SELECT; sent unchanged
+    Unchanged,
+}
+
+/// Add `/*+ MAX_EXECUTION_TIME(ms) */` right after the leading SELECT keyword,
+/// skipping comments and opening parentheses before it
+pub fn limit_execution_time(sql: &str, ms: u64) -> TimeLimit {
+    let Ok(tokens) = Tokenizer::new(&MySqlDialect {}, sql).tokenize() else {
+        return if is_select(sql) {
+            TimeLimit::Session
+        } else {
+            TimeLimit::Unchanged
+        };
+    };
+
+    // Byte offset of the end of the SELECT keyword
+    let mut offset = 0;
+    let mut tokens = tokens.iter();
+    for token in tokens.by_ref() {
+        offset += token.to_string().len();
+        match token {
+            Token::Whitespace(_) | Token::LParen => continue,
+            Token::Word(word) if word.keyword == Keyword::SELECT => break,
+            Token::Word(word) if word.keyword == Keyword::WITH => return TimeLimit::Session,
+            _ => return TimeLimit::Unchanged,
+        }
+    }
+    if offset == 0
+        || !sql.is_char_boundary(offset)
+        || !sql[..offset].to_uppercase().ends_with("SELECT")
+    {
+        return TimeLimit::Unchanged;
+    }
+
+    // Only one hint comment is read after SELECT, so an existing one rules out adding ours
+    let existing_hint = tokens
+        .find(|token| {
+            !matches!(
+                token,
+                Token::Whitespace(Whitespace::Space | Whitespace::Newline | Whitespace::Tab)
+            )
+        })
+        .is_some_and(|token| {
+            matches!(token, Token::Whitespace(Whitespace::MultiLineComment(comment)) if comment.starts_with('+'))
+        });
+    if existing_hint {
+        return TimeLimit::Session;
+    }
+
+    TimeLimit::Hint(format!(
+        "{} /*+ MAX_EXECUTION_TIME({}) */{}",
+        &sql[..offset],
+        ms,
+        &sql[offset..]
+    ))
+}
+
+/// Expand the `{user}`, `{pid}` and `{counter}` placeholders of a
+/// `--query-comment` template
+pub fn expand_query_comment(template: &str, user: &str, pid: u32, counter: u64) -> String {
+    template
+        .replace("{user}", user)
+        .replace("{pid}", &pid.to_string())
+        .replace("{counter}", &counter.to_string())
+}
+
+/// Put `/* comment */` in front of the first token of a statement, after any
+/// leading whitespace. Statements that start with an optimizer hint or a
+/// versioned comment (`/*+`, `/*!`) are returned unchanged, and a `*/` inside
+/// the comment is broken up so it can't end the comment early
+pub fn prepend_query_comment(sql: &str, comment: &str) -> String {
+    let body = sql.trim_start();
+    if body.starts_with("/*+") || body.starts_with("/*!") {
+        return sql.to_string();
+    }
+    let indent = &sql[..sql.len() - body.len()];
+    format!("{}/* {} */ {}", indent, comment.replace("*/", "* /"), body)
+}
+
+/// Whether the statement's first keyword is SELECT or WITH
+fn is_select(sql: &str) -> bool {
+    let first_word = sql
+        .trim_start_matches(|c: char| c == '(' || c.is_whitespace())
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .to_uppercase();
+    first_word.starts_with("SELECT") || first_word == "WITH"
+}
+
+/// Statement keywords that show pasted text is meant as SQL
+const STATEMENT_KEYWORDS: &[&str] = &[
+    "ALTER", "BEGIN", "CALL", "CREATE", "DELETE", "DROP", "EXPLAIN", "GRANT", "INSERT", "LOAD",
+    "REPLACE", "SELECT", "SHOW", "START", "TRUNCATE", "UPDATE",
+];
+
+/// Words that can follow `SET` besides an assigned variable
+const SET_TARGETS: &[&str] = &[
+    "CHARACTER",
+    "CHARSET",
+    "DEFAULT",
+    "GLOBAL",
+    "LOCAL",
+    "NAMES",
+    "PASSWORD",
+    "PERSIST",
+    "PERSIST_ONLY",
+    "ROLE",
+    "SESSION",
+    "TRANSACTION",
+];
+
+/// Whether a submitted statement looks like pasted data rather than SQL: larger than
+/// `max_bytes` or `max_lines` (0 disables either), and not starting like a statement
+pub fn looks_like_pasted_data(sql: &str, max_bytes: usize, max_lines: usize) -> bool {
+    let large = (max_bytes > 0 && sql.len() > max_bytes)
+        || (max_lines > 0 && sql.bytes().filter(|b| *b == b'\n').count() >= max_lines);
+    if !large {
+        return false;
+    }
+
+    let Ok(tokens) = Tokenizer::new(&MySqlDialect {}, sql).tokenize() else {
+        return true;
+    };
+    let tokens: Vec<Token> = tokens
+        .into_iter()
+        .filter(|token| !matches!(token, Token::Whitespace(_)))
+        .collect();
+    let Some((keyword, rest)) = statement_keyword(&tokens) else {
+        return true;
+    };
+    let is_sql = match keyword.as_str() {
+        // `SET name = ...`, `SET @var := ...`, `SET NAMES ...`
+        "SET" => match rest {
+            [Token::Word(word), ..] if word.value.starts_with('@') => true,
+            [Token::Word(word), ..]
+                if SET_TARGETS.contains(&word.value.to_uppercase().as_str()) =>
+            {
+                true
+            }
+            [Token::Word(_), Token::Eq | Token::DuckAssignment, ..] => true,
+            _ => false,
+        },
+        // `USE db` and nothing more
+        "USE" => matches!(rest, [Token::Word(_)] | [Token::Word(_), Token::SemiColon]),
+        keyword => STATEMENT_KEYWORDS.contains(&keyword),
+    };
+    !is_sql
+}
+
+/// The upper-cased keyword a statement starts with, after leading parentheses,
+/// and the tokens after it; a WITH statement is classified by the keyword after
+/// its common table expressions. `tokens` holds no whitespace
+fn statement_keyword(tokens: &[Token]) -> Option<(String, &[Token])> {
+    let skip_parens = |i: usize| {
+        i + tokens[i..]
+            .iter()
+            .take_while(|token| **token == Token::LParen)
+            .count()
+    };
+    // Index after the parenthesized group opening at `i`
+    let skip_group = |i: usize| -> Option<usize> {
+        let mut depth = 0;
+        for (j, token) in tokens.iter().enumerate().skip(i) {
+            match token {
+                Token::LParen => depth += 1,
+                Token::RParen if depth == 1 => return Some(j + 1),
+                Token::RParen => depth -= 1,
+                _ => {}
+            }
+        }
+        None
+    };
+    let keyword_at = |i: usize| match tokens.get(i) {
+        Some(Token::Word(word)) if word.quote_style.is_none() => Some(word.keyword),
+        _ => None,
+    };
+
+    let mut i = skip_parens(0);
+    if keyword_at(i)? == Keyword::WITH {
+        i += 1;
+        if keyword_at(i) == Some(Keyword::RECURSIVE) {
+            i += 1;
+        }
+        loop {
+            // `name [(columns)] AS (query)`
+            if !matches!(tokens.get(i), Some(Token::Word(_))) {
+                return None;
+            }
+            i += 1;
+            if tokens.get(i) == Some(&Token::LParen) {
+                i = skip_group(i)?;
+            }
+            if keyword_at(i) != Some(Keyword::AS) || tokens.get(i + 1) != Some(&Token::LParen) {
+                return None;
+            }
+            i = skip_group(i + 1)?;
+            if tokens.get(i) != Some(&Token::Comma) {
+                break;
+            }
+            i += 1;
+        }
+        i = skip_parens(i);
+        keyword_at(i)?;
+    }
+    match &tokens[i] {
+        Token::Word(word) => Some((word.value.to_uppercase(), &tokens[i + 1..])),
+        _ => None,
+    }
+}
+
+/// Whether text parses as one or more complete SQL statements
+pub fn is_complete_sql(sql: &str) -> bool {
+    !sql.trim().is_empty()
+        && Parser::parse_sql(&MySqlDialect {}, sql).is_ok_and(|statements| !statements.is_empty())
+}
+
+/// For each byte of the text, whether it lies outside quotes and comments
+pub fn code_mask(sql: &str) -> Vec<bool> {
+    let mut mask = vec![false; sql.len()];
+    scan_code(sql, |i| mask[i] = true);
+    mask
+}
+
+/// Find `:name` placeholders outside quotes and comments, in order of appearance
+pub fn find_placeholders(sql: &str) -> Vec<String> {
+    placeholder_spans(sql)
+        .into_iter()
+        .map(|(start, end)| sql[start + 1..end].to_string())
+        .fold(Vec::new(), |mut names, name| {
+            if !names.contains(&name) {
+                names.push(name);
+            }
+            names
+        })
+}
+
+/// Replace `:name` placeholders with their values; unknown names are left untouched
+pub fn substitute_placeholders(sql: &str, lookup: impl Fn(&str) -> Option<String>) -> String {
+    let mut result = String::with_capacity(sql.len());
+    let mut last = 0;
+
+    for (start, end) in placeholder_spans(sql) {
+        if let Some(value) = lookup(&sql[start + 1..end]) {
+            result.push_str(&sql[last..start]);
+            result.push_str(&value);
+            last = end;
+        }
+    }
+    result.push_str(&sql[last..]);
+    result
+}
+
+/// Byte ranges of `:name` placeholders, including the colon
+fn placeholder_spans(sql: &str) -> Vec<(usize, usize)> {
+    let bytes = sql.as_bytes();
+    let is_name_char = |b: u8| b.is_ascii_alphanumeric() || b == b'_';
+    let mut spans = Vec::new();
+    let mut skip_until = 0;
+
+    scan_code(sql, |i| {
+        if i < skip_until || bytes[i] != b':' {
+            return;
+        }
+        // Skip `::`, `:=` and things like `a:b` glued to a preceding word
+        let preceded = i > 0 && (is_name_char(bytes[i - 1]) || bytes[i - 1] == b':');
+        let starts_name = bytes
+            .get(i + 1)
+            .is_some_and(|b| b.is_ascii_alphabetic() || *b == b'_');
+        if preceded || !starts_name {
+            return;
+        }
+
+        let mut end = i + 1;
+        while end < bytes.len() && is_name_char(bytes[end]) {
+            end += 1;
+        }
+        spans.push((i, end));
+        skip_until = end;
+    });
+
+    spans
+}
+
+#[cfg(test)]
+#[path = "./statement_tests.rs"]
+mod statement_tests;
@@ -0,0 +1,264 @@
+use super::*;
+
+#[test]
+fn test_split_statements() {
+    assert_eq!(
+        split_statements("SELECT 1; SELECT ';' ; -- done;\nSELECT 2;"),
+        vec!["SELECT 1", "SELECT ';'", "-- done;\nSELECT 2"]
+    );
+    assert_eq!(
+        split_statements("INSERT INTO t VALUES ('a;b') /* ; */"),
+        vec!["INSERT INTO t VALUES ('a;b') /* ; */"]
+    );
+}
+
+#[test]
+fn test_split_script_with_initial_delimiter() {
+    let dump = "CREATE PROCEDURE p()\nBEGIN\n  SELECT 1;\n  SELECT ';$$';\nEND$$\n\
+                CREATE FUNCTION f() RETURNS INT RETURN 1$$\n\
+                DELIMITER ;\n\
+                SELECT 3;";
+    let (statements, delimiter) = split_script(dump, "$$");
+    assert_eq!(
+        statements,
+        vec![
+            "CREATE PROCEDURE p()\nBEGIN\n  SELECT 1;\n  SELECT ';$$';\nEND",
+            "CREATE FUNCTION f() RETURNS INT RETURN 1",
+            "SELECT 3",
+        ]
+    );
+    assert_eq!(delimiter, ";");
+}
+
+#[test]
+fn test_split_script_follows_delimiter_lines() {
+    let (statements, delimiter) =
+        split_script("SELECT 1;\ndelimiter //\nSELECT 2; SELECT 3//\n", ";");
+    assert_eq!(statements, vec!["SELECT 1", "SELECT 2; SELECT 3"]);
+    assert_eq!(delimiter, "//");
+    assert_eq!(delimiter_command("DELIMITER $$"), Some("$$".to_string()));
+    assert_eq!(delimiter_command("DELIMITERS"), None);
+}
+
+#[test]
+fn test_find_placeholders() {
+    assert_eq!(
+        find_placeholders("SELECT * FROM t WHERE id = :id AND owner = :owner OR id = :id"),
+        vec!["id", "owner"]
+    );
+}
+
+#[test]
+fn test_placeholders_ignore_quotes_and_assignments() {
+    assert!(find_placeholders("SELECT '12:30', `a:b`, @x := 1 -- :nope").is_empty());
+    assert!(find_placeholders("SELECT @x:=1, a::b").is_empty());
+}
+
+#[test]
+fn test_substitute_placeholders() {
+    let sql = "SELECT * FROM t WHERE id = :id AND name = ':id' AND x = :missing";
+    let result = substitute_placeholders(sql, |name| (name == "id").then(|| "42".to_string()));
+    assert_eq!(
+        result,
+        "SELECT * FROM t WHERE id = 42 AND name = ':id' AND x = :missing"
+    );
+}
+
+#[test]
+fn test_complete_statement_across_lines() {
+    assert_eq!(
+        complete_statement("SELECT *\nFROM users\\g", ";"),
+        Some(("SELECT *\nFROM users".to_string(), Terminator::Go))
+    );
+    assert_eq!(
+        complete_statement("SELECT *\nFROM users \\G  ", ";"),
+        Some(("SELECT *\nFROM users".to_string(), Terminator::Vertical))
+    );
+    assert_eq!(
+        complete_statement("SELECT 1\n;", ";"),
+        Some(("SELECT 1".to_string(), Terminator::Delimiter))
+    );
+}
+
+#[test]
+fn test_incomplete_statement() {
+    assert_eq!(complete_statement("SELECT *\nFROM users", ";"), None);
+    assert_eq!(complete_statement("SELECT ';", ";"), None);
+    assert_eq!(complete_statement("SELECT 1 -- \\g", ";"), None);
+    assert_eq!(
+        complete_statement("CREATE PROCEDURE p() BEGIN SELECT 1; END$$", "$$"),
+        Some((
+            "CREATE PROCEDURE p() BEGIN SELECT 1; END".to_string(),
+            Terminator::Delimiter
+        ))
+    );
+}
+
+#[test]
+fn test_large_insert_is_not_pasted_data() {
+    let rows = vec!["(1, '{\"a\": 1}')"; 50_000].join(",\n");
+    let insert = format!(
+        "-- import\nINSERT INTO events (id, payload) VALUES\n{}",
+        rows
+    );
+    assert!(insert.len() > 512 * 1024);
+    assert!(!looks_like_pasted_data(&insert, 512 * 1024, 500));
+    assert!(!looks_like_pasted_data("{\"a\": 1}", 512 * 1024, 500));
+}
+
+#[test]
+fn test_pasted_data_detected_by_size_or_lines() {
+    let blob = format!(
+        "{{\"items\": [{}]}}",
+        vec!["{\"id\": 1}"; 100_000].join(", ")
+    );
+    assert!(looks_like_pasted_data(&blob, 512 * 1024, 500));
+    assert!(!looks_like_pasted_data(&blob, 0, 0));
+
+    let lines = vec!["ééé,42,\"x\""; 600].join("\n");
+    assert!(looks_like_pasted_data(&lines, 512 * 1024, 500));
+    assert!(!looks_like_pasted_data(&lines, 512 * 1024, 1000));
+}
+
+#[test]
+fn test_pasted_data_classified_by_first_keyword() {
+    let rows = vec!["use,set,with"; 600].join("\n");
+    assert!(looks_like_pasted_data(&rows, 512 * 1024, 500));
+    let prose = format!("With the data below\n{}", rows);
+    assert!(looks_like_pasted_data(&prose, 512 * 1024, 500));
+
+    let values = vec!["(1, 'a')"; 600].join(",\n");
+    let with = format!(
+        "WITH RECURSIVE t (n) AS (SELECT 1), u AS (SELECT 2)\nINSERT INTO x VALUES\n{}",
+        values
+    );
+    assert!(!looks_like_pasted_data(&with, 512 * 1024, 500));
+    let set = format!("SET @ids = '{}'", vec!["1"; 600].join("\n"));
+    assert!(!looks_like_pasted_data(&set, 512 * 1024, 500));
+    let set = format!("SET sql_mode = '{}'", vec!["x"; 600].join("\n"));
+    assert!(!looks_like_pasted_data(&set, 512 * 1024, 500));
+}
+
+#[test]
+fn test_is_complete_sql() {
+    assert!(is_complete_sql("SELECT id FROM users WHERE id = 1"));
+    assert!(!is_complete_sql("SELECT id FROM users WHERE"));
+    assert!(!is_complete_sql("  "));
+}
+
+#[test]
+fn test_explain_terminator() {
+    assert_eq!(
+        complete_statement("SELECT * FROM t WHERE id = 1\\E", ";"),
+        Some((
+            "SELECT * FROM t WHERE id = 1".to_string(),
+            Terminator::Explain
+        ))
+    );
+    assert_eq!(
+        explain("update t set a = 1").as_deref(),
+        Some("EXPLAIN update t set a = 1")
+    );
+    assert_eq!(explain("CREATE TABLE t (id INT)"), None);
+}
+
+#[test]
+fn test_limit_execution_time_places_hint_after_select() {
+    assert_eq!(
+        limit_execution_time("SELECT * FROM t", 500),
+        TimeLimit::Hint("SELECT /*+ MAX_EXECUTION_TIME(500) */ * FROM t".to_string())
+    );
+    assert_eq!(
+        limit_execution_time("-- report\n/* totals */ select sum(a) from t", 10),
+        TimeLimit::Hint(
+            "-- report\n/* totals */ select /*+ MAX_EXECUTION_TIME(10) */ sum(a) from t"
+                .to_string()
+        )
+    );
+    assert_eq!(
+        limit_execution_time("((SELECT 1) UNION (SELECT 2))", 10),
+        TimeLimit::Hint("((SELECT /*+ MAX_EXECUTION_TIME(10) */ 1) UNION (SELECT 2))".to_string())
+    );
+}
+
+#[test]
+fn test_limit_execution_time_falls_back_or_leaves_statement() {
+    assert_eq!(
+        limit_execution_time("SELECT /*+ NO_INDEX_MERGE(t) */ * FROM t", 10),
+        TimeLimit::Session
+    );
+    assert_eq!(
+        limit_execution_time("WITH x AS (SELECT 1) SELECT * FROM x", 10),
+        TimeLimit::Session
+    );
+    assert_eq!(
+        limit_execution_time("UPDATE t SET a = (SELECT 1)", 10),
+        TimeLimit::Unchanged
+    );
+    assert_eq!(
+        limit_execution_time("/* SELECT */ DELETE FROM t", 10),
+        TimeLimit::Unchanged
+    );
+}
+
+#[test]
+fn test_split_terminators_glued_to_word() {
+    assert_eq!(
+        split_terminators("SELECT * FROM users\\G"),
+        vec![("SELECT * FROM users".to_string(), Terminator::Vertical)]
+    );
+    assert_eq!(
+        split_terminators("SELECT 1\\g SELECT '\\G' -- \\G\n"),
+        vec![
+            ("SELECT 1".to_string(), Terminator::Go),
+            ("SELECT '\\G' -- \\G".to_string(), Terminator::Delimiter),
+        ]
+    );
+}
+
+#[test]
+fn test_expand_query_comment() {
+    assert_eq!(
+        expand_query_comment("tool=cli user={user} pid={pid} n={counter}", "app", 4242, 7),
+        "tool=cli user=app pid=4242 n=7"
+    );
+    assert_eq!(expand_query_comment("{other}", "app", 1, 1), "{other}");
+}
+
+#[test]
+fn test_prepend_query_comment_position() {
+    assert_eq!(
+        prepend_query_comment("SELECT 1", "cli"),
+        "/* cli */ SELECT 1"
+    );
+    assert_eq!(
+        prepend_query_comment("\n  SELECT /*+ MAX_EXECUTION_TIME(5) */ 1", "cli"),
+        "\n  /* cli */ SELECT /*+ MAX_EXECUTION_TIME(5) */ 1"
+    );
+    assert_eq!(
+        prepend_query_comment("(SELECT 1) UNION (SELECT 2)", "cli"),
+        "/* cli */ (SELECT 1) UNION (SELECT 2)"
+    );
+    assert_eq!(
+        prepend_query_comment("-- note\nSELECT 1", "cli"),
+        "/* cli */ -- note\nSELECT 1"
+    );
+    assert_eq!(
+        prepend_query_comment("/* mine */ EXPLAIN SELECT 1", "cli"),
+        "/* cli */ /* mine */ EXPLAIN SELECT 1"
+    );
+    assert_eq!(
+        prepend_query_comment("x", "a */ DROP TABLE t; /*"),
+        "/* a * / DROP TABLE t; /* */ x"
+    );
+}
+
+#[test]
+fn test_prepend_query_comment_skips_hint_comments() {
+    for sql in [
+        "/*+ SET_VAR(sort_buffer_size = 16M) */ SELECT 1",
+        "  /*!40101 SET NAMES utf8 */",
+    ] {
+        assert_eq!(prepend_query_comment(sql, "cli"), sql);
+    }
+}
@@ -0,0 +1,49 @@
+/*!
+ * Shared helpers
+ *
+ * Small text utilities used across commands
+ */
+
+use sqlparser::dialect::MySqlDialect;
+use sqlparser::keywords::Keyword;
+use sqlparser::parser::Parser;
+use sqlparser::tokenizer::{Token, Tokenizer};
+
+/// Canonical form of a statement for comparing queries: comments and extra
+/// whitespace removed, keywords upper-cased. Parseable SQL uses the AST's
+/// rendering; anything else is rebuilt from its tokens.
+pub fn normalize_query(sql: &str) -> String {
+    let dialect = MySqlDialect {};
+    let sql = sql.trim().trim_end_matches(';').trim_end();
+
+    if let Ok(statements) = Parser::parse_sql(&dialect, sql) {
+        if !statements.is_empty() {
+            return statements
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join("; ");
+        }
+    }
+
+    match Tokenizer::new(&dialect, sql).tokenize() {
+        Ok(tokens) => tokens
+            .iter()
+            .filter(|token| !matches!(token, Token::Whitespace(_)))
+            .map(|token| match token {
+                Token::Word(word)
+                    if word.keyword != Keyword::NoKeyword && word.quote_style.is_none() =>
+                {
+                    word.value.to_uppercase()
+                }
+                token => token.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(" "),
+        Err(_) => sql.split_whitespace().collect::<Vec<_>>().join(" "),
+    }
+}
+
+#[cfg(test)]
+#[path = "./util_tests.rs"]
+mod util_tests;
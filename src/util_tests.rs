@@ -0,0 +1,31 @@
+use super::*;
+
+#[test]
+fn test_normalize_query_ignores_case_spacing_and_comments() {
+    let canonical = normalize_query("SELECT * FROM users");
+    assert_eq!(canonical, normalize_query("select *   from users;  "));
+    assert_eq!(
+        canonical,
+        normalize_query("select * -- all of them\n from /* table */ users")
+    );
+}
+
+#[test]
+fn test_normalize_query_keeps_literals_and_identifiers() {
+    assert_ne!(
+        normalize_query("SELECT * FROM users WHERE name = 'a'"),
+        normalize_query("SELECT * FROM users WHERE name = 'A'")
+    );
+    assert_ne!(
+        normalize_query("SELECT * FROM Users"),
+        normalize_query("SELECT * FROM users")
+    );
+}
+
+#[test]
+fn test_normalize_unparseable_query() {
+    assert_eq!(
+        normalize_query("select *  from users   where"),
+        normalize_query("SELECT * FROM users WHERE")
+    );
+}